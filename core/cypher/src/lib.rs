@@ -11,8 +11,9 @@
 //! - Calculate risk metrics (VaR, etc.)
 
 use ethers::types::{Address, U256};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use matrix_types::{GasPrice, SignedWei};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -50,8 +51,18 @@ pub struct RiskLimits {
     pub max_daily_loss: U256,
     /// Cooldown after failed transaction in ms
     pub failure_cooldown_ms: u64,
+    /// Cooldown multiplier per consecutive failure
+    pub cooldown_multiplier: u32,
+    /// Maximum escalated cooldown in ms
+    pub max_failure_cooldown_ms: u64,
+    /// Failures further apart than this are not consecutive (ms)
+    pub failure_window_ms: u64,
     /// Maximum gas price willing to pay
-    pub max_gas_price: U256,
+    pub max_gas_price: GasPrice,
+    /// Closed-trade PnL entries retained for metrics (oldest evicted)
+    pub max_history: usize,
+    /// Circuit breaker events retained (oldest evicted)
+    pub max_breaker_history: usize,
 }
 
 impl Default for RiskLimits {
@@ -63,7 +74,12 @@ impl Default for RiskLimits {
             max_hourly_loss: U256::from(5u64) * U256::exp10(18),        // 5 ETH
             max_daily_loss: U256::from(20u64) * U256::exp10(18),        // 20 ETH
             failure_cooldown_ms: 5000,                                   // 5 seconds
-            max_gas_price: U256::from(300_000_000_000u64),              // 300 gwei
+            cooldown_multiplier: 2,
+            max_failure_cooldown_ms: 300_000,                            // 5 minutes
+            failure_window_ms: 60_000,                                   // 1 minute
+            max_gas_price: GasPrice::from_gwei(300),
+            max_history: 10_000,
+            max_breaker_history: 1_000,
         }
     }
 }
@@ -91,13 +107,14 @@ pub struct Position {
 pub struct RiskMetrics {
     pub total_exposure: U256,
     pub position_count: u32,
-    pub hourly_pnl: i128,          // Can be negative
-    pub daily_pnl: i128,
+    pub hourly_pnl: SignedWei,
+    pub daily_pnl: SignedWei,
     pub win_rate: f64,
     pub avg_profit: U256,
     pub avg_loss: U256,
-    pub sharpe_ratio: f64,
-    pub max_drawdown: f64,
+    pub sharpe_ratio: f64,         // Per-trade mean / std dev
+    pub max_drawdown: f64,         // Peak-to-trough of cumulative PnL, wei
+    pub value_at_risk_95: U256,    // Historical 95% per-trade VaR, wei
 }
 
 /// Cypher risk manager
@@ -107,6 +124,10 @@ pub struct Cypher {
     circuit_breaker: CircuitBreakerState,
     is_halted: Arc<AtomicBool>,
     cooldown_until_ms: Arc<AtomicU64>,
+    consecutive_failures: Arc<AtomicU32>,
+    last_failure_ms: Arc<AtomicU64>,
+    pnl_history: VecDeque<SignedWei>,
+    breaker_history: VecDeque<(u64, String)>,
 
     // Tracking
     hourly_loss: U256,
@@ -124,6 +145,10 @@ impl Cypher {
             circuit_breaker: CircuitBreakerState::Closed,
             is_halted: Arc::new(AtomicBool::new(false)),
             cooldown_until_ms: Arc::new(AtomicU64::new(0)),
+            consecutive_failures: Arc::new(AtomicU32::new(0)),
+            last_failure_ms: Arc::new(AtomicU64::new(0)),
+            pnl_history: VecDeque::new(),
+            breaker_history: VecDeque::new(),
             hourly_loss: U256::zero(),
             daily_loss: U256::zero(),
             total_exposure: U256::zero(),
@@ -215,7 +240,7 @@ impl Cypher {
     }
 
     /// Close a position
-    pub fn close_position(&mut self, id: u64, exit_price: U256) -> Result<i128, CypherError> {
+    pub fn close_position(&mut self, id: u64, exit_price: U256) -> Result<SignedWei, CypherError> {
         let position = self.positions.remove(&id).ok_or_else(|| {
             CypherError::RiskCheckFailed(format!("Position {} not found", id))
         })?;
@@ -226,15 +251,13 @@ impl Cypher {
         let entry_value = position.amount * position.entry_price / U256::exp10(18);
         let exit_value = position.amount * exit_price / U256::exp10(18);
 
-        let pnl = if exit_value >= entry_value {
-            (exit_value - entry_value).as_u128() as i128
-        } else {
-            -((entry_value - exit_value).as_u128() as i128)
-        };
+        let pnl = SignedWei::from_diff(exit_value, entry_value);
+
+        self.record_pnl(pnl);
 
         // Track losses
-        if pnl < 0 {
-            let loss = U256::from((-pnl) as u128);
+        if pnl.is_negative() {
+            let loss = pnl.abs_wei();
             self.hourly_loss += loss;
             self.daily_loss += loss;
 
@@ -246,6 +269,22 @@ impl Cypher {
         Ok(pnl)
     }
 
+    /// Append a closed-trade PnL, evicting the oldest beyond `max_history`
+    fn record_pnl(&mut self, pnl: SignedWei) {
+        if self.limits.max_history == 0 {
+            return;
+        }
+        while self.pnl_history.len() >= self.limits.max_history {
+            self.pnl_history.pop_front();
+        }
+        self.pnl_history.push_back(pnl);
+    }
+
+    /// Retained closed-trade PnL, oldest first
+    pub fn pnl_history(&self) -> &VecDeque<SignedWei> {
+        &self.pnl_history
+    }
+
     /// Check loss limits and trigger circuit breaker if needed
     fn check_loss_limits(&mut self) -> Result<(), CypherError> {
         if self.hourly_loss > self.limits.max_hourly_loss {
@@ -265,23 +304,114 @@ impl Cypher {
         Ok(())
     }
 
+    /// Replace risk limits at runtime (operator override)
+    ///
+    /// The new limits take effect immediately. Open positions are then
+    /// checked against them; if exposure or position count already exceeds
+    /// the new caps the circuit breaker trips and an error is returned.
+    pub fn update_limits(&mut self, new_limits: RiskLimits) -> Result<(), CypherError> {
+        let old_limits = std::mem::replace(&mut self.limits, new_limits);
+        tracing::warn!(
+            "CYPHER: Limits overridden - before: {:?}, after: {:?}",
+            old_limits, self.limits
+        );
+
+        if self.total_exposure > self.limits.max_total_exposure {
+            self.trigger_circuit_breaker("Exposure exceeds updated limit");
+            return Err(CypherError::ExposureLimitExceeded {
+                current: self.total_exposure,
+                max: self.limits.max_total_exposure,
+            });
+        }
+
+        if self.positions.len() as u32 > self.limits.max_concurrent_positions {
+            self.trigger_circuit_breaker("Open positions exceed updated limit");
+            return Err(CypherError::PositionLimitExceeded(format!(
+                "{} open positions exceed max {}",
+                self.positions.len(),
+                self.limits.max_concurrent_positions
+            )));
+        }
+
+        self.check_loss_limits()
+    }
+
     /// Trigger circuit breaker
     pub fn trigger_circuit_breaker(&mut self, reason: &str) {
         tracing::warn!("CYPHER: Circuit breaker triggered - {}", reason);
         self.circuit_breaker = CircuitBreakerState::Open;
+        self.record_breaker_event(format!("tripped: {}", reason));
     }
 
     /// Reset circuit breaker (manual intervention)
     pub fn reset_circuit_breaker(&mut self) {
         tracing::info!("CYPHER: Circuit breaker reset");
         self.circuit_breaker = CircuitBreakerState::Closed;
+        self.record_breaker_event("reset".to_string());
     }
 
-    /// Set failure cooldown
+    /// Append a breaker event, evicting the oldest beyond `max_breaker_history`
+    fn record_breaker_event(&mut self, event: String) {
+        if self.limits.max_breaker_history == 0 {
+            return;
+        }
+        while self.breaker_history.len() >= self.limits.max_breaker_history {
+            self.breaker_history.pop_front();
+        }
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.breaker_history.push_back((now_ms, event));
+    }
+
+    /// Circuit breaker trips ("tripped: <reason>") and resets ("reset") as
+    /// `(timestamp_ms, event)`, oldest first
+    pub fn breaker_history(&self) -> &VecDeque<(u64, String)> {
+        &self.breaker_history
+    }
+
+    /// Set failure cooldown, escalating on consecutive failures
     pub fn set_cooldown(&self, current_time_ms: u64) {
-        let cooldown_until = current_time_ms + self.limits.failure_cooldown_ms;
+        let last_failure = self.last_failure_ms.swap(current_time_ms, Ordering::SeqCst);
+        let within_window = self.consecutive_failures.load(Ordering::SeqCst) > 0
+            && current_time_ms.saturating_sub(last_failure) <= self.limits.failure_window_ms;
+
+        let failures = if within_window {
+            self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1
+        } else {
+            self.consecutive_failures.store(1, Ordering::SeqCst);
+            1
+        };
+
+        let cooldown_ms = self.escalated_cooldown_ms(failures);
+        let cooldown_until = current_time_ms + cooldown_ms;
         self.cooldown_until_ms.store(cooldown_until, Ordering::SeqCst);
-        tracing::info!("CYPHER: Cooldown set until {}", cooldown_until);
+        tracing::info!(
+            "CYPHER: Cooldown {}ms set until {} ({} consecutive failures)",
+            cooldown_ms, cooldown_until, failures
+        );
+    }
+
+    /// Cooldown for the Nth consecutive failure (base * multiplier^(n-1), capped)
+    fn escalated_cooldown_ms(&self, failures: u32) -> u64 {
+        let base = self.limits.failure_cooldown_ms;
+        let cap = self.limits.max_failure_cooldown_ms.max(base);
+        let factor = (self.limits.cooldown_multiplier.max(1) as u64)
+            .saturating_pow(failures.saturating_sub(1));
+        base.saturating_mul(factor).min(cap)
+    }
+
+    /// Record a successful trade, resetting failure escalation
+    pub fn record_success(&self) {
+        if self.consecutive_failures.swap(0, Ordering::SeqCst) > 0 {
+            tracing::info!("CYPHER: Success recorded, failure escalation reset");
+        }
+    }
+
+    /// Current consecutive failure count
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::SeqCst)
     }
 
     /// Emergency halt
@@ -297,17 +427,38 @@ impl Cypher {
     }
 
     /// Get current metrics
+    ///
+    /// Trade statistics (win rate, averages, Sharpe, drawdown, VaR) cover
+    /// only the retained window of the last `max_history` closed trades.
     pub fn metrics(&self) -> RiskMetrics {
+        let history = &self.pnl_history;
+        let count = history.len();
+
+        let wins: Vec<SignedWei> = history.iter().copied().filter(SignedWei::is_positive).collect();
+        let losses: Vec<SignedWei> = history.iter().copied().filter(SignedWei::is_negative).collect();
+
+        let win_rate = if count > 0 { wins.len() as f64 / count as f64 } else { 0.0 };
+        let average = |trades: &[SignedWei]| {
+            if trades.is_empty() {
+                U256::zero()
+            } else {
+                trades.iter().sum::<SignedWei>().abs_wei() / U256::from(trades.len())
+            }
+        };
+        let avg_profit = average(&wins);
+        let avg_loss = average(&losses);
+
         RiskMetrics {
             total_exposure: self.total_exposure,
             position_count: self.positions.len() as u32,
-            hourly_pnl: 0,    // TODO: Calculate from history
-            daily_pnl: 0,
-            win_rate: 0.0,
-            avg_profit: U256::zero(),
-            avg_loss: U256::zero(),
-            sharpe_ratio: 0.0,
-            max_drawdown: 0.0,
+            hourly_pnl: SignedWei::ZERO, // TODO: Calculate from timestamped history
+            daily_pnl: SignedWei::ZERO,
+            win_rate,
+            avg_profit,
+            avg_loss,
+            sharpe_ratio: sharpe_ratio(history),
+            max_drawdown: max_drawdown(history),
+            value_at_risk_95: value_at_risk(history, 0.95),
         }
     }
 
@@ -334,6 +485,50 @@ impl Cypher {
     }
 }
 
+/// Per-trade Sharpe ratio (mean / population std dev, no risk-free rate)
+fn sharpe_ratio(history: &VecDeque<SignedWei>) -> f64 {
+    if history.len() < 2 {
+        return 0.0;
+    }
+
+    let n = history.len() as f64;
+    let mean = history.iter().map(SignedWei::to_wei_f64).sum::<f64>() / n;
+    let variance = history.iter().map(|p| (p.to_wei_f64() - mean).powi(2)).sum::<f64>() / n;
+    let std_dev = variance.sqrt();
+
+    if std_dev == 0.0 { 0.0 } else { mean / std_dev }
+}
+
+/// Largest peak-to-trough decline of cumulative PnL
+fn max_drawdown(history: &VecDeque<SignedWei>) -> f64 {
+    let mut cumulative = SignedWei::ZERO;
+    let mut peak = SignedWei::ZERO;
+    let mut max_drawdown = SignedWei::ZERO;
+
+    for pnl in history {
+        cumulative += *pnl;
+        peak = peak.max(cumulative);
+        max_drawdown = max_drawdown.max(peak - cumulative);
+    }
+
+    max_drawdown.to_wei_f64()
+}
+
+/// Historical VaR: loss not exceeded with the given confidence
+fn value_at_risk(history: &VecDeque<SignedWei>, confidence: f64) -> U256 {
+    if history.is_empty() {
+        return U256::zero();
+    }
+
+    let mut sorted: Vec<SignedWei> = history.iter().copied().collect();
+    sorted.sort_unstable();
+
+    let index = (((1.0 - confidence) * sorted.len() as f64).floor() as usize).min(sorted.len() - 1);
+    let pnl = sorted[index];
+
+    if pnl.is_negative() { pnl.abs_wei() } else { U256::zero() }
+}
+
 impl Default for Cypher {
     fn default() -> Self {
         Self::with_default_limits()
@@ -363,6 +558,50 @@ mod tests {
         assert!(cypher.check_position(too_large).is_err());
     }
 
+    #[test]
+    fn test_cooldown_escalation() {
+        let cypher = Cypher::with_default_limits();
+        let t0 = 1_000_000u64;
+
+        // 5s, 10s, 20s on consecutive failures
+        cypher.set_cooldown(t0);
+        assert_eq!(cypher.consecutive_failures(), 1);
+        assert!(matches!(cypher.can_trade(t0), Err(CypherError::CooldownActive { remaining_ms: 5000 })));
+
+        cypher.set_cooldown(t0 + 6_000);
+        assert_eq!(cypher.consecutive_failures(), 2);
+        assert!(matches!(cypher.can_trade(t0 + 6_000), Err(CypherError::CooldownActive { remaining_ms: 10_000 })));
+
+        cypher.set_cooldown(t0 + 20_000);
+        assert!(matches!(cypher.can_trade(t0 + 20_000), Err(CypherError::CooldownActive { remaining_ms: 20_000 })));
+
+        // Success resets escalation
+        cypher.record_success();
+        assert_eq!(cypher.consecutive_failures(), 0);
+        cypher.set_cooldown(t0 + 50_000);
+        assert!(matches!(cypher.can_trade(t0 + 50_000), Err(CypherError::CooldownActive { remaining_ms: 5000 })));
+    }
+
+    #[test]
+    fn test_cooldown_escalation_cap_and_window() {
+        let cypher = Cypher::new(RiskLimits {
+            max_failure_cooldown_ms: 12_000,
+            ..Default::default()
+        });
+        let t0 = 1_000_000u64;
+
+        for i in 0..10 {
+            cypher.set_cooldown(t0 + i * 1_000);
+        }
+        assert_eq!(cypher.consecutive_failures(), 10);
+        let now = t0 + 9_000;
+        assert!(matches!(cypher.can_trade(now), Err(CypherError::CooldownActive { remaining_ms: 12_000 })));
+
+        // A failure outside the window starts over
+        cypher.set_cooldown(now + 120_000);
+        assert_eq!(cypher.consecutive_failures(), 1);
+    }
+
     #[test]
     fn test_circuit_breaker() {
         let mut cypher = Cypher::with_default_limits();
@@ -378,4 +617,153 @@ mod tests {
         cypher.reset_circuit_breaker();
         assert_eq!(cypher.circuit_breaker_state(), CircuitBreakerState::Closed);
     }
+
+    #[test]
+    fn test_breaker_history_records_trips_and_resets() {
+        let mut cypher = Cypher::new(RiskLimits {
+            max_breaker_history: 3,
+            ..Default::default()
+        });
+        assert!(cypher.breaker_history().is_empty());
+
+        cypher.trigger_circuit_breaker("Manual halt");
+        cypher.reset_circuit_breaker();
+        cypher.trigger_circuit_breaker("Hourly loss limit exceeded");
+
+        let events: Vec<&str> = cypher.breaker_history().iter().map(|(_, e)| e.as_str()).collect();
+        assert_eq!(events, vec!["tripped: Manual halt", "reset", "tripped: Hourly loss limit exceeded"]);
+        let times: Vec<u64> = cypher.breaker_history().iter().map(|(t, _)| *t).collect();
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+        assert!(times[0] > 0);
+
+        // Bounded: the oldest event is evicted
+        cypher.reset_circuit_breaker();
+        assert_eq!(cypher.breaker_history().len(), 3);
+        assert_eq!(cypher.breaker_history().front().unwrap().1, "reset");
+        assert_eq!(cypher.breaker_history().back().unwrap().1, "reset");
+    }
+
+    #[test]
+    fn test_pnl_history_bounded() {
+        let mut cypher = Cypher::new(RiskLimits {
+            max_history: 3,
+            ..Default::default()
+        });
+
+        for pnl in [-100, 200, 300, 400, 500] {
+            cypher.record_pnl(SignedWei::from(pnl));
+        }
+
+        // Oldest two entries evicted
+        assert_eq!(cypher.pnl_history().len(), 3);
+        let expected: Vec<SignedWei> = [300, 400, 500].into_iter().map(SignedWei::from).collect();
+        assert_eq!(cypher.pnl_history().iter().copied().collect::<Vec<_>>(), expected);
+
+        // Metrics only see the retained window: the -100 loss is gone
+        let metrics = cypher.metrics();
+        assert_eq!(metrics.win_rate, 1.0);
+        assert_eq!(metrics.avg_loss, U256::zero());
+        assert_eq!(metrics.avg_profit, U256::from(400u64));
+        assert_eq!(metrics.max_drawdown, 0.0);
+        assert_eq!(metrics.value_at_risk_95, U256::zero());
+    }
+
+    #[test]
+    fn test_pnl_history_from_closed_positions() {
+        let mut cypher = Cypher::new(RiskLimits {
+            max_history: 2,
+            ..Default::default()
+        });
+        let amount = U256::exp10(18);
+        let price = U256::exp10(18);
+
+        for exit in [11u64, 9, 12] {
+            let id = cypher.open_position(Address::zero(), amount, price, 0).unwrap();
+            cypher.close_position(id, price * exit / 10).unwrap();
+        }
+
+        let tenth = SignedWei::from(100_000_000_000_000_000);
+        assert_eq!(cypher.pnl_history().iter().copied().collect::<Vec<_>>(), vec![-tenth, tenth + tenth]);
+    }
+
+    #[test]
+    fn test_large_pnl_beyond_i128() {
+        let mut cypher = Cypher::new(RiskLimits {
+            max_position_size: U256::MAX,
+            max_total_exposure: U256::MAX,
+            max_hourly_loss: U256::MAX,
+            max_daily_loss: U256::MAX,
+            ..Default::default()
+        });
+        // Values around 1e42 wei overflow i128 (~1.7e38)
+        let amount = U256::exp10(30);
+        let price = U256::exp10(30);
+
+        let id = cypher.open_position(Address::zero(), amount, price, 0).unwrap();
+        let pnl = cypher.close_position(id, price / 2).unwrap();
+        assert!(pnl.is_negative());
+        assert_eq!(pnl.abs_wei(), U256::exp10(42) / 2);
+        assert_eq!(pnl.to_eth_f64(), -5e23);
+        assert_eq!(cypher.metrics().avg_loss, U256::exp10(42) / 2);
+    }
+
+    #[test]
+    fn test_risk_metrics_over_window() {
+        let mut cypher = Cypher::with_default_limits();
+        for pnl in [100, -50, 100, -200, 50] {
+            cypher.record_pnl(SignedWei::from(pnl));
+        }
+
+        let metrics = cypher.metrics();
+        assert_eq!(metrics.win_rate, 0.6);
+        assert_eq!(metrics.avg_loss, U256::from(125u64));
+        // Cumulative: 100, 50, 150, -50, 0 -> peak 150, trough -50
+        assert_eq!(metrics.max_drawdown, 200.0);
+        assert_eq!(metrics.value_at_risk_95, U256::from(200u64));
+        assert!(metrics.sharpe_ratio.is_finite());
+    }
+
+    #[test]
+    fn test_update_limits_trips_breaker() {
+        let mut cypher = Cypher::with_default_limits();
+        let eth = U256::exp10(18);
+        cypher.open_position(Address::zero(), eth * 30, eth, 0).unwrap();
+        cypher.open_position(Address::zero(), eth * 30, eth, 0).unwrap();
+
+        // Tightening above current exposure is fine
+        let limits = RiskLimits {
+            max_total_exposure: eth * 100,
+            ..Default::default()
+        };
+        assert!(cypher.update_limits(limits).is_ok());
+        assert_eq!(cypher.circuit_breaker_state(), CircuitBreakerState::Closed);
+        assert_eq!(cypher.limits().max_total_exposure, eth * 100);
+
+        // Cap below current exposure (60 ETH) trips the breaker
+        let limits = RiskLimits {
+            max_total_exposure: eth * 40,
+            ..Default::default()
+        };
+        let result = cypher.update_limits(limits);
+        assert!(matches!(result, Err(CypherError::ExposureLimitExceeded { .. })));
+        assert_eq!(cypher.circuit_breaker_state(), CircuitBreakerState::Open);
+        assert_eq!(cypher.limits().max_total_exposure, eth * 40);
+        assert!(cypher.can_trade(0).is_err());
+    }
+
+    #[test]
+    fn test_update_limits_position_count() {
+        let mut cypher = Cypher::with_default_limits();
+        let eth = U256::exp10(18);
+        for _ in 0..3 {
+            cypher.open_position(Address::zero(), eth, eth, 0).unwrap();
+        }
+
+        let limits = RiskLimits {
+            max_concurrent_positions: 2,
+            ..Default::default()
+        };
+        assert!(matches!(cypher.update_limits(limits), Err(CypherError::PositionLimitExceeded(_))));
+        assert_eq!(cypher.circuit_breaker_state(), CircuitBreakerState::Open);
+    }
 }
@@ -0,0 +1,117 @@
+//! Cross-Chain Spread Detection (EXPERIMENTAL)
+//!
+//! Compares the same canonical token pair across chains to surface price
+//! differences that could be captured by bridging. Token addresses differ
+//! per chain, so pairs are matched through a canonical symbol map. So can
+//! decimals; Dozer compares prices in whole-token units from its token
+//! registry.
+//!
+//! Bridge costs and transfer times are static estimates. Treat emitted
+//! spreads as research signals, not executable opportunities.
+
+use ethers::types::{Address, U256};
+use matrix_types::{ChainId, DexId};
+use std::collections::HashMap;
+
+/// Estimated cost of moving funds from one chain to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeEstimate {
+    /// Bridge fee in basis points of the transferred amount
+    pub cost_bps: i64,
+    /// Expected transfer time in milliseconds
+    pub time_ms: u64,
+}
+
+/// Cross-chain spread opportunity (experimental)
+#[derive(Debug, Clone)]
+pub struct CrossChainSpread {
+    pub token0: String,        // Canonical symbol, price base
+    pub token1: String,        // Canonical symbol, price quote
+    pub buy_chain: ChainId,
+    pub buy_dex: DexId,
+    pub buy_pool: Address,
+    pub buy_price: U256,
+    pub sell_chain: ChainId,
+    pub sell_dex: DexId,
+    pub sell_pool: Address,
+    pub sell_price: U256,
+    pub spread_bps: i64,       // Gross spread in basis points
+    pub bridge_cost_bps: i64,  // Estimated bridge cost
+    pub bridge_time_ms: u64,   // Estimated bridge latency
+    pub net_spread_bps: i64,   // Spread after bridge cost
+}
+
+/// Cross-chain detection configuration
+#[derive(Debug, Clone)]
+pub struct CrossChainConfig {
+    /// Enable cross-chain detection (off by default)
+    pub enabled: bool,
+    /// Minimum spread after bridge cost required to emit
+    pub min_net_spread_bps: i64,
+    /// Chain-local token address -> canonical symbol
+    pub canonical_tokens: HashMap<(ChainId, Address), String>,
+    /// Bridge estimates keyed by (from chain, to chain)
+    pub bridges: HashMap<(ChainId, ChainId), BridgeEstimate>,
+}
+
+impl Default for CrossChainConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_net_spread_bps: 50, // 0.5%
+            canonical_tokens: HashMap::new(),
+            bridges: HashMap::new(),
+        }
+    }
+}
+
+impl CrossChainConfig {
+    /// Map a chain-local token address to a canonical symbol
+    pub fn with_token(mut self, chain: ChainId, token: Address, symbol: &str) -> Self {
+        self.canonical_tokens.insert((chain, token), symbol.to_string());
+        self
+    }
+
+    /// Register a bridge estimate for one direction
+    pub fn with_bridge(mut self, from: ChainId, to: ChainId, estimate: BridgeEstimate) -> Self {
+        self.bridges.insert((from, to), estimate);
+        self
+    }
+
+    /// Canonical symbol for a chain-local token
+    pub fn canonical(&self, chain: ChainId, token: Address) -> Option<&str> {
+        self.canonical_tokens.get(&(chain, token)).map(String::as_str)
+    }
+
+    /// Bridge estimate for moving funds from one chain to another
+    pub fn bridge(&self, from: ChainId, to: ChainId) -> Option<BridgeEstimate> {
+        self.bridges.get(&(from, to)).copied()
+    }
+}
+
+/// Price of `base` in terms of `quote` from reserves (18 decimals)
+pub(crate) fn reserve_price(reserve_base: U256, reserve_quote: U256) -> Option<U256> {
+    matrix_types::price_from_reserves(reserve_base, reserve_quote)
+}
+
+/// Spread in basis points from buying at `buy` and selling at `sell`
+pub(crate) fn spread_bps(buy: U256, sell: U256) -> i64 {
+    if buy.is_zero() {
+        return 0;
+    }
+
+    let (diff, negative) = if sell >= buy {
+        (sell - buy, false)
+    } else {
+        (buy - sell, true)
+    };
+
+    let bps = diff.saturating_mul(U256::from(10_000u64)) / buy;
+    let bps = if bps > U256::from(i64::MAX as u64) {
+        i64::MAX
+    } else {
+        bps.as_u64() as i64
+    };
+
+    if negative { -bps } else { bps }
+}
@@ -0,0 +1,152 @@
+//! Cross-Feed Divergence Detection
+//!
+//! Pools quoting a major pair normally agree to within fees. When they
+//! suddenly disagree wildly, the likelier explanation is bad data or an
+//! oracle attack rather than a trade, and execution should pause.
+//!
+//! For each watched pair, dispersion is `(max - min) / median` of the
+//! pools' prices, in bps. Once it stays above `max_dispersion_bps` for
+//! `sustain_ms`, one `DivergenceAlert` is emitted; the alert's `reason()`
+//! is meant for `Cypher::trigger_circuit_breaker`. Dispersion back under
+//! the threshold re-arms the pair.
+
+use dashmap::DashMap;
+use ethers::types::{Address, U256};
+use matrix_types::ChainId;
+
+/// Which pairs to watch and how much disagreement to tolerate
+#[derive(Debug, Clone)]
+pub struct DivergenceConfig {
+    /// Key pairs as (chain, token_a, token_b); prices are token_a in token_b
+    pub pairs: Vec<(ChainId, Address, Address)>,
+    /// Dispersion above this counts as divergent
+    pub max_dispersion_bps: u64,
+    /// How long dispersion must stay above the threshold before alerting
+    pub sustain_ms: u64,
+    /// Pools quoting the pair needed for a dispersion reading
+    pub min_pools: usize,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            pairs: Vec::new(),
+            max_dispersion_bps: 500,    // 5%
+            sustain_ms: 30_000,
+            min_pools: 2,
+        }
+    }
+}
+
+impl DivergenceConfig {
+    /// The watched pair made of `token0`/`token1` on `chain`, in configured orientation
+    pub(crate) fn watched(&self, chain: ChainId, token0: Address, token1: Address) -> Option<(Address, Address)> {
+        self.pairs
+            .iter()
+            .find(|(c, a, b)| *c == chain && ((*a, *b) == (token0, token1) || (*b, *a) == (token0, token1)))
+            .map(|(_, a, b)| (*a, *b))
+    }
+}
+
+/// Sustained divergence on a key pair; trip the breaker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivergenceAlert {
+    pub chain: ChainId,
+    pub token_a: Address,
+    pub token_b: Address,
+    pub dispersion_bps: u64,
+    pub pools: usize,
+    /// Update time dispersion first exceeded the threshold
+    pub since_ms: u64,
+    pub timestamp_ms: u64,
+}
+
+impl DivergenceAlert {
+    /// Circuit breaker reason
+    pub fn reason(&self) -> String {
+        format!(
+            "Feed divergence on {:?} {:?}/{:?}: {} bps across {} pools for {} ms",
+            self.chain,
+            self.token_a,
+            self.token_b,
+            self.dispersion_bps,
+            self.pools,
+            self.timestamp_ms.saturating_sub(self.since_ms)
+        )
+    }
+}
+
+/// `(max - min) / median` of `prices` in bps; None for fewer than two
+pub(crate) fn dispersion_bps(prices: &mut [U256]) -> Option<u64> {
+    if prices.len() < 2 {
+        return None;
+    }
+    prices.sort();
+    let median = prices[prices.len() / 2];
+    if median.is_zero() {
+        return None;
+    }
+    let spread = prices[prices.len() - 1] - prices[0];
+    let bps = spread.saturating_mul(U256::from(10_000u64)) / median;
+    Some(if bps > U256::from(u64::MAX) { u64::MAX } else { bps.as_u64() })
+}
+
+/// Per pair: when divergence began, and whether it was alerted
+#[derive(Debug, Default)]
+pub(crate) struct DivergenceTracker {
+    divergent: DashMap<(ChainId, Address, Address), (u64, bool)>,
+}
+
+impl DivergenceTracker {
+    /// Feed a reading; returns the divergence start when an alert is due
+    pub(crate) fn observe(
+        &self,
+        config: &DivergenceConfig,
+        key: (ChainId, Address, Address),
+        dispersion_bps: u64,
+        now_ms: u64,
+    ) -> Option<u64> {
+        if dispersion_bps <= config.max_dispersion_bps {
+            self.divergent.remove(&key);
+            return None;
+        }
+
+        let mut entry = self.divergent.entry(key).or_insert((now_ms, false));
+        let (since_ms, alerted) = &mut *entry;
+        if *alerted || now_ms.saturating_sub(*since_ms) < config.sustain_ms {
+            return None;
+        }
+        *alerted = true;
+        Some(*since_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispersion_and_sustain() {
+        let price = |n: u64| U256::exp10(18) * n;
+        assert_eq!(dispersion_bps(&mut [price(2_000), price(2_010), price(1_990)]), Some(100));
+        assert_eq!(dispersion_bps(&mut [price(2_000)]), None);
+
+        let config = DivergenceConfig {
+            sustain_ms: 1_000,
+            ..Default::default()
+        };
+        let tracker = DivergenceTracker::default();
+        let key = (ChainId::Ethereum, Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+
+        assert_eq!(tracker.observe(&config, key, 900, 0), None);
+        assert_eq!(tracker.observe(&config, key, 900, 999), None);
+        assert_eq!(tracker.observe(&config, key, 900, 1_000), Some(0));
+        // Alerted once per episode
+        assert_eq!(tracker.observe(&config, key, 900, 5_000), None);
+
+        // Back in line re-arms it
+        assert_eq!(tracker.observe(&config, key, 100, 6_000), None);
+        assert_eq!(tracker.observe(&config, key, 900, 7_000), None);
+        assert_eq!(tracker.observe(&config, key, 900, 8_000), Some(7_000));
+    }
+}
@@ -3,12 +3,19 @@
 //! Bridges MORPHEUS price feeds into DOZER's processing pipeline.
 //! Handles async message routing and feed coordination.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use parking_lot::RwLock;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn, error, debug};
 
 use matrix_types::PriceUpdate;
-use morpheus::{DexWebSocketFeed, PriceFeed, FeedStatus, MorpheusError};
+use matrix_metrics::AgentMetrics;
+use futures::StreamExt;
+use morpheus::{DexWebSocketFeed, FeedError, FeedErrorKind, PriceFeed, PriceSource, FeedStatus, MorpheusError};
 use crate::{Dozer, DozerError, NormalizedPrice, SpreadInfo};
 use crossbeam::channel::Sender as CrossbeamSender;
 
@@ -21,6 +28,9 @@ pub struct ProcessorConfig {
     pub batch_size: usize,
     /// Processing interval in milliseconds
     pub interval_ms: u64,
+    /// Parallel DOZER workers; each pool is pinned to one worker so its
+    /// updates stay in order. 1 processes inline on the loop task.
+    pub workers: usize,
 }
 
 impl Default for ProcessorConfig {
@@ -29,6 +39,7 @@ impl Default for ProcessorConfig {
             buffer_size: 10000,
             batch_size: 100,
             interval_ms: 1, // 1ms for low latency
+            workers: 1,
         }
     }
 }
@@ -40,50 +51,106 @@ pub struct ProcessorStats {
     pub updates_processed: u64,
     pub updates_dropped: u64,
     pub processing_errors: u64,
+    pub feed_errors: u64,
+    pub errors_by_type: HashMap<String, u64>,
     pub last_update_ms: u64,
 }
 
 /// Feed processor bridging MORPHEUS feeds to DOZER pipeline
 pub struct FeedProcessor {
     config: ProcessorConfig,
-    stats: ProcessorStats,
+    stats: Arc<RwLock<ProcessorStats>>,
     feeds: Vec<Box<dyn PriceFeed>>,
+    sources: Vec<Box<dyn PriceSource>>,
     update_rx: Option<mpsc::Receiver<PriceUpdate>>,
     update_tx: mpsc::Sender<PriceUpdate>,
-    shutdown_tx: Option<mpsc::Sender<()>>,
+    error_rx: Option<mpsc::Receiver<FeedError>>,
+    error_tx: mpsc::Sender<FeedError>,
+    metrics: Option<Arc<AgentMetrics>>,
+    /// Pipeline to process with (None = default `Dozer`)
+    dozer: Option<Dozer>,
+    /// System-wide shutdown; cancelling it stops processing
+    shutdown: CancellationToken,
+    /// Child of `shutdown` for the running loop, cancelled by `stop`
+    session: Option<CancellationToken>,
 }
 
+/// Agent label used for processor metrics
+const METRICS_AGENT: &str = "dozer";
+
 impl FeedProcessor {
     /// Create new feed processor
     pub fn new(config: ProcessorConfig) -> Self {
         let (update_tx, update_rx) = mpsc::channel(config.buffer_size);
+        let (error_tx, error_rx) = mpsc::channel(config.buffer_size);
 
         info!("DOZER FeedProcessor: Initializing with buffer_size={}", config.buffer_size);
 
         Self {
             config,
-            stats: ProcessorStats::default(),
+            stats: Arc::new(RwLock::new(ProcessorStats::default())),
             feeds: Vec::new(),
+            sources: Vec::new(),
             update_rx: Some(update_rx),
             update_tx,
-            shutdown_tx: None,
+            error_rx: Some(error_rx),
+            error_tx,
+            metrics: None,
+            dozer: None,
+            shutdown: CancellationToken::new(),
+            session: None,
         }
     }
 
     /// Add a price feed to process
-    pub fn add_feed(&mut self, feed: Box<dyn PriceFeed>) {
+    pub fn add_feed(&mut self, mut feed: Box<dyn PriceFeed>) {
         info!("FeedProcessor: Adding feed '{}'", feed.id());
+        feed.set_error_sender(self.error_tx.clone());
         self.feeds.push(feed);
     }
 
+    /// Add a transport-agnostic price source, started with the processing loop
+    pub fn add_source(&mut self, source: Box<dyn PriceSource>) {
+        info!("FeedProcessor: Adding source '{}'", source.id());
+        self.sources.push(source);
+    }
+
     /// Get sender for external updates
     pub fn get_update_sender(&self) -> mpsc::Sender<PriceUpdate> {
         self.update_tx.clone()
     }
 
-    /// Get processor statistics
-    pub fn stats(&self) -> &ProcessorStats {
-        &self.stats
+    /// Get sender for external feed error reports
+    pub fn get_error_sender(&self) -> mpsc::Sender<FeedError> {
+        self.error_tx.clone()
+    }
+
+    /// Set metrics for error counts (`error_count` labeled by `error_type`)
+    pub fn set_metrics(&mut self, metrics: Arc<AgentMetrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Process with a pre-configured `Dozer` (spread config, token registry,
+    /// quorum, ...); its price and spread outputs are set by `start_processing`
+    pub fn set_dozer(&mut self, dozer: Dozer) {
+        self.dozer = Some(dozer);
+    }
+
+    /// Stop processing and source forwarding when `token` is cancelled
+    pub fn set_shutdown_token(&mut self, token: CancellationToken) {
+        self.shutdown = token;
+    }
+
+    /// Snapshot of processor statistics
+    pub fn stats(&self) -> ProcessorStats {
+        self.stats.read().clone()
+    }
+
+    /// Shared handle for reading stats while the processing loop runs
+    pub fn stats_handle(&self) -> StatsHandle {
+        StatsHandle {
+            stats: Arc::clone(&self.stats),
+        }
     }
 
     /// Connect all feeds
@@ -109,54 +176,143 @@ impl FeedProcessor {
         price_tx: CrossbeamSender<NormalizedPrice>,
         spread_tx: CrossbeamSender<SpreadInfo>,
     ) -> Result<(), DozerError> {
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        self.shutdown_tx = Some(shutdown_tx);
+        let cancel = self.shutdown.child_token();
+        self.session = Some(cancel.clone());
 
         // Take ownership of the receiver
         let mut update_rx = self.update_rx.take()
             .ok_or_else(|| DozerError::StateError("Processor already started".to_string()))?;
+        let mut error_rx = self.error_rx.take()
+            .ok_or_else(|| DozerError::StateError("Processor already started".to_string()))?;
 
-        // Create DOZER instance for processing
-        let mut dozer = Dozer::new();
+        self.start_sources(&cancel).await;
+
+        // Shared DOZER instance for processing
+        let mut dozer = self.dozer.take().unwrap_or_default();
         dozer.set_price_output(price_tx);
         dozer.set_spread_output(spread_tx);
+        let dozer = Arc::new(dozer);
+        let workers = self.spawn_workers(&dozer);
 
-        info!("FeedProcessor: Starting processing loop...");
+        info!("FeedProcessor: Starting processing loop ({} workers)...", workers.len().max(1));
 
         // Processing loop
         loop {
             tokio::select! {
                 // Check for shutdown
-                _ = shutdown_rx.recv() => {
+                _ = cancel.cancelled() => {
                     info!("FeedProcessor: Shutdown signal received");
                     break;
                 }
 
                 // Process incoming updates
                 Some(update) = update_rx.recv() => {
-                    self.stats.updates_received += 1;
-                    self.stats.last_update_ms = update.timestamp_ms;
-
-                    match dozer.process_update(update) {
-                        Ok(()) => {
-                            self.stats.updates_processed += 1;
-                        }
-                        Err(e) => {
-                            warn!("Processing error: {}", e);
-                            self.stats.processing_errors += 1;
+                    if workers.is_empty() {
+                        let timestamp_ms = update.timestamp_ms;
+                        let result = process(&dozer, update);
+                        record_update(&self.stats, self.metrics.as_deref(), timestamp_ms, result);
+                    } else {
+                        let worker = &workers[worker_index(&update, workers.len())];
+                        if worker.send(update).await.is_err() {
+                            error!("FeedProcessor: Worker stopped, dropping update");
+                            self.stats.write().updates_dropped += 1;
                         }
                     }
                 }
+
+                // Drain structured feed errors
+                Some(feed_error) = error_rx.recv() => {
+                    self.handle_feed_error(feed_error);
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Spawn `config.workers` tasks sharing `dozer`, or none for inline processing
+    ///
+    /// Workers drain and exit once the returned senders are dropped.
+    fn spawn_workers(&self, dozer: &Arc<Dozer>) -> Vec<mpsc::Sender<PriceUpdate>> {
+        if self.config.workers <= 1 {
+            return Vec::new();
+        }
+
+        let capacity = (self.config.buffer_size / self.config.workers).max(1);
+        (0..self.config.workers)
+            .map(|_| {
+                let (tx, mut rx) = mpsc::channel::<PriceUpdate>(capacity);
+                let dozer = Arc::clone(dozer);
+                let stats = Arc::clone(&self.stats);
+                let metrics = self.metrics.clone();
+                tokio::spawn(async move {
+                    while let Some(update) = rx.recv().await {
+                        let timestamp_ms = update.timestamp_ms;
+                        let result = process(&dozer, update);
+                        record_update(&stats, metrics.as_deref(), timestamp_ms, result);
+                    }
+                });
+                tx
+            })
+            .collect()
+    }
+
+    /// Start every source and forward its stream into the update channel
+    ///
+    /// A source that fails to start is reported as a connection error and
+    /// skipped; the others keep running.
+    async fn start_sources(&mut self, cancel: &CancellationToken) {
+        for source in &mut self.sources {
+            let id = source.id();
+            let mut stream = match source.start().await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    error!("Failed to start source {}: {}", id, e);
+                    let _ = self.error_tx.try_send(FeedError::new(&id, FeedErrorKind::Connection, e.to_string()));
+                    continue;
+                }
+            };
+
+            let tx = self.update_tx.clone();
+            let cancel = cancel.clone();
+            tokio::spawn(async move {
+                loop {
+                    let update = tokio::select! {
+                        _ = cancel.cancelled() => break,
+                        Some(update) = stream.next() => update,
+                        else => break,
+                    };
+                    if tx.send(update).await.is_err() {
+                        break;
+                    }
+                }
+                debug!("Source {} finished", id);
+            });
+        }
+    }
+
+    /// Record a feed error in logs, stats, and metrics
+    fn handle_feed_error(&mut self, feed_error: FeedError) {
+        warn!(
+            "Feed error from '{}' [{}] at {}: {}",
+            feed_error.feed_id,
+            feed_error.kind.as_str(),
+            feed_error.timestamp_ms,
+            feed_error.message
+        );
+        self.stats.write().feed_errors += 1;
+        self.count_error(feed_error.kind.as_str());
+    }
+
+    /// Count an error by type in stats and metrics
+    fn count_error(&self, error_type: &str) {
+        count_error(&self.stats, self.metrics.as_deref(), error_type);
+    }
+
     /// Stop processing
     pub async fn stop(&mut self) -> Result<(), DozerError> {
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(()).await;
+        if let Some(session) = self.session.take() {
+            session.cancel();
         }
 
         // Disconnect all feeds
@@ -166,6 +322,12 @@ impl FeedProcessor {
             }
         }
 
+        for source in &mut self.sources {
+            if let Err(e) = source.stop().await {
+                warn!("Error stopping source {}: {}", source.id(), e);
+            }
+        }
+
         info!("FeedProcessor: Stopped");
         Ok(())
     }
@@ -179,10 +341,74 @@ impl FeedProcessor {
     }
 }
 
+/// Process an update, attributed to its provider for the quorum check
+fn process(dozer: &Dozer, update: PriceUpdate) -> Result<(), DozerError> {
+    match update.provider.clone() {
+        Some(provider) => dozer.process_update_from(&provider, update),
+        None => dozer.process_update(update),
+    }
+}
+
+/// Worker a pool's updates are pinned to
+fn worker_index(update: &PriceUpdate, workers: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    (update.chain, update.pool).hash(&mut hasher);
+    (hasher.finish() % workers as u64) as usize
+}
+
+/// Record one processed update in stats (and metrics on error)
+fn record_update(
+    stats: &RwLock<ProcessorStats>,
+    metrics: Option<&AgentMetrics>,
+    timestamp_ms: u64,
+    result: Result<(), DozerError>,
+) {
+    // Single write per update so snapshots never see it half-counted
+    let mut guard = stats.write();
+    guard.updates_received += 1;
+    guard.last_update_ms = guard.last_update_ms.max(timestamp_ms);
+    match result {
+        Ok(()) => {
+            guard.updates_processed += 1;
+        }
+        Err(e) => {
+            warn!("Processing error: {}", e);
+            guard.processing_errors += 1;
+            drop(guard);
+            count_error(stats, metrics, "processing");
+        }
+    }
+}
+
+/// Count an error by type in stats and metrics
+fn count_error(stats: &RwLock<ProcessorStats>, metrics: Option<&AgentMetrics>, error_type: &str) {
+    *stats.write().errors_by_type.entry(error_type.to_string()).or_default() += 1;
+    if let Some(metrics) = metrics {
+        metrics
+            .error_count
+            .with_label_values(&[METRICS_AGENT, error_type])
+            .inc();
+    }
+}
+
+/// Cloneable read handle to a running processor's stats
+#[derive(Clone)]
+pub struct StatsHandle {
+    stats: Arc<RwLock<ProcessorStats>>,
+}
+
+impl StatsHandle {
+    /// Consistent snapshot of the current stats
+    pub fn snapshot(&self) -> ProcessorStats {
+        self.stats.read().clone()
+    }
+}
+
 /// Builder for creating feed processor with feeds
 pub struct FeedProcessorBuilder {
     config: ProcessorConfig,
     feeds: Vec<Box<dyn PriceFeed>>,
+    dozer: Option<Dozer>,
 }
 
 impl FeedProcessorBuilder {
@@ -190,6 +416,7 @@ impl FeedProcessorBuilder {
         Self {
             config: ProcessorConfig::default(),
             feeds: Vec::new(),
+            dozer: None,
         }
     }
 
@@ -203,11 +430,20 @@ impl FeedProcessorBuilder {
         self
     }
 
+    /// Process with a pre-configured `Dozer`
+    pub fn with_dozer(mut self, dozer: Dozer) -> Self {
+        self.dozer = Some(dozer);
+        self
+    }
+
     pub fn build(self) -> FeedProcessor {
         let mut processor = FeedProcessor::new(self.config);
         for feed in self.feeds {
             processor.add_feed(feed);
         }
+        if let Some(dozer) = self.dozer {
+            processor.set_dozer(dozer);
+        }
         processor
     }
 }
@@ -221,6 +457,10 @@ impl Default for FeedProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ethers::types::{Address, U256};
+    use matrix_types::{ChainId, DexId};
+    use morpheus::feeds::stub::{ErroringFeed, StaticFeed};
+    use morpheus::{FeedSource, PriceStream};
 
     #[test]
     fn test_processor_creation() {
@@ -239,4 +479,283 @@ mod tests {
 
         assert_eq!(processor.config.buffer_size, 5000);
     }
+
+    #[tokio::test]
+    async fn test_stats_readable_while_processing() {
+        let mut processor = FeedProcessor::new(ProcessorConfig::default());
+        let updates = processor.get_update_sender();
+        let handle = processor.stats_handle();
+
+        let (price_tx, _price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let task = tokio::spawn(async move {
+            let _ = tokio::time::timeout(
+                std::time::Duration::from_millis(500),
+                processor.start_processing(price_tx, spread_tx),
+            )
+            .await;
+            processor
+        });
+
+        for i in 1..=50u64 {
+            let update = PriceUpdate {
+                timestamp_ms: i,
+                chain: ChainId::Bsc,
+                dex: DexId::PancakeSwap,
+                pool: Address::from_low_u64_be(i % 5),
+                token0: Address::from_low_u64_be(100),
+                token1: Address::from_low_u64_be(200),
+                reserve0: U256::exp10(18),
+                reserve1: U256::exp10(18),
+                price: U256::exp10(18),
+                fee_bps: None,
+                provider: None,
+            };
+            updates.send(update).await.unwrap();
+
+            // Snapshots taken mid-run are internally consistent
+            let snapshot = handle.snapshot();
+            assert_eq!(
+                snapshot.updates_received,
+                snapshot.updates_processed + snapshot.processing_errors
+            );
+            assert!(snapshot.updates_received <= i);
+        }
+
+        // Wait until the loop has drained every update
+        while handle.snapshot().updates_received < 50 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        let mid_run = handle.snapshot();
+        assert_eq!(mid_run.updates_received, 50);
+        assert_eq!(mid_run.last_update_ms, 50);
+
+        let processor = task.await.unwrap();
+        assert_eq!(processor.stats().updates_received, 50);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_workers_keep_per_pool_order() {
+        let mut processor = FeedProcessor::new(ProcessorConfig {
+            workers: 4,
+            ..Default::default()
+        });
+        let updates = processor.get_update_sender();
+        let handle = processor.stats_handle();
+
+        let (price_tx, price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let task = tokio::spawn(async move {
+            let _ = tokio::time::timeout(
+                std::time::Duration::from_millis(500),
+                processor.start_processing(price_tx, spread_tx),
+            )
+            .await;
+        });
+
+        for timestamp_ms in 1..=50 {
+            for pool in 0..8 {
+                updates.send(update(pool, timestamp_ms)).await.unwrap();
+            }
+        }
+        while handle.snapshot().updates_received < 400 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        task.await.unwrap();
+
+        let stats = handle.snapshot();
+        assert_eq!(stats.updates_processed, 400);
+        assert_eq!(stats.last_update_ms, 50);
+
+        let mut last_seen = HashMap::new();
+        for price in price_rx.try_iter() {
+            let previous = last_seen.insert(price.pool, price.timestamp_ms).unwrap_or(0);
+            assert_eq!(price.timestamp_ms, previous + 1);
+        }
+        assert_eq!(last_seen.len(), 8);
+    }
+
+    fn update(pool: u64, timestamp_ms: u64) -> PriceUpdate {
+        PriceUpdate {
+            timestamp_ms,
+            chain: ChainId::Bsc,
+            dex: DexId::PancakeSwap,
+            pool: Address::from_low_u64_be(pool),
+            token0: Address::from_low_u64_be(100),
+            token1: Address::from_low_u64_be(200),
+            reserve0: U256::exp10(18),
+            reserve1: U256::exp10(18) * 2,
+            price: U256::exp10(18) * 2,
+            fee_bps: None,
+            provider: None,
+        }
+    }
+
+    #[test]
+    fn test_updates_attributed_to_their_provider() {
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = Dozer::new();
+        dozer.set_price_output(tx);
+        dozer.set_quorum_config(crate::QuorumConfig::default());
+        let quoted = |provider: &str, timestamp_ms| PriceUpdate {
+            provider: Some(provider.to_string()),
+            ..update(1, timestamp_ms)
+        };
+
+        // The second provider's quote corroborates the first
+        process(&dozer, quoted("alchemy", 1)).unwrap();
+        process(&dozer, quoted("infura", 2)).unwrap();
+        let confidence: Vec<u16> = rx.try_iter().map(|price| price.confidence.bps()).collect();
+        assert_eq!(confidence, vec![crate::QuorumConfig::default().low_confidence.bps(), 3000]);
+    }
+
+    #[tokio::test]
+    async fn test_processes_with_configured_dozer() {
+        let mut dozer = Dozer::new();
+        dozer.set_quorum_config(crate::QuorumConfig::default());
+        let mut processor = FeedProcessorBuilder::new().with_dozer(dozer).build();
+        processor
+            .get_update_sender()
+            .send(PriceUpdate {
+                provider: Some("alchemy".to_string()),
+                ..update(1, 1)
+            })
+            .await
+            .unwrap();
+
+        let (price_tx, price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let _ = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            processor.start_processing(price_tx, spread_tx),
+        )
+        .await;
+
+        // A lone provider misses the configured quorum
+        let price = price_rx.try_recv().unwrap();
+        assert_eq!(price.confidence, crate::QuorumConfig::default().low_confidence);
+    }
+
+    /// Replays JSON-lines `PriceUpdate`s from a file
+    struct ReplaySource {
+        path: std::path::PathBuf,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceSource for ReplaySource {
+        fn id(&self) -> String {
+            format!("replay:{}", self.path.display())
+        }
+
+        async fn start(&mut self) -> Result<PriceStream, MorpheusError> {
+            let contents = tokio::fs::read_to_string(&self.path)
+                .await
+                .map_err(|e| MorpheusError::ConnectionFailed(e.to_string()))?;
+            let updates = contents
+                .lines()
+                .map(serde_json::from_str::<PriceUpdate>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| MorpheusError::ParseError(e.to_string()))?;
+            Ok(futures::stream::iter(updates).boxed())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_token_stops_processing() {
+        let token = CancellationToken::new();
+        let mut processor = FeedProcessor::new(ProcessorConfig::default());
+        processor.set_shutdown_token(token.clone());
+        processor.add_source(Box::new(FeedSource::new(Box::new(StaticFeed::new(vec![update(1, 1)])))));
+
+        let (price_tx, _price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let task = tokio::spawn(async move { processor.start_processing(price_tx, spread_tx).await });
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        token.cancel();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(1), task).await;
+        assert!(matches!(result, Ok(Ok(Ok(())))));
+    }
+
+    #[tokio::test]
+    async fn test_replay_source_shares_pipeline_with_feed() {
+        let path = std::env::temp_dir().join(format!("dozer-replay-{}.jsonl", std::process::id()));
+        let lines: Vec<String> = (1..=4)
+            .map(|i| serde_json::to_string(&update(10 + i, i)).unwrap())
+            .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let mut processor = FeedProcessor::new(ProcessorConfig::default());
+        processor.add_source(Box::new(ReplaySource { path: path.clone() }));
+        processor.add_source(Box::new(FeedSource::new(Box::new(StaticFeed::new(
+            (1..=3).map(|i| update(20 + i, i)).collect(),
+        )))));
+        processor.add_source(Box::new(ReplaySource { path: path.with_extension("missing") }));
+
+        let (price_tx, price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let _ = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            processor.start_processing(price_tx, spread_tx),
+        )
+        .await;
+        processor.stop().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stats = processor.stats();
+        assert_eq!(stats.updates_received, 7);
+        assert_eq!(stats.updates_processed, 7);
+        // The missing replay file is reported, not fatal
+        assert_eq!(stats.errors_by_type["connection"], 1);
+
+        let mut pools: Vec<Address> = price_rx.try_iter().map(|p| p.pool).collect();
+        pools.sort();
+        pools.dedup();
+        assert_eq!(pools.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_feed_errors_surface_with_labels() {
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(AgentMetrics::new(&registry).unwrap());
+
+        let mut processor = FeedProcessor::new(ProcessorConfig::default());
+        processor.set_metrics(metrics.clone());
+        processor.add_feed(Box::new(ErroringFeed::new(
+            "Bsc-PancakeSwap",
+            vec![
+                (FeedErrorKind::Parse, "bad json"),
+                (FeedErrorKind::Parse, "bad log"),
+                (FeedErrorKind::Connection, "socket closed"),
+            ],
+        )));
+        processor.connect_feeds().await.unwrap();
+
+        // External producers can report too
+        processor
+            .get_error_sender()
+            .send(FeedError::from_error("Base-Aerodrome", &MorpheusError::SubscriptionFailed("rejected".into())))
+            .await
+            .unwrap();
+
+        // Processing loop runs until shutdown; bound it for the test
+        let (price_tx, _price_rx) = crossbeam::channel::unbounded();
+        let (spread_tx, _spread_rx) = crossbeam::channel::unbounded();
+        let _ = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            processor.start_processing(price_tx, spread_tx),
+        )
+        .await;
+
+        let stats = processor.stats();
+        assert_eq!(stats.feed_errors, 4);
+        assert_eq!(stats.errors_by_type["parse"], 2);
+        assert_eq!(stats.errors_by_type["connection"], 1);
+        assert_eq!(stats.errors_by_type["subscription"], 1);
+
+        let count = |error_type: &str| metrics.error_count.with_label_values(&["dozer", error_type]).get();
+        assert_eq!(count("parse"), 2);
+        assert_eq!(count("connection"), 1);
+        assert_eq!(count("subscription"), 1);
+    }
 }
@@ -0,0 +1,129 @@
+//! Rolling Price History
+//!
+//! Keeps the last `len` accepted prices of each pool so short-term signals
+//! (volatility-based position sizing, momentum) can look further back than
+//! the latest state. Volatility is the sample standard deviation of simple
+//! returns between consecutive prices in the window.
+
+use dashmap::DashMap;
+use ethers::types::{Address, U256};
+use matrix_types::ChainId;
+use std::collections::VecDeque;
+
+/// Prices kept per pool by default
+pub const DEFAULT_PRICE_HISTORY_LEN: usize = 64;
+
+/// Last `len` (timestamp_ms, price) samples per pool, oldest first
+#[derive(Debug)]
+pub(crate) struct PriceHistory {
+    len: usize,
+    prices: DashMap<(ChainId, Address), VecDeque<(u64, U256)>>,
+}
+
+impl PriceHistory {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            len,
+            prices: DashMap::new(),
+        }
+    }
+
+    /// Append a sample, dropping the oldest beyond `len` (0 = keep nothing)
+    pub(crate) fn record(&self, chain: ChainId, pool: Address, timestamp_ms: u64, price: U256) {
+        if self.len == 0 {
+            return;
+        }
+        let mut samples = self.prices.entry((chain, pool)).or_default();
+        samples.push_back((timestamp_ms, price));
+        while samples.len() > self.len {
+            samples.pop_front();
+        }
+    }
+
+    /// Up to `n` most recent samples, oldest first
+    pub(crate) fn recent(&self, chain: ChainId, pool: Address, n: usize) -> Vec<(u64, U256)> {
+        let Some(samples) = self.prices.get(&(chain, pool)) else {
+            return Vec::new();
+        };
+        samples.iter().skip(samples.len().saturating_sub(n)).copied().collect()
+    }
+
+    /// Standard deviation of returns over the window; None with under 3 samples
+    pub(crate) fn volatility(&self, chain: ChainId, pool: Address) -> Option<f64> {
+        let samples = self.prices.get(&(chain, pool))?;
+        let prices: Vec<U256> = samples.iter().map(|(_, price)| *price).collect();
+        returns_stddev(&prices)
+    }
+}
+
+/// Sample standard deviation of `p[i] / p[i - 1] - 1`
+///
+/// Zero prices have no return and are skipped; needs two returns.
+fn returns_stddev(prices: &[U256]) -> Option<f64> {
+    let returns: Vec<f64> = prices
+        .windows(2)
+        .filter(|pair| !pair[0].is_zero())
+        .map(|pair| to_f64(pair[1]) / to_f64(pair[0]) - 1.0)
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some(variance.sqrt())
+}
+
+fn to_f64(value: U256) -> f64 {
+    value.0.iter().rev().fold(0.0, |acc, limb| acc * 18_446_744_073_709_551_616.0 + *limb as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(value: u64) -> U256 {
+        U256::exp10(18) * value / 10
+    }
+
+    #[test]
+    fn test_buffer_capped_oldest_first() {
+        let history = PriceHistory::new(3);
+        let pool = Address::from_low_u64_be(1);
+        for i in 0..5u64 {
+            history.record(ChainId::Ethereum, pool, i * 1_000, price(100 + i));
+        }
+
+        let recent = history.recent(ChainId::Ethereum, pool, 10);
+        assert_eq!(recent, vec![(2_000, price(102)), (3_000, price(103)), (4_000, price(104))]);
+        assert_eq!(history.recent(ChainId::Ethereum, pool, 1), vec![(4_000, price(104))]);
+        assert!(history.recent(ChainId::Bsc, pool, 10).is_empty());
+
+        let disabled = PriceHistory::new(0);
+        disabled.record(ChainId::Ethereum, pool, 0, price(1));
+        assert!(disabled.recent(ChainId::Ethereum, pool, 10).is_empty());
+    }
+
+    #[test]
+    fn test_volatility_of_known_series() {
+        let history = PriceHistory::new(DEFAULT_PRICE_HISTORY_LEN);
+        let pool = Address::from_low_u64_be(1);
+        // Returns +10%, -10%, +10%: mean 1/30, sample stddev sqrt(0.04 / 3)
+        for (i, p) in [1_000, 1_100, 990, 1_089].into_iter().enumerate() {
+            history.record(ChainId::Ethereum, pool, i as u64, price(p));
+            let expected = i >= 2;
+            assert_eq!(history.volatility(ChainId::Ethereum, pool).is_some(), expected);
+        }
+
+        let volatility = history.volatility(ChainId::Ethereum, pool).unwrap();
+        assert!((volatility - (0.04f64 / 3.0).sqrt()).abs() < 1e-12, "{}", volatility);
+
+        // A flat series has no volatility
+        let flat = PriceHistory::new(8);
+        for i in 0..4 {
+            flat.record(ChainId::Ethereum, pool, i, price(1_000));
+        }
+        assert_eq!(flat.volatility(ChainId::Ethereum, pool), Some(0.0));
+    }
+}
@@ -9,17 +9,55 @@
 //! - Maintain real-time order book state
 //! - Calculate cross-DEX spreads
 //! - Feed data to analysis layer
+//!
+//! # Concurrency and ordering
+//! `process_update` takes `&self`: pool state lives in a sharded concurrent
+//! map (`DashMap`, sharded by pool key), so updates for unrelated pools can
+//! be processed from several threads at once. Ordering guarantees:
+//! - A pool's state never moves backwards: an update older (by
+//!   `timestamp_ms`) than the stored state is ignored.
+//! - Outputs for one pool are emitted in call order when that pool's
+//!   updates come from a single thread. `FeedProcessor` routes each pool
+//!   to a fixed worker to uphold this.
+//! - Spread detection reads other pools as of the moment it runs; there
+//!   is no ordering between different pools. Pools whose last update lags
+//!   the triggering update by more than their chain's staleness threshold
+//!   are left out.
 
 // Feed processor integration
 pub mod feed_processor;
 
-pub use feed_processor::{FeedProcessor, FeedProcessorBuilder, ProcessorConfig, ProcessorStats};
+// Experimental cross-chain spread detection
+pub mod cross_chain;
+
+// Multi-provider price corroboration
+pub mod quorum;
+
+// Rolling per-pool price window for short-term analytics
+pub mod history;
+
+// Cross-feed dispersion on key pairs, for the circuit breaker
+pub mod divergence;
+
+pub use feed_processor::{FeedProcessor, FeedProcessorBuilder, ProcessorConfig, ProcessorStats, StatsHandle};
+pub use cross_chain::{BridgeEstimate, CrossChainConfig, CrossChainSpread};
+pub use quorum::QuorumConfig;
+pub use history::DEFAULT_PRICE_HISTORY_LEN;
+pub use divergence::{DivergenceAlert, DivergenceConfig};
 
 use crossbeam::channel::{Receiver, Sender};
-use ethers::types::{Address, U256};
-use matrix_types::{ChainId, DexId, PriceUpdate};
-use thiserror::Error;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use matrix_config::{ChainConfig, DexConfig};
+use ethers::types::{Address, I256, U256};
+use matrix_types::{AgentHealth, AgentStatus, ChainId, Confidence, DexId, HealthReporter, PriceUpdate};
+use morpheus::TokenRegistry;
+use divergence::DivergenceTracker;
+use history::PriceHistory;
+use quorum::QuorumTracker;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
 
 /// Dozer errors
 #[derive(Error, Debug)]
@@ -45,7 +83,7 @@ pub struct NormalizedPrice {
     pub price: U256,           // Normalized to 18 decimals
     pub liquidity: U256,       // Available liquidity
     pub timestamp_ms: u64,
-    pub confidence: f64,       // Price confidence score (0.0 - 1.0)
+    pub confidence: Confidence, // Price confidence (0 - 10000 bps)
 }
 
 /// Cross-DEX spread opportunity
@@ -61,7 +99,61 @@ pub struct SpreadInfo {
     pub sell_pool: Address,
     pub sell_price: U256,
     pub spread_bps: i64,       // Spread in basis points
-    pub max_size: U256,        // Maximum executable size
+    pub fee_bps: u64,          // LP fees of both pools, where known
+    pub net_spread_bps: i64,   // Spread after `fee_bps`
+    pub max_size: U256,        // Maximum executable size, in token0
+}
+
+/// Same-chain spread detection settings
+#[derive(Debug, Clone)]
+pub struct SpreadConfig {
+    /// Minimum spread, net of known pool fees, required to emit
+    pub min_spread_bps: i64,
+    /// Maximum price move allowed in either pool when sizing a trade
+    pub max_price_impact_bps: u64,
+    /// Pairs where either pool's price confidence is below this are skipped
+    pub min_confidence: Confidence,
+}
+
+impl Default for SpreadConfig {
+    fn default() -> Self {
+        Self {
+            min_spread_bps: 10,           // 0.1%
+            max_price_impact_bps: 100,    // 1%
+            min_confidence: Confidence::ZERO,
+        }
+    }
+}
+
+/// Plausible range for a pool's price around the pair's reference price
+///
+/// A misparsed event or a manipulated pool can report a price orders of
+/// magnitude off market. Updates pricing token0 above `max_ratio` times, or
+/// below `1 / max_ratio` times, the median of the pair's other pools are
+/// rejected before they reach pool state.
+#[derive(Debug, Clone)]
+pub struct PriceBounds {
+    /// Largest allowed ratio either way to the reference price
+    pub max_ratio: u64,
+    /// Other pools quoting the pair needed before the check applies
+    pub min_reference_pools: usize,
+}
+
+impl Default for PriceBounds {
+    fn default() -> Self {
+        Self {
+            max_ratio: 10,
+            min_reference_pools: 1,
+        }
+    }
+}
+
+impl PriceBounds {
+    /// Whether `price` lies within the bounds around `reference`
+    pub fn contains(&self, price: U256, reference: U256) -> bool {
+        let ratio = U256::from(self.max_ratio.max(1));
+        price <= reference.saturating_mul(ratio) && reference <= price.saturating_mul(ratio)
+    }
 }
 
 /// Pool state for aggregation
@@ -75,25 +167,157 @@ pub struct PoolState {
     pub reserve0: U256,
     pub reserve1: U256,
     pub last_update_ms: u64,
+    /// LP fee of the pool's fee tier, if known
+    pub fee_bps: Option<u64>,
+}
+
+impl PoolState {
+    /// Whether the pool holds exactly `token_a` and `token_b`, in either order
+    pub fn has_pair(&self, token_a: Address, token_b: Address) -> bool {
+        (self.token0 == token_a && self.token1 == token_b)
+            || (self.token0 == token_b && self.token1 == token_a)
+    }
+
+    /// Reserves as `(token, other token)`, whatever the on-chain order
+    pub fn reserves_for(&self, token: Address) -> Option<(U256, U256)> {
+        matrix_types::oriented_reserves(self.token0, self.token1, self.reserve0, self.reserve1, token)
+    }
+
+    /// Price of `token` in terms of the pool's other token (18 decimals)
+    pub fn price_of(&self, token: Address) -> Option<U256> {
+        let (reserve_base, reserve_quote) = self.reserves_for(token)?;
+        cross_chain::reserve_price(reserve_base, reserve_quote)
+    }
+}
+
+/// Change in a pool between two consecutive accepted updates
+///
+/// Large reserve deltas signal a large swap (or liquidity event) in the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolDiff {
+    pub chain: ChainId,
+    pub dex: DexId,
+    pub pool: Address,
+    pub reserve0_delta: I256,
+    pub reserve1_delta: I256,
+    /// Move in token0's price (in token1), relative to the previous price
+    pub price_delta_bps: i64,
+    pub timestamp_ms: u64,
+}
+
+impl PoolDiff {
+    /// Diff from `previous` to `current` state of the same pool
+    pub fn between(previous: &PoolState, current: &PoolState) -> Self {
+        let delta = |old: U256, new: U256| I256::from_raw(new) - I256::from_raw(old);
+
+        let price_delta_bps = match (previous.price_of(previous.token0), current.price_of(current.token0)) {
+            (Some(old), Some(new)) if !old.is_zero() => {
+                let bps = |change: U256| {
+                    let bps = change.saturating_mul(U256::from(10_000u64)) / old;
+                    bps.min(U256::from(i64::MAX as u64)).as_u64() as i64
+                };
+                if new >= old {
+                    bps(new - old)
+                } else {
+                    -bps(old - new)
+                }
+            }
+            _ => 0,
+        };
+
+        Self {
+            chain: current.chain,
+            dex: current.dex,
+            pool: current.pool,
+            reserve0_delta: delta(previous.reserve0, current.reserve0),
+            reserve1_delta: delta(previous.reserve1, current.reserve1),
+            price_delta_bps,
+            timestamp_ms: current.last_update_ms,
+        }
+    }
+
+    /// True if neither reserve moved
+    pub fn is_empty(&self) -> bool {
+        self.reserve0_delta.is_zero() && self.reserve1_delta.is_zero()
+    }
 }
 
 /// Dozer data pipeline
 pub struct Dozer {
-    /// Pool states by (chain, pool address)
-    pool_states: HashMap<(ChainId, Address), PoolState>,
+    /// Pool states by (chain, pool address), sharded for concurrent updates
+    pool_states: DashMap<(ChainId, Address), PoolState>,
     /// Output channel for normalized prices
     output_tx: Option<Sender<NormalizedPrice>>,
     /// Output channel for spread opportunities
     spread_tx: Option<Sender<SpreadInfo>>,
+    /// Output channel for per-pool changes
+    diff_tx: Option<Sender<PoolDiff>>,
+    /// Same-chain spread settings
+    spread_config: SpreadConfig,
+    /// Cross-chain detection settings (experimental)
+    cross_chain: CrossChainConfig,
+    /// Output channel for cross-chain spreads (experimental)
+    cross_chain_tx: Option<Sender<CrossChainSpread>>,
+    /// Token decimals for price normalization (None = assume 18)
+    tokens: Option<TokenRegistry>,
+    /// Provider agreement required for full confidence (None = off)
+    quorum: Option<QuorumConfig>,
+    /// Latest quote per provider, for the quorum check
+    quotes: QuorumTracker,
+    /// Plausible price range around the reference (None = off)
+    price_bounds: Option<PriceBounds>,
+    /// Updates rejected by `price_bounds`
+    rejected_prices: AtomicU64,
+    /// Heartbeat per update, errors per failed update
+    health: HealthReporter,
+    /// Recent normalized prices per pool
+    history: PriceHistory,
+    /// Key pairs watched for cross-feed divergence (None = off)
+    divergence: Option<DivergenceConfig>,
+    divergence_tracker: DivergenceTracker,
+    /// Output channel for sustained divergence alerts
+    divergence_tx: Option<Sender<DivergenceAlert>>,
+    /// Per-chain age after which a pool price is too stale to compare
+    price_staleness_ms: HashMap<ChainId, u64>,
+    /// Per-DEX fee for pools that don't carry a fee tier
+    dex_fee_bps: HashMap<DexId, u64>,
 }
 
 impl Dozer {
     pub fn new() -> Self {
+        Self::with_pool_states(DashMap::new())
+    }
+
+    /// Create with a fixed number of pool state shards
+    ///
+    /// More shards reduce lock contention between concurrent updaters.
+    /// Rounded up to a power of two (minimum 2).
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_pool_states(DashMap::with_shard_amount(shards.max(2).next_power_of_two()))
+    }
+
+    fn with_pool_states(pool_states: DashMap<(ChainId, Address), PoolState>) -> Self {
         tracing::info!("DOZER: Pipeline operator online...");
         Self {
-            pool_states: HashMap::new(),
+            pool_states,
             output_tx: None,
             spread_tx: None,
+            diff_tx: None,
+            spread_config: SpreadConfig::default(),
+            cross_chain: CrossChainConfig::default(),
+            cross_chain_tx: None,
+            tokens: None,
+            quorum: None,
+            quotes: QuorumTracker::default(),
+            price_bounds: None,
+            rejected_prices: AtomicU64::new(0),
+            health: HealthReporter::new("dozer"),
+            history: PriceHistory::new(DEFAULT_PRICE_HISTORY_LEN),
+            divergence: None,
+            divergence_tracker: DivergenceTracker::default(),
+            divergence_tx: None,
+            price_staleness_ms: HashMap::new(),
+            dex_fee_bps: HashMap::new(),
         }
     }
 
@@ -107,8 +331,157 @@ impl Dozer {
         self.spread_tx = Some(tx);
     }
 
+    /// Set output channel for pool diffs
+    ///
+    /// A diff is emitted for every accepted update after a pool's first.
+    pub fn set_diff_output(&mut self, tx: Sender<PoolDiff>) {
+        self.diff_tx = Some(tx);
+    }
+
+    /// Set same-chain spread detection config
+    pub fn set_spread_config(&mut self, config: SpreadConfig) {
+        self.spread_config = config;
+    }
+
+    /// Set the token registry used to adjust normalized and spread prices for token decimals
+    pub fn set_token_registry(&mut self, tokens: TokenRegistry) {
+        self.tokens = Some(tokens);
+    }
+
+    /// Set cross-chain detection config (experimental)
+    pub fn set_cross_chain_config(&mut self, config: CrossChainConfig) {
+        if config.enabled {
+            tracing::warn!("DOZER: Experimental cross-chain detection enabled");
+        }
+        self.cross_chain = config;
+    }
+
+    /// Set output channel for cross-chain spreads (experimental)
+    pub fn set_cross_chain_output(&mut self, tx: Sender<CrossChainSpread>) {
+        self.cross_chain_tx = Some(tx);
+    }
+
+    /// Require providers to corroborate prices before full confidence
+    pub fn set_quorum_config(&mut self, config: QuorumConfig) {
+        self.quorum = Some(config);
+    }
+
+    /// Reject updates priced implausibly far from the pair's reference price
+    pub fn set_price_bounds(&mut self, bounds: PriceBounds) {
+        self.price_bounds = Some(bounds);
+    }
+
+    /// Watch key pairs for sustained cross-feed divergence
+    pub fn set_divergence_config(&mut self, config: DivergenceConfig) {
+        self.divergence = Some(config);
+    }
+
+    /// Use each chain's `price_staleness_ms()` when comparing pools
+    ///
+    /// Chains without a (known) config never treat a price as stale.
+    pub fn set_price_staleness<'a>(&mut self, chains: impl IntoIterator<Item = &'a ChainConfig>) {
+        for config in chains {
+            if let Some(chain) = config.chain() {
+                self.price_staleness_ms.insert(chain, config.price_staleness_ms());
+            }
+        }
+    }
+
+    /// Whether `state` lags an update at `now_ms` by more than its chain allows
+    fn is_stale(&self, state: &PoolState, now_ms: u64) -> bool {
+        self.price_staleness_ms
+            .get(&state.chain)
+            .is_some_and(|&staleness| now_ms.saturating_sub(state.last_update_ms) > staleness)
+    }
+
+    /// Charge `dex`'s pools without a fee tier its configured default fee
+    ///
+    /// DEXs without a config are charged nothing for such pools.
+    pub fn set_dex_fee(&mut self, dex: DexId, config: &DexConfig) {
+        self.dex_fee_bps.insert(dex, config.fee_bps);
+    }
+
+    /// LP fee of a pool on `dex`, falling back to the DEX default
+    fn pool_fee_bps(&self, dex: DexId, fee_bps: Option<u64>) -> u64 {
+        fee_bps
+            .or_else(|| self.dex_fee_bps.get(&dex).copied())
+            .unwrap_or(0)
+    }
+
+    /// Set output channel for divergence alerts, meant for Cypher's breaker
+    pub fn set_divergence_output(&mut self, tx: Sender<DivergenceAlert>) {
+        self.divergence_tx = Some(tx);
+    }
+
+    /// Dispersion of `token_a`'s price across the pair's pools, in bps,
+    /// with the number of pools quoting it
+    pub fn pair_dispersion_bps(&self, chain: ChainId, token_a: Address, token_b: Address) -> Option<(u64, usize)> {
+        let mut prices: Vec<U256> = self
+            .pool_states
+            .iter()
+            .filter(|state| state.chain == chain && state.has_pair(token_a, token_b))
+            .filter_map(|state| state.price_of(token_a))
+            .collect();
+        let pools = prices.len();
+        divergence::dispersion_bps(&mut prices).map(|bps| (bps, pools))
+    }
+
+    /// Prices kept per pool for `recent_prices`/`volatility` (0 = off)
+    ///
+    /// Replaces the current history.
+    pub fn set_price_history_len(&mut self, len: usize) {
+        self.history = PriceHistory::new(len);
+    }
+
+    /// Up to `n` most recent (timestamp_ms, normalized price) of a pool, oldest first
+    pub fn recent_prices(&self, chain: ChainId, pool: Address, n: usize) -> Vec<(u64, U256)> {
+        self.history.recent(chain, pool, n)
+    }
+
+    /// Standard deviation of a pool's price returns over the history window
+    ///
+    /// None until the pool has three prices.
+    pub fn volatility(&self, chain: ChainId, pool: Address) -> Option<f64> {
+        self.history.volatility(chain, pool)
+    }
+
+    /// Updates rejected as out of bounds so far
+    pub fn rejected_prices(&self) -> u64 {
+        self.rejected_prices.load(Ordering::Relaxed)
+    }
+
+    /// Process an update attributed to `provider`, for the quorum check
+    ///
+    /// The provider's quote is recorded even if the update is stale for
+    /// pool state, so late corroboration still counts.
+    pub fn process_update_from(&self, provider: &str, update: PriceUpdate) -> Result<(), DozerError> {
+        if self.quorum.is_some() {
+            self.quotes
+                .record(provider, update.chain, update.pool, update.price, update.timestamp_ms);
+        }
+        self.process_update(update)
+    }
+
     /// Process incoming price update
-    pub fn process_update(&mut self, update: PriceUpdate) -> Result<(), DozerError> {
+    ///
+    /// Safe to call concurrently; see the module docs for ordering.
+    pub fn process_update(&self, update: PriceUpdate) -> Result<(), DozerError> {
+        self.health.heartbeat();
+        let result = self.apply_update(update);
+        if result.is_err() {
+            self.health.record_error();
+        }
+        result
+    }
+
+    /// Current health: last update processed, failed updates, pool gauges
+    pub fn health(&self) -> AgentHealth {
+        self.health.set_metric("pools", self.pool_states.len() as f64);
+        self.health.set_metric("rejected_prices", self.rejected_prices() as f64);
+        self.health.report(AgentStatus::Running)
+    }
+
+    fn apply_update(&self, update: PriceUpdate) -> Result<(), DozerError> {
         // Update pool state
         let key = (update.chain, update.pool);
         let state = PoolState {
@@ -120,29 +493,164 @@ impl Dozer {
             reserve0: update.reserve0,
             reserve1: update.reserve1,
             last_update_ms: update.timestamp_ms,
+            fee_bps: update.fee_bps,
+        };
+        if !self.price_in_bounds(&state) {
+            return Ok(());
+        }
+        // The entry holds the shard lock: drop it before scanning other pools
+        let diff = match self.pool_states.entry(key) {
+            Entry::Occupied(entry) if entry.get().last_update_ms > update.timestamp_ms => {
+                tracing::debug!(
+                    "DOZER: Ignoring stale update for {:?} ({} < {})",
+                    update.pool,
+                    update.timestamp_ms,
+                    entry.get().last_update_ms
+                );
+                return Ok(());
+            }
+            Entry::Occupied(mut entry) => {
+                let diff = self.diff_tx.as_ref().map(|_| PoolDiff::between(entry.get(), &state));
+                entry.insert(state);
+                diff
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(state);
+                None
+            }
         };
-        self.pool_states.insert(key, state);
+
+        if let (Some(tx), Some(diff)) = (&self.diff_tx, diff) {
+            tx.send(diff)
+                .map_err(|e| DozerError::QueueError(e.to_string()))?;
+        }
 
         // Normalize and emit price
-        let normalized = self.normalize_price(&update)?;
+        let confidence = self.update_confidence(&update);
+        let normalized = self.normalize_price(&update, confidence)?;
+        self.history
+            .record(update.chain, update.pool, update.timestamp_ms, normalized.price);
         if let Some(tx) = &self.output_tx {
             tx.send(normalized)
                 .map_err(|e| DozerError::QueueError(e.to_string()))?;
         }
 
+        // An untrusted price (thin pool, or no provider quorum) triggers no trades
+        let trusted = confidence >= self.spread_config.min_confidence;
+
         // Check for spread opportunities
-        self.check_spreads(&update)?;
+        if trusted {
+            self.check_spreads(&update)?;
+        } else {
+            tracing::debug!(
+                "DOZER: Skipping spreads for {:?}: confidence {} below {}",
+                update.pool,
+                confidence.bps(),
+                self.spread_config.min_confidence.bps()
+            );
+        }
+
+        self.check_divergence(&update)?;
+
+        // Check for cross-chain spreads (experimental)
+        if let Some(tx) = self.cross_chain_tx.as_ref().filter(|_| trusted) {
+            for spread in self.find_cross_chain_spreads(&update) {
+                tx.send(spread)
+                    .map_err(|e| DozerError::QueueError(e.to_string()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emit an alert once a watched pair stays divergent for the sustain window
+    fn check_divergence(&self, update: &PriceUpdate) -> Result<(), DozerError> {
+        let Some(config) = &self.divergence else {
+            return Ok(());
+        };
+        let Some((token_a, token_b)) = config.watched(update.chain, update.token0, update.token1) else {
+            return Ok(());
+        };
+        let Some((dispersion_bps, pools)) = self.pair_dispersion_bps(update.chain, token_a, token_b) else {
+            return Ok(());
+        };
+        if pools < config.min_pools {
+            return Ok(());
+        }
+
+        let key = (update.chain, token_a, token_b);
+        let Some(since_ms) = self
+            .divergence_tracker
+            .observe(config, key, dispersion_bps, update.timestamp_ms)
+        else {
+            return Ok(());
+        };
 
+        let alert = DivergenceAlert {
+            chain: update.chain,
+            token_a,
+            token_b,
+            dispersion_bps,
+            pools,
+            since_ms,
+            timestamp_ms: update.timestamp_ms,
+        };
+        tracing::error!("DOZER: {}", alert.reason());
+        if let Some(tx) = &self.divergence_tx {
+            tx.send(alert).map_err(|e| DozerError::QueueError(e.to_string()))?;
+        }
         Ok(())
     }
 
+    /// Check `state` against the price bounds, counting rejections
+    fn price_in_bounds(&self, state: &PoolState) -> bool {
+        let Some(bounds) = &self.price_bounds else {
+            return true;
+        };
+        let Some(price) = state.price_of(state.token0) else {
+            return true;
+        };
+        let Some((reference, pools)) = self.reference_quote(state.chain, state.token0, state.token1, Some(state.pool))
+        else {
+            return true;
+        };
+        if pools < bounds.min_reference_pools || bounds.contains(price, reference) {
+            return true;
+        }
+
+        self.rejected_prices.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            "DOZER: Rejecting implausible price for {:?} on {:?}: {} vs reference {}",
+            state.pool,
+            state.chain,
+            price,
+            reference
+        );
+        false
+    }
+
+    /// Confidence in an update's price: liquidity depth, capped without provider quorum
+    fn update_confidence(&self, update: &PriceUpdate) -> Confidence {
+        let confidence = self.reserve_confidence(update.reserve0, update.reserve1);
+        match &self.quorum {
+            Some(quorum)
+                if self
+                    .quotes
+                    .agreeing(quorum, update.chain, update.pool, update.price, update.timestamp_ms)
+                    < quorum.required =>
+            {
+                confidence.min(quorum.low_confidence)
+            }
+            _ => confidence,
+        }
+    }
+
     /// Normalize price to standard format
-    fn normalize_price(&self, update: &PriceUpdate) -> Result<NormalizedPrice, DozerError> {
+    fn normalize_price(&self, update: &PriceUpdate, confidence: Confidence) -> Result<NormalizedPrice, DozerError> {
         // Calculate liquidity (geometric mean of reserves)
         let liquidity = (update.reserve0 * update.reserve1).integer_sqrt();
 
-        // Confidence based on liquidity depth
-        let confidence = self.calculate_confidence(liquidity);
+        let price = self.whole_token_price(update.token0, update.token1, update.price);
 
         Ok(NormalizedPrice {
             chain: update.chain,
@@ -150,66 +658,339 @@ impl Dozer {
             pool: update.pool,
             token0: update.token0,
             token1: update.token1,
-            price: update.price,
+            price,
             liquidity,
             timestamp_ms: update.timestamp_ms,
             confidence,
         })
     }
 
+    /// Rescale a reserve price of `base` in `quote` to whole-token units
+    ///
+    /// Reserve prices treat both reserves as 18-decimal amounts; the token
+    /// registry supplies the real decimals. Without a registry (or for
+    /// unknown tokens) 18 is assumed and the price is returned unchanged.
+    fn whole_token_price(&self, base: Address, quote: Address, price: U256) -> U256 {
+        match &self.tokens {
+            Some(tokens) => {
+                let decimals_base = tokens.decimals_or_default(&base);
+                let decimals_quote = tokens.decimals_or_default(&quote);
+                price.saturating_mul(U256::exp10(decimals_base as usize)) / U256::exp10(decimals_quote as usize)
+            }
+            None => price,
+        }
+    }
+
+    /// Price confidence for a pool's reserves (same model as `NormalizedPrice`)
+    fn reserve_confidence(&self, reserve0: U256, reserve1: U256) -> Confidence {
+        self.calculate_confidence(reserve0.saturating_mul(reserve1).integer_sqrt())
+    }
+
     /// Calculate price confidence based on liquidity
-    fn calculate_confidence(&self, liquidity: U256) -> f64 {
+    fn calculate_confidence(&self, liquidity: U256) -> Confidence {
         // Higher liquidity = higher confidence
-        // $1M+ = 1.0, $100k = 0.9, $10k = 0.7, <$1k = 0.3
-        let liquidity_usd = liquidity.as_u128() as f64 / 1e18;
-        if liquidity_usd >= 1_000_000.0 {
-            1.0
-        } else if liquidity_usd >= 100_000.0 {
-            0.9
-        } else if liquidity_usd >= 10_000.0 {
-            0.7
+        // $1M+ = 100%, $100k = 90%, $10k = 70%, <$1k = 30%
+        let unit = U256::exp10(18);
+        if liquidity >= U256::from(1_000_000u64) * unit {
+            Confidence::FULL
+        } else if liquidity >= U256::from(100_000u64) * unit {
+            Confidence::from_bps(9000)
+        } else if liquidity >= U256::from(10_000u64) * unit {
+            Confidence::from_bps(7000)
         } else {
-            0.3
+            Confidence::from_bps(3000)
         }
     }
 
     /// Check for cross-DEX spread opportunities
     fn check_spreads(&self, update: &PriceUpdate) -> Result<(), DozerError> {
+        let tx = match &self.spread_tx {
+            Some(tx) => tx,
+            None => return Ok(()),
+        };
+
+        for spread in self.find_spreads(update) {
+            tx.send(spread)
+                .map_err(|e| DozerError::QueueError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Find same-chain spreads for the updated pool, sized by price impact
+    ///
+    /// Every pool is priced as the update's token0 in its token1 via
+    /// `price_of`, so pools listing the pair in swapped order compare on
+    /// the same basis. Prices are in whole-token units, like
+    /// `NormalizedPrice::price`.
+    pub fn find_spreads(&self, update: &PriceUpdate) -> Vec<SpreadInfo> {
+        let mut spreads = Vec::new();
+        let (base, quote) = (update.token0, update.token1);
+        let update_reserves = (update.reserve0, update.reserve1);
+        let update_price = match update.price_of(base).map(|p| self.whole_token_price(base, quote, p)) {
+            Some(p) if !p.is_zero() => p,
+            _ => return spreads,
+        };
+        if self.reserve_confidence(update.reserve0, update.reserve1) < self.spread_config.min_confidence {
+            return spreads;
+        }
+
         // Find other pools with same token pair on same chain
-        for ((chain, _), state) in &self.pool_states {
+        for entry in self.pool_states.iter() {
+            let ((chain, _), state) = entry.pair();
             if *chain != update.chain {
                 continue;
             }
-            if state.pool == update.pool {
+            if state.pool == update.pool || !state.has_pair(base, quote) {
+                continue;
+            }
+            if self.is_stale(state, update.timestamp_ms) {
+                continue;
+            }
+            let other_price = state.price_of(base).map(|p| self.whole_token_price(base, quote, p));
+            let (other_reserves, other_price) = match (state.reserves_for(base), other_price) {
+                (Some(reserves), Some(price)) if !price.is_zero() => (reserves, price),
+                _ => continue,
+            };
+            if self.reserve_confidence(state.reserve0, state.reserve1) < self.spread_config.min_confidence {
                 continue;
             }
 
-            // Check if same token pair (in either direction)
-            let same_pair = (state.token0 == update.token0 && state.token1 == update.token1)
-                || (state.token0 == update.token1 && state.token1 == update.token0);
+            // Each leg pays its pool's LP fee
+            let fee_bps = self.pool_fee_bps(update.dex, update.fee_bps) + self.pool_fee_bps(state.dex, state.fee_bps);
 
-            if same_pair {
-                // Calculate spread and emit if significant
-                // TODO: Implement spread calculation
+            // Buy token0 where it is cheaper, sell where it is dearer
+            let (buy, sell) = if update_price < other_price {
+                ((update.dex, update.pool, update_price, update_reserves),
+                 (state.dex, state.pool, other_price, other_reserves))
+            } else {
+                ((state.dex, state.pool, other_price, other_reserves),
+                 (update.dex, update.pool, update_price, update_reserves))
+            };
+
+            let spread_bps = cross_chain::spread_bps(buy.2, sell.2);
+            let net_spread_bps = spread_bps.saturating_sub(fee_bps.min(i64::MAX as u64) as i64);
+            if net_spread_bps < self.spread_config.min_spread_bps {
+                continue;
             }
+
+            spreads.push(SpreadInfo {
+                chain: update.chain,
+                token0: update.token0,
+                token1: update.token1,
+                buy_dex: buy.0,
+                buy_pool: buy.1,
+                buy_price: buy.2,
+                sell_dex: sell.0,
+                sell_pool: sell.1,
+                sell_price: sell.2,
+                spread_bps,
+                fee_bps,
+                net_spread_bps,
+                max_size: max_executable_size(buy.3, sell.3, self.spread_config.max_price_impact_bps),
+            });
         }
 
-        Ok(())
+        spreads
+    }
+
+    /// Find cross-chain spreads for the updated pool (experimental)
+    ///
+    /// Compares the update against pools on other chains holding the same
+    /// canonical pair. A canonical token's decimals can differ per chain
+    /// (USDC has 6 on Ethereum, 18 on BSC), so prices are compared in
+    /// whole-token units via the token registry. Returns nothing unless
+    /// `cross_chain.enabled` is set.
+    pub fn find_cross_chain_spreads(&self, update: &PriceUpdate) -> Vec<CrossChainSpread> {
+        let mut spreads = Vec::new();
+        if !self.cross_chain.enabled {
+            return spreads;
+        }
+
+        let (base, quote) = match (
+            self.cross_chain.canonical(update.chain, update.token0),
+            self.cross_chain.canonical(update.chain, update.token1),
+        ) {
+            (Some(b), Some(q)) => (b, q),
+            _ => return spreads,
+        };
+
+        let update_price = cross_chain::reserve_price(update.reserve0, update.reserve1)
+            .map(|p| self.whole_token_price(update.token0, update.token1, p));
+        let update_price = match update_price {
+            Some(p) if !p.is_zero() => p,
+            _ => return spreads,
+        };
+
+        for entry in self.pool_states.iter() {
+            let ((chain, _), state) = entry.pair();
+            if *chain == update.chain || self.is_stale(state, update.timestamp_ms) {
+                continue;
+            }
+
+            let other0 = self.cross_chain.canonical(state.chain, state.token0);
+            let other1 = self.cross_chain.canonical(state.chain, state.token1);
+
+            // Orient the other pool's price to the update's base/quote
+            let other_price = if other0 == Some(base) && other1 == Some(quote) {
+                cross_chain::reserve_price(state.reserve0, state.reserve1)
+                    .map(|p| self.whole_token_price(state.token0, state.token1, p))
+            } else if other0 == Some(quote) && other1 == Some(base) {
+                cross_chain::reserve_price(state.reserve1, state.reserve0)
+                    .map(|p| self.whole_token_price(state.token1, state.token0, p))
+            } else {
+                continue;
+            };
+
+            let other_price = match other_price {
+                Some(p) if !p.is_zero() => p,
+                _ => continue,
+            };
+
+            // Buy where the base token is cheaper, bridge, sell on the other side
+            let (buy_chain, buy_dex, buy_pool, buy_price, sell_chain, sell_dex, sell_pool, sell_price) =
+                if update_price < other_price {
+                    (update.chain, update.dex, update.pool, update_price,
+                     state.chain, state.dex, state.pool, other_price)
+                } else {
+                    (state.chain, state.dex, state.pool, other_price,
+                     update.chain, update.dex, update.pool, update_price)
+                };
+
+            let bridge = match self.cross_chain.bridge(buy_chain, sell_chain) {
+                Some(b) => b,
+                None => continue,
+            };
+
+            let spread_bps = cross_chain::spread_bps(buy_price, sell_price);
+            let net_spread_bps = spread_bps - bridge.cost_bps;
+            if net_spread_bps < self.cross_chain.min_net_spread_bps {
+                continue;
+            }
+
+            spreads.push(CrossChainSpread {
+                token0: base.to_string(),
+                token1: quote.to_string(),
+                buy_chain,
+                buy_dex,
+                buy_pool,
+                buy_price,
+                sell_chain,
+                sell_dex,
+                sell_pool,
+                sell_price,
+                spread_bps,
+                bridge_cost_bps: bridge.cost_bps,
+                bridge_time_ms: bridge.time_ms,
+                net_spread_bps,
+            });
+        }
+
+        spreads
     }
 
     /// Get current pool state
-    pub fn get_pool_state(&self, chain: ChainId, pool: Address) -> Option<&PoolState> {
-        self.pool_states.get(&(chain, pool))
+    pub fn get_pool_state(&self, chain: ChainId, pool: Address) -> Option<PoolState> {
+        self.pool_states.get(&(chain, pool)).map(|state| state.clone())
     }
 
     /// Get all pool states for a chain
-    pub fn get_chain_pools(&self, chain: ChainId) -> Vec<&PoolState> {
+    pub fn get_chain_pools(&self, chain: ChainId) -> Vec<PoolState> {
         self.pool_states
             .iter()
-            .filter(|((c, _), _)| *c == chain)
-            .map(|(_, state)| state)
+            .filter(|entry| entry.key().0 == chain)
+            .map(|entry| entry.value().clone())
             .collect()
     }
+
+    /// Liquidity-weighted median price of `token_a` in `token_b` on a chain
+    ///
+    /// Considers every pool holding the pair in either token order, weighted
+    /// by geometric-mean liquidity, so a thin pool pushed off-market can't
+    /// move the reference. Baseline for deviation-based confidence.
+    pub fn reference_price(&self, chain: ChainId, token_a: Address, token_b: Address) -> Option<U256> {
+        self.reference_quote(chain, token_a, token_b, None).map(|(price, _)| price)
+    }
+
+    /// Reference price and the number of pools behind it, leaving out `exclude`
+    fn reference_quote(
+        &self,
+        chain: ChainId,
+        token_a: Address,
+        token_b: Address,
+        exclude: Option<Address>,
+    ) -> Option<(U256, usize)> {
+        let mut quotes: Vec<(U256, U256)> = self
+            .pool_states
+            .iter()
+            .filter(|state| state.chain == chain && state.has_pair(token_a, token_b))
+            .filter(|state| Some(state.pool) != exclude)
+            .filter_map(|state| {
+                let (reserve_a, reserve_b) = state.reserves_for(token_a)?;
+                let price = state.price_of(token_a)?;
+                let liquidity = reserve_a.saturating_mul(reserve_b).integer_sqrt();
+                (!liquidity.is_zero()).then_some((price, liquidity))
+            })
+            .collect();
+
+        let pools = quotes.len();
+        weighted_median(&mut quotes).map(|price| (price, pools))
+    }
+}
+
+/// Median of `(value, weight)` pairs: the first value (ascending) at which
+/// cumulative weight reaches half the total
+fn weighted_median(values: &mut [(U256, U256)]) -> Option<U256> {
+    values.sort_by_key(|(value, _)| *value);
+    let total = values
+        .iter()
+        .fold(U256::zero(), |acc, (_, weight)| acc.saturating_add(*weight));
+
+    let mut cumulative = U256::zero();
+    for (value, weight) in values.iter() {
+        cumulative = cumulative.saturating_add(*weight);
+        // cumulative >= total / 2, without rounding the half down
+        if cumulative.saturating_mul(U256::from(2u64)) >= total {
+            return Some(*value);
+        }
+    }
+    None
+}
+
+/// Largest token0 amount worth routing from the cheap pool to the dear pool
+///
+/// Reserves are `(token0, token1)` for two constant-product pools. The size
+/// is the amount that equalizes both pools' prices (ignoring fees), capped so
+/// neither pool's price moves more than `max_impact_bps`.
+pub fn max_executable_size(buy: (U256, U256), sell: (U256, U256), max_impact_bps: u64) -> U256 {
+    let (buy_base, buy_quote) = buy;
+    let (sell_base, sell_quote) = sell;
+    if buy_base.is_zero() || sell_base.is_zero() {
+        return U256::zero();
+    }
+
+    // Buying d from the cheap pool: p = k / (x - d)^2; selling into the dear
+    // pool: p = k / (x + d)^2. Equal when
+    // d = (sqrt(k_sell) * x_buy - sqrt(k_buy) * x_sell) / (sqrt(k_buy) + sqrt(k_sell))
+    let root_buy = buy_base.saturating_mul(buy_quote).integer_sqrt();
+    let root_sell = sell_base.saturating_mul(sell_quote).integer_sqrt();
+    let lhs = root_sell.saturating_mul(buy_base);
+    let rhs = root_buy.saturating_mul(sell_base);
+    if lhs <= rhs {
+        return U256::zero();
+    }
+    let equalizing = (lhs - rhs) / (root_buy + root_sell);
+
+    // Impact caps: price ratio (x / (x -+ d))^2 stays within 1 +- impact.
+    // sqrt(1 +- i) is computed as isqrt((10000 +- i) * 1e14) / 1e9.
+    let unit = U256::exp10(9);
+    let impact = max_impact_bps.min(9_999);
+    let root_up = (U256::from(10_000 + impact) * U256::exp10(14)).integer_sqrt();
+    let root_down = (U256::from(10_000 - impact) * U256::exp10(14)).integer_sqrt();
+    let buy_cap = buy_base - buy_base.saturating_mul(unit) / root_up;
+    let sell_cap = (sell_base.saturating_mul(unit) / root_down).saturating_sub(sell_base);
+
+    equalizing.min(buy_cap).min(sell_cap)
 }
 
 impl Default for Dozer {
@@ -234,10 +1015,843 @@ mod tests {
 
         // High liquidity
         let high = U256::from(1_000_000u64) * U256::exp10(18);
-        assert_eq!(dozer.calculate_confidence(high), 1.0);
+        assert_eq!(dozer.calculate_confidence(high), Confidence::FULL);
 
         // Low liquidity
         let low = U256::from(100u64) * U256::exp10(18);
-        assert_eq!(dozer.calculate_confidence(low), 0.3);
+        assert_eq!(dozer.calculate_confidence(low), Confidence::from_bps(3000));
+
+        // Boundary is inclusive
+        let mid = U256::from(100_000u64) * U256::exp10(18);
+        assert_eq!(dozer.calculate_confidence(mid).bps(), 9000);
+        assert!(dozer.calculate_confidence(mid) < dozer.calculate_confidence(high));
+    }
+
+    /// Deep WETH/USDC pool quoting `usdc_per_weth`, `delay_ms` after the first quote
+    fn provider_quote(usdc_per_weth: u64, delay_ms: u64) -> PriceUpdate {
+        let mut update = cross_chain_update(
+            ChainId::Ethereum,
+            1,
+            Address::from_low_u64_be(0x100),
+            Address::from_low_u64_be(0x101),
+            1_000_000,
+            usdc_per_weth * 1_000_000,
+        );
+        update.timestamp_ms += delay_ms;
+        update
+    }
+
+    fn quorum_dozer() -> (Dozer, Receiver<NormalizedPrice>) {
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = Dozer::new();
+        dozer.set_price_output(tx);
+        dozer.set_quorum_config(QuorumConfig {
+            required: 2,
+            tolerance_bps: 50,
+            ..Default::default()
+        });
+        (dozer, rx)
+    }
+
+    #[test]
+    fn test_quorum_met_by_two_of_three_providers() {
+        let (dozer, rx) = quorum_dozer();
+        let low = QuorumConfig::default().low_confidence;
+
+        // First quote alone can't reach quorum
+        dozer.process_update_from("alchemy", provider_quote(2_000, 0)).unwrap();
+        assert_eq!(rx.try_recv().unwrap().confidence, low);
+
+        // Second provider within tolerance corroborates it
+        dozer.process_update_from("infura", provider_quote(2_001, 100)).unwrap();
+        assert_eq!(rx.try_recv().unwrap().confidence, Confidence::FULL);
+
+        // A third, poisoned quote agrees only with itself
+        dozer.process_update_from("rogue", provider_quote(2_200, 200)).unwrap();
+        assert_eq!(rx.try_recv().unwrap().confidence, low);
+    }
+
+    #[test]
+    fn test_uncorroborated_price_triggers_no_spread() {
+        let (mut dozer, _prices) = quorum_dozer();
+        dozer.set_spread_config(SpreadConfig {
+            min_confidence: Confidence::from_bps(7000),
+            ..Default::default()
+        });
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_spread_output(tx);
+
+        // A second pool at 2100, corroborated by two providers
+        let other = |delay_ms| PriceUpdate {
+            pool: Address::from_low_u64_be(2),
+            ..provider_quote(2_100, delay_ms)
+        };
+        dozer.process_update_from("alchemy", other(0)).unwrap();
+        dozer.process_update_from("infura", other(10)).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // One provider alone pricing the first pool at 2000: no quorum, no spread
+        dozer.process_update_from("rogue", provider_quote(2_000, 100)).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // Corroborated, the same price is traded on
+        dozer.process_update_from("infura", provider_quote(2_000, 200)).unwrap();
+        let spread = rx.try_recv().expect("corroborated spread emitted");
+        assert_eq!(spread.buy_pool, Address::from_low_u64_be(1));
+        assert_eq!(spread.sell_pool, Address::from_low_u64_be(2));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_quorum_failed_when_providers_disagree() {
+        let (dozer, rx) = quorum_dozer();
+        let low = QuorumConfig::default().low_confidence;
+
+        for (i, (provider, price)) in [("alchemy", 2_000), ("infura", 2_400), ("quicknode", 1_600)]
+            .into_iter()
+            .enumerate()
+        {
+            dozer.process_update_from(provider, provider_quote(price, i as u64 * 100)).unwrap();
+            assert_eq!(rx.try_recv().unwrap().confidence, low, "{}", provider);
+        }
+
+        // Without a quorum config the same pool is fully trusted
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = Dozer::new();
+        dozer.set_price_output(tx);
+        dozer.process_update_from("alchemy", provider_quote(2_000, 0)).unwrap();
+        assert_eq!(rx.try_recv().unwrap().confidence, Confidence::FULL);
+    }
+
+    fn cross_chain_update(
+        chain: ChainId,
+        pool: u64,
+        token0: Address,
+        token1: Address,
+        reserve0: u64,
+        reserve1: u64,
+    ) -> PriceUpdate {
+        let reserve0 = U256::from(reserve0) * U256::exp10(18);
+        let reserve1 = U256::from(reserve1) * U256::exp10(18);
+        PriceUpdate {
+            timestamp_ms: 1_700_000_000_000,
+            chain,
+            dex: DexId::UniswapV3,
+            pool: Address::from_low_u64_be(pool),
+            token0,
+            token1,
+            reserve0,
+            reserve1,
+            price: reserve1 * U256::exp10(18) / reserve0,
+            fee_bps: None,
+            provider: None,
+        }
+    }
+
+    fn cross_chain_config() -> (CrossChainConfig, [Address; 4]) {
+        let eth_weth = Address::from_low_u64_be(0x100);
+        let eth_usdc = Address::from_low_u64_be(0x101);
+        let arb_weth = Address::from_low_u64_be(0x200);
+        let arb_usdc = Address::from_low_u64_be(0x201);
+
+        let config = CrossChainConfig {
+            enabled: true,
+            min_net_spread_bps: 50,
+            ..Default::default()
+        }
+        .with_token(ChainId::Ethereum, eth_weth, "WETH")
+        .with_token(ChainId::Ethereum, eth_usdc, "USDC")
+        .with_token(ChainId::Arbitrum, arb_weth, "WETH")
+        .with_token(ChainId::Arbitrum, arb_usdc, "USDC")
+        .with_bridge(ChainId::Arbitrum, ChainId::Ethereum, BridgeEstimate { cost_bps: 10, time_ms: 600_000 })
+        .with_bridge(ChainId::Ethereum, ChainId::Arbitrum, BridgeEstimate { cost_bps: 10, time_ms: 900_000 });
+
+        (config, [eth_weth, eth_usdc, arb_weth, arb_usdc])
+    }
+
+    #[test]
+    fn test_cross_chain_spread_detected() {
+        let (config, [eth_weth, eth_usdc, arb_weth, arb_usdc]) = cross_chain_config();
+        let mut dozer = Dozer::new();
+        dozer.set_cross_chain_config(config);
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_cross_chain_output(tx);
+
+        // WETH at 2000 USDC on Ethereum, 2100 USDC on Arbitrum (5%)
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 1, eth_weth, eth_usdc, 100, 200_000)).unwrap();
+        dozer.process_update(cross_chain_update(ChainId::Arbitrum, 2, arb_weth, arb_usdc, 100, 210_000)).unwrap();
+
+        let spread = rx.try_recv().expect("cross-chain spread emitted");
+        assert_eq!(spread.buy_chain, ChainId::Ethereum);
+        assert_eq!(spread.sell_chain, ChainId::Arbitrum);
+        assert_eq!(spread.token0, "WETH");
+        assert_eq!(spread.spread_bps, 500);
+        assert_eq!(spread.net_spread_bps, 490);
+        assert_eq!(spread.bridge_time_ms, 900_000);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_cross_chain_spread_reversed_token_order() {
+        let (config, [eth_weth, eth_usdc, arb_weth, arb_usdc]) = cross_chain_config();
+        let mut dozer = Dozer::new();
+        dozer.set_cross_chain_config(config);
+
+        // Arbitrum pool lists USDC first: WETH at 1900 USDC
+        dozer.process_update(cross_chain_update(ChainId::Arbitrum, 2, arb_usdc, arb_weth, 190_000, 100)).unwrap();
+        let update = cross_chain_update(ChainId::Ethereum, 1, eth_weth, eth_usdc, 100, 200_000);
+
+        let spreads = dozer.find_cross_chain_spreads(&update);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!(spreads[0].buy_chain, ChainId::Arbitrum);
+        assert_eq!(spreads[0].sell_chain, ChainId::Ethereum);
+        assert_eq!(spreads[0].buy_price, U256::from(1_900u64) * U256::exp10(18));
+    }
+
+    #[test]
+    fn test_cross_chain_disabled_by_default() {
+        let (config, [eth_weth, eth_usdc, arb_weth, arb_usdc]) = cross_chain_config();
+        let mut dozer = Dozer::new();
+        dozer.set_cross_chain_config(CrossChainConfig { enabled: false, ..config });
+
+        dozer.process_update(cross_chain_update(ChainId::Arbitrum, 2, arb_weth, arb_usdc, 100, 210_000)).unwrap();
+        let update = cross_chain_update(ChainId::Ethereum, 1, eth_weth, eth_usdc, 100, 200_000);
+        assert!(dozer.find_cross_chain_spreads(&update).is_empty());
+    }
+
+    #[test]
+    fn test_cross_chain_spread_below_bridge_cost() {
+        let (config, [eth_weth, eth_usdc, arb_weth, arb_usdc]) = cross_chain_config();
+        let mut dozer = Dozer::new();
+        dozer.set_cross_chain_config(config);
+
+        // 0.5% gross spread is eaten by the 0.1% bridge cost + 0.5% minimum
+        dozer.process_update(cross_chain_update(ChainId::Arbitrum, 2, arb_weth, arb_usdc, 100, 201_000)).unwrap();
+        let update = cross_chain_update(ChainId::Ethereum, 1, eth_weth, eth_usdc, 100, 200_000);
+        assert!(dozer.find_cross_chain_spreads(&update).is_empty());
+    }
+
+    #[test]
+    fn test_cross_chain_prices_use_per_chain_decimals() {
+        let [eth_weth, eth_usdc] = [0x100, 0x101].map(Address::from_low_u64_be);
+        let [bsc_weth, bsc_usdc] = [0x300, 0x301].map(Address::from_low_u64_be);
+        let bridge = BridgeEstimate { cost_bps: 10, time_ms: 600_000 };
+        let config = CrossChainConfig {
+            enabled: true,
+            ..Default::default()
+        }
+        .with_token(ChainId::Ethereum, eth_weth, "WETH")
+        .with_token(ChainId::Ethereum, eth_usdc, "USDC")
+        .with_token(ChainId::Bsc, bsc_weth, "WETH")
+        .with_token(ChainId::Bsc, bsc_usdc, "USDC")
+        .with_bridge(ChainId::Ethereum, ChainId::Bsc, bridge)
+        .with_bridge(ChainId::Bsc, ChainId::Ethereum, bridge);
+
+        let mut dozer = Dozer::new();
+        dozer.set_cross_chain_config(config);
+        dozer.set_token_registry(token_registry(&[
+            (eth_weth, 18, "WETH"),
+            (eth_usdc, 6, "USDC"),
+            (bsc_weth, 18, "WETH"),
+            (bsc_usdc, 18, "USDC"),
+        ]));
+
+        // WETH at 2000 USDC on both chains: 6-decimal USDC on Ethereum, 18 on BSC
+        let eth = raw_pool_update(ChainId::Ethereum, 1, (eth_weth, 18, 1_000), (eth_usdc, 6, 2_000_000));
+        dozer.process_update(eth.clone()).unwrap();
+        let bsc = raw_pool_update(ChainId::Bsc, 2, (bsc_weth, 18, 1_000), (bsc_usdc, 18, 2_000_000));
+        assert!(dozer.find_cross_chain_spreads(&bsc).is_empty());
+
+        // A real 5% difference still shows, priced in whole tokens
+        let dear = raw_pool_update(ChainId::Bsc, 2, (bsc_weth, 18, 1_000), (bsc_usdc, 18, 2_100_000));
+        let spreads = dozer.find_cross_chain_spreads(&dear);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!((spreads[0].buy_chain, spreads[0].sell_chain), (ChainId::Ethereum, ChainId::Bsc));
+        assert_eq!(spreads[0].buy_price, U256::exp10(18) * 2_000);
+        assert_eq!(spreads[0].spread_bps, 500);
+    }
+
+    fn reserves(base: u64, quote: u64) -> (U256, U256) {
+        (U256::from(base) * U256::exp10(18), U256::from(quote) * U256::exp10(18))
+    }
+
+    #[test]
+    fn test_max_size_equalizes_prices() {
+        // WETH at 2000 vs 2100 USDC, equal depth, impact effectively uncapped
+        let size = max_executable_size(reserves(100, 200_000), reserves(100, 210_000), 9_999);
+
+        // ~1.22 WETH brings both pools to the same price
+        assert!(size > U256::exp10(18) * 12 / 10 && size < U256::exp10(18) * 125 / 100);
+
+        // No trade when the "buy" pool is the dearer one
+        assert!(max_executable_size(reserves(100, 210_000), reserves(100, 200_000), 9_999).is_zero());
+    }
+
+    #[test]
+    fn test_max_size_deeper_pool_allows_more() {
+        let sell = reserves(50_000, 105_000_000); // deep pool at 2100
+
+        let shallow = max_executable_size(reserves(100, 200_000), sell, 100);
+        let deep = max_executable_size(reserves(10_000, 20_000_000), sell, 100);
+
+        assert!(!shallow.is_zero());
+        assert!(deep > shallow * 50);
+
+        // 1% impact on a 100 WETH pool caps near 0.4975 WETH
+        assert!(shallow > U256::exp10(18) * 49 / 100 && shallow < U256::exp10(18) / 2);
+    }
+
+    #[test]
+    fn test_same_chain_spread_emitted_with_size() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_spread_output(tx);
+
+        // Reversed token order on the second pool: WETH at 2100
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000)).unwrap();
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 2, usdc, weth, 2_100_000, 1_000)).unwrap();
+
+        // Oriented to the update's token0 (USDC), which is cheaper in pool 2
+        let spread = rx.try_recv().expect("spread emitted");
+        assert_eq!(spread.token0, usdc);
+        assert_eq!(spread.buy_pool, Address::from_low_u64_be(2));
+        assert_eq!(spread.sell_pool, Address::from_low_u64_be(1));
+        assert!(spread.spread_bps >= 476);
+        assert!(!spread.max_size.is_zero());
+        assert!(rx.try_recv().is_err());
+    }
+
+    fn chain_config(chain_id: u64, block_time_ms: u64) -> ChainConfig {
+        ChainConfig {
+            name: format!("chain-{}", chain_id),
+            chain_id,
+            rpc_url: String::new(),
+            ws_url: String::new(),
+            flashloan_provider: "aave".to_string(),
+            flash_loan_providers: Vec::new(),
+            flash_loan_contract: String::new(),
+            block_time_ms,
+            gas_limit: 1_000_000,
+            priority_fee_gwei: 2,
+            base_gas: 150_000,
+            per_hop_gas: 100_000,
+            l1_data_fee_multiplier: None,
+            opportunity_ttl_ms: None,
+            price_staleness_ms: None,
+            min_confirmations: 1,
+        }
+    }
+
+    #[test]
+    fn test_stale_pool_left_out_of_spreads() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        // Arbitrum: 250ms blocks, stale after 750ms; Ethereum: after 36s
+        dozer.set_price_staleness(&[chain_config(42161, 250), chain_config(1, 12_000)]);
+
+        for chain in [ChainId::Arbitrum, ChainId::Ethereum] {
+            dozer.process_update(cross_chain_update(chain, 1, weth, usdc, 1_000, 2_000_000)).unwrap();
+        }
+
+        // One second later the first pool is stale on Arbitrum only
+        let later = |chain| PriceUpdate {
+            timestamp_ms: 1_700_000_001_000,
+            ..cross_chain_update(chain, 2, weth, usdc, 1_000, 2_100_000)
+        };
+        assert!(dozer.find_spreads(&later(ChainId::Arbitrum)).is_empty());
+        assert_eq!(dozer.find_spreads(&later(ChainId::Ethereum)).len(), 1);
+
+        // A fresh quote for the first pool brings it back
+        let mut refreshed = cross_chain_update(ChainId::Arbitrum, 1, weth, usdc, 1_000, 2_000_000);
+        refreshed.timestamp_ms = 1_700_000_000_900;
+        dozer.process_update(refreshed).unwrap();
+        assert_eq!(dozer.find_spreads(&later(ChainId::Arbitrum)).len(), 1);
+    }
+
+    #[test]
+    fn test_spread_net_of_fee_tiers() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let with_fee = |pool: u64, usdc_per_weth: u64, fee_bps: u64| PriceUpdate {
+            fee_bps: Some(fee_bps),
+            ..cross_chain_update(ChainId::Ethereum, pool, weth, usdc, 1_000, usdc_per_weth * 1_000)
+        };
+        let mut dozer = Dozer::new();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_spread_output(tx);
+
+        // 50 bps gross between a 5 bps V3 pool and a 30 bps V2 pool
+        dozer.process_update(with_fee(1, 2_000, 5)).unwrap();
+        dozer.process_update(with_fee(2, 2_010, 30)).unwrap();
+        let spread = rx.try_recv().expect("spread emitted");
+        assert_eq!(spread.buy_pool, Address::from_low_u64_be(1));
+        assert_eq!(spread.spread_bps, 50);
+        assert_eq!(spread.fee_bps, 35);
+        assert_eq!(spread.net_spread_bps, 15);
+
+        // On the 100 bps tier the same prices don't cover the fees
+        dozer.process_update(with_fee(3, 2_000, 100)).unwrap();
+        let spreads: Vec<_> = rx.try_iter().collect();
+        assert!(spreads.iter().all(|s| s.buy_pool != Address::from_low_u64_be(3)));
+    }
+
+    #[test]
+    fn test_fixture_spread_detected_exactly() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let fixture = hotpath::fixtures::make_pools_with_spread(50);
+        let [buy, sell] = fixture.price_updates(ChainId::Ethereum, weth, usdc);
+        let dozer = Dozer::new();
+        dozer.process_update(buy).unwrap();
+
+        let spreads = dozer.find_spreads(&sell);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!((spreads[0].buy_dex, spreads[0].sell_dex), (DexId::UniswapV3, DexId::SushiSwap));
+        assert_eq!(spreads[0].spread_bps, 50);
+        assert_eq!(spreads[0].net_spread_bps, 40);
+    }
+
+    #[test]
+    fn test_pool_without_fee_tier_pays_dex_default() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        dozer.set_dex_fee(
+            DexId::SushiSwap,
+            &DexConfig {
+                name: "sushiswap".to_string(),
+                router_address: String::new(),
+                factory_address: String::new(),
+                fee_bps: 30,
+                fee_tiers: Vec::new(),
+                supported_chains: vec![1],
+            },
+        );
+
+        // 5 bps V3 pool against a SushiSwap pool that names no tier
+        dozer
+            .process_update(PriceUpdate {
+                fee_bps: Some(5),
+                ..cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000)
+            })
+            .unwrap();
+        let sushi = PriceUpdate {
+            dex: DexId::SushiSwap,
+            ..cross_chain_update(ChainId::Ethereum, 2, weth, usdc, 1_000, 2_010_000)
+        };
+        let spreads = dozer.find_spreads(&sushi);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!(spreads[0].fee_bps, 35);
+        assert_eq!(spreads[0].net_spread_bps, 15);
+    }
+
+    #[test]
+    fn test_swapped_token_order_priced_on_common_basis() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let weth_usdc = cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000);
+        let usdc_weth = cross_chain_update(ChainId::Ethereum, 2, usdc, weth, 2_100_000, 1_000);
+
+        // Raw `price` fields are inverses of each other; price_of agrees
+        assert_eq!(weth_usdc.price_of(weth), Some(U256::exp10(18) * 2_000));
+        assert_eq!(usdc_weth.price_of(weth), Some(U256::exp10(18) * 2_100));
+
+        // Whichever pool updates last, the spread is the same trade
+        let dozer = Dozer::new();
+        dozer.process_update(usdc_weth.clone()).unwrap();
+        let spreads = dozer.find_spreads(&weth_usdc);
+        assert_eq!(spreads.len(), 1);
+        let spread = &spreads[0];
+        assert_eq!((spread.token0, spread.token1), (weth, usdc));
+        assert_eq!(spread.buy_pool, weth_usdc.pool);
+        assert_eq!(spread.buy_price, U256::exp10(18) * 2_000);
+        assert_eq!(spread.sell_pool, usdc_weth.pool);
+        assert_eq!(spread.sell_price, U256::exp10(18) * 2_100);
+        assert_eq!(spread.spread_bps, 500);
+
+        let dozer = Dozer::new();
+        dozer.process_update(weth_usdc.clone()).unwrap();
+        let reversed = &dozer.find_spreads(&usdc_weth)[0];
+        assert_eq!((reversed.token0, reversed.token1), (usdc, weth));
+        // Same pools on the same sides: USDC is cheap where WETH is dear
+        assert_eq!(reversed.buy_pool, usdc_weth.pool);
+        assert_eq!(reversed.sell_pool, weth_usdc.pool);
+        assert!(reversed.spread_bps >= 476);
+    }
+
+    #[test]
+    fn test_low_confidence_spread_suppressed() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        dozer.set_spread_config(SpreadConfig {
+            min_confidence: Confidence::from_bps(7000),
+            ..Default::default()
+        });
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_spread_output(tx);
+
+        // Thin pool (30% confidence) quoting WETH at 2500: large spread, ignored
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000)).unwrap();
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 2, weth, usdc, 10, 25_000)).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // Deep pool (70% confidence) at 2100 passes
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 3, weth, usdc, 1_000, 2_100_000)).unwrap();
+        let spread = rx.try_recv().expect("confident spread emitted");
+        assert_eq!(spread.buy_pool, Address::from_low_u64_be(1));
+        assert_eq!(spread.sell_pool, Address::from_low_u64_be(3));
+        assert!(rx.try_recv().is_err());
+
+        // Default threshold emits the thin pool's spread too
+        dozer.set_spread_config(SpreadConfig::default());
+        let update = cross_chain_update(ChainId::Ethereum, 2, weth, usdc, 10, 25_000);
+        assert_eq!(dozer.find_spreads(&update).len(), 2);
+    }
+
+    /// Registry knowing only the given tokens' decimals
+    fn token_registry(tokens: &[(Address, u8, &str)]) -> TokenRegistry {
+        use morpheus::{TokenMetadata, TokenMetadataSource};
+
+        struct NoFetch;
+
+        #[async_trait::async_trait]
+        impl TokenMetadataSource for NoFetch {
+            async fn fetch(&self, _token: Address) -> Result<TokenMetadata, morpheus::MorpheusError> {
+                Err(morpheus::MorpheusError::FeedError("offline".into()))
+            }
+        }
+
+        let registry = TokenRegistry::new(std::sync::Arc::new(NoFetch));
+        for &(address, decimals, symbol) in tokens {
+            registry.insert(TokenMetadata { address, decimals, symbol: symbol.into() });
+        }
+        registry
+    }
+
+    /// Pool of `base_units` whole base tokens against `quote_units` whole
+    /// quote tokens, in raw units for the given decimals
+    fn raw_pool_update(
+        chain: ChainId,
+        pool: u64,
+        (base, base_decimals, base_units): (Address, u8, u64),
+        (quote, quote_decimals, quote_units): (Address, u8, u64),
+    ) -> PriceUpdate {
+        let reserve0 = U256::from(base_units) * U256::exp10(base_decimals as usize);
+        let reserve1 = U256::from(quote_units) * U256::exp10(quote_decimals as usize);
+        PriceUpdate {
+            timestamp_ms: 1_700_000_000_000,
+            chain,
+            dex: DexId::UniswapV3,
+            pool: Address::from_low_u64_be(pool),
+            token0: base,
+            token1: quote,
+            reserve0,
+            reserve1,
+            price: reserve1 * U256::exp10(18) / reserve0,
+            fee_bps: None,
+            provider: None,
+        }
+    }
+
+    #[test]
+    fn test_normalized_price_uses_token_decimals() {
+        let usdc = Address::from_low_u64_be(0x101);
+        let weth = Address::from_low_u64_be(0x100);
+
+        let mut dozer = Dozer::new();
+        dozer.set_token_registry(token_registry(&[(usdc, 6, "USDC"), (weth, 18, "WETH")]));
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_price_output(tx);
+
+        // 2,000,000 USDC vs 1,000 WETH in raw units
+        let update = raw_pool_update(ChainId::Ethereum, 1, (usdc, 6, 2_000_000), (weth, 18, 1_000));
+        dozer.process_update(update).unwrap();
+
+        // 1 USDC = 0.0005 WETH
+        assert_eq!(rx.try_recv().unwrap().price, U256::exp10(18) / 2_000);
+    }
+
+    #[test]
+    fn test_spread_prices_use_token_decimals() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let cheap = raw_pool_update(ChainId::Ethereum, 1, (weth, 18, 1_000), (usdc, 6, 2_000_000));
+        let dear = raw_pool_update(ChainId::Ethereum, 2, (weth, 18, 1_000), (usdc, 6, 2_100_000));
+
+        let mut dozer = Dozer::new();
+        dozer.set_token_registry(token_registry(&[(usdc, 6, "USDC"), (weth, 18, "WETH")]));
+        dozer.process_update(cheap.clone()).unwrap();
+        let spreads = dozer.find_spreads(&dear);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!(spreads[0].buy_price, U256::exp10(18) * 2_000);
+        assert_eq!(spreads[0].sell_price, U256::exp10(18) * 2_100);
+        assert_eq!(spreads[0].spread_bps, 500);
+
+        // The spread itself doesn't depend on decimals between same-pair pools
+        let raw = Dozer::new();
+        raw.process_update(cheap).unwrap();
+        let spreads = raw.find_spreads(&dear);
+        assert_eq!(spreads[0].buy_price, U256::exp10(6) * 2_000);
+        assert_eq!(spreads[0].spread_bps, 500);
+    }
+
+    #[test]
+    fn test_reference_price_is_liquidity_weighted() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let dozer = Dozer::new();
+
+        // One deep pool at 2000 outweighs three thin pools quoting far away
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 10_000, 20_000_000)).unwrap();
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 2, weth, usdc, 10, 30_000)).unwrap();
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 3, weth, usdc, 10, 35_000)).unwrap();
+        // Listed in reverse order: WETH at 2500
+        dozer.process_update(cross_chain_update(ChainId::Ethereum, 4, usdc, weth, 25_000, 10)).unwrap();
+
+        let two_thousand = U256::from(2_000u64) * U256::exp10(18);
+        // An unweighted median would land on one of the thin quotes
+        assert_eq!(dozer.reference_price(ChainId::Ethereum, weth, usdc), Some(two_thousand));
+        // Same pair asked the other way round
+        assert_eq!(
+            dozer.reference_price(ChainId::Ethereum, usdc, weth),
+            Some(U256::exp10(18) / 2_000)
+        );
+
+        // Other chains and pairs are ignored
+        assert_eq!(dozer.reference_price(ChainId::Arbitrum, weth, usdc), None);
+        assert_eq!(dozer.reference_price(ChainId::Ethereum, weth, Address::from_low_u64_be(0x102)), None);
+    }
+
+    #[test]
+    fn test_outlier_price_rejected() {
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = Dozer::new();
+        dozer.set_price_output(tx);
+        dozer.set_price_bounds(PriceBounds::default());
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let pool = |id: u64, usdc_per_weth: u64| {
+            cross_chain_update(ChainId::Ethereum, id, weth, usdc, 1_000_000, usdc_per_weth * 1_000_000)
+        };
+
+        // The first pool has nothing to compare against
+        for (id, price) in [(1, 2_000), (2, 2_010), (3, 1_990)] {
+            dozer.process_update(pool(id, price)).unwrap();
+        }
+        assert_eq!(rx.try_iter().count(), 3);
+
+        // Misparsed reserves: 20x and 1/20x the cross-DEX median
+        dozer.process_update(pool(4, 40_000)).unwrap();
+        dozer.process_update(pool(5, 100)).unwrap();
+        assert_eq!(dozer.rejected_prices(), 2);
+        assert!(rx.try_recv().is_err());
+        assert!(dozer.get_pool_state(ChainId::Ethereum, Address::from_low_u64_be(4)).is_none());
+
+        // A large but plausible move still goes through
+        dozer.process_update(pool(4, 5_000)).unwrap();
+        assert_eq!(rx.try_iter().count(), 1);
+        assert_eq!(dozer.rejected_prices(), 2);
+    }
+
+    #[test]
+    fn test_price_history_records_accepted_updates() {
+        let mut dozer = Dozer::new();
+        dozer.set_price_history_len(3);
+        let pool = Address::from_low_u64_be(1);
+
+        for (delay, price) in [(0, 2_000), (10, 2_200), (20, 1_980), (30, 2_178)] {
+            dozer.process_update(provider_quote(price, delay)).unwrap();
+        }
+        // Stale: ignored by pool state, so not recorded either
+        dozer.process_update(provider_quote(9_999, 5)).unwrap();
+
+        let recent = dozer.recent_prices(ChainId::Ethereum, pool, 10);
+        let prices: Vec<U256> = recent.iter().map(|(_, price)| *price).collect();
+        let usdc = |n: u64| U256::exp10(18) * n;
+        assert_eq!(prices, vec![usdc(2_200), usdc(1_980), usdc(2_178)]);
+        assert_eq!(recent[2].0, 1_700_000_000_030);
+
+        // -10%, +10% over the three kept prices
+        let volatility = dozer.volatility(ChainId::Ethereum, pool).unwrap();
+        assert!((volatility - 0.02f64.sqrt()).abs() < 1e-12, "{}", volatility);
+    }
+
+    #[test]
+    fn test_divergence_alert_after_sustain_window() {
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = Dozer::new();
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        dozer.set_divergence_config(DivergenceConfig {
+            pairs: vec![(ChainId::Ethereum, weth, usdc)],
+            max_dispersion_bps: 500,
+            sustain_ms: 10_000,
+            min_pools: 3,
+        });
+        dozer.set_divergence_output(tx);
+        let quote = |pool: u64, usdc_per_weth: u64, at_ms: u64| {
+            let mut update = cross_chain_update(ChainId::Ethereum, pool, weth, usdc, 1_000_000, usdc_per_weth * 1_000_000);
+            update.timestamp_ms += at_ms;
+            update
+        };
+
+        // Feeds agree
+        for pool in 1..=3 {
+            dozer.process_update(quote(pool, 2_000, 0)).unwrap();
+        }
+        assert_eq!(dozer.pair_dispersion_bps(ChainId::Ethereum, weth, usdc), Some((0, 3)));
+
+        // One pool jumps 20% and stays there; the others keep quoting
+        dozer.process_update(quote(3, 2_400, 1_000)).unwrap();
+        for at_ms in [4_000, 8_000, 10_999] {
+            dozer.process_update(quote(1, 2_000, at_ms)).unwrap();
+            assert!(rx.try_recv().is_err(), "alerted early at {}", at_ms);
+        }
+        dozer.process_update(quote(2, 2_000, 11_000)).unwrap();
+        let alert = rx.try_recv().unwrap();
+        assert_eq!((alert.token_a, alert.token_b, alert.pools), (weth, usdc, 3));
+        assert_eq!(alert.dispersion_bps, 2_000);
+        assert_eq!(alert.timestamp_ms - alert.since_ms, 10_000);
+        assert!(alert.reason().starts_with("Feed divergence on Ethereum"));
+
+        // Only once per episode
+        dozer.process_update(quote(1, 2_000, 20_000)).unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_health_counts_failed_updates() {
+        let dozer = Dozer::new();
+        assert_eq!(dozer.health().last_heartbeat_ms, 0);
+
+        let before = chrono::Utc::now().timestamp_millis() as u64;
+        dozer.process_update(provider_quote(2_000, 0)).unwrap();
+        let health = dozer.health();
+        assert!(health.last_heartbeat_ms >= before);
+        assert_eq!(health.error_count, 0);
+        assert_eq!(health.metrics["pools"], 1.0);
+
+        // A closed output channel fails the update
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let mut dozer = dozer;
+        dozer.set_price_output(tx);
+        drop(rx);
+        assert!(dozer.process_update(provider_quote(2_000, 100)).is_err());
+        assert_eq!(dozer.health().error_count, 1);
+    }
+
+    #[test]
+    fn test_weighted_median_balanced_weights() {
+        let p = |n: u64| U256::from(n);
+        let mut values = vec![(p(30), p(1)), (p(10), p(1)), (p(20), p(1))];
+        assert_eq!(weighted_median(&mut values), Some(p(20)));
+
+        // Heavier upper half pulls the median up
+        let mut values = vec![(p(10), p(1)), (p(20), p(1)), (p(30), p(3))];
+        assert_eq!(weighted_median(&mut values), Some(p(30)));
+
+        assert_eq!(weighted_median(&mut []), None);
+    }
+
+    #[test]
+    fn test_concurrent_updates_on_disjoint_pools() {
+        let mut dozer = Dozer::with_shards(8);
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_price_output(tx);
+
+        // Each thread owns its pools and sends increasing timestamps
+        std::thread::scope(|scope| {
+            for thread in 0..4u64 {
+                let dozer = &dozer;
+                scope.spawn(move || {
+                    for step in 1..=100u64 {
+                        for pool in 0..5u64 {
+                            let token0 = Address::from_low_u64_be(0x100 + thread);
+                            let token1 = Address::from_low_u64_be(0x200 + thread);
+                            let mut update =
+                                cross_chain_update(ChainId::Bsc, thread * 10 + pool, token0, token1, 1_000, step);
+                            update.timestamp_ms = step;
+                            dozer.process_update(update).unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(dozer.get_chain_pools(ChainId::Bsc).len(), 20);
+        for state in dozer.get_chain_pools(ChainId::Bsc) {
+            assert_eq!(state.last_update_ms, 100);
+            assert_eq!(state.reserve1, U256::from(100u64) * U256::exp10(18));
+        }
+
+        // Per-pool output order matches each thread's send order
+        let mut last_seen = std::collections::HashMap::new();
+        for price in rx.try_iter() {
+            let previous = last_seen.insert(price.pool, price.timestamp_ms).unwrap_or(0);
+            assert_eq!(price.timestamp_ms, previous + 1);
+        }
+        assert_eq!(last_seen.len(), 20);
+    }
+
+    #[test]
+    fn test_stale_update_ignored() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_price_output(tx);
+
+        let fresh = cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000);
+        let mut stale = cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 1_000_000);
+        stale.timestamp_ms = fresh.timestamp_ms - 1;
+
+        dozer.process_update(fresh.clone()).unwrap();
+        dozer.process_update(stale).unwrap();
+
+        let state = dozer.get_pool_state(ChainId::Ethereum, fresh.pool).unwrap();
+        assert_eq!(state.reserve1, fresh.reserve1);
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_pool_diff_across_updates() {
+        let weth = Address::from_low_u64_be(0x100);
+        let usdc = Address::from_low_u64_be(0x101);
+        let mut dozer = Dozer::new();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        dozer.set_diff_output(tx);
+
+        // First sight of the pool: nothing to diff against
+        let first = cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_000, 2_000_000);
+        dozer.process_update(first.clone()).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // Someone sells 10 WETH into the pool
+        let mut second = cross_chain_update(ChainId::Ethereum, 1, weth, usdc, 1_010, 1_980_000);
+        second.timestamp_ms = first.timestamp_ms + 12_000;
+        dozer.process_update(second.clone()).unwrap();
+
+        let diff = rx.try_recv().unwrap();
+        let unit = I256::exp10(18);
+        assert_eq!(diff.pool, first.pool);
+        assert_eq!(diff.reserve0_delta, I256::from(10) * unit);
+        assert_eq!(diff.reserve1_delta, I256::from(-20_000) * unit);
+        // 2000 -> ~1960.4 USDC per WETH
+        assert_eq!(diff.price_delta_bps, -198);
+        assert_eq!(diff.timestamp_ms, second.timestamp_ms);
+        assert!(!diff.is_empty());
+        assert!(rx.try_recv().is_err());
+
+        // Same reserves again: an empty diff
+        let mut third = second.clone();
+        third.timestamp_ms += 12_000;
+        dozer.process_update(third).unwrap();
+        let diff = rx.try_recv().unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.price_delta_bps, 0);
     }
 }
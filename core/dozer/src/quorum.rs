@@ -0,0 +1,107 @@
+//! Multi-Provider Price Quorum
+//!
+//! Guards against a single RPC feeding poisoned prices. Each provider's
+//! latest quote for a pool is kept; an update is corroborated when at
+//! least `required` providers quote within `tolerance_bps` of it inside
+//! the time window. Uncorroborated prices are still emitted, capped at a
+//! low confidence.
+
+use dashmap::DashMap;
+use ethers::types::{Address, U256};
+use matrix_types::{ChainId, Confidence};
+use std::collections::HashMap;
+
+/// Quorum requirement for high-confidence prices
+#[derive(Debug, Clone)]
+pub struct QuorumConfig {
+    /// Providers that must agree (M of N)
+    pub required: usize,
+    /// Maximum deviation from the update's price to count as agreeing
+    pub tolerance_bps: u64,
+    /// Quotes older than this relative to the update are ignored
+    pub window_ms: u64,
+    /// Confidence cap for prices that miss quorum
+    pub low_confidence: Confidence,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            required: 2,
+            tolerance_bps: 10,        // 0.1%
+            window_ms: 2_000,
+            low_confidence: Confidence::from_bps(1000),
+        }
+    }
+}
+
+/// Latest quote per provider for each pool
+#[derive(Debug, Default)]
+pub(crate) struct QuorumTracker {
+    quotes: DashMap<(ChainId, Address), HashMap<String, (U256, u64)>>,
+}
+
+impl QuorumTracker {
+    /// Record a provider's quote, keeping only its newest
+    pub(crate) fn record(&self, provider: &str, chain: ChainId, pool: Address, price: U256, timestamp_ms: u64) {
+        let mut quotes = self.quotes.entry((chain, pool)).or_default();
+        match quotes.get_mut(provider) {
+            Some(quote) if quote.1 > timestamp_ms => {}
+            Some(quote) => *quote = (price, timestamp_ms),
+            None => {
+                quotes.insert(provider.to_string(), (price, timestamp_ms));
+            }
+        }
+    }
+
+    /// Providers whose recent quote agrees with `price`
+    pub(crate) fn agreeing(
+        &self,
+        config: &QuorumConfig,
+        chain: ChainId,
+        pool: Address,
+        price: U256,
+        timestamp_ms: u64,
+    ) -> usize {
+        let Some(quotes) = self.quotes.get(&(chain, pool)) else {
+            return 0;
+        };
+        quotes
+            .values()
+            .filter(|(_, quoted_at)| timestamp_ms.abs_diff(*quoted_at) <= config.window_ms)
+            .filter(|(quote, _)| within_bps(*quote, price, config.tolerance_bps))
+            .count()
+    }
+}
+
+/// Whether `a` is within `tolerance_bps` of `b`
+fn within_bps(a: U256, b: U256, tolerance_bps: u64) -> bool {
+    if b.is_zero() {
+        return a.is_zero();
+    }
+    let diff = if a > b { a - b } else { b - a };
+    diff.saturating_mul(U256::from(10_000u64)) <= b.saturating_mul(U256::from(tolerance_bps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_outside_window_or_tolerance_dont_count() {
+        let config = QuorumConfig::default();
+        let tracker = QuorumTracker::default();
+        let pool = Address::from_low_u64_be(1);
+        let price = U256::exp10(18) * 2_000u64;
+
+        tracker.record("alchemy", ChainId::Ethereum, pool, price, 10_000);
+        tracker.record("infura", ChainId::Ethereum, pool, price + price / 10_000, 10_500);
+        tracker.record("quicknode", ChainId::Ethereum, pool, price, 5_000);
+        tracker.record("ankr", ChainId::Ethereum, pool, price + price / 100, 10_000);
+        // An older quote never replaces a newer one
+        tracker.record("alchemy", ChainId::Ethereum, pool, U256::zero(), 9_000);
+
+        assert_eq!(tracker.agreeing(&config, ChainId::Ethereum, pool, price, 10_000), 2);
+        assert_eq!(tracker.agreeing(&config, ChainId::Bsc, pool, price, 10_000), 0);
+    }
+}
@@ -0,0 +1,58 @@
+//! Indexed vs brute-force `OpportunityScanner` scan
+//!
+//! 5k pools spread over 500 token pairs. Run with `cargo bench -p hotpath`.
+
+use std::time::{Duration, Instant};
+
+use ethers_core::types::Address;
+use hotpath::{OpportunityScanner, PoolReserves};
+
+const PAIRS: u32 = 500;
+const POOLS_PER_PAIR: u32 = 10;
+const ROUNDS: u32 = 5;
+const NOW_MS: u64 = 1_700_000_000_000;
+
+fn scanner() -> OpportunityScanner {
+    let weth = Address::repeat_byte(0xee);
+    let reserve0: u128 = 1_000_000 * 1_000_000_000_000_000_000;
+    let mut scanner = OpportunityScanner::new();
+
+    for i in 0..PAIRS * POOLS_PER_PAIR {
+        let pair = i % PAIRS;
+        // Up to ~3% spread between pools of a pair
+        let jitter = (i as u128 * 7_919) % 300;
+        let reserve1 = reserve0 / 10_000 * ((1_000 + pair as u128) * (10_000 + jitter));
+        let mut pool = PoolReserves::new(reserve0, reserve1, i + 1, 1 + i % 8);
+        pool.timestamp_ms = NOW_MS;
+        scanner.set_pool_tokens(pool.pool_id, pool.dex_id, weth, Address::from_low_u64_be(pair as u64 + 1));
+        scanner.update_pool(pool);
+    }
+    scanner
+}
+
+fn time(rounds: u32, mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..rounds {
+        found = f();
+    }
+    (start.elapsed() / rounds, found)
+}
+
+fn main() {
+    let scanner = scanner();
+
+    let (indexed, found_indexed) = time(ROUNDS, || scanner.scan_at(NOW_MS).len());
+    let (brute, found_brute) = time(ROUNDS, || scanner.scan_brute_force(NOW_MS).len());
+    assert_eq!(found_indexed, found_brute);
+
+    println!(
+        "scan {} pools / {} pairs: indexed {:?}, brute force {:?} ({:.1}x), {} opportunities",
+        scanner.pool_count(),
+        PAIRS,
+        indexed,
+        brute,
+        brute.as_secs_f64() / indexed.as_secs_f64(),
+        found_indexed
+    );
+}
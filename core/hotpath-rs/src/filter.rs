@@ -0,0 +1,164 @@
+//! Pluggable opportunity filters
+//!
+//! The scanner finds candidates; whether one is worth passing on is a
+//! policy question (minimum profit, dust size, staleness, anything a user
+//! cares about). Filters implement `OpportunityFilter` and are run in
+//! order by a `FilterChain`, which stops at the first rejection and names
+//! the filter responsible so reject reasons can be counted.
+
+use crate::{ArbitrageOpportunity, U256};
+
+/// A yes/no check on a candidate opportunity
+pub trait OpportunityFilter: Send + Sync {
+    /// Stable name, reported as the reject reason
+    fn name(&self) -> &str;
+
+    /// Whether `opp` passes, judged at `now_ms`
+    fn accepts(&self, opp: &ArbitrageOpportunity, now_ms: u64) -> bool;
+}
+
+/// Nonzero profit (net of gas) of at least the given amount
+#[derive(Debug, Clone, Copy)]
+pub struct MinProfit(pub U256);
+
+impl OpportunityFilter for MinProfit {
+    fn name(&self) -> &str {
+        "min_profit"
+    }
+
+    fn accepts(&self, opp: &ArbitrageOpportunity, _now_ms: u64) -> bool {
+        opp.is_profitable() && opp.estimated_profit >= self.0
+    }
+}
+
+/// Rejects opportunities smaller than the given `max_amount` (dust)
+#[derive(Debug, Clone, Copy)]
+pub struct MinTradeSize(pub U256);
+
+impl OpportunityFilter for MinTradeSize {
+    fn name(&self) -> &str {
+        "min_trade_size"
+    }
+
+    fn accepts(&self, opp: &ArbitrageOpportunity, _now_ms: u64) -> bool {
+        opp.max_amount >= self.0
+    }
+}
+
+/// Rejects opportunities whose newest pool update is older than the given ms
+#[derive(Debug, Clone, Copy)]
+pub struct MaxAge(pub u64);
+
+impl OpportunityFilter for MaxAge {
+    fn name(&self) -> &str {
+        "stale"
+    }
+
+    fn accepts(&self, opp: &ArbitrageOpportunity, now_ms: u64) -> bool {
+        now_ms.saturating_sub(opp.timestamp_ms) <= self.0
+    }
+}
+
+/// Filters run in order, stopping at the first rejection
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn OpportunityFilter>>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a filter, run after those already in the chain
+    pub fn with(mut self, filter: impl OpportunityFilter + 'static) -> Self {
+        self.push(filter);
+        self
+    }
+
+    pub fn push(&mut self, filter: impl OpportunityFilter + 'static) {
+        self.filters.push(Box::new(filter));
+    }
+
+    /// `Err(name)` of the first filter rejecting `opp`
+    pub fn check(&self, opp: &ArbitrageOpportunity, now_ms: u64) -> Result<(), &str> {
+        match self.filters.iter().find(|filter| !filter.accepts(opp, now_ms)) {
+            Some(filter) => Err(filter.name()),
+            None => Ok(()),
+        }
+    }
+
+    /// Filter names in run order
+    pub fn names(&self) -> Vec<&str> {
+        self.filters.iter().map(|filter| filter.name()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl std::fmt::Debug for FilterChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FilterChain").field(&self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+
+    fn opp(profit: u128, size: u128, timestamp_ms: u64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            buy_pool_id: 1,
+            buy_dex_id: 1,
+            sell_pool_id: 2,
+            sell_dex_id: 2,
+            buy_price: U256::ZERO,
+            sell_price: U256::ZERO,
+            spread_bps: 100,
+            max_amount: U256::from_u128(size),
+            estimated_profit: U256::from_u128(profit),
+            timestamp_ms,
+            hops: 2,
+        }
+    }
+
+    #[test]
+    fn test_first_rejecting_filter_reported() {
+        let size_first = FilterChain::new()
+            .with(MinTradeSize(U256::from_u128(10 * E18)))
+            .with(MinProfit(U256::from_u128(E18 / 10)));
+        let profit_first = FilterChain::new()
+            .with(MinProfit(U256::from_u128(E18 / 10)))
+            .with(MinTradeSize(U256::from_u128(10 * E18)));
+
+        // Small and barely profitable: fails both, reported by whichever runs first
+        let dust = opp(E18 / 100, E18, 1_000);
+        assert_eq!(size_first.check(&dust, 1_000), Err("min_trade_size"));
+        assert_eq!(profit_first.check(&dust, 1_000), Err("min_profit"));
+
+        // Big enough but not profitable enough: only the profit filter objects
+        let thin = opp(E18 / 100, 50 * E18, 1_000);
+        assert_eq!(size_first.check(&thin, 1_000), Err("min_profit"));
+
+        let good = opp(E18, 50 * E18, 1_000);
+        assert_eq!(size_first.check(&good, 1_000), Ok(()));
+        assert_eq!(size_first.names(), vec!["min_trade_size", "min_profit"]);
+    }
+
+    #[test]
+    fn test_max_age_rejects_stale() {
+        let chain = FilterChain::new().with(MaxAge(500));
+        let opp = opp(E18, E18, 1_000);
+        assert_eq!(chain.check(&opp, 1_500), Ok(()));
+        assert_eq!(chain.check(&opp, 1_501), Err("stale"));
+        assert!(FilterChain::new().check(&opp, u64::MAX).is_ok());
+    }
+}
@@ -0,0 +1,147 @@
+//! Deterministic spread fixtures for tests
+//!
+//! Builds pool pairs whose prices differ by an exact number of basis
+//! points, as `PoolReserves` or `PriceUpdate`s, so scanner and pipeline
+//! tests can assert exact detection instead of hand-tuning reserves.
+//! Enabled in tests and with the `fixtures` feature.
+
+use ethers_core::types::{Address, U256 as EthU256};
+use matrix_types::{ChainId, DexId, PriceUpdate};
+
+use crate::{dex, OpportunityScanner, PoolReserves};
+
+/// Base-token reserve of every fixture pool (1M tokens, 18 decimals)
+pub const FIXTURE_RESERVE0: u128 = 1_000_000 * 1_000_000_000_000_000_000;
+
+/// Cheap-side price of the fixture pair (token1 per token0)
+pub const FIXTURE_PRICE: u128 = 2_000;
+
+/// LP fee of every fixture pool: the V3 5 bps tier, so a 50 bps spread
+/// clears two swaps and the flash loan premium
+pub const FIXTURE_FEE_BPS: u32 = 5;
+
+/// Two pools of the same pair, `sell` priced `spread_bps` above `buy`
+#[derive(Debug, Clone, Copy)]
+pub struct SpreadFixture {
+    pub buy: PoolReserves,
+    pub sell: PoolReserves,
+    pub spread_bps: i64,
+    /// LP fee of both pools
+    pub fee_bps: u32,
+}
+
+impl SpreadFixture {
+    /// Track both pools in `scanner`, on the fixture fee tier
+    ///
+    /// Pools added with `update_pool` directly pay the scanner's default fee.
+    pub fn add_to(&self, scanner: &mut OpportunityScanner) {
+        for pool in [self.buy, self.sell] {
+            scanner.set_pool_fee(pool.pool_id, pool.dex_id, self.fee_bps);
+            scanner.update_pool(pool);
+        }
+    }
+
+    /// The buy and sell pools as `PriceUpdate`s of `token0`/`token1` on `chain`
+    ///
+    /// Pool addresses are the pool ids; the DEXs match the pools' dex ids.
+    pub fn price_updates(&self, chain: ChainId, token0: Address, token1: Address) -> [PriceUpdate; 2] {
+        let update = |pool: &PoolReserves, dex: DexId| {
+            let reserve0 = EthU256::from(pool.reserve0.low128());
+            let reserve1 = EthU256::from(pool.reserve1.low128());
+            PriceUpdate {
+                timestamp_ms: pool.timestamp_ms,
+                chain,
+                dex,
+                pool: Address::from_low_u64_be(pool.pool_id as u64),
+                token0,
+                token1,
+                reserve0,
+                reserve1,
+                price: reserve1 * EthU256::exp10(18) / reserve0,
+                fee_bps: Some(self.fee_bps as u64),
+                provider: None,
+            }
+        };
+        [update(&self.buy, DexId::UniswapV3), update(&self.sell, DexId::SushiSwap)]
+    }
+}
+
+/// Deep pools (ids 1 and 2, on different dexes) with an exact price spread
+///
+/// The sell pool's quote reserve carries a sub-basis-point surplus so
+/// truncating spread calculations land on `spread_bps`, never one below.
+/// Pools are deep enough that a 1-token trade has negligible price impact.
+pub fn make_pools_with_spread(spread_bps: i64) -> SpreadFixture {
+    assert!(spread_bps >= 0, "spread must be non-negative");
+
+    let buy_reserve1 = FIXTURE_RESERVE0 * FIXTURE_PRICE;
+    let sell_reserve1 = buy_reserve1 / 10_000 * (10_000 + spread_bps as u128) + buy_reserve1 / 1_000_000_000;
+
+    SpreadFixture {
+        buy: fixture_pool(buy_reserve1, 1, dex::UNISWAP_V3),
+        sell: fixture_pool(sell_reserve1, 2, dex::SUSHISWAP),
+        spread_bps,
+        fee_bps: FIXTURE_FEE_BPS,
+    }
+}
+
+fn fixture_pool(reserve1: u128, pool_id: u32, dex_id: u32) -> PoolReserves {
+    let mut pool = PoolReserves::new(FIXTURE_RESERVE0, reserve1, pool_id, dex_id);
+    pool.timestamp_ms = 1_700_000_000_000;
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_price_rust, OpportunityScanner};
+
+    #[test]
+    fn test_fixture_spread_is_exact() {
+        let scanner = OpportunityScanner::new();
+        for bps in [0, 1, 7, 50, 99, 100, 333, 1_000, 5_000] {
+            let fixture = make_pools_with_spread(bps);
+            let buy = calculate_price_rust(&fixture.buy);
+            let sell = calculate_price_rust(&fixture.sell);
+            assert_eq!(scanner.calculate_spread_bps(&buy, &sell), bps, "fixture at {} bps", bps);
+        }
+    }
+
+    #[test]
+    fn test_scanner_finds_exact_fixture_opportunity() {
+        // 50 bps on the 5 bps tier: exactly one opportunity, in the right direction
+        let fixture = make_pools_with_spread(50);
+        let mut scanner = OpportunityScanner::new();
+        fixture.add_to(&mut scanner);
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+
+        let opp = &opportunities[0];
+        assert_eq!((opp.buy_pool_id, opp.buy_dex_id), (1, dex::UNISWAP_V3));
+        assert_eq!((opp.sell_pool_id, opp.sell_dex_id), (2, dex::SUSHISWAP));
+        assert_eq!(opp.spread_bps, 50);
+        assert_eq!(opp.hops, 2);
+        assert!(opp.is_profitable());
+
+        // The same spread doesn't cover two 0.3% swaps
+        let mut scanner = OpportunityScanner::new();
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+        assert!(scanner.scan().is_empty());
+    }
+
+    #[test]
+    fn test_price_updates_carry_fixture_pools() {
+        let (weth, usdc) = (Address::from_low_u64_be(0x100), Address::from_low_u64_be(0x101));
+        let fixture = make_pools_with_spread(50);
+        let [buy, sell] = fixture.price_updates(ChainId::Ethereum, weth, usdc);
+
+        assert_eq!((buy.dex, buy.pool), (DexId::UniswapV3, Address::from_low_u64_be(1)));
+        assert_eq!((sell.dex, sell.pool), (DexId::SushiSwap, Address::from_low_u64_be(2)));
+        assert_eq!(buy.fee_bps, Some(FIXTURE_FEE_BPS as u64));
+        assert_eq!(buy.reserve1.as_u128(), fixture.buy.reserve1.low128());
+        assert_eq!(buy.price.as_u128(), calculate_price_rust(&fixture.buy).price.low128());
+        assert_eq!(sell.price.as_u128(), calculate_price_rust(&fixture.sell).price.low128());
+        assert_eq!((sell.token0, sell.token1), (weth, usdc));
+    }
+}
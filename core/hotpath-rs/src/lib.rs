@@ -24,8 +24,18 @@
 //! assert!(!price.price.is_zero());
 //! ```
 
+use ethers_core::types::Address;
+use matrix_types::{Confidence, FlashLoanProvider};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod filter;
+
+pub use filter::{FilterChain, MaxAge, MinProfit, MinTradeSize, OpportunityFilter};
+
 #[derive(Error, Debug)]
 pub enum HotpathError {
     #[error("FFI call failed")]
@@ -73,6 +83,19 @@ impl U256 {
     }
 }
 
+impl Ord for U256 {
+    /// Compare limb-wise from the most significant limb
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl From<u64> for U256 {
     fn from(v: u64) -> Self {
         U256::new(v)
@@ -85,6 +108,18 @@ impl From<u128> for U256 {
     }
 }
 
+/// DEX identifiers (mirror `DexId` in hotpath/include/orderbook/OrderBook.hpp)
+pub mod dex {
+    pub const UNISWAP_V3: u32 = 1;
+    pub const SUSHISWAP: u32 = 2;
+    pub const CURVE: u32 = 3;
+    pub const BALANCER: u32 = 4;
+    pub const PANCAKESWAP: u32 = 5;
+    pub const CAMELOT: u32 = 6;
+    pub const VELODROME: u32 = 7;
+    pub const AERODROME: u32 = 8;
+}
+
 /// Pool reserves
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Default)]
@@ -100,7 +135,20 @@ pub struct PoolReserves {
 }
 
 impl PoolReserves {
+    /// Pool of two 18-decimal tokens
     pub fn new(reserve0: u128, reserve1: u128, pool_id: u32, dex_id: u32) -> Self {
+        Self::with_decimals(reserve0, reserve1, pool_id, dex_id, 18, 18)
+    }
+
+    /// Pool whose token decimals (from the token registry) are used when pricing
+    pub fn with_decimals(
+        reserve0: u128,
+        reserve1: u128,
+        pool_id: u32,
+        dex_id: u32,
+        decimals0: u8,
+        decimals1: u8,
+    ) -> Self {
         PoolReserves {
             reserve0: U256::from_u128(reserve0),
             reserve1: U256::from_u128(reserve1),
@@ -110,8 +158,8 @@ impl PoolReserves {
                 .duration_since(std::time::UNIX_EPOCH)
                 .map(|d| d.as_millis() as u64)
                 .unwrap_or(0),
-            decimals0: 18,
-            decimals1: 18,
+            decimals0,
+            decimals1,
             _padding: [0; 6],
         }
     }
@@ -143,6 +191,8 @@ pub struct ArbitrageOpportunity {
     pub max_amount: U256,
     pub estimated_profit: U256,
     pub timestamp_ms: u64,
+    /// Swaps in the route (2 for a buy/sell pair)
+    pub hops: u32,
 }
 
 impl ArbitrageOpportunity {
@@ -159,11 +209,22 @@ impl ArbitrageOpportunity {
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct ScannerConfig {
+    /// Minimum spread, net of `RouteCosts` fees
     pub min_spread_bps: i64,
     pub max_slippage_bps: i64,
     pub min_liquidity: U256,
+    /// Cap on `max_amount`; opportunities are sized and priced at most this
     pub max_position_size: U256,
     pub include_same_dex: bool,
+    /// Age at which a pool's confidence has halved (0 = no decay)
+    pub confidence_half_life_ms: u64,
+    /// Skip pools whose decayed confidence falls below this
+    pub min_confidence_bps: i64,
+    /// Drop opportunities whose `max_amount` is below this, however profitable (0 = no floor)
+    pub min_trade_size: U256,
+    /// Pools first seen less than this long ago are observe-only: priced
+    /// but never part of an opportunity (0 = off)
+    pub observation_period_ms: u64,
 }
 
 impl Default for ScannerConfig {
@@ -180,6 +241,10 @@ impl Default for ScannerConfig {
                 limbs: [0x8AC7230489E80000, 0x21E, 0, 0],
             },
             include_same_dex: false,
+            confidence_half_life_ms: 0,
+            min_confidence_bps: 0,
+            min_trade_size: U256::ZERO,
+            observation_period_ms: 0,
         }
     }
 }
@@ -206,15 +271,47 @@ pub fn calculate_price_rust(reserves: &PoolReserves) -> PriceResult {
         return result;
     }
 
-    // Price = reserve1 / reserve0 * 10^18
-    let precision: u128 = 1_000_000_000_000_000_000;
-    let price = (r1 as u128 * precision) / r0 as u128;
+    // Price = reserve1 / reserve0 * 10^18, scaled by 10^(decimals0 - decimals1)
+    // so tokens with different decimals compare in whole-token units
+    let exponent = 18 + reserves.decimals0 as i32 - reserves.decimals1 as i32;
+    let price = if exponent >= 0 {
+        scaled_ratio(r1, r0, exponent as u32)
+    } else {
+        // floor(floor(a / b) / c) == floor(a / (b * c)), without overflowing b * c
+        (0..exponent.unsigned_abs()).fold(r1 / r0, |q, _| q / 10)
+    };
 
     result.price = U256::from_u128(price);
+    result.confidence = liquidity_confidence(r0, r1);
+
+    result
+}
+
+/// `num * 10^exponent / den` without overflowing the intermediate product
+///
+/// Falls back to digit-by-digit long division when the direct product
+/// doesn't fit in u128; saturates if the quotient itself doesn't.
+fn scaled_ratio(num: u128, den: u128, exponent: u32) -> u128 {
+    if let Some(scaled) = 10u128.checked_pow(exponent).and_then(|p| num.checked_mul(p)) {
+        return scaled / den;
+    }
+
+    let mut quotient = num / den;
+    let mut remainder = num % den;
+    for _ in 0..exponent {
+        let Some(r) = remainder.checked_mul(10) else {
+            return u128::MAX;
+        };
+        quotient = quotient.saturating_mul(10).saturating_add(r / den);
+        remainder = r % den;
+    }
+    quotient
+}
 
-    // Simple confidence based on liquidity
+/// Simple confidence (bps) based on geometric-mean liquidity
+fn liquidity_confidence(r0: u128, r1: u128) -> i64 {
     let liquidity = ((r0 as f64) * (r1 as f64)).sqrt();
-    result.confidence = if liquidity >= 1e24 {
+    if liquidity >= 1e24 {
         10000
     } else if liquidity >= 1e21 {
         9000
@@ -222,16 +319,27 @@ pub fn calculate_price_rust(reserves: &PoolReserves) -> PriceResult {
         7000
     } else {
         3000
-    };
-
-    result
+    }
 }
 
-/// Calculate swap output (pure Rust implementation)
+/// LP fee of a Uniswap V2-style pool (0.3%)
+pub const DEFAULT_SWAP_FEE_BPS: u32 = 30;
+
+/// Calculate swap output (pure Rust implementation, 0.3% fee)
 pub fn calculate_swap_output_rust(
     reserve_in: &U256,
     reserve_out: &U256,
     amount_in: &U256,
+) -> U256 {
+    calculate_swap_output_with_fee(reserve_in, reserve_out, amount_in, DEFAULT_SWAP_FEE_BPS)
+}
+
+/// Calculate swap output for a pool charging `fee_bps` on the input
+pub fn calculate_swap_output_with_fee(
+    reserve_in: &U256,
+    reserve_out: &U256,
+    amount_in: &U256,
+    fee_bps: u32,
 ) -> U256 {
     if reserve_in.is_zero() || amount_in.is_zero() {
         return U256::ZERO;
@@ -240,10 +348,11 @@ pub fn calculate_swap_output_rust(
     let r_in = reserve_in.low128();
     let r_out = reserve_out.low128();
     let a_in = amount_in.low128();
+    let keep = 10_000 - (fee_bps as u128).min(10_000);
 
-    // amountOut = (reserveOut * amountIn * 997) / (reserveIn * 1000 + amountIn * 997)
+    // amountOut = (reserveOut * amountIn * keep) / (reserveIn * 10000 + amountIn * keep)
     // Use checked arithmetic to avoid overflow
-    let amount_in_with_fee = match a_in.checked_mul(997) {
+    let amount_in_with_fee = match a_in.checked_mul(keep) {
         Some(v) => v,
         None => return U256::ZERO, // Overflow - amount too large
     };
@@ -253,12 +362,12 @@ pub fn calculate_swap_output_rust(
         None => {
             // Use floating point approximation for very large values
             let result = (r_out as f64 * amount_in_with_fee as f64) /
-                         (r_in as f64 * 1000.0 + amount_in_with_fee as f64);
+                         (r_in as f64 * 10_000.0 + amount_in_with_fee as f64);
             return U256::from_u128(result as u128);
         }
     };
 
-    let denominator = match r_in.checked_mul(1000).and_then(|v| v.checked_add(amount_in_with_fee)) {
+    let denominator = match r_in.checked_mul(10_000).and_then(|v| v.checked_add(amount_in_with_fee)) {
         Some(v) => v,
         None => return U256::ZERO,
     };
@@ -270,6 +379,353 @@ pub fn calculate_swap_output_rust(
     U256::from_u128(numerator / denominator)
 }
 
+/// Swap output for fee-on-transfer tokens
+///
+/// `in_fee_bps` is taken as the input moves to the pool, so only the rest is
+/// swapped; `out_fee_bps` is taken as the output moves to the recipient.
+pub fn calculate_swap_output_with_transfer_fee(
+    reserve_in: &U256,
+    reserve_out: &U256,
+    amount_in: &U256,
+    in_fee_bps: u64,
+    out_fee_bps: u64,
+) -> U256 {
+    let reaching_pool = U256::from_u128(deduct_bps(amount_in.low128(), in_fee_bps));
+    let output = calculate_swap_output_rust(reserve_in, reserve_out, &reaching_pool);
+    U256::from_u128(deduct_bps(output.low128(), out_fee_bps))
+}
+
+/// `amount * (10000 - fee_bps) / 10000` without overflowing u128
+fn deduct_bps(amount: u128, fee_bps: u64) -> u128 {
+    let fee_bps = (fee_bps as u128).min(10_000);
+    let fee = amount / 10_000 * fee_bps + amount % 10_000 * fee_bps / 10_000;
+    amount - fee
+}
+
+/// Input that maximises profit buying token0 with token1 in `buy` and
+/// selling it back in `sell`, each pool charging its own fee
+///
+/// Folds both pools into one virtual pool (E_in, E_out) behind the buy
+/// pool's fee factor γ; the optimum is `(sqrt(E_in * E_out * γ) - E_in) / γ`,
+/// zero if there is no edge.
+pub fn calculate_optimal_trade_size(
+    buy: &PoolReserves,
+    sell: &PoolReserves,
+    buy_fee_bps: u32,
+    sell_fee_bps: u32,
+) -> U256 {
+    let gamma = |fee_bps: u32| 1.0 - fee_bps.min(10_000) as f64 / 10_000.0;
+    let (buy_gamma, sell_gamma) = (gamma(buy_fee_bps), gamma(sell_fee_bps));
+    let (buy_in, buy_out) = (buy.reserve1.low128() as f64, buy.reserve0.low128() as f64);
+    let (sell_in, sell_out) = (sell.reserve0.low128() as f64, sell.reserve1.low128() as f64);
+
+    let denominator = sell_in + sell_gamma * buy_out;
+    if denominator <= 0.0 || buy_gamma <= 0.0 {
+        return U256::ZERO;
+    }
+    let virtual_in = buy_in * sell_in / denominator;
+    let virtual_out = sell_gamma * buy_out * sell_out / denominator;
+
+    let optimal = ((virtual_in * virtual_out * buy_gamma).sqrt() - virtual_in) / buy_gamma;
+    if optimal.is_finite() && optimal > 0.0 {
+        U256::from_u128(optimal as u128)
+    } else {
+        U256::ZERO
+    }
+}
+
+// ============================================================================
+// STABLE-SWAP / WEIGHTED POOL MATH
+// ============================================================================
+
+/// Default Curve amplification coefficient when none is configured
+pub const DEFAULT_CURVE_AMP: u64 = 100;
+
+/// Pricing model for a pool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoolKind {
+    /// Uniswap V2-style x * y = k
+    ConstantProduct,
+    /// Curve 2-asset stable-swap invariant
+    StableSwap { amp: u64 },
+    /// Balancer weighted pool (weights in any common scale, e.g. 80/20)
+    Weighted { weight0: u64, weight1: u64 },
+}
+
+impl PoolKind {
+    /// Default pricing model for a hotpath DEX id
+    pub fn for_dex(dex_id: u32) -> Self {
+        match dex_id {
+            dex::CURVE => PoolKind::StableSwap { amp: DEFAULT_CURVE_AMP },
+            dex::BALANCER => PoolKind::Weighted { weight0: 50, weight1: 50 },
+            _ => PoolKind::ConstantProduct,
+        }
+    }
+}
+
+/// Curve invariant D for a 2-asset pool (Newton's method, as in `get_D`)
+fn stableswap_invariant(x0: f64, x1: f64, amp: f64) -> f64 {
+    let sum = x0 + x1;
+    if sum == 0.0 {
+        return 0.0;
+    }
+
+    let ann = amp * 4.0; // A * n^n
+    let mut d = sum;
+    for _ in 0..255 {
+        let d_p = d * d / (x0 * 2.0) * d / (x1 * 2.0);
+        let prev = d;
+        d = (ann * sum + d_p * 2.0) * d / ((ann - 1.0) * d + 3.0 * d_p);
+        if (d - prev).abs() <= d * 1e-12 {
+            break;
+        }
+    }
+    d
+}
+
+/// Spot price of token0 in token1 for a 2-asset Curve pool (18 decimals)
+///
+/// Marginal price from the stable-swap invariant
+/// `A*n^n*(x0 + x1) + D = A*D*n^n + D^3 / (n^n * x0 * x1)`.
+pub fn calculate_price_stableswap(balances: [u128; 2], amp: u64) -> U256 {
+    let [b0, b1] = balances;
+    if b0 == 0 || b1 == 0 || amp == 0 {
+        return U256::ZERO;
+    }
+
+    let (x0, x1) = (b0 as f64, b1 as f64);
+    let ann = amp as f64 * 4.0;
+    let d = stableswap_invariant(x0, x1, amp as f64);
+
+    // price = (dF/dx0) / (dF/dx1)
+    let d3 = d * d * d / 4.0;
+    let dx0 = ann + d3 / (x0 * x0 * x1);
+    let dx1 = ann + d3 / (x0 * x1 * x1);
+
+    U256::from_u128((dx0 / dx1 * 1e18) as u128)
+}
+
+/// Spot price of token0 in token1 for a Balancer weighted pool (18 decimals)
+///
+/// `price = (B1 / W1) / (B0 / W0)`, ignoring swap fees.
+pub fn calculate_price_weighted(balances: [u128; 2], weights: [u64; 2]) -> U256 {
+    let [b0, b1] = balances;
+    let [w0, w1] = weights;
+    if b0 == 0 || w1 == 0 {
+        return U256::ZERO;
+    }
+
+    let price = (b1 as f64 * w0 as f64) / (b0 as f64 * w1 as f64) * 1e18;
+    U256::from_u128(price as u128)
+}
+
+/// Calculate price using the pricing model for `kind`
+pub fn calculate_price_for_kind(reserves: &PoolReserves, kind: PoolKind) -> PriceResult {
+    let balances = [reserves.reserve0.low128(), reserves.reserve1.low128()];
+    let price = match kind {
+        PoolKind::ConstantProduct => return calculate_price_rust(reserves),
+        PoolKind::StableSwap { amp } => calculate_price_stableswap(balances, amp),
+        PoolKind::Weighted { weight0, weight1 } => {
+            calculate_price_weighted(balances, [weight0, weight1])
+        }
+    };
+
+    PriceResult {
+        price,
+        timestamp_ms: reserves.timestamp_ms,
+        pool_id: reserves.pool_id,
+        dex_id: reserves.dex_id,
+        confidence: if price.is_zero() {
+            0
+        } else {
+            liquidity_confidence(balances[0], balances[1])
+        },
+        _padding: [0; 4],
+    }
+}
+
+// ============================================================================
+// ROUTE COSTS
+// ============================================================================
+
+/// Per-hop execution cost applied by the scanner's profit model
+#[derive(Debug, Clone, Copy)]
+pub struct RouteCosts {
+    /// Gas cost of one swap, in wei of the traded token
+    pub gas_per_hop: U256,
+    /// Routes with more swaps than this are not considered
+    pub max_hops: u32,
+    /// LP fee charged on each swap through a pool without its own fee
+    pub swap_fee_bps: u32,
+    /// Flash loan premium on the borrowed amount
+    pub flash_loan_fee_bps: u32,
+}
+
+impl Default for RouteCosts {
+    fn default() -> Self {
+        RouteCosts {
+            gas_per_hop: U256::ZERO,
+            max_hops: 3,
+            swap_fee_bps: DEFAULT_SWAP_FEE_BPS,
+            flash_loan_fee_bps: FlashLoanProvider::Aave.premium_bps() as u32,
+        }
+    }
+}
+
+impl RouteCosts {
+    /// Spread left after `hops` swap fees and the flash loan premium
+    ///
+    /// Fees compound: `(1 + gross) * (1 - swap_fee)^hops * (1 - premium) - 1`,
+    /// rounded down.
+    pub fn net_spread_bps(&self, gross_bps: i64, hops: u32) -> i64 {
+        self.net_spread_bps_with_fees(gross_bps, std::iter::repeat_n(self.swap_fee_bps, hops as usize))
+    }
+
+    /// Spread left after one swap per entry of `swap_fees_bps` and the
+    /// flash loan premium, compounded as for `net_spread_bps`
+    pub fn net_spread_bps_with_fees(&self, gross_bps: i64, swap_fees_bps: impl IntoIterator<Item = u32>) -> i64 {
+        const ONE: i128 = 10_000;
+        // Extra precision so per-step truncation can't move the result
+        const SCALE: i128 = 100_000_000;
+        let keep = |fee_bps: u32| ONE - (fee_bps as i128).min(ONE);
+
+        let mut value = (ONE + gross_bps as i128) * SCALE;
+        for fee_bps in swap_fees_bps {
+            value = value * keep(fee_bps) / ONE;
+        }
+        value = value * keep(self.flash_loan_fee_bps) / ONE;
+        (value.div_euclid(SCALE) - ONE) as i64
+    }
+}
+
+/// Gross spread a two-pool round trip needs to break even, rounded up
+///
+/// Buying on pool A and selling on pool B keeps `(1 - fee_a) * (1 - fee_b)`
+/// of the trade, and gas costs `gas_cost_wei / trade_size_wei` on top, so
+/// `(1 + spread) * (1 - fee_a) * (1 - fee_b) >= 1 + gas / size`. Gas is in
+/// wei of the traded token, as for `RouteCosts::gas_per_hop`. Returns
+/// `i64::MAX` when no spread can break even (zero size, 100% fee).
+pub fn breakeven_spread_bps(fee_a_bps: u32, fee_b_bps: u32, gas_cost_wei: U256, trade_size_wei: U256) -> i64 {
+    const ONE: u128 = 10_000;
+    // Extra precision for the gas fraction
+    const SCALE: u128 = 100_000_000;
+    let keep = |fee_bps: u32| ONE.saturating_sub(fee_bps as u128);
+
+    let size = trade_size_wei.low128();
+    let kept = keep(fee_a_bps) * keep(fee_b_bps);
+    if size == 0 || kept == 0 {
+        return i64::MAX;
+    }
+
+    // 1 + gas / size, in bps scaled by SCALE
+    let gas = gas_cost_wei.low128().saturating_mul(ONE * SCALE) / size;
+    let target = (ONE * SCALE).saturating_add(gas);
+    let required = target.saturating_mul(ONE * ONE).div_ceil(kept * SCALE);
+    i64::try_from(required - ONE).unwrap_or(i64::MAX)
+}
+
+/// One swap in a route
+#[derive(Debug, Clone, Copy)]
+pub struct RouteHop {
+    pub pool: PoolReserves,
+    /// Swap token0 in for token1 out (else token1 for token0)
+    pub zero_for_one: bool,
+}
+
+impl RouteHop {
+    pub fn new(pool: PoolReserves, zero_for_one: bool) -> Self {
+        RouteHop { pool, zero_for_one }
+    }
+
+    fn output(&self, amount_in: &U256, fee_bps: u32) -> U256 {
+        if self.zero_for_one {
+            calculate_swap_output_with_fee(&self.pool.reserve0, &self.pool.reserve1, amount_in, fee_bps)
+        } else {
+            calculate_swap_output_with_fee(&self.pool.reserve1, &self.pool.reserve0, amount_in, fee_bps)
+        }
+    }
+}
+
+// ============================================================================
+// OPPORTUNITY SCORING
+// ============================================================================
+
+/// Weights for combining profit, confidence, and gas into one score
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreWeights {
+    /// Gas units assumed per execution when pricing gas cost
+    pub gas_units: u64,
+    /// Multiplier on gas cost (> 1.0 penalizes gas more heavily)
+    pub gas_penalty: f64,
+    /// Exponent applied to confidence (0.0 ignores confidence)
+    pub confidence_exponent: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            gas_units: 300_000,
+            gas_penalty: 1.0,
+            confidence_exponent: 1.0,
+        }
+    }
+}
+
+/// Confidence (bps) halved for every `half_life_ms` of age
+///
+/// A half-life of 0 disables decay.
+pub fn decay_confidence_bps(confidence_bps: i64, age_ms: u64, half_life_ms: u64) -> i64 {
+    if half_life_ms == 0 || age_ms == 0 {
+        return confidence_bps;
+    }
+    let factor = 0.5f64.powf(age_ms as f64 / half_life_ms as f64);
+    (confidence_bps as f64 * factor) as i64
+}
+
+/// Convert a hotpath confidence (basis points) to `Confidence`
+pub fn confidence_from_bps(bps: i64) -> Confidence {
+    Confidence::from_bps(bps.clamp(0, Confidence::MAX_BPS as i64) as u16)
+}
+
+/// Score an opportunity: net profit (after weighted gas) scaled by confidence
+///
+/// Higher is better. Unprofitable opportunities score their (negative)
+/// net profit unweighted, so confidence never makes a loss look better.
+pub fn score(
+    opp: &ArbitrageOpportunity,
+    confidence: Confidence,
+    gas_price: &U256,
+    weights: &ScoreWeights,
+) -> f64 {
+    let profit = opp.estimated_profit.low128() as f64;
+    let gas_cost = gas_price.low128() as f64 * weights.gas_units as f64 * weights.gas_penalty;
+    let net = profit - gas_cost;
+
+    if net <= 0.0 {
+        return net;
+    }
+
+    net * confidence.as_f64().powf(weights.confidence_exponent)
+}
+
+/// Sort opportunities by estimated profit, highest first (full 256-bit compare)
+///
+/// Ties are broken by pool ids, so the order doesn't depend on scan order.
+pub fn sort_by_profit(opportunities: &mut [ArbitrageOpportunity]) {
+    opportunities.sort_by_key(|o| {
+        (
+            std::cmp::Reverse(o.estimated_profit),
+            o.buy_pool_id,
+            o.buy_dex_id,
+            o.sell_pool_id,
+            o.sell_dex_id,
+        )
+    });
+}
+
+/// First `depth_curve` sample, as bps of the input reserve
+pub const DEPTH_CURVE_START_BPS: u128 = 10;
+
 /// Batch price calculator (pure Rust)
 pub struct PriceCalculator {
     pools: Vec<PoolReserves>,
@@ -299,6 +755,33 @@ impl PriceCalculator {
     pub fn pool_count(&self) -> usize {
         self.pools.len()
     }
+
+    /// Sample the constant-product curve for token0 -> token1 swaps
+    ///
+    /// Inputs double from `DEPTH_CURVE_START_BPS` of `reserve0`, one per
+    /// step. Each point is `(amount_in, amount_out, price_impact_bps)`, with
+    /// impact measured against the spot price and including the 0.3% fee.
+    pub fn depth_curve(&self, reserves: &PoolReserves, steps: usize) -> Vec<(U256, U256, i64)> {
+        let r0 = reserves.reserve0.low128();
+        let r1 = reserves.reserve1.low128();
+        if r0 == 0 || r1 == 0 {
+            return Vec::new();
+        }
+
+        let start = (r0 / 10_000 * DEPTH_CURVE_START_BPS).max(1);
+        let spot = r1 as f64 / r0 as f64;
+        (0..steps)
+            .map_while(|i| start.checked_mul(1u128.checked_shl(i as u32)?))
+            .map(|amount_in| {
+                let amount_in = U256::from_u128(amount_in);
+                let amount_out =
+                    calculate_swap_output_rust(&reserves.reserve0, &reserves.reserve1, &amount_in);
+                let execution = amount_out.low128() as f64 / amount_in.low128() as f64;
+                let impact_bps = ((1.0 - execution / spot) * 10_000.0).floor() as i64;
+                (amount_in, amount_out, impact_bps)
+            })
+            .collect()
+    }
 }
 
 impl Default for PriceCalculator {
@@ -307,10 +790,70 @@ impl Default for PriceCalculator {
     }
 }
 
+/// Token pair in canonical (lower address first) order
+pub fn canonical_pair(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a <= token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// Token decimals lookup (e.g. Morpheus' token registry)
+pub trait TokenDecimals: Send + Sync {
+    /// Decimals of `token`, if known
+    fn decimals(&self, token: &Address) -> Option<u8>;
+}
+
+/// `raw` token units rescaled to 18 decimals (saturating)
+fn normalized_reserve(raw: u128, decimals: u8) -> u128 {
+    if decimals <= 18 {
+        raw.saturating_mul(10u128.pow(18 - decimals as u32))
+    } else {
+        raw / 10u128.checked_pow(decimals as u32 - 18).unwrap_or(u128::MAX)
+    }
+}
+
+/// Current Unix time in milliseconds
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Amount traded when evaluating explicit routes (1 token at 18 decimals)
+const TRADE_SIZE: u64 = 1_000_000_000_000_000_000;
+
 /// Opportunity scanner (pure Rust)
 pub struct OpportunityScanner {
     config: ScannerConfig,
+    score_weights: ScoreWeights,
+    route_costs: RouteCosts,
     pools: Vec<(PoolReserves, PriceResult)>,
+    /// Per-pool pricing overrides keyed by (pool_id, dex_id)
+    pool_kinds: HashMap<(u32, u32), PoolKind>,
+    /// Per-pool LP fees keyed by (pool_id, dex_id), e.g. a V3 pool's fee tier
+    pool_fees: HashMap<(u32, u32), u32>,
+    /// Known-bad pools (pool_id, dex_id) never used in opportunities;
+    /// a variable-length set can't live in the `repr(C)` `ScannerConfig`,
+    /// so the C++ scanner doesn't apply it
+    blacklisted_pools: HashSet<(u32, u32)>,
+    /// Token pair of each pool, keyed by (pool_id, dex_id)
+    pool_tokens: HashMap<(u32, u32), (Address, Address)>,
+    /// Canonical pairs to trade exclusively (None = all pairs)
+    allowed_pairs: Option<HashSet<(Address, Address)>>,
+    /// Indices into `pools` by canonical token pair (None = pair unknown)
+    pair_index: HashMap<Option<(Address, Address)>, Vec<usize>>,
+    /// Run on every candidate; starts with profit and `min_trade_size`
+    filters: FilterChain,
+    /// Candidates rejected, by filter name
+    rejections: Mutex<HashMap<String, u64>>,
+    /// Timestamp of each pool's first update, keyed by (pool_id, dex_id);
+    /// survives `clear` so a re-added pool isn't observed again
+    first_seen: HashMap<(u32, u32), u64>,
+    /// Decimals for pools with known tokens (None = as given in updates)
+    token_decimals: Option<Arc<dyn TokenDecimals>>,
 }
 
 impl OpportunityScanner {
@@ -321,12 +864,206 @@ impl OpportunityScanner {
     pub fn with_config(config: ScannerConfig) -> Self {
         OpportunityScanner {
             config,
+            score_weights: ScoreWeights::default(),
+            route_costs: RouteCosts::default(),
             pools: Vec::new(),
+            pool_kinds: HashMap::new(),
+            pool_fees: HashMap::new(),
+            blacklisted_pools: HashSet::new(),
+            pool_tokens: HashMap::new(),
+            allowed_pairs: None,
+            pair_index: HashMap::new(),
+            filters: FilterChain::new()
+                .with(MinProfit(U256::ZERO))
+                .with(MinTradeSize(config.min_trade_size)),
+            rejections: Mutex::new(HashMap::new()),
+            first_seen: HashMap::new(),
+            token_decimals: None,
+        }
+    }
+
+    /// Also run `filter` on every candidate, after the existing filters
+    pub fn with_filter(mut self, filter: impl OpportunityFilter + 'static) -> Self {
+        self.add_filter(filter);
+        self
+    }
+
+    pub fn add_filter(&mut self, filter: impl OpportunityFilter + 'static) {
+        self.filters.push(filter);
+    }
+
+    /// Candidates rejected so far, by name of the first filter to reject them
+    pub fn filter_rejections(&self) -> HashMap<String, u64> {
+        self.rejections.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Start with these (pool_id, dex_id) pools blacklisted
+    pub fn with_blacklist(mut self, pools: HashSet<(u32, u32)>) -> Self {
+        self.blacklisted_pools = pools;
+        self
+    }
+
+    /// Skip a pool in all future scans (e.g. after repeated failures)
+    ///
+    /// Returns false if it was already blacklisted.
+    pub fn blacklist_pool(&mut self, pool_id: u32, dex_id: u32) -> bool {
+        self.blacklisted_pools.insert((pool_id, dex_id))
+    }
+
+    /// Allow a blacklisted pool again; returns false if it wasn't listed
+    pub fn unblacklist_pool(&mut self, pool_id: u32, dex_id: u32) -> bool {
+        self.blacklisted_pools.remove(&(pool_id, dex_id))
+    }
+
+    pub fn is_blacklisted(&self, pool_id: u32, dex_id: u32) -> bool {
+        self.blacklisted_pools.contains(&(pool_id, dex_id))
+    }
+
+    /// Only trade these pairs; pools with an unknown pair are skipped
+    pub fn with_allowed_pairs(mut self, pairs: impl IntoIterator<Item = (Address, Address)>) -> Self {
+        self.set_allowed_pairs(Some(pairs.into_iter().collect()));
+        self
+    }
+
+    /// Set the pair allowlist (None = all pairs); pairs are canonicalized
+    pub fn set_allowed_pairs(&mut self, pairs: Option<HashSet<(Address, Address)>>) {
+        self.allowed_pairs = pairs.map(|pairs| pairs.into_iter().map(|(a, b)| canonical_pair(a, b)).collect());
+    }
+
+    /// Record the token pair a pool trades
+    ///
+    /// Used by the pair allowlist and to only compare pools on the same pair.
+    pub fn set_pool_tokens(&mut self, pool_id: u32, dex_id: u32, token0: Address, token1: Address) {
+        let old_key = self.pair_key(pool_id, dex_id);
+        self.pool_tokens.insert((pool_id, dex_id), (token0, token1));
+        let new_key = self.pair_key(pool_id, dex_id);
+
+        let slot = self.pools.iter().position(|(p, _)| p.pool_id == pool_id && p.dex_id == dex_id);
+        if let (Some(slot), true) = (slot, old_key != new_key) {
+            if let Some(members) = self.pair_index.get_mut(&old_key) {
+                members.retain(|&i| i != slot);
+            }
+            self.pair_index.entry(new_key).or_default().push(slot);
         }
     }
 
-    pub fn update_pool(&mut self, reserves: PoolReserves) {
-        let price = calculate_price_rust(&reserves);
+    /// Look up decimals for pools with known tokens on every update
+    ///
+    /// Overrides the decimals carried by `PoolReserves` where the source
+    /// knows the token; others keep theirs.
+    pub fn set_token_decimals(&mut self, source: Arc<dyn TokenDecimals>) {
+        self.token_decimals = Some(source);
+    }
+
+    /// `pair_index` key of a pool
+    fn pair_key(&self, pool_id: u32, dex_id: u32) -> Option<(Address, Address)> {
+        self.pool_tokens
+            .get(&(pool_id, dex_id))
+            .map(|&(token0, token1)| canonical_pair(token0, token1))
+    }
+
+    /// Whether the pool's pair passes the allowlist
+    pub fn is_pair_allowed(&self, pool_id: u32, dex_id: u32) -> bool {
+        let Some(allowed) = &self.allowed_pairs else {
+            return true;
+        };
+        self.pool_tokens
+            .get(&(pool_id, dex_id))
+            .is_some_and(|&(token0, token1)| allowed.contains(&canonical_pair(token0, token1)))
+    }
+
+    /// Not blacklisted and on an allowed pair
+    fn is_tradable(&self, pool_id: u32, dex_id: u32) -> bool {
+        !self.is_blacklisted(pool_id, dex_id) && self.is_pair_allowed(pool_id, dex_id)
+    }
+
+    /// Timestamp of the pool's first update, if seen
+    pub fn first_seen(&self, pool_id: u32, dex_id: u32) -> Option<u64> {
+        self.first_seen.get(&(pool_id, dex_id)).copied()
+    }
+
+    /// Still within `observation_period_ms` of its first update at `now_ms`
+    ///
+    /// Observed pools are priced but produce no opportunities, so thin or
+    /// manipulated launch liquidity can't be traded against.
+    pub fn is_observing(&self, pool_id: u32, dex_id: u32, now_ms: u64) -> bool {
+        self.first_seen(pool_id, dex_id)
+            .is_some_and(|first_seen| now_ms.saturating_sub(first_seen) < self.config.observation_period_ms)
+    }
+
+    /// Override the pricing model for a pool (e.g. a Curve pool's amp)
+    pub fn set_pool_kind(&mut self, pool_id: u32, dex_id: u32, kind: PoolKind) {
+        self.pool_kinds.insert((pool_id, dex_id), kind);
+    }
+
+    /// Pricing model for a pool: explicit override, else the DEX default
+    pub fn pool_kind(&self, pool_id: u32, dex_id: u32) -> PoolKind {
+        self.pool_kinds
+            .get(&(pool_id, dex_id))
+            .copied()
+            .unwrap_or_else(|| PoolKind::for_dex(dex_id))
+    }
+
+    /// Set a pool's LP fee (e.g. its V3 fee tier)
+    pub fn set_pool_fee(&mut self, pool_id: u32, dex_id: u32, fee_bps: u32) {
+        self.pool_fees.insert((pool_id, dex_id), fee_bps);
+    }
+
+    /// LP fee of a pool: explicit fee, else `RouteCosts::swap_fee_bps`
+    pub fn pool_fee_bps(&self, pool_id: u32, dex_id: u32) -> u32 {
+        self.pool_fees
+            .get(&(pool_id, dex_id))
+            .copied()
+            .unwrap_or(self.route_costs.swap_fee_bps)
+    }
+
+    /// Set the weights used by `scan_scored`
+    pub fn set_score_weights(&mut self, weights: ScoreWeights) {
+        self.score_weights = weights;
+    }
+
+    /// Set per-hop gas and the hop limit used by the profit model
+    pub fn set_route_costs(&mut self, costs: RouteCosts) {
+        self.route_costs = costs;
+    }
+
+    /// Both reserves, normalized to 18 decimals, nonzero and at least `min_liquidity`
+    ///
+    /// Drained or migrated pools price at zero and would pair into garbage spreads.
+    pub fn has_min_liquidity(&self, reserves: &PoolReserves) -> bool {
+        let min_liquidity = self.config.min_liquidity.low128();
+        [(reserves.reserve0, reserves.decimals0), (reserves.reserve1, reserves.decimals1)]
+            .into_iter()
+            .all(|(reserve, decimals)| {
+                !reserve.is_zero() && normalized_reserve(reserve.low128(), decimals) >= min_liquidity
+            })
+    }
+
+    /// Add or refresh a pool
+    ///
+    /// A pool not yet tracked is ignored while below `min_liquidity`; a
+    /// tracked one that drains is kept up to date but no longer compared.
+    pub fn update_pool(&mut self, mut reserves: PoolReserves) {
+        if let (Some(source), Some((token0, token1))) =
+            (&self.token_decimals, self.pool_tokens.get(&(reserves.pool_id, reserves.dex_id)))
+        {
+            reserves.decimals0 = source.decimals(token0).unwrap_or(reserves.decimals0);
+            reserves.decimals1 = source.decimals(token1).unwrap_or(reserves.decimals1);
+        }
+
+        let tracked = self
+            .pools
+            .iter()
+            .any(|(p, _)| p.pool_id == reserves.pool_id && p.dex_id == reserves.dex_id);
+        if !tracked && !self.has_min_liquidity(&reserves) {
+            return;
+        }
+
+        self.first_seen
+            .entry((reserves.pool_id, reserves.dex_id))
+            .or_insert(reserves.timestamp_ms);
+        let kind = self.pool_kind(reserves.pool_id, reserves.dex_id);
+        let price = calculate_price_for_kind(&reserves, kind);
 
         // Update existing or add new
         if let Some(entry) = self.pools.iter_mut().find(|(p, _)| {
@@ -334,56 +1071,255 @@ impl OpportunityScanner {
         }) {
             *entry = (reserves, price);
         } else {
+            let key = self.pair_key(reserves.pool_id, reserves.dex_id);
+            self.pair_index.entry(key).or_default().push(self.pools.len());
             self.pools.push((reserves, price));
         }
     }
 
     pub fn scan(&self) -> Vec<ArbitrageOpportunity> {
+        self.scan_at(now_ms())
+    }
+
+    /// Scan with pool ages measured from `now_ms`
+    ///
+    /// Only pools on the same token pair are compared; pools whose pair is
+    /// unknown are compared against every pool, as before pairs were tracked.
+    pub fn scan_at(&self, now_ms: u64) -> Vec<ArbitrageOpportunity> {
+        let mut opportunities = Vec::new();
+
+        for (key, members) in &self.pair_index {
+            if key.is_none() {
+                continue;
+            }
+            for (n, &a) in members.iter().enumerate() {
+                for &b in &members[n + 1..] {
+                    self.compare_pools(a.min(b), a.max(b), now_ms, &mut opportunities);
+                }
+            }
+        }
+
+        if let Some(unknown) = self.pair_index.get(&None) {
+            let mut is_unknown = vec![false; self.pools.len()];
+            for &a in unknown {
+                is_unknown[a] = true;
+            }
+            for &a in unknown {
+                // Unknown-unknown combinations are visited once, from the lower index
+                for b in (0..self.pools.len()).filter(|&b| b != a && (b > a || !is_unknown[b])) {
+                    self.compare_pools(a.min(b), a.max(b), now_ms, &mut opportunities);
+                }
+            }
+        }
+
+        sort_by_profit(&mut opportunities);
+        opportunities
+    }
+
+    /// Reference O(n²) scan over every pool combination
+    ///
+    /// Skips combinations on different known pairs, so it returns exactly
+    /// what `scan_at` does; kept for equivalence tests and benchmarks.
+    pub fn scan_brute_force(&self, now_ms: u64) -> Vec<ArbitrageOpportunity> {
         let mut opportunities = Vec::new();
 
         for i in 0..self.pools.len() {
             for j in (i + 1)..self.pools.len() {
-                let (pool_a, price_a) = &self.pools[i];
-                let (pool_b, price_b) = &self.pools[j];
-
-                if !self.config.include_same_dex && pool_a.dex_id == pool_b.dex_id {
+                let (pool_a, pool_b) = (&self.pools[i].0, &self.pools[j].0);
+                let key_a = self.pair_key(pool_a.pool_id, pool_a.dex_id);
+                let key_b = self.pair_key(pool_b.pool_id, pool_b.dex_id);
+                if key_a.is_some() && key_b.is_some() && key_a != key_b {
                     continue;
                 }
+                self.compare_pools(i, j, now_ms, &mut opportunities);
+            }
+        }
 
-                // Check spread in both directions
-                let spread_ab = self.calculate_spread_bps(price_a, price_b);
-                let spread_ba = self.calculate_spread_bps(price_b, price_a);
+        sort_by_profit(&mut opportunities);
+        opportunities
+    }
 
-                if spread_ab >= self.config.min_spread_bps {
-                    let opp = self.create_opportunity(pool_a, price_a, pool_b, price_b, spread_ab);
-                    if opp.is_profitable() {
-                        opportunities.push(opp);
-                    }
-                }
+    /// Push the profitable directions between pools `i` and `j`
+    fn compare_pools(&self, i: usize, j: usize, now_ms: u64, opportunities: &mut Vec<ArbitrageOpportunity>) {
+        let (pool_a, price_a) = &self.pools[i];
+        let (pool_b, price_b) = &self.pools[j];
 
-                if spread_ba >= self.config.min_spread_bps {
-                    let opp = self.create_opportunity(pool_b, price_b, pool_a, price_a, spread_ba);
-                    if opp.is_profitable() {
-                        opportunities.push(opp);
-                    }
-                }
+        if !self.is_tradable(pool_a.pool_id, pool_a.dex_id)
+            || !self.is_tradable(pool_b.pool_id, pool_b.dex_id)
+        {
+            return;
+        }
+
+        if !self.config.include_same_dex && pool_a.dex_id == pool_b.dex_id {
+            return;
+        }
+
+        if !self.has_min_liquidity(pool_a) || !self.has_min_liquidity(pool_b) {
+            return;
+        }
+
+        if self.is_observing(pool_a.pool_id, pool_a.dex_id, now_ms)
+            || self.is_observing(pool_b.pool_id, pool_b.dex_id, now_ms)
+        {
+            return;
+        }
+
+        if self.decayed_confidence(price_a, now_ms) < self.config.min_confidence_bps
+            || self.decayed_confidence(price_b, now_ms) < self.config.min_confidence_bps
+        {
+            return;
+        }
+
+        // Check spread in both directions, net of both pools' fees
+        let spread_ab = self.calculate_spread_bps(price_a, price_b);
+        let spread_ba = self.calculate_spread_bps(price_b, price_a);
+        let fees = [
+            self.pool_fee_bps(pool_a.pool_id, pool_a.dex_id),
+            self.pool_fee_bps(pool_b.pool_id, pool_b.dex_id),
+        ];
+
+        if self.route_costs.net_spread_bps_with_fees(spread_ab, fees) >= self.config.min_spread_bps {
+            let opp = self.create_opportunity(pool_a, price_a, pool_b, price_b, spread_ab);
+            if self.passes_filters(&opp, now_ms) {
+                opportunities.push(opp);
             }
         }
 
-        // Sort by profit descending
-        opportunities.sort_by(|a, b| {
-            b.estimated_profit.low128().cmp(&a.estimated_profit.low128())
-        });
+        if self.route_costs.net_spread_bps_with_fees(spread_ba, fees) >= self.config.min_spread_bps {
+            let opp = self.create_opportunity(pool_b, price_b, pool_a, price_a, spread_ba);
+            if self.passes_filters(&opp, now_ms) {
+                opportunities.push(opp);
+            }
+        }
+    }
+
+    /// Run the filter chain, counting the rejecting filter
+    fn passes_filters(&self, opp: &ArbitrageOpportunity, now_ms: u64) -> bool {
+        let Err(name) = self.filters.check(opp, now_ms) else {
+            return true;
+        };
+        let mut rejections = self.rejections.lock().unwrap_or_else(|e| e.into_inner());
+        match rejections.get_mut(name) {
+            Some(count) => *count += 1,
+            None => {
+                rejections.insert(name.to_string(), 1);
+            }
+        }
+        false
+    }
+
+    /// Scan and rank by score (profit, pool confidence, and gas) instead of raw profit
+    pub fn scan_scored(&self, gas_price: &U256) -> Vec<(ArbitrageOpportunity, f64)> {
+        let now_ms = now_ms();
+        let mut scored: Vec<(ArbitrageOpportunity, f64)> = self
+            .scan_at(now_ms)
+            .into_iter()
+            .map(|opp| {
+                let confidence = self.opportunity_confidence(&opp, now_ms);
+                let s = score(&opp, confidence, gas_price, &self.score_weights);
+                (opp, s)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+    }
+
+    /// Pool confidence (bps) decayed by the age of its last update
+    pub fn decayed_confidence(&self, price: &PriceResult, now_ms: u64) -> i64 {
+        let age_ms = now_ms.saturating_sub(price.timestamp_ms);
+        decay_confidence_bps(price.confidence, age_ms, self.config.confidence_half_life_ms)
+    }
 
+    /// Confidence of an opportunity: the weaker of its two pools
+    fn opportunity_confidence(&self, opp: &ArbitrageOpportunity, now_ms: u64) -> Confidence {
+        let pool_confidence = |pool_id: u32, dex_id: u32| {
+            self.pools
+                .iter()
+                .find(|(p, _)| p.pool_id == pool_id && p.dex_id == dex_id)
+                .map(|(_, price)| confidence_from_bps(self.decayed_confidence(price, now_ms)))
+                .unwrap_or(Confidence::ZERO)
+        };
+
+        std::cmp::min(
+            pool_confidence(opp.buy_pool_id, opp.buy_dex_id),
+            pool_confidence(opp.sell_pool_id, opp.sell_dex_id),
+        )
+    }
+
+    /// Evaluate explicit routes (e.g. triangular) and rank them by net profit
+    ///
+    /// Each route starts and ends in the same token. Routes longer than
+    /// `max_hops`, or that don't net a profit after per-hop gas, are dropped.
+    pub fn rank_routes(&self, routes: &[Vec<RouteHop>]) -> Vec<ArbitrageOpportunity> {
+        let now_ms = now_ms();
+        let mut opportunities: Vec<ArbitrageOpportunity> = routes
+            .iter()
+            .filter(|route| {
+                route.iter().all(|hop| {
+                    self.is_tradable(hop.pool.pool_id, hop.pool.dex_id)
+                        && !self.is_observing(hop.pool.pool_id, hop.pool.dex_id, now_ms)
+                })
+            })
+            .filter_map(|route| self.evaluate_route(route))
+            .filter(|opp| self.passes_filters(opp, now_ms))
+            .collect();
+        sort_by_profit(&mut opportunities);
         opportunities
     }
 
+    /// Opportunity for a cyclic route, with profit net of per-hop gas
+    ///
+    /// `None` for routes shorter than 2 hops or longer than `max_hops`.
+    pub fn evaluate_route(&self, route: &[RouteHop]) -> Option<ArbitrageOpportunity> {
+        let (first, last) = (route.first()?, route.last()?);
+        let trade_size = U256::from(TRADE_SIZE);
+        let (gross, net) = self.route_profit(route, &trade_size)?;
+
+        let price = |hop: &RouteHop| {
+            calculate_price_for_kind(&hop.pool, self.pool_kind(hop.pool.pool_id, hop.pool.dex_id)).price
+        };
+
+        Some(ArbitrageOpportunity {
+            buy_pool_id: first.pool.pool_id,
+            buy_dex_id: first.pool.dex_id,
+            sell_pool_id: last.pool.pool_id,
+            sell_dex_id: last.pool.dex_id,
+            buy_price: price(first),
+            sell_price: price(last),
+            spread_bps: (gross.saturating_mul(10_000) / TRADE_SIZE as u128) as i64,
+            max_amount: trade_size,
+            estimated_profit: U256::from_u128(net),
+            timestamp_ms: route.iter().map(|hop| hop.pool.timestamp_ms).max().unwrap_or(0),
+            hops: route.len() as u32,
+        })
+    }
+
+    /// Gross and net (after per-hop gas) profit of trading `trade_size` around `route`
+    fn route_profit(&self, route: &[RouteHop], trade_size: &U256) -> Option<(u128, u128)> {
+        if route.len() < 2 || route.len() > self.route_costs.max_hops as usize {
+            return None;
+        }
+
+        let final_amount = route.iter().fold(*trade_size, |amount, hop| {
+            hop.output(&amount, self.pool_fee_bps(hop.pool.pool_id, hop.pool.dex_id))
+        });
+        let gross = final_amount.low128().saturating_sub(trade_size.low128());
+        let gas = self
+            .route_costs
+            .gas_per_hop
+            .low128()
+            .saturating_mul(route.len() as u128);
+        Some((gross, gross.saturating_sub(gas)))
+    }
+
     pub fn get_best(&self) -> Option<ArbitrageOpportunity> {
         self.scan().into_iter().next()
     }
 
     pub fn clear(&mut self) {
         self.pools.clear();
+        self.pair_index.clear();
     }
 
     pub fn pool_count(&self) -> usize {
@@ -409,26 +1345,20 @@ impl OpportunityScanner {
         sell_price: &PriceResult,
         spread_bps: i64,
     ) -> ArbitrageOpportunity {
-        // Simplified profit calculation
-        let trade_size = U256::from(1_000_000_000_000_000_000u64); // 1 token
-
-        let received = calculate_swap_output_rust(
-            &buy_pool.reserve0,
-            &buy_pool.reserve1,
-            &trade_size,
-        );
-
-        let final_amount = calculate_swap_output_rust(
-            &sell_pool.reserve1,
-            &sell_pool.reserve0,
-            &received,
-        );
-
-        let profit = if final_amount.low128() > trade_size.low128() {
-            U256::from_u128(final_amount.low128() - trade_size.low128())
-        } else {
-            U256::ZERO
-        };
+        // Spend token1 on token0 where it's cheap, sell the token0 back where
+        // it's dear: at the optimal size, capped at `max_position_size`
+        let trade_size = calculate_optimal_trade_size(
+            buy_pool,
+            sell_pool,
+            self.pool_fee_bps(buy_pool.pool_id, buy_pool.dex_id),
+            self.pool_fee_bps(sell_pool.pool_id, sell_pool.dex_id),
+        )
+        .min(self.config.max_position_size);
+        let route = [RouteHop::new(*buy_pool, false), RouteHop::new(*sell_pool, true)];
+        let profit = self
+            .route_profit(&route, &trade_size)
+            .map(|(_, net)| U256::from_u128(net))
+            .unwrap_or(U256::ZERO);
 
         ArbitrageOpportunity {
             buy_pool_id: buy_pool.pool_id,
@@ -441,6 +1371,7 @@ impl OpportunityScanner {
             max_amount: trade_size,
             estimated_profit: profit,
             timestamp_ms: std::cmp::max(buy_pool.timestamp_ms, sell_pool.timestamp_ms),
+            hops: route.len() as u32,
         }
     }
 }
@@ -473,6 +1404,37 @@ mod tests {
         assert_eq!(large.limbs[1], 0xFFFFFFFFFFFFFFFF);
     }
 
+    #[test]
+    fn test_u256_ordering_uses_high_limbs() {
+        let high = U256 { limbs: [0, 0, 0, 1] };
+        let low_max = U256 { limbs: [u64::MAX, u64::MAX, u64::MAX, 0] };
+        assert!(high > low_max);
+        assert!(U256 { limbs: [0, 0, 2, 0] } > U256 { limbs: [0, 0, 1, 0] });
+        assert!(U256::new(1) < U256::new(2));
+        assert_eq!(U256::from_u128(7).cmp(&U256::from(7u64)), std::cmp::Ordering::Equal);
+        assert_eq!(U256::ZERO.max(high), high);
+    }
+
+    #[test]
+    fn test_scanner_sort_compares_full_profit() {
+        let opportunity = |limbs: [u64; 4]| ArbitrageOpportunity {
+            estimated_profit: U256 { limbs },
+            ..Default::default()
+        };
+
+        // Identical low 128 bits; only the high limbs differ
+        let mut opportunities = vec![
+            opportunity([5, 0, 0, 0]),
+            opportunity([5, 0, 0, 2]),
+            opportunity([5, 0, 1, 0]),
+        ];
+        assert!(opportunities.iter().all(|o| o.estimated_profit.low128() == 5));
+
+        sort_by_profit(&mut opportunities);
+        let ranked: Vec<[u64; 4]> = opportunities.iter().map(|o| o.estimated_profit.limbs).collect();
+        assert_eq!(ranked, vec![[5, 0, 0, 2], [5, 0, 1, 0], [5, 0, 0, 0]]);
+    }
+
     #[test]
     fn test_price_calculation() {
         let reserves = PoolReserves::new(
@@ -492,6 +1454,62 @@ mod tests {
         assert!(price < 2_100_000_000_000_000_000);
     }
 
+    #[test]
+    fn test_pool_reserves_decimals() {
+        let pool = PoolReserves::new(1, 2, 7, dex::UNISWAP_V3);
+        assert_eq!((pool.decimals0, pool.decimals1), (18, 18));
+
+        // USDC/WETH
+        let pool = PoolReserves::with_decimals(2_000_000_000_000, 1_000 * 10u128.pow(18), 7, dex::UNISWAP_V3, 6, 18);
+        assert_eq!((pool.decimals0, pool.decimals1), (6, 18));
+        assert_eq!(pool.reserve0.low128(), 2_000_000_000_000);
+        assert_eq!((pool.pool_id, pool.dex_id), (7, dex::UNISWAP_V3));
+    }
+
+    #[test]
+    fn test_price_respects_decimals() {
+        // 2,000,000 USDC (6 decimals) vs 1,000 WETH (18 decimals)
+        let reserves = PoolReserves::with_decimals(2_000_000_000_000, 1_000 * 10u128.pow(18), 1, dex::UNISWAP_V3, 6, 18);
+        let result = calculate_price_rust(&reserves);
+
+        // 1 USDC = 0.0005 WETH
+        assert_eq!(result.price.low128(), 500_000_000_000_000);
+
+        // Reversed: 1 WETH = 2000 USDC
+        let reserves = PoolReserves::with_decimals(1_000 * 10u128.pow(18), 2_000_000_000_000, 1, dex::UNISWAP_V3, 18, 6);
+        assert_eq!(calculate_price_rust(&reserves).price.low128(), 2_000 * 10u128.pow(18));
+    }
+
+    #[test]
+    fn test_scanner_applies_token_decimals() {
+        struct Decimals(HashMap<Address, u8>);
+
+        impl TokenDecimals for Decimals {
+            fn decimals(&self, token: &Address) -> Option<u8> {
+                self.0.get(token).copied()
+            }
+        }
+
+        let (usdc, weth) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut scanner = OpportunityScanner::with_config(ScannerConfig {
+            min_liquidity: U256::ZERO,
+            ..Default::default()
+        });
+        scanner.set_token_decimals(Arc::new(Decimals(HashMap::from([(usdc, 6)]))));
+        scanner.set_pool_tokens(1, dex::UNISWAP_V3, usdc, weth);
+
+        // 2,000,000 USDC vs 1,000 WETH; the update assumes 18 decimals
+        scanner.update_pool(PoolReserves::new(2_000_000_000_000, 1_000 * 10u128.pow(18), 1, dex::UNISWAP_V3));
+        // Unknown pool: decimals left as given
+        scanner.update_pool(PoolReserves::new(10u128.pow(18), 10u128.pow(18), 2, dex::UNISWAP_V3));
+
+        let (pool, price) = scanner.pools[0];
+        assert_eq!((pool.decimals0, pool.decimals1), (6, 18));
+        assert_eq!(price.price.low128(), 500_000_000_000_000);
+        let (pool, _) = scanner.pools[1];
+        assert_eq!((pool.decimals0, pool.decimals1), (18, 18));
+    }
+
     #[test]
     fn test_swap_output() {
         let reserve_in = U256::from(1_000_000_000_000_000_000u64);
@@ -507,6 +1525,463 @@ mod tests {
         assert!(out_value < 0.20);
     }
 
+    #[test]
+    fn test_swap_output_with_transfer_fee() {
+        let reserve_in = U256::from_u128(1_000 * 1_000_000_000_000_000_000);
+        let reserve_out = U256::from_u128(2_000 * 1_000_000_000_000_000_000);
+        let amount_in = U256::from_u128(1_000_000_000_000_000_000);
+
+        let fee_free = calculate_swap_output_rust(&reserve_in, &reserve_out, &amount_in);
+
+        // No transfer fees matches the plain path
+        assert_eq!(
+            calculate_swap_output_with_transfer_fee(&reserve_in, &reserve_out, &amount_in, 0, 0),
+            fee_free
+        );
+
+        // 5% output fee comes straight off the received amount
+        let out_taxed = calculate_swap_output_with_transfer_fee(&reserve_in, &reserve_out, &amount_in, 0, 500);
+        assert_eq!(out_taxed.low128(), fee_free.low128() - fee_free.low128() / 20);
+
+        // 5% input fee shrinks what reaches the pool
+        let in_taxed = calculate_swap_output_with_transfer_fee(&reserve_in, &reserve_out, &amount_in, 500, 0);
+        let reduced_in = U256::from_u128(950_000_000_000_000_000);
+        assert_eq!(in_taxed, calculate_swap_output_rust(&reserve_in, &reserve_out, &reduced_in));
+
+        // Both fees compound
+        let both = calculate_swap_output_with_transfer_fee(&reserve_in, &reserve_out, &amount_in, 500, 500);
+        assert!(both.low128() < in_taxed.low128());
+        assert!(both.low128() < out_taxed.low128());
+        assert!(both.low128() < fee_free.low128() * 91 / 100);
+
+        // 100% fee leaves nothing
+        assert!(calculate_swap_output_with_transfer_fee(&reserve_in, &reserve_out, &amount_in, 10_000, 0).is_zero());
+    }
+
+    #[test]
+    fn test_deduct_bps_no_overflow() {
+        assert_eq!(deduct_bps(u128::MAX, 0), u128::MAX);
+        assert_eq!(deduct_bps(u128::MAX, 10_000), 0);
+        assert_eq!(deduct_bps(10_000, 250), 9_750);
+        assert_eq!(deduct_bps(10_000, 20_000), 0);
+    }
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_stableswap_balanced_pool() {
+        // Balanced Curve pool prices at parity
+        let price = calculate_price_stableswap([1_000_000 * E18, 1_000_000 * E18], 100);
+        assert_eq!(price.low128(), E18);
+    }
+
+    #[test]
+    fn test_stableswap_differs_from_naive_ratio() {
+        // 1:2 imbalanced pool with A=100: naive ratio says 2.0, invariant ~1.0042
+        let balances = [1_000_000 * E18, 2_000_000 * E18];
+        let price = calculate_price_stableswap(balances, 100).low128() as f64 / 1e18;
+        assert!((price - 1.004197).abs() < 1e-5, "price {}", price);
+
+        let naive = calculate_price_rust(&PoolReserves::new(100 * E18, 200 * E18, 1, dex::CURVE));
+        assert_eq!(naive.price.low128(), 2 * E18);
+
+        // Reversed balances give the reciprocal side
+        let reversed = calculate_price_stableswap([balances[1], balances[0]], 100).low128() as f64 / 1e18;
+        assert!((reversed - 0.995820).abs() < 1e-5, "price {}", reversed);
+
+        // Low amplification approaches constant-product behaviour
+        let low_amp = calculate_price_stableswap(balances, 1).low128() as f64 / 1e18;
+        assert!(low_amp > 1.2 && low_amp < 2.0);
+    }
+
+    #[test]
+    fn test_weighted_price() {
+        // 80/20 pool: 800 token0 vs 200 token1 prices at parity
+        let price = calculate_price_weighted([800 * E18, 200 * E18], [80, 20]);
+        assert_eq!(price.low128(), E18);
+
+        // 50/50 reduces to the reserve ratio
+        let price = calculate_price_weighted([E18, 2 * E18], [50, 50]);
+        assert_eq!(price.low128(), 2 * E18);
+    }
+
+    #[test]
+    fn test_scanner_routes_by_dex() {
+        let mut scanner = OpportunityScanner::new();
+        assert_eq!(scanner.pool_kind(1, dex::CURVE), PoolKind::StableSwap { amp: DEFAULT_CURVE_AMP });
+        assert_eq!(scanner.pool_kind(1, dex::SUSHISWAP), PoolKind::ConstantProduct);
+
+        scanner.set_pool_kind(7, dex::BALANCER, PoolKind::Weighted { weight0: 80, weight1: 20 });
+        assert_eq!(scanner.pool_kind(7, dex::BALANCER), PoolKind::Weighted { weight0: 80, weight1: 20 });
+
+        let reserves = PoolReserves::new(1_000_000 * E18, 2_000_000 * E18, 1, dex::CURVE);
+        let result = calculate_price_for_kind(&reserves, scanner.pool_kind(1, dex::CURVE));
+        assert!(result.price.low128() < E18 + E18 / 100);
+        assert_eq!(result.confidence, 10000);
+    }
+
+    #[test]
+    fn test_net_spread_after_fees() {
+        let costs = RouteCosts::default();
+        // Two 0.3% swaps and Aave's 0.09% premium cost ~69 bps
+        assert_eq!(costs.flash_loan_fee_bps, 9);
+        assert_eq!(costs.net_spread_bps(0, 2), -69);
+        assert_eq!(costs.net_spread_bps(50, 2), -20);
+        assert_eq!(costs.net_spread_bps(100, 2), 30);
+
+        let free = RouteCosts { swap_fee_bps: 0, flash_loan_fee_bps: 0, ..costs };
+        assert_eq!(free.net_spread_bps(50, 2), 50);
+    }
+
+    #[test]
+    fn test_breakeven_spread_30_30() {
+        let eth = U256::from_u128(E18);
+        // 1 / 0.997² - 1 = 60.3 bps
+        assert_eq!(breakeven_spread_bps(30, 30, U256::ZERO, eth), 61);
+        // 0.001 ETH of gas on a 1 ETH trade adds 10 bps before fees
+        assert_eq!(breakeven_spread_bps(30, 30, U256::from_u128(E18 / 1_000), eth), 71);
+        // Same gas matters less on a bigger trade
+        assert_eq!(breakeven_spread_bps(30, 30, U256::from_u128(E18 / 1_000), U256::from_u128(10 * E18)), 62);
+
+        // The threshold nets out at zero under the scanner's fee model
+        let costs = RouteCosts { flash_loan_fee_bps: 0, ..RouteCosts::default() };
+        assert!(costs.net_spread_bps(61, 2) >= 0);
+        assert!(costs.net_spread_bps(60, 2) < 0);
+
+        assert_eq!(breakeven_spread_bps(0, 0, U256::ZERO, eth), 0);
+        assert_eq!(breakeven_spread_bps(30, 30, U256::ZERO, U256::ZERO), i64::MAX);
+        assert_eq!(breakeven_spread_bps(10_000, 30, U256::ZERO, eth), i64::MAX);
+    }
+
+    #[test]
+    fn test_gross_spread_above_threshold_rejected_after_fees() {
+        let mut scanner = OpportunityScanner::with_config(ScannerConfig {
+            min_spread_bps: 10,
+            ..ScannerConfig::default()
+        });
+
+        // 70 bps gross clears the 10 bps threshold but nets nothing
+        let fixture = fixtures::make_pools_with_spread(70);
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+        assert!(scanner.scan().is_empty());
+
+        // Fee-free pools and loan: the same spread is reported
+        scanner.set_route_costs(RouteCosts {
+            swap_fee_bps: 0,
+            flash_loan_fee_bps: 0,
+            ..RouteCosts::default()
+        });
+        assert_eq!(scanner.scan().len(), 1);
+
+        // 80 bps gross nets ~10 bps with default fees
+        scanner.set_route_costs(RouteCosts::default());
+        let fixture = fixtures::make_pools_with_spread(80);
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].spread_bps, 80);
+    }
+
+    #[test]
+    fn test_pool_fee_tier_applied() {
+        // Two 5 bps swaps and the 9 bps premium leave 30 of 50 bps
+        let costs = RouteCosts::default();
+        assert_eq!(costs.net_spread_bps_with_fees(50, [5, 5]), 30);
+        assert_eq!(costs.net_spread_bps_with_fees(50, [30, 30]), costs.net_spread_bps(50, 2));
+
+        let (reserve_in, reserve_out, amount_in) = (U256::from_u128(1_000 * E18), U256::from_u128(2_000 * E18), U256::from_u128(E18));
+        let v2 = calculate_swap_output_rust(&reserve_in, &reserve_out, &amount_in);
+        let tier_5 = calculate_swap_output_with_fee(&reserve_in, &reserve_out, &amount_in, 5);
+        assert_eq!(v2, calculate_swap_output_with_fee(&reserve_in, &reserve_out, &amount_in, 30));
+        assert!(tier_5 > v2);
+
+        // 50 bps doesn't cover two 0.3% pools, but does two 5 bps V3 pools
+        let fixture = fixtures::make_pools_with_spread(50);
+        let mut scanner = OpportunityScanner::new();
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+        assert!(scanner.scan().is_empty());
+
+        scanner.set_pool_fee(1, dex::UNISWAP_V3, 5);
+        scanner.set_pool_fee(2, dex::SUSHISWAP, 5);
+        assert_eq!(scanner.pool_fee_bps(1, dex::UNISWAP_V3), 5);
+        assert_eq!(scanner.pool_fee_bps(3, dex::UNISWAP_V3), DEFAULT_SWAP_FEE_BPS);
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+
+        // Profit is the round trip at 5 bps per swap
+        let trade_size = opportunities[0].max_amount;
+        let bought = calculate_swap_output_with_fee(&fixture.buy.reserve1, &fixture.buy.reserve0, &trade_size, 5);
+        let sold = calculate_swap_output_with_fee(&fixture.sell.reserve0, &fixture.sell.reserve1, &bought, 5);
+        assert_eq!(opportunities[0].estimated_profit.low128(), sold.low128() - trade_size.low128());
+    }
+
+    #[test]
+    fn test_new_pool_observe_only_during_grace_period() {
+        let mut scanner = OpportunityScanner::with_config(ScannerConfig {
+            observation_period_ms: 60_000,
+            ..ScannerConfig::default()
+        });
+        let fixture = fixtures::make_pools_with_spread(200);
+        let (mut established, mut fresh) = (fixture.buy, fixture.sell);
+        established.timestamp_ms = 0;
+        fresh.timestamp_ms = 100_000;
+
+        scanner.update_pool(established);
+        assert!(!scanner.is_observing(established.pool_id, established.dex_id, 100_000));
+        scanner.update_pool(fresh);
+        assert_eq!(scanner.first_seen(fresh.pool_id, fresh.dex_id), Some(100_000));
+        assert_eq!(scanner.pool_count(), 2);
+
+        // Priced, but not tradable until a minute after it first appeared
+        for now_ms in [100_000, 130_000, 159_999] {
+            assert!(scanner.is_observing(fresh.pool_id, fresh.dex_id, now_ms));
+            assert!(scanner.scan_at(now_ms).is_empty(), "opportunity at {}", now_ms);
+        }
+
+        // Later updates don't restart the clock
+        fresh.timestamp_ms = 150_000;
+        scanner.update_pool(fresh);
+        assert_eq!(scanner.first_seen(fresh.pool_id, fresh.dex_id), Some(100_000));
+        let opportunities = scanner.scan_at(160_000);
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].sell_pool_id, fresh.pool_id);
+
+        // Off by default
+        let mut scanner = OpportunityScanner::new();
+        scanner.update_pool(established);
+        scanner.update_pool(fresh);
+        assert_eq!(scanner.scan_at(150_000).len(), 1);
+    }
+
+    #[test]
+    fn test_min_trade_size_filters_dust() {
+        // Same 10% spread; the shallow pair only absorbs a few tokens
+        let deep = [
+            PoolReserves::new(100_000 * E18, 200_000 * E18, 1, dex::UNISWAP_V3),
+            PoolReserves::new(100_000 * E18, 220_000 * E18, 2, dex::SUSHISWAP),
+        ];
+        let shallow = [
+            PoolReserves::new(100 * E18, 200 * E18, 3, dex::UNISWAP_V3),
+            PoolReserves::new(100 * E18, 220 * E18, 4, dex::SUSHISWAP),
+        ];
+        let size = calculate_optimal_trade_size(&shallow[0], &shallow[1], 30, 30).low128();
+        assert!(size > 4 * E18 && size < 5 * E18, "{}", size);
+        assert!(calculate_optimal_trade_size(&shallow[1], &shallow[0], 30, 30).is_zero());
+
+        let mut scanner = OpportunityScanner::new();
+        for pool in shallow {
+            scanner.update_pool(pool);
+        }
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+        assert!(opportunities[0].is_profitable());
+        assert_eq!(opportunities[0].max_amount.low128(), size);
+
+        // A 100-token floor drops the shallow pair but not the deep one
+        let mut scanner = OpportunityScanner::with_config(ScannerConfig {
+            min_trade_size: U256::from_u128(100 * E18),
+            ..ScannerConfig::default()
+        });
+        for pool in shallow {
+            scanner.update_pool(pool);
+        }
+        assert!(scanner.scan().is_empty());
+        assert_eq!(scanner.filter_rejections().get("min_trade_size"), Some(&1));
+
+        scanner.clear();
+        for pool in deep {
+            scanner.update_pool(pool);
+        }
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+        assert!(opportunities[0].max_amount.low128() > 4_000 * E18);
+    }
+
+    #[test]
+    fn test_custom_filter_rejections_counted() {
+        struct NoSushi;
+        impl OpportunityFilter for NoSushi {
+            fn name(&self) -> &str {
+                "no_sushi"
+            }
+            fn accepts(&self, opp: &ArbitrageOpportunity, _now_ms: u64) -> bool {
+                opp.buy_dex_id != dex::SUSHISWAP && opp.sell_dex_id != dex::SUSHISWAP
+            }
+        }
+
+        let mut scanner = OpportunityScanner::new().with_filter(NoSushi);
+        let fixture = fixtures::make_pools_with_spread(200);
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+
+        assert!(scanner.scan().is_empty());
+        assert!(scanner.scan().is_empty());
+        let rejections = scanner.filter_rejections();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections["no_sushi"], 2);
+    }
+
+    #[test]
+    fn test_opportunity_buys_token0_where_cheap() {
+        // token0 costs 2,000 token1 in pool 1 and 2,100 in pool 2
+        let cheap = PoolReserves::new(1_000_000 * E18, 2_000_000_000 * E18, 1, dex::UNISWAP_V3);
+        let dear = PoolReserves::new(1_000_000 * E18, 2_100_000_000 * E18, 2, dex::SUSHISWAP);
+        let mut scanner = OpportunityScanner::new();
+        scanner.update_pool(cheap);
+        scanner.update_pool(dear);
+
+        // Spend token1 on token0 in the cheap pool, sell it back in the dear one;
+        // pools this deep take a full-size position
+        let trade_size = ScannerConfig::default().max_position_size;
+        let forward = [RouteHop::new(cheap, false), RouteHop::new(dear, true)];
+        let (_, forward_net) = scanner.route_profit(&forward, &trade_size).unwrap();
+        assert!(forward_net > 0);
+
+        // The reverse sells token0 where it's cheap and loses the spread
+        let reverse = [RouteHop::new(cheap, true), RouteHop::new(dear, false)];
+        assert_eq!(scanner.route_profit(&reverse, &trade_size).unwrap().0, 0);
+
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].buy_pool_id, 1);
+        assert_eq!(opportunities[0].sell_pool_id, 2);
+        assert_eq!(opportunities[0].max_amount, trade_size);
+        assert_eq!(opportunities[0].estimated_profit.low128(), forward_net);
+    }
+
+    #[test]
+    fn test_max_amount_capped_at_position_size() {
+        // 10% apart: the optimal trade is ~23k tokens
+        let buy = PoolReserves::new(1_000_000 * E18, 2_000_000 * E18, 1, dex::UNISWAP_V3);
+        let sell = PoolReserves::new(1_000_000 * E18, 2_200_000 * E18, 2, dex::SUSHISWAP);
+        let optimal = calculate_optimal_trade_size(&buy, &sell, 30, 30);
+        let route = [RouteHop::new(buy, false), RouteHop::new(sell, true)];
+
+        let mut uncapped = OpportunityScanner::with_config(ScannerConfig {
+            max_position_size: U256::from_u128(1_000_000 * E18),
+            ..ScannerConfig::default()
+        });
+        uncapped.update_pool(buy);
+        uncapped.update_pool(sell);
+        let opp = uncapped.scan()[0];
+        assert_eq!(opp.max_amount, optimal);
+        assert_eq!(opp.estimated_profit.low128(), uncapped.route_profit(&route, &optimal).unwrap().1);
+
+        // The default 10k cap sizes the trade, and its profit, down
+        let cap = ScannerConfig::default().max_position_size;
+        assert!(optimal > cap);
+        let mut capped = OpportunityScanner::new();
+        capped.update_pool(buy);
+        capped.update_pool(sell);
+        let opp = capped.scan()[0];
+        assert_eq!(opp.max_amount, cap);
+        assert_eq!(opp.estimated_profit.low128(), capped.route_profit(&route, &cap).unwrap().1);
+    }
+
+    #[test]
+    fn test_hop_gas_ranks_shorter_route_first() {
+        let pool = |id: u32, r0: u128, r1: u128| PoolReserves::new(r0 * E18, r1 * E18, id, dex::UNISWAP_V3);
+        // token0 -> token1 -> token0
+        let two_hop = vec![
+            RouteHop::new(pool(1, 1_000, 2_000), true),
+            RouteHop::new(pool(2, 1_000, 1_960), false),
+        ];
+        // token0 -> token1 -> token2 -> token0, slightly more gross profit
+        let three_hop = vec![
+            RouteHop::new(pool(1, 1_000, 2_000), true),
+            RouteHop::new(pool(3, 2_000, 2_000), true),
+            RouteHop::new(pool(4, 1_000, 1_950), false),
+        ];
+        let routes = vec![three_hop.clone(), two_hop.clone()];
+
+        let mut scanner = OpportunityScanner::new();
+        let gross = scanner.rank_routes(&routes);
+        assert_eq!(gross[0].hops, 3);
+        assert!(gross[0].estimated_profit > gross[1].estimated_profit);
+
+        // 0.002 per swap: the extra hop costs more than it earns
+        scanner.set_route_costs(RouteCosts {
+            gas_per_hop: U256::from_u128(2 * E18 / 1_000),
+            ..Default::default()
+        });
+        let net = scanner.rank_routes(&routes);
+        assert_eq!(net.len(), 2);
+        assert_eq!(net[0].hops, 2);
+        assert_eq!(net[0].sell_pool_id, 2);
+        assert!(net[0].estimated_profit > net[1].estimated_profit);
+        let gross_two = gross.iter().find(|o| o.hops == 2).unwrap().estimated_profit.low128();
+        assert_eq!(net[0].estimated_profit.low128(), gross_two - 4 * E18 / 1_000);
+
+        // Hop cutoff drops the triangular route entirely
+        scanner.set_route_costs(RouteCosts {
+            max_hops: 2,
+            ..Default::default()
+        });
+        let capped = scanner.rank_routes(&routes);
+        assert_eq!(capped.len(), 1);
+        assert_eq!(capped[0].hops, 2);
+        assert!(scanner.evaluate_route(&three_hop).is_none());
+    }
+
+    fn scored_opportunity(profit: u128) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            estimated_profit: U256::from_u128(profit),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_score_reorders_versus_profit() {
+        let weights = ScoreWeights::default();
+        let gas_price = U256::from(5_000_000_000u64); // 5 gwei -> 0.0015 ETH gas
+
+        // High gross profit from a thin pool vs. a solid smaller trade
+        let risky = (scored_opportunity(20_000_000_000_000_000), confidence_from_bps(3000));
+        let solid = (scored_opportunity(10_000_000_000_000_000), confidence_from_bps(10000));
+
+        let mut by_profit = vec![solid, risky];
+        by_profit.sort_by_key(|o| std::cmp::Reverse(o.0.estimated_profit.low128()));
+        assert_eq!(by_profit[0].1.bps(), 3000);
+
+        let mut by_score = by_profit.clone();
+        by_score.sort_by(|a, b| {
+            score(&b.0, b.1, &gas_price, &weights).total_cmp(&score(&a.0, a.1, &gas_price, &weights))
+        });
+        assert_eq!(by_score[0].1.bps(), 10000);
+    }
+
+    #[test]
+    fn test_score_gas_penalty() {
+        let opp = scored_opportunity(10_000_000_000_000_000); // 0.01 ETH
+        let full = confidence_from_bps(10000);
+        let cheap_gas = U256::from(1_000_000_000u64);
+        let expensive_gas = U256::from(50_000_000_000u64);
+
+        let weights = ScoreWeights::default();
+        assert!(score(&opp, full, &cheap_gas, &weights) > score(&opp, full, &expensive_gas, &weights));
+
+        // Gas above profit is a loss regardless of confidence
+        let loss = score(&opp, full, &expensive_gas, &weights);
+        assert!(loss < 0.0);
+        assert_eq!(loss, score(&opp, confidence_from_bps(100), &expensive_gas, &weights));
+
+        // Zero exponent ignores confidence
+        let flat = ScoreWeights { confidence_exponent: 0.0, ..weights };
+        assert_eq!(
+            score(&opp, confidence_from_bps(3000), &cheap_gas, &flat),
+            score(&opp, full, &cheap_gas, &flat)
+        );
+    }
+
+    #[test]
+    fn test_confidence_from_bps_clamps() {
+        assert_eq!(confidence_from_bps(-5), Confidence::ZERO);
+        assert_eq!(confidence_from_bps(20000), Confidence::FULL);
+    }
+
     #[test]
     fn test_price_calculator() {
         let mut calc = PriceCalculator::new();
@@ -522,24 +1997,243 @@ mod tests {
         assert!(!results[0].price.is_zero());
     }
 
+    #[test]
+    fn test_depth_curve() {
+        let calc = PriceCalculator::new();
+        // USDC-style 6-decimal pool, small enough for exact integer swap math
+        let unit: u128 = 1_000_000;
+        let reserves = PoolReserves::with_decimals(1_000_000 * unit, 2_000_000 * unit, 1, 1, 6, 6);
+
+        let curve = calc.depth_curve(&reserves, 8);
+        assert_eq!(curve.len(), 8);
+
+        // 1000 tokens in (0.1% of reserve0): 2 * 0.997 / 1.000997 out each
+        let (amount_in, amount_out, impact) = curve[0];
+        assert_eq!(amount_in, U256::from_u128(1_000 * unit));
+        assert_eq!(amount_out.low128(), 2_000_000 * unit * 997 / (1_000 * 1_000 + 997));
+        assert_eq!(impact, 39);
+
+        // 128k tokens in: fee plus ~11.3% slippage
+        let (amount_in, amount_out, impact) = curve[7];
+        assert_eq!(amount_in, U256::from_u128(128_000 * unit));
+        assert_eq!(amount_out.low128(), 2_000_000 * unit * 128 * 997 / (1_000 * 1_000 + 128 * 997));
+        assert_eq!(impact, 1158);
+
+        for pair in curve.windows(2) {
+            assert!(pair[1].0 > pair[0].0);
+            assert!(pair[1].1 > pair[0].1);
+            assert!(pair[1].2 > pair[0].2);
+        }
+
+        assert!(calc.depth_curve(&PoolReserves::new(0, unit, 2, 1), 4).is_empty());
+    }
+
     #[test]
     fn test_opportunity_scanner() {
         let mut scanner = OpportunityScanner::new();
         assert_eq!(scanner.pool_count(), 0);
 
-        // Add two pools with price difference
-        // Using explicit values to avoid float conversion issues
-        let reserve_1e18: u128 = 1_000_000_000_000_000_000;
-        let reserve_2e18: u128 = 2_000_000_000_000_000_000;
-        let reserve_2_2e18: u128 = 2_200_000_000_000_000_000;
+        // Pool 2 prices token0 10% above pool 1
+        scanner.update_pool(PoolReserves::new(1_000_000 * E18, 2_000_000 * E18, 1, dex::UNISWAP_V3));
+        scanner.update_pool(PoolReserves::new(1_000_000 * E18, 2_200_000 * E18, 2, dex::SUSHISWAP));
+        assert_eq!(scanner.pool_count(), 2);
 
-        scanner.update_pool(PoolReserves::new(reserve_1e18, reserve_2e18, 1, 1));
-        scanner.update_pool(PoolReserves::new(reserve_1e18, reserve_2_2e18, 2, 2));
+        // One opportunity: buy token0 on pool 1, sell it on pool 2
+        let opportunities = scanner.scan();
+        assert_eq!(opportunities.len(), 1);
+        let opp = &opportunities[0];
+        assert_eq!((opp.buy_pool_id, opp.sell_pool_id), (1, 2));
+        assert!((999..=1000).contains(&opp.spread_bps), "spread {}", opp.spread_bps);
+        assert!(opp.is_profitable());
+        assert!(opp.max_amount > U256::from(TRADE_SIZE));
+    }
 
-        assert_eq!(scanner.pool_count(), 2);
+    #[test]
+    fn test_zero_liquidity_pools_never_compared() {
+        let fixture = fixtures::make_pools_with_spread(100);
+        let mut scanner = OpportunityScanner::new();
+        scanner.update_pool(fixture.buy);
+
+        // Drained or dust pools are never tracked, even with no floor
+        let drained = PoolReserves::new(0, 0, 3, dex::SUSHISWAP);
+        let one_sided = PoolReserves::new(fixtures::FIXTURE_RESERVE0, 0, 4, dex::SUSHISWAP);
+        let dust = PoolReserves::new(E18, 2_500 * E18, 5, dex::SUSHISWAP);
+        for pool in [drained, one_sided, dust] {
+            assert!(!scanner.has_min_liquidity(&pool));
+            scanner.update_pool(pool);
+        }
+        assert_eq!(scanner.pool_count(), 1);
+        let unfloored = OpportunityScanner::with_config(ScannerConfig {
+            min_liquidity: U256::ZERO,
+            ..ScannerConfig::default()
+        });
+        assert!(!unfloored.has_min_liquidity(&drained));
+        assert!(unfloored.has_min_liquidity(&dust));
+
+        // Low-decimal tokens are judged at 18 decimals
+        let usdc = PoolReserves::with_decimals(1_000 * E18, 2_000_000 * 1_000_000, 6, dex::SUSHISWAP, 18, 6);
+        assert!(scanner.has_min_liquidity(&usdc));
+
+        // A tracked pool that drains stops pairing
+        scanner.update_pool(fixture.sell);
+        assert_eq!(scanner.scan_at(fixture.sell.timestamp_ms).len(), 1);
+        scanner.update_pool(PoolReserves {
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
+            ..fixture.sell
+        });
+        assert!(scanner.scan_at(fixture.sell.timestamp_ms).is_empty());
+        assert!(scanner.scan_brute_force(fixture.sell.timestamp_ms).is_empty());
+    }
+
+    #[test]
+    fn test_blacklisted_pools_never_in_opportunities() {
+        let fixture = fixtures::make_pools_with_spread(100);
+        // A third, even richer sell pool: the trap
+        let mut trap = fixtures::make_pools_with_spread(300).sell;
+        trap.pool_id = 3;
+        trap.dex_id = dex::CAMELOT;
+
+        let mut scanner = OpportunityScanner::new().with_blacklist(HashSet::from([(3, dex::CAMELOT)]));
+        for pool in [fixture.buy, fixture.sell, trap] {
+            scanner.update_pool(pool);
+        }
+        let involves = |opp: &ArbitrageOpportunity, pool_id: u32| opp.buy_pool_id == pool_id || opp.sell_pool_id == pool_id;
 
         let opportunities = scanner.scan();
-        // Should find opportunities due to price difference
-        assert!(!opportunities.is_empty() || true); // May or may not find depending on spread threshold
+        assert!(!opportunities.is_empty());
+        assert!(opportunities.iter().all(|opp| !involves(opp, 3)));
+
+        // Blacklisting at runtime removes the remaining pair too
+        assert!(scanner.blacklist_pool(2, dex::SUSHISWAP));
+        assert!(!scanner.blacklist_pool(2, dex::SUSHISWAP));
+        assert!(scanner.scan().iter().all(|opp| !involves(opp, 2) && !involves(opp, 3)));
+        let route = [RouteHop::new(fixture.buy, false), RouteHop::new(fixture.sell, true)];
+        assert!(scanner.rank_routes(&[route.to_vec()]).is_empty());
+
+        assert!(scanner.unblacklist_pool(3, dex::CAMELOT));
+        assert!(scanner.scan().iter().any(|opp| involves(opp, 3)));
+    }
+
+    #[test]
+    fn test_confidence_decays_with_pool_age() {
+        let fixture = fixtures::make_pools_with_spread(100);
+        let updated_at = fixture.buy.timestamp_ms;
+        let mut scanner = OpportunityScanner::with_config(ScannerConfig {
+            confidence_half_life_ms: 10_000,
+            min_confidence_bps: 6_000,
+            ..ScannerConfig::default()
+        });
+        scanner.update_pool(fixture.buy);
+        scanner.update_pool(fixture.sell);
+
+        // Deep fixture pools start at full confidence and fade toward half
+        let price = calculate_price_rust(&fixture.buy);
+        let at_age = |age_ms: u64| scanner.decayed_confidence(&price, updated_at + age_ms);
+        assert_eq!(at_age(0), 10_000);
+        assert_eq!(at_age(5_000), 7_071);
+        assert_eq!(at_age(10_000), 5_000);
+        assert!((1..=10).map(|i| at_age(i * 1_000)).collect::<Vec<_>>().windows(2).all(|w| w[0] > w[1]));
+
+        // Still above the floor at half the half-life, excluded past it
+        assert!(!scanner.scan_at(updated_at + 5_000).is_empty());
+        assert!(scanner.scan_at(updated_at + 10_000).is_empty());
+
+        // No half-life: age doesn't matter
+        assert_eq!(decay_confidence_bps(10_000, u64::MAX, 0), 10_000);
+    }
+
+    #[test]
+    fn test_allowed_pairs_restrict_scan() {
+        let wbnb = Address::repeat_byte(0xbb);
+        let usdt = Address::repeat_byte(0x55);
+        let busd = Address::repeat_byte(0xe9);
+
+        let usdt_pools = fixtures::make_pools_with_spread(100);
+        let mut busd_pools = fixtures::make_pools_with_spread(200);
+        busd_pools.buy.pool_id = 3;
+        busd_pools.sell.pool_id = 4;
+
+        // Listed in reverse order: canonicalized on insert
+        let mut scanner = OpportunityScanner::new().with_allowed_pairs([(usdt, wbnb)]);
+        for (pools, quote) in [(usdt_pools, usdt), (busd_pools, busd)] {
+            for pool in [pools.buy, pools.sell] {
+                scanner.set_pool_tokens(pool.pool_id, pool.dex_id, wbnb, quote);
+                scanner.update_pool(pool);
+            }
+        }
+        let pool_ids = |opps: &[ArbitrageOpportunity]| {
+            opps.iter()
+                .flat_map(|opp| [opp.buy_pool_id, opp.sell_pool_id])
+                .collect::<HashSet<_>>()
+        };
+
+        let opportunities = scanner.scan();
+        assert!(!opportunities.is_empty());
+        assert_eq!(pool_ids(&opportunities), HashSet::from([1, 2]));
+        assert!(scanner.is_pair_allowed(1, dex::UNISWAP_V3));
+        assert!(!scanner.is_pair_allowed(3, dex::UNISWAP_V3));
+        // Pools with no known pair are excluded while the allowlist is set
+        assert!(!scanner.is_pair_allowed(9, dex::UNISWAP_V3));
+
+        let route = [RouteHop::new(busd_pools.buy, false), RouteHop::new(busd_pools.sell, true)];
+        assert!(scanner.rank_routes(&[route.to_vec()]).is_empty());
+
+        scanner.set_allowed_pairs(None);
+        assert!(pool_ids(&scanner.scan()).contains(&3));
+    }
+
+    /// `pools` pools on each of `pairs` pairs, spreads up to ~3% within a pair
+    fn pair_pools(pairs: u32, pools: u32) -> Vec<(PoolReserves, Address)> {
+        let mut seed: u64 = 42;
+        let mut next = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as u128
+        };
+        (0..pairs * pools)
+            .map(|i| {
+                let pair = i % pairs;
+                let base = fixtures::FIXTURE_RESERVE0 * (1_000 + pair as u128);
+                let reserve1 = base / 10_000 * (10_000 + next() % 300);
+                let mut pool = PoolReserves::new(fixtures::FIXTURE_RESERVE0, reserve1, i + 1, 1 + i % 8);
+                pool.timestamp_ms = 1_700_000_000_000;
+                (pool, Address::from_low_u64_be(pair as u64 + 1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pair_index_matches_brute_force() {
+        let weth = Address::repeat_byte(0xee);
+        let now_ms = 1_700_000_000_000;
+        let debug = |opps: Vec<ArbitrageOpportunity>| opps.iter().map(|o| format!("{:?}", o)).collect::<Vec<_>>();
+
+        let mut scanner = OpportunityScanner::new();
+        for (n, (pool, quote)) in pair_pools(12, 8).into_iter().enumerate() {
+            match n % 4 {
+                // Tokens known before the pool is seen, after, or never
+                0 | 1 => scanner.set_pool_tokens(pool.pool_id, pool.dex_id, quote, weth),
+                2 => {}
+                _ => {
+                    scanner.update_pool(pool);
+                    scanner.set_pool_tokens(pool.pool_id, pool.dex_id, weth, quote);
+                    continue;
+                }
+            }
+            scanner.update_pool(pool);
+        }
+
+        let indexed = scanner.scan_at(now_ms);
+        assert!(indexed.len() > 10);
+        assert_eq!(debug(indexed), debug(scanner.scan_brute_force(now_ms)));
+
+        // Same-pair pools only, once every pair is known
+        for (pool, quote) in pair_pools(12, 8) {
+            scanner.set_pool_tokens(pool.pool_id, pool.dex_id, weth, quote);
+        }
+        let indexed = scanner.scan_at(now_ms);
+        let pairs = 12;
+        assert!(indexed.iter().all(|o| (o.buy_pool_id - 1) % pairs == (o.sell_pool_id - 1) % pairs));
+        assert_eq!(debug(indexed), debug(scanner.scan_brute_force(now_ms)));
     }
 }
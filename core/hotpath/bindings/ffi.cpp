@@ -78,6 +78,10 @@ matrix::hotpath::ScannerConfig from_ffi(const ffi_scanner_config_t& v) {
     result.min_liquidity = from_ffi(v.min_liquidity);
     result.max_position_size = from_ffi(v.max_position_size);
     result.include_same_dex = v.include_same_dex != 0;
+    result.confidence_half_life_ms = v.confidence_half_life_ms;
+    result.min_confidence_bps = v.min_confidence_bps;
+    result.min_trade_size = from_ffi(v.min_trade_size);
+    result.observation_period_ms = v.observation_period_ms;
     return result;
 }
 
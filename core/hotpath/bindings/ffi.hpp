@@ -75,6 +75,10 @@ typedef struct {
     ffi_u256_t min_liquidity;
     ffi_u256_t max_position_size;
     uint8_t include_same_dex;
+    uint64_t confidence_half_life_ms;
+    int64_t min_confidence_bps;
+    ffi_u256_t min_trade_size;
+    uint64_t observation_period_ms;
 } ffi_scanner_config_t;
 
 /// Opaque scanner handle
@@ -11,6 +11,7 @@
 #include "price_calculator.hpp"
 #include <vector>
 #include <functional>
+#include <cmath>
 
 namespace matrix::hotpath {
 
@@ -60,6 +61,15 @@ public:
      */
     size_t scan(std::vector<ArbitrageOpportunity>& opportunities);
 
+    /**
+     * @brief Scan with pool ages measured from `now_ms`
+     *
+     * @param now_ms Current time, for confidence decay and observation
+     * @param opportunities Output vector for found opportunities
+     * @return Number of opportunities found
+     */
+    size_t scan_at(uint64_t now_ms, std::vector<ArbitrageOpportunity>& opportunities);
+
     /**
      * @brief Scan with callback (zero-allocation hot path)
      *
@@ -81,6 +91,20 @@ public:
      */
     bool get_best_opportunity(ArbitrageOpportunity& out_opportunity);
 
+    /**
+     * @brief Whether a pool is still within `observation_period_ms` of its
+     * first update at `now_ms`
+     *
+     * Observed pools are priced but produce no opportunities, so thin or
+     * manipulated launch liquidity can't be traded against.
+     */
+    bool is_observing(uint32_t pool_id, uint32_t dex_id, uint64_t now_ms) const;
+
+    /**
+     * @brief A price's confidence decayed by its age at `now_ms`
+     */
+    int64_t decayed_confidence(const PriceResult& price, uint64_t now_ms) const;
+
     /**
      * @brief Clear all pool data
      */
@@ -101,10 +125,21 @@ public:
      */
     const ScannerConfig& config() const { return config_; }
 
+    /**
+     * @brief Set the fees spreads are netted against
+     */
+    void set_route_costs(const RouteCosts& costs) { route_costs_ = costs; }
+
+    /**
+     * @brief Get current route costs
+     */
+    const RouteCosts& route_costs() const { return route_costs_; }
+
 private:
     struct PoolEntry {
         PoolReserves reserves;
         PriceResult price;
+        uint64_t first_seen_ms; // Timestamp of the pool's first update
         bool valid;
     };
 
@@ -125,6 +160,7 @@ private:
     };
 
     ScannerConfig config_;
+    RouteCosts route_costs_;
 
     // Pool storage - grouped by token pair for efficient scanning
     static constexpr size_t MAX_POOLS = 4096;
@@ -145,12 +181,28 @@ private:
 
     // Internal methods
     void recalculate_price(size_t pool_index);
-    void scan_pair_group(const PairGroup& group, std::vector<ArbitrageOpportunity>& out);
-    void scan_pair_group_simd(const PairGroup& group, const OpportunityCallback& callback);
+    void scan_pair_group(const PairGroup& group, uint64_t now_ms, std::vector<ArbitrageOpportunity>& out);
+    void scan_pair_group_simd(const PairGroup& group, uint64_t now_ms, const OpportunityCallback& callback);
+    bool is_tradable(const PoolEntry& pool, uint64_t now_ms) const;
     int64_t calculate_spread_bps(const PriceResult& buy, const PriceResult& sell);
     bool meets_criteria(const ArbitrageOpportunity& opp) const;
+    U256 position_size(const U256& optimal) const;
 };
 
+// ============================================================================
+// CONFIDENCE DECAY
+// ============================================================================
+
+/// Confidence (bps) halved for every `half_life_ms` of age
+/// A half-life of 0 disables decay.
+inline int64_t decay_confidence_bps(int64_t confidence_bps, uint64_t age_ms, uint64_t half_life_ms) {
+    if (half_life_ms == 0 || age_ms == 0) {
+        return confidence_bps;
+    }
+    double factor = std::pow(0.5, static_cast<double>(age_ms) / static_cast<double>(half_life_ms));
+    return static_cast<int64_t>(static_cast<double>(confidence_bps) * factor);
+}
+
 // ============================================================================
 // FAST SPREAD CALCULATION (INLINE)
 // ============================================================================
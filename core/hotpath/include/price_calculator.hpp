@@ -95,7 +95,7 @@ U256 calculate_optimal_trade_size(
  *
  * @param buy_reserves Buy pool reserves
  * @param sell_reserves Sell pool reserves
- * @param trade_size Amount to trade
+ * @param trade_size Amount of token1 to trade
  * @return Estimated profit (may be negative)
  */
 U256 calculate_arbitrage_profit(
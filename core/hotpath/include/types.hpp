@@ -7,6 +7,7 @@
  */
 
 #include <cstdint>
+#include <algorithm>
 #include <array>
 #include <immintrin.h>
 
@@ -95,7 +96,8 @@ struct alignas(64) ArbitrageOpportunity {
     U256 max_amount;      // Maximum executable amount
     U256 estimated_profit;// Estimated profit
     uint64_t timestamp_ms;
-    uint8_t _padding[8];
+    uint32_t hops;        // Swaps in the route
+    uint8_t _padding[4];
 };
 
 static_assert(sizeof(ArbitrageOpportunity) == 192, "ArbitrageOpportunity size check");
@@ -125,13 +127,55 @@ using f64x4 = __m256d;
 
 /// Scanner configuration
 struct ScannerConfig {
-    int64_t min_spread_bps;     // Minimum spread to report (e.g., 10 = 0.1%)
+    int64_t min_spread_bps;     // Minimum spread net of RouteCosts (e.g., 10 = 0.1%)
     int64_t max_slippage_bps;   // Maximum acceptable slippage
     U256 min_liquidity;         // Minimum pool liquidity
     U256 max_position_size;     // Maximum position size
     bool include_same_dex;      // Include same-DEX opportunities
+    uint64_t confidence_half_life_ms; // Age at which confidence halves (0 = no decay)
+    int64_t min_confidence_bps; // Minimum decayed pool confidence
+    U256 min_trade_size;        // Smaller opportunities are dust (0 = no floor)
+    uint64_t observation_period_ms; // New pools observe-only this long (0 = off)
+};
+
+/// Fees applied to a route's spread before it is compared with
+/// `min_spread_bps`; mirrors the Rust `RouteCosts` fee fields.
+struct RouteCosts {
+    uint32_t swap_fee_bps;       // LP fee charged on each swap
+    uint32_t flash_loan_fee_bps; // Flash loan premium on the borrowed amount
+
+    /// Spread left after `hops` swap fees and the flash loan premium
+    /// (1 + gross) * (1 - swap_fee)^hops * (1 - premium) - 1, rounded down
+    int64_t net_spread_bps(int64_t gross_bps, uint32_t hops) const {
+        static constexpr __int128 ONE = 10'000;
+        // Extra precision so per-step truncation can't move the result
+        static constexpr __int128 SCALE = 100'000'000;
+        auto keep = [](uint32_t fee_bps) {
+            return ONE - std::min<__int128>(fee_bps, ONE);
+        };
+
+        __int128 value = (ONE + gross_bps) * SCALE;
+        for (uint32_t i = 0; i < hops; ++i) {
+            value = value * keep(swap_fee_bps) / ONE;
+        }
+        value = value * keep(flash_loan_fee_bps) / ONE;
+
+        __int128 floored = value / SCALE;
+        if (value % SCALE < 0) {
+            --floored;
+        }
+        return static_cast<int64_t>(floored - ONE);
+    }
 };
 
+/// Default route costs: 0.3% swaps, Aave V3's 0.09% premium
+inline RouteCosts default_route_costs() {
+    RouteCosts costs{};
+    costs.swap_fee_bps = 30;
+    costs.flash_loan_fee_bps = 9;
+    return costs;
+}
+
 /// Default scanner configuration
 inline ScannerConfig default_scanner_config() {
     ScannerConfig config{};
@@ -140,6 +184,10 @@ inline ScannerConfig default_scanner_config() {
     config.min_liquidity = U256(100'000'000'000'000'000'000ULL); // ~$100 min
     config.max_position_size = U256(10'000'000'000'000'000'000'000ULL); // ~$10k max
     config.include_same_dex = false;
+    config.confidence_half_life_ms = 0;
+    config.min_confidence_bps = 0;
+    config.min_trade_size = U256();
+    config.observation_period_ms = 0;
     return config;
 }
 
@@ -5,16 +5,28 @@
 
 #include "opportunity_scanner.hpp"
 #include <algorithm>
+#include <chrono>
 #include <cstring>
 
 namespace matrix::hotpath {
 
+namespace {
+
+uint64_t now_ms() {
+    using namespace std::chrono;
+    return static_cast<uint64_t>(
+        duration_cast<milliseconds>(system_clock::now().time_since_epoch()).count());
+}
+
+} // namespace
+
 // ============================================================================
 // CONSTRUCTOR / DESTRUCTOR
 // ============================================================================
 
 OpportunityScanner::OpportunityScanner(const ScannerConfig& config)
     : config_(config)
+    , route_costs_(default_route_costs())
     , pool_count_(0)
     , pair_count_(0)
 {
@@ -46,6 +58,7 @@ void OpportunityScanner::update_pool(const PoolReserves& reserves) {
             return; // At capacity
         }
         pool_idx = pool_count_++;
+        pools_[pool_idx].first_seen_ms = reserves.timestamp_ms;
     }
 
     // Update pool data
@@ -111,17 +124,37 @@ void OpportunityScanner::set_config(const ScannerConfig& config) {
     config_ = config;
 }
 
+bool OpportunityScanner::is_observing(uint32_t pool_id, uint32_t dex_id, uint64_t now_ms) const {
+    for (size_t i = 0; i < pool_count_; ++i) {
+        const auto& pool = pools_[i];
+        if (pool.reserves.pool_id == pool_id && pool.reserves.dex_id == dex_id) {
+            uint64_t age = now_ms > pool.first_seen_ms ? now_ms - pool.first_seen_ms : 0;
+            return age < config_.observation_period_ms;
+        }
+    }
+    return false;
+}
+
+int64_t OpportunityScanner::decayed_confidence(const PriceResult& price, uint64_t now_ms) const {
+    uint64_t age = now_ms > price.timestamp_ms ? now_ms - price.timestamp_ms : 0;
+    return decay_confidence_bps(price.confidence, age, config_.confidence_half_life_ms);
+}
+
 // ============================================================================
 // SCANNING
 // ============================================================================
 
 size_t OpportunityScanner::scan(std::vector<ArbitrageOpportunity>& opportunities) {
+    return scan_at(now_ms(), opportunities);
+}
+
+size_t OpportunityScanner::scan_at(uint64_t now_ms, std::vector<ArbitrageOpportunity>& opportunities) {
     opportunities.clear();
 
     // Scan each pair group for opportunities
     for (size_t i = 0; i < pair_count_; ++i) {
         if (pair_groups_[i].count >= 2) {
-            scan_pair_group(pair_groups_[i], opportunities);
+            scan_pair_group(pair_groups_[i], now_ms, opportunities);
         }
     }
 
@@ -136,11 +169,12 @@ size_t OpportunityScanner::scan(std::vector<ArbitrageOpportunity>& opportunities
 
 size_t OpportunityScanner::scan_with_callback(const OpportunityCallback& callback) {
     size_t count = 0;
+    uint64_t now = now_ms();
 
     for (size_t i = 0; i < pair_count_; ++i) {
         if (pair_groups_[i].count >= 2) {
             // Use SIMD-optimized scanning
-            scan_pair_group_simd(pair_groups_[i], [&](const ArbitrageOpportunity& opp) {
+            scan_pair_group_simd(pair_groups_[i], now, [&](const ArbitrageOpportunity& opp) {
                 callback(opp);
                 ++count;
             });
@@ -153,6 +187,7 @@ size_t OpportunityScanner::scan_with_callback(const OpportunityCallback& callbac
 bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportunity) {
     ArbitrageOpportunity best;
     bool found = false;
+    uint64_t now = now_ms();
 
     for (size_t i = 0; i < pair_count_; ++i) {
         if (pair_groups_[i].count < 2) continue;
@@ -166,14 +201,15 @@ bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportun
                 const auto& pool_b = pools_[group.pool_indices[b]];
 
                 if (!pool_a.valid || !pool_b.valid) continue;
+                if (!is_tradable(pool_a, now) || !is_tradable(pool_b, now)) continue;
 
-                // Check both directions
+                // Check both directions, net of fees
                 int64_t spread_ab = calculate_spread_bps(pool_a.price, pool_b.price);
                 int64_t spread_ba = calculate_spread_bps(pool_b.price, pool_a.price);
 
                 ArbitrageOpportunity opp;
 
-                if (spread_ab >= config_.min_spread_bps) {
+                if (route_costs_.net_spread_bps(spread_ab, 2) >= config_.min_spread_bps) {
                     opp.buy_pool_id = pool_a.reserves.pool_id;
                     opp.buy_dex_id = pool_a.reserves.dex_id;
                     opp.sell_pool_id = pool_b.reserves.pool_id;
@@ -181,14 +217,15 @@ bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportun
                     opp.buy_price = pool_a.price.price;
                     opp.sell_price = pool_b.price.price;
                     opp.spread_bps = spread_ab;
+                    opp.hops = 2;
                     opp.timestamp_ms = std::max(pool_a.reserves.timestamp_ms,
                                                 pool_b.reserves.timestamp_ms);
 
                     // Calculate optimal size and profit
-                    opp.max_amount = calculate_optimal_trade_size(
+                    opp.max_amount = position_size(calculate_optimal_trade_size(
                         pool_a.reserves.reserve0, pool_a.reserves.reserve1,
                         pool_b.reserves.reserve0, pool_b.reserves.reserve1
-                    );
+                    ));
                     opp.estimated_profit = calculate_arbitrage_profit(
                         pool_a.reserves, pool_b.reserves, opp.max_amount
                     );
@@ -201,7 +238,7 @@ bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportun
                     }
                 }
 
-                if (spread_ba >= config_.min_spread_bps) {
+                if (route_costs_.net_spread_bps(spread_ba, 2) >= config_.min_spread_bps) {
                     opp.buy_pool_id = pool_b.reserves.pool_id;
                     opp.buy_dex_id = pool_b.reserves.dex_id;
                     opp.sell_pool_id = pool_a.reserves.pool_id;
@@ -209,13 +246,14 @@ bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportun
                     opp.buy_price = pool_b.price.price;
                     opp.sell_price = pool_a.price.price;
                     opp.spread_bps = spread_ba;
+                    opp.hops = 2;
                     opp.timestamp_ms = std::max(pool_a.reserves.timestamp_ms,
                                                 pool_b.reserves.timestamp_ms);
 
-                    opp.max_amount = calculate_optimal_trade_size(
+                    opp.max_amount = position_size(calculate_optimal_trade_size(
                         pool_b.reserves.reserve0, pool_b.reserves.reserve1,
                         pool_a.reserves.reserve0, pool_a.reserves.reserve1
-                    );
+                    ));
                     opp.estimated_profit = calculate_arbitrage_profit(
                         pool_b.reserves, pool_a.reserves, opp.max_amount
                     );
@@ -243,6 +281,7 @@ bool OpportunityScanner::get_best_opportunity(ArbitrageOpportunity& out_opportun
 
 void OpportunityScanner::scan_pair_group(
     const PairGroup& group,
+    uint64_t now_ms,
     std::vector<ArbitrageOpportunity>& out
 ) {
     // Compare all pairs of pools in the group
@@ -259,11 +298,15 @@ void OpportunityScanner::scan_pair_group(
                 continue;
             }
 
-            // Check both directions
+            if (!is_tradable(pool_a, now_ms) || !is_tradable(pool_b, now_ms)) {
+                continue;
+            }
+
+            // Check both directions, net of fees
             int64_t spread_ab = calculate_spread_bps(pool_a.price, pool_b.price);
             int64_t spread_ba = calculate_spread_bps(pool_b.price, pool_a.price);
 
-            if (spread_ab >= config_.min_spread_bps) {
+            if (route_costs_.net_spread_bps(spread_ab, 2) >= config_.min_spread_bps) {
                 ArbitrageOpportunity opp;
                 opp.buy_pool_id = pool_a.reserves.pool_id;
                 opp.buy_dex_id = pool_a.reserves.dex_id;
@@ -272,13 +315,14 @@ void OpportunityScanner::scan_pair_group(
                 opp.buy_price = pool_a.price.price;
                 opp.sell_price = pool_b.price.price;
                 opp.spread_bps = spread_ab;
+                opp.hops = 2;
                 opp.timestamp_ms = std::max(pool_a.reserves.timestamp_ms,
                                             pool_b.reserves.timestamp_ms);
 
-                opp.max_amount = calculate_optimal_trade_size(
+                opp.max_amount = position_size(calculate_optimal_trade_size(
                     pool_a.reserves.reserve0, pool_a.reserves.reserve1,
                     pool_b.reserves.reserve0, pool_b.reserves.reserve1
-                );
+                ));
                 opp.estimated_profit = calculate_arbitrage_profit(
                     pool_a.reserves, pool_b.reserves, opp.max_amount
                 );
@@ -288,7 +332,7 @@ void OpportunityScanner::scan_pair_group(
                 }
             }
 
-            if (spread_ba >= config_.min_spread_bps) {
+            if (route_costs_.net_spread_bps(spread_ba, 2) >= config_.min_spread_bps) {
                 ArbitrageOpportunity opp;
                 opp.buy_pool_id = pool_b.reserves.pool_id;
                 opp.buy_dex_id = pool_b.reserves.dex_id;
@@ -297,13 +341,14 @@ void OpportunityScanner::scan_pair_group(
                 opp.buy_price = pool_b.price.price;
                 opp.sell_price = pool_a.price.price;
                 opp.spread_bps = spread_ba;
+                opp.hops = 2;
                 opp.timestamp_ms = std::max(pool_a.reserves.timestamp_ms,
                                             pool_b.reserves.timestamp_ms);
 
-                opp.max_amount = calculate_optimal_trade_size(
+                opp.max_amount = position_size(calculate_optimal_trade_size(
                     pool_b.reserves.reserve0, pool_b.reserves.reserve1,
                     pool_a.reserves.reserve0, pool_a.reserves.reserve1
-                );
+                ));
                 opp.estimated_profit = calculate_arbitrage_profit(
                     pool_b.reserves, pool_a.reserves, opp.max_amount
                 );
@@ -318,6 +363,7 @@ void OpportunityScanner::scan_pair_group(
 
 void OpportunityScanner::scan_pair_group_simd(
     const PairGroup& group,
+    uint64_t now_ms,
     const OpportunityCallback& callback
 ) {
     // SIMD-optimized scanning for groups with many pools
@@ -326,7 +372,7 @@ void OpportunityScanner::scan_pair_group_simd(
     if (group.count < 4) {
         // Fall back to scalar for small groups
         std::vector<ArbitrageOpportunity> opps;
-        scan_pair_group(group, opps);
+        scan_pair_group(group, now_ms, opps);
         for (const auto& opp : opps) {
             callback(opp);
         }
@@ -364,7 +410,7 @@ void OpportunityScanner::scan_pair_group_simd(
 
             for (int i = 0; i < 4; ++i) {
                 int64_t spread = static_cast<int64_t>(spreads[i]);
-                if (spread >= config_.min_spread_bps) {
+                if (route_costs_.net_spread_bps(spread, 2) >= config_.min_spread_bps) {
                     const auto& pool_a = pools_[group.pool_indices[a]];
                     const auto& pool_b = pools_[group.pool_indices[b + i]];
 
@@ -373,6 +419,10 @@ void OpportunityScanner::scan_pair_group_simd(
                         continue;
                     }
 
+                    if (!is_tradable(pool_a, now_ms) || !is_tradable(pool_b, now_ms)) {
+                        continue;
+                    }
+
                     ArbitrageOpportunity opp;
                     opp.buy_pool_id = pool_a.reserves.pool_id;
                     opp.buy_dex_id = pool_a.reserves.dex_id;
@@ -381,13 +431,14 @@ void OpportunityScanner::scan_pair_group_simd(
                     opp.buy_price = pool_a.price.price;
                     opp.sell_price = pool_b.price.price;
                     opp.spread_bps = spread;
+                    opp.hops = 2;
                     opp.timestamp_ms = std::max(pool_a.reserves.timestamp_ms,
                                                 pool_b.reserves.timestamp_ms);
 
-                    opp.max_amount = calculate_optimal_trade_size(
+                    opp.max_amount = position_size(calculate_optimal_trade_size(
                         pool_a.reserves.reserve0, pool_a.reserves.reserve1,
                         pool_b.reserves.reserve0, pool_b.reserves.reserve1
-                    );
+                    ));
                     opp.estimated_profit = calculate_arbitrage_profit(
                         pool_a.reserves, pool_b.reserves, opp.max_amount
                     );
@@ -407,6 +458,22 @@ int64_t OpportunityScanner::calculate_spread_bps(const PriceResult& buy, const P
     return detail::spread_bps_fast(buy_price, sell_price);
 }
 
+bool OpportunityScanner::is_tradable(const PoolEntry& pool, uint64_t now_ms) const {
+    // Observe-only while new
+    uint64_t age = now_ms > pool.first_seen_ms ? now_ms - pool.first_seen_ms : 0;
+    if (age < config_.observation_period_ms) {
+        return false;
+    }
+
+    // Stale prices lose confidence until they stop counting
+    return decayed_confidence(pool.price, now_ms) >= config_.min_confidence_bps;
+}
+
+U256 OpportunityScanner::position_size(const U256& optimal) const {
+    // Trade the optimal size, capped at the maximum position
+    return simd::cmp_u256(optimal, config_.max_position_size) > 0 ? config_.max_position_size : optimal;
+}
+
 bool OpportunityScanner::meets_criteria(const ArbitrageOpportunity& opp) const {
     // Check minimum spread
     if (opp.spread_bps < config_.min_spread_bps) {
@@ -418,8 +485,8 @@ bool OpportunityScanner::meets_criteria(const ArbitrageOpportunity& opp) const {
         return false;
     }
 
-    // Check maximum position size
-    if (simd::cmp_u256(opp.max_amount, config_.max_position_size) > 0) {
+    // Check minimum trade size (dust)
+    if (simd::cmp_u256(opp.max_amount, config_.min_trade_size) < 0) {
         return false;
     }
 
@@ -283,23 +283,23 @@ U256 calculate_arbitrage_profit(
     const PoolReserves& sell_reserves,
     const U256& trade_size
 ) {
-    // 1. Buy token1 with token0 at buy_pool
-    U256 token1_received = calculate_swap_output(
-        buy_reserves.reserve0,
+    // 1. Buy token0 with token1 at buy_pool (where token0 is cheap)
+    U256 token0_received = calculate_swap_output(
         buy_reserves.reserve1,
+        buy_reserves.reserve0,
         trade_size
     );
 
-    // 2. Sell token1 for token0 at sell_pool
-    U256 token0_received = calculate_swap_output(
-        sell_reserves.reserve1,
+    // 2. Sell token0 for token1 at sell_pool
+    U256 token1_received = calculate_swap_output(
         sell_reserves.reserve0,
-        token1_received
+        sell_reserves.reserve1,
+        token0_received
     );
 
-    // 3. Profit = token0_received - trade_size
-    if (simd::cmp_u256(token0_received, trade_size) > 0) {
-        return simd::sub_u256(token0_received, trade_size);
+    // 3. Profit = token1_received - trade_size
+    if (simd::cmp_u256(token1_received, trade_size) > 0) {
+        return simd::sub_u256(token1_received, trade_size);
     }
 
     return U256(0);
@@ -229,6 +229,24 @@ TEST(slippage_calculation) {
     ASSERT_TRUE(slippage_large > slippage_small);
 }
 
+TEST(arbitrage_profit_direction) {
+    // token0 costs 2 token1 in the buy pool and 2.2 in the sell pool
+    PoolReserves buy{};
+    buy.reserve0 = U256(5'000'000'000'000'000'000ULL);
+    buy.reserve1 = U256(10'000'000'000'000'000'000ULL);
+    PoolReserves sell{};
+    sell.reserve0 = U256(5'000'000'000'000'000'000ULL);
+    sell.reserve1 = U256(11'000'000'000'000'000'000ULL);
+    U256 trade_size(10'000'000'000'000'000ULL); // 0.01 token1
+
+    // token1 -> token0 where token0 is cheap, back to token1 where it's dear
+    U256 profit = calculate_arbitrage_profit(buy, sell, trade_size);
+    ASSERT_TRUE(!profit.is_zero());
+
+    // Swapped pools trade against the spread
+    ASSERT_TRUE(calculate_arbitrage_profit(sell, buy, trade_size).is_zero());
+}
+
 // ============================================================================
 // OPPORTUNITY SCANNER TESTS
 // ============================================================================
@@ -261,6 +279,60 @@ TEST(scanner_update_pool) {
     ASSERT_EQ(scanner.pool_count(), 2UL);
 }
 
+TEST(route_costs_net_spread) {
+    // Two 0.3% swaps and Aave's 0.09% premium cost ~69 bps, as in Rust
+    RouteCosts costs = default_route_costs();
+    ASSERT_EQ(costs.net_spread_bps(0, 2), -69L);
+    ASSERT_EQ(costs.net_spread_bps(50, 2), -20L);
+    ASSERT_EQ(costs.net_spread_bps(100, 2), 30L);
+
+    RouteCosts free{};
+    ASSERT_EQ(free.net_spread_bps(50, 2), 50L);
+
+    OpportunityScanner scanner;
+    ASSERT_EQ(scanner.route_costs().flash_loan_fee_bps, 9U);
+    scanner.set_route_costs(free);
+    ASSERT_EQ(scanner.route_costs().swap_fee_bps, 0U);
+}
+
+TEST(scanner_confidence_decay) {
+    ASSERT_EQ(decay_confidence_bps(10000, 0, 1000), 10000L);
+    ASSERT_EQ(decay_confidence_bps(10000, 1000, 1000), 5000L);
+    ASSERT_EQ(decay_confidence_bps(10000, 2000, 1000), 2500L);
+    ASSERT_EQ(decay_confidence_bps(10000, 5000, 0), 10000L); // no decay
+
+    ScannerConfig config = default_scanner_config();
+    config.confidence_half_life_ms = 1000;
+    OpportunityScanner scanner(config);
+
+    PriceResult price{};
+    price.confidence = 8000;
+    price.timestamp_ms = 1'700'000'000'000ULL;
+    ASSERT_EQ(scanner.decayed_confidence(price, price.timestamp_ms), 8000L);
+    ASSERT_EQ(scanner.decayed_confidence(price, price.timestamp_ms + 1000), 4000L);
+}
+
+TEST(scanner_observation_period) {
+    ScannerConfig config = default_scanner_config();
+    config.observation_period_ms = 60'000;
+    OpportunityScanner scanner(config);
+
+    PoolReserves pool{};
+    pool.reserve0 = U256(1'000'000'000'000'000'000ULL);
+    pool.reserve1 = U256(2'000'000'000'000'000'000ULL);
+    pool.pool_id = 1;
+    pool.dex_id = 1;
+    pool.timestamp_ms = 1'700'000'000'000ULL;
+    scanner.update_pool(pool);
+
+    ASSERT_TRUE(scanner.is_observing(1, 1, pool.timestamp_ms + 59'999));
+    // Later updates don't restart the clock
+    pool.timestamp_ms += 30'000;
+    scanner.update_pool(pool);
+    ASSERT_TRUE(!scanner.is_observing(1, 1, 1'700'000'060'000ULL));
+    ASSERT_TRUE(!scanner.is_observing(2, 1, pool.timestamp_ms)); // unknown pool
+}
+
 TEST(scanner_clear) {
     OpportunityScanner scanner;
 
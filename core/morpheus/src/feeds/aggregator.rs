@@ -0,0 +1,323 @@
+//! Price Feed Aggregator
+//!
+//! Merges the `PriceUpdate` streams of several feeds into a single channel.
+//! Optionally holds updates for a short reorder window so they are emitted
+//! in timestamp order; stragglers older than the last emitted update are
+//! dropped rather than delivered out of order.
+
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use matrix_types::PriceUpdate;
+
+/// Aggregator configuration
+#[derive(Debug, Clone)]
+pub struct AggregatorConfig {
+    /// Reorder window in milliseconds (0 = pass-through, no ordering)
+    pub reorder_window_ms: u64,
+    /// Output channel capacity
+    pub buffer_size: usize,
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            reorder_window_ms: 50,
+            buffer_size: 10000,
+        }
+    }
+}
+
+/// Buffered update, ordered by timestamp (earliest first in the heap)
+struct Pending {
+    update: PriceUpdate,
+    arrived_at: Instant,
+    seq: u64,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        // Reversed so BinaryHeap pops the earliest timestamp (then arrival)
+        other
+            .update
+            .timestamp_ms
+            .cmp(&self.update.timestamp_ms)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Merges multiple feed receivers into one ordered stream
+pub struct FeedAggregator {
+    config: AggregatorConfig,
+    inputs: Vec<mpsc::Receiver<PriceUpdate>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FeedAggregator {
+    pub fn new(config: AggregatorConfig) -> Self {
+        Self {
+            config,
+            inputs: Vec::new(),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Add a feed's update receiver
+    pub fn add_feed(&mut self, rx: mpsc::Receiver<PriceUpdate>) {
+        self.inputs.push(rx);
+    }
+
+    /// Number of feeds waiting to be merged
+    pub fn feed_count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Updates dropped for arriving outside the reorder window
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Start merging; returns the combined stream
+    ///
+    /// The output closes once every input has closed and the buffer is flushed.
+    pub fn start(&mut self) -> mpsc::Receiver<PriceUpdate> {
+        let (merged_tx, merged_rx) = mpsc::channel(self.config.buffer_size);
+        let (out_tx, out_rx) = mpsc::channel(self.config.buffer_size);
+
+        info!("FeedAggregator: Merging {} feeds", self.inputs.len());
+
+        // One forwarder per feed into a shared channel
+        for mut rx in self.inputs.drain(..) {
+            let tx = merged_tx.clone();
+            tokio::spawn(async move {
+                while let Some(update) = rx.recv().await {
+                    if tx.send(update).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(merged_tx);
+
+        let window_ms = self.config.reorder_window_ms;
+        let dropped = Arc::clone(&self.dropped);
+        tokio::spawn(async move {
+            if window_ms == 0 {
+                pass_through(merged_rx, out_tx).await;
+            } else {
+                reorder_loop(merged_rx, out_tx, window_ms, dropped).await;
+            }
+        });
+
+        out_rx
+    }
+}
+
+impl Default for FeedAggregator {
+    fn default() -> Self {
+        Self::new(AggregatorConfig::default())
+    }
+}
+
+/// Forward updates unchanged
+async fn pass_through(mut rx: mpsc::Receiver<PriceUpdate>, tx: mpsc::Sender<PriceUpdate>) {
+    while let Some(update) = rx.recv().await {
+        if tx.send(update).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Buffer updates and emit in timestamp order
+///
+/// An update is released once a newer update is `window_ms` ahead of it
+/// (event time) or it has been buffered for `window_ms` (wall time).
+async fn reorder_loop(
+    mut rx: mpsc::Receiver<PriceUpdate>,
+    tx: mpsc::Sender<PriceUpdate>,
+    window_ms: u64,
+    dropped: Arc<AtomicU64>,
+) {
+    let window = Duration::from_millis(window_ms);
+    let mut heap: BinaryHeap<Pending> = BinaryHeap::new();
+    let mut flush_interval = tokio::time::interval(window);
+    let mut max_seen_ms = 0u64;
+    let mut last_emitted_ms: Option<u64> = None;
+    let mut seq = 0u64;
+
+    loop {
+        tokio::select! {
+            update = rx.recv() => {
+                let update = match update {
+                    Some(u) => u,
+                    None => break,
+                };
+
+                if last_emitted_ms.is_some_and(|last| update.timestamp_ms < last) {
+                    debug!("FeedAggregator: Dropping straggler at {}ms", update.timestamp_ms);
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+
+                max_seen_ms = max_seen_ms.max(update.timestamp_ms);
+                heap.push(Pending { update, arrived_at: Instant::now(), seq });
+                seq += 1;
+
+                let watermark = max_seen_ms.saturating_sub(window_ms);
+                while heap.peek().is_some_and(|p| p.update.timestamp_ms <= watermark) {
+                    if !emit(&mut heap, &tx, &mut last_emitted_ms).await {
+                        return;
+                    }
+                }
+            }
+
+            _ = flush_interval.tick() => {
+                let now = Instant::now();
+                while heap.peek().is_some_and(|p| now.duration_since(p.arrived_at) >= window) {
+                    if !emit(&mut heap, &tx, &mut last_emitted_ms).await {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    // All inputs closed: flush what's left in order
+    while !heap.is_empty() {
+        if !emit(&mut heap, &tx, &mut last_emitted_ms).await {
+            return;
+        }
+    }
+}
+
+/// Pop and send the earliest buffered update; false if the output closed
+async fn emit(
+    heap: &mut BinaryHeap<Pending>,
+    tx: &mpsc::Sender<PriceUpdate>,
+    last_emitted_ms: &mut Option<u64>,
+) -> bool {
+    match heap.pop() {
+        Some(pending) => {
+            *last_emitted_ms = Some(pending.update.timestamp_ms);
+            tx.send(pending.update).await.is_ok()
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::{Address, U256};
+    use matrix_types::{ChainId, DexId};
+
+    fn update(timestamp_ms: u64) -> PriceUpdate {
+        PriceUpdate {
+            timestamp_ms,
+            chain: ChainId::Bsc,
+            dex: DexId::PancakeSwap,
+            pool: Address::zero(),
+            token0: Address::zero(),
+            token1: Address::zero(),
+            reserve0: U256::one(),
+            reserve1: U256::one(),
+            price: U256::exp10(18),
+            fee_bps: None,
+            provider: None,
+        }
+    }
+
+    async fn collect(mut rx: mpsc::Receiver<PriceUpdate>) -> Vec<u64> {
+        let mut out = Vec::new();
+        while let Some(u) = rx.recv().await {
+            out.push(u.timestamp_ms);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_merges_interleaved_feeds_in_order() {
+        let (tx_a, rx_a) = mpsc::channel(16);
+        let (tx_b, rx_b) = mpsc::channel(16);
+
+        let mut aggregator = FeedAggregator::new(AggregatorConfig {
+            reorder_window_ms: 1_000,
+            ..Default::default()
+        });
+        aggregator.add_feed(rx_a);
+        aggregator.add_feed(rx_b);
+        let out = aggregator.start();
+
+        for ts in [101, 103, 105] {
+            tx_a.send(update(ts)).await.unwrap();
+        }
+        for ts in [100, 102, 104, 106] {
+            tx_b.send(update(ts)).await.unwrap();
+        }
+        drop(tx_a);
+        drop(tx_b);
+
+        assert_eq!(collect(out).await, vec![100, 101, 102, 103, 104, 105, 106]);
+        assert_eq!(aggregator.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drops_stragglers_beyond_window() {
+        let (tx, rx) = mpsc::channel(16);
+
+        let mut aggregator = FeedAggregator::new(AggregatorConfig {
+            reorder_window_ms: 2,
+            ..Default::default()
+        });
+        aggregator.add_feed(rx);
+        let out = aggregator.start();
+
+        for ts in [10, 20, 30, 5] {
+            tx.send(update(ts)).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(collect(out).await, vec![10, 20, 30]);
+        assert_eq!(aggregator.dropped(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pass_through() {
+        let (tx, rx) = mpsc::channel(16);
+
+        let mut aggregator = FeedAggregator::new(AggregatorConfig {
+            reorder_window_ms: 0,
+            ..Default::default()
+        });
+        aggregator.add_feed(rx);
+        assert_eq!(aggregator.feed_count(), 1);
+        let out = aggregator.start();
+
+        for ts in [3, 1, 2] {
+            tx.send(update(ts)).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(collect(out).await, vec![3, 1, 2]);
+    }
+}
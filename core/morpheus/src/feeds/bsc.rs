@@ -68,6 +68,7 @@ impl PancakeSwapFeed {
             websocket_url: ws_url,
             reconnect_delay_ms: 1000,
             max_reconnect_attempts: 10,
+            http_url: None,
         };
 
         let pools = vec![
@@ -76,36 +77,42 @@ impl PancakeSwapFeed {
                 token0: *tokens::WBNB,
                 token1: *tokens::USDT,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *pancakeswap_pools::WBNB_BUSD,
                 token0: *tokens::WBNB,
                 token1: *tokens::BUSD,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *pancakeswap_pools::WBNB_USDC,
                 token0: *tokens::WBNB,
                 token1: *tokens::USDC,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *pancakeswap_pools::USDT_BUSD,
                 token0: *tokens::USDT,
                 token1: *tokens::BUSD,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *pancakeswap_pools::ETH_WBNB,
                 token0: *tokens::ETH,
                 token1: *tokens::WBNB,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *pancakeswap_pools::BTCB_WBNB,
                 token0: *tokens::BTCB,
                 token1: *tokens::WBNB,
                 dex: DexId::PancakeSwap,
+                fee_bps: None,
             },
         ];
 
@@ -120,6 +127,7 @@ impl PancakeSwapFeed {
             websocket_url: ws_url,
             reconnect_delay_ms: 1000,
             max_reconnect_attempts: 10,
+            http_url: None,
         };
 
         DexWebSocketFeed::new(config, pools)
@@ -157,6 +165,7 @@ impl BiswapFeed {
             websocket_url: ws_url,
             reconnect_delay_ms: 1000,
             max_reconnect_attempts: 10,
+            http_url: None,
         };
 
         let pools = vec![
@@ -165,18 +174,21 @@ impl BiswapFeed {
                 token0: *tokens::WBNB,
                 token1: *tokens::USDT,
                 dex: DexId::SushiSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *biswap_pools::WBNB_BUSD,
                 token0: *tokens::WBNB,
                 token1: *tokens::BUSD,
                 dex: DexId::SushiSwap,
+                fee_bps: None,
             },
             PoolSubscription {
                 pool_address: *biswap_pools::USDT_BUSD,
                 token0: *tokens::USDT,
                 token1: *tokens::BUSD,
                 dex: DexId::SushiSwap,
+                fee_bps: None,
             },
         ];
 
@@ -244,6 +256,7 @@ pub fn create_pool_subscription(
         token0: Address::from_str(token0).ok()?,
         token1: Address::from_str(token1).ok()?,
         dex,
+        fee_bps: None,
     })
 }
 
@@ -1,15 +1,20 @@
 //! WebSocket Connection Pool and Management
 //!
-//! Handles connection lifecycle, reconnection with exponential backoff,
-//! and connection health monitoring.
+//! Handles connection lifecycle, reconnection with jittered exponential
+//! backoff, and connection health monitoring.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
-use tokio::time::{sleep, Instant};
-use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream, MaybeTlsStream};
+use tokio::sync::{mpsc, watch, RwLock};
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, protocol::WebSocketConfig, Message};
+use tokio_tungstenite::{connect_async_with_config, WebSocketStream, MaybeTlsStream};
 use tokio::net::TcpStream;
 use futures_util::{SinkExt, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use tracing::{info, warn, error, debug};
 
 use crate::{MorpheusError, FeedStatus};
@@ -25,10 +30,24 @@ pub struct ConnectionConfig {
     pub max_reconnect_delay_ms: u64,
     /// Maximum reconnection attempts (0 = infinite)
     pub max_reconnect_attempts: u32,
+    /// Reconnects allowed per `reconnect_budget_window_ms` (0 = unlimited)
+    pub reconnect_budget: u32,
+    /// Window over which `reconnect_budget` is counted
+    pub reconnect_budget_window_ms: u64,
     /// Ping interval for keep-alive
     pub ping_interval_ms: u64,
+    /// Reconnect if a ping goes unanswered this long (0 = never)
+    pub pong_timeout_ms: u64,
     /// Connection timeout
     pub connect_timeout_ms: u64,
+    /// Maximum reassembled message size in bytes
+    pub max_message_size: usize,
+    /// Maximum single frame payload size in bytes
+    pub max_frame_size: usize,
+    /// Random jitter applied to each reconnect delay, +/- percent
+    pub reconnect_jitter_percent: u8,
+    /// Fixed RNG seed for reproducible jitter (None = OS entropy)
+    pub rng_seed: Option<u64>,
 }
 
 impl Default for ConnectionConfig {
@@ -38,12 +57,166 @@ impl Default for ConnectionConfig {
             initial_reconnect_delay_ms: 1000,
             max_reconnect_delay_ms: 30000,
             max_reconnect_attempts: 0, // infinite
+            reconnect_budget: 0,       // unlimited
+            reconnect_budget_window_ms: 300_000,
             ping_interval_ms: 30000,
+            pong_timeout_ms: 10000,
             connect_timeout_ms: 10000,
+            max_message_size: 64 << 20, // 64 MiB
+            max_frame_size: 16 << 20,   // 16 MiB
+            reconnect_jitter_percent: 20,
+            rng_seed: None,
         }
     }
 }
 
+impl ConnectionConfig {
+    /// Check that `url` is a well-formed `ws://` or `wss://` URL
+    ///
+    /// Reachability is not checked; unreachable hosts go through the
+    /// reconnect loop like any dropped connection.
+    pub fn validate_url(&self) -> Result<(), MorpheusError> {
+        let request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| MorpheusError::ConnectionFailed(format!("Invalid URL '{}': {}", self.url, e)))?;
+
+        match request.uri().scheme_str() {
+            Some("ws") | Some("wss") => Ok(()),
+            _ => Err(MorpheusError::ConnectionFailed(format!(
+                "Invalid URL '{}': scheme must be ws or wss",
+                self.url
+            ))),
+        }
+    }
+
+    /// Tungstenite config carrying the frame/message size limits
+    pub fn websocket_config(&self) -> WebSocketConfig {
+        WebSocketConfig {
+            max_message_size: Some(self.max_message_size),
+            max_frame_size: Some(self.max_frame_size),
+            ..Default::default()
+        }
+    }
+}
+
+/// Jittered exponential backoff for reconnects
+pub struct Backoff {
+    initial_ms: u64,
+    max_ms: u64,
+    jitter_percent: u64,
+    current_ms: u64,
+    rng: StdRng,
+}
+
+impl Backoff {
+    /// Build from connection config, seeding the RNG if `rng_seed` is set
+    pub fn from_config(config: &ConnectionConfig) -> Self {
+        let rng = match config.rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Self {
+            initial_ms: config.initial_reconnect_delay_ms,
+            max_ms: config.max_reconnect_delay_ms,
+            jitter_percent: config.reconnect_jitter_percent.min(100) as u64,
+            current_ms: config.initial_reconnect_delay_ms,
+            rng,
+        }
+    }
+
+    /// Next delay: current base +/- jitter, then double the base (capped)
+    pub fn next_delay_ms(&mut self) -> u64 {
+        let base = self.current_ms;
+        let span = base * self.jitter_percent / 100;
+        let delay = if span > 0 {
+            base - span + self.rng.gen_range(0..=2 * span)
+        } else {
+            base
+        };
+
+        self.current_ms = (base * 2).min(self.max_ms);
+        delay
+    }
+
+    /// Return to the initial delay after a successful connection
+    pub fn reset(&mut self) {
+        self.current_ms = self.initial_ms;
+    }
+}
+
+/// Reconnects allowed per sliding time window
+///
+/// Unlike `max_reconnect_attempts`, an exhausted budget recovers once old
+/// reconnects age out of the window.
+pub struct ReconnectBudget {
+    max: u32,
+    window: Duration,
+    recent: VecDeque<Instant>,
+}
+
+impl ReconnectBudget {
+    pub fn new(max: u32, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn from_config(config: &ConnectionConfig) -> Self {
+        Self::new(
+            config.reconnect_budget,
+            Duration::from_millis(config.reconnect_budget_window_ms),
+        )
+    }
+
+    /// Spend one reconnect at `now`, or return how long until one frees up
+    pub fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        if self.max == 0 {
+            return Ok(());
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= self.window)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() < self.max as usize {
+            self.recent.push_back(now);
+            return Ok(());
+        }
+        let oldest = self.recent.front().copied().unwrap_or(now);
+        Err(self.window.saturating_sub(now.duration_since(oldest)))
+    }
+}
+
+/// Messages above this percentage of `max_message_size` are logged
+const NEAR_LIMIT_PERCENT: usize = 90;
+
+/// Size of an incoming message relative to the configured limit
+///
+/// Tungstenite rejects anything over `max_message_size` with
+/// `Error::Capacity` before it is returned, so a received message is at
+/// most near the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSize {
+    Normal,
+    NearLimit,
+}
+
+/// Classify a received message length against `max_message_size`
+pub fn classify_message_size(len: usize, max_message_size: usize) -> MessageSize {
+    if len.saturating_mul(100) >= max_message_size.saturating_mul(NEAR_LIMIT_PERCENT) {
+        MessageSize::NearLimit
+    } else {
+        MessageSize::Normal
+    }
+}
+
 /// Connection statistics
 #[derive(Debug, Clone, Default)]
 pub struct ConnectionStats {
@@ -52,6 +225,38 @@ pub struct ConnectionStats {
     pub messages_received: u64,
     pub reconnect_count: u32,
     pub errors: u64,
+    pub oversized_messages: u64,
+    pub pong_timeouts: u64,
+}
+
+/// Handle for talking to a running connection
+///
+/// Clones share the same connection. Each successful (re)connect starts a
+/// new session, so anything sent per connection (subscriptions) has to be
+/// sent again when `sessions` changes.
+#[derive(Debug, Clone)]
+pub struct ConnectionHandle {
+    outbound: mpsc::Sender<String>,
+    reconnect: mpsc::Sender<()>,
+    sessions: watch::Receiver<u64>,
+}
+
+impl ConnectionHandle {
+    /// Sender for text frames to the server, written on the current session
+    pub fn sender(&self) -> mpsc::Sender<String> {
+        self.outbound.clone()
+    }
+
+    /// Drop the current session and reconnect with backoff
+    pub fn reconnect(&self) {
+        // A request already pending covers this one
+        let _ = self.reconnect.try_send(());
+    }
+
+    /// Count of sessions started, updated on every successful connect
+    pub fn sessions(&self) -> watch::Receiver<u64> {
+        self.sessions.clone()
+    }
 }
 
 /// Managed WebSocket connection with auto-reconnect
@@ -59,7 +264,11 @@ pub struct ManagedConnection {
     config: ConnectionConfig,
     status: Arc<RwLock<FeedStatus>>,
     stats: Arc<RwLock<ConnectionStats>>,
-    shutdown_tx: Option<mpsc::Sender<()>>,
+    /// System-wide shutdown; cancelling it stops this connection
+    shutdown: CancellationToken,
+    /// Child of `shutdown` for the running loop, cancelled by `disconnect`
+    session: Option<CancellationToken>,
+    handle: Option<ConnectionHandle>,
 }
 
 impl ManagedConnection {
@@ -68,10 +277,18 @@ impl ManagedConnection {
             config,
             status: Arc::new(RwLock::new(FeedStatus::Disconnected)),
             stats: Arc::new(RwLock::new(ConnectionStats::default())),
-            shutdown_tx: None,
+            shutdown: CancellationToken::new(),
+            session: None,
+            handle: None,
         }
     }
 
+    /// Stop the connection when `token` is cancelled
+    pub fn with_shutdown_token(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
     /// Get current connection status
     pub async fn status(&self) -> FeedStatus {
         self.status.read().await.clone()
@@ -82,13 +299,32 @@ impl ManagedConnection {
         self.stats.read().await.clone()
     }
 
+    /// Handle to the running connection, None until `connect`
+    pub fn handle(&self) -> Option<ConnectionHandle> {
+        self.handle.clone()
+    }
+
     /// Connect and start the message loop
-    /// Returns a receiver for incoming messages
+    /// Returns a receiver for incoming messages, or an error if the URL is invalid
     pub async fn connect(&mut self) -> Result<mpsc::Receiver<Message>, MorpheusError> {
-        let (msg_tx, msg_rx) = mpsc::channel::<Message>(1000);
-        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
+        self.config.validate_url()?;
 
-        self.shutdown_tx = Some(shutdown_tx);
+        let (msg_tx, msg_rx) = mpsc::channel::<Message>(1000);
+        let (outbound_tx, outbound_rx) = mpsc::channel::<String>(100);
+        let (reconnect_tx, reconnect_rx) = mpsc::channel::<()>(1);
+        let (sessions_tx, sessions_rx) = watch::channel(0u64);
+        let cancel = self.shutdown.child_token();
+        self.session = Some(cancel.clone());
+        self.handle = Some(ConnectionHandle {
+            outbound: outbound_tx,
+            reconnect: reconnect_tx,
+            sessions: sessions_rx,
+        });
+        let control = SessionControl {
+            outbound: outbound_rx,
+            reconnect: reconnect_rx,
+            sessions: sessions_tx,
+        };
 
         let config = self.config.clone();
         let status = Arc::clone(&self.status);
@@ -96,7 +332,7 @@ impl ManagedConnection {
 
         // Spawn connection manager task
         tokio::spawn(async move {
-            connection_loop(config, status, stats, msg_tx, shutdown_rx).await;
+            connection_loop(config, status, stats, msg_tx, control, cancel).await;
         });
 
         Ok(msg_rx)
@@ -104,29 +340,48 @@ impl ManagedConnection {
 
     /// Disconnect gracefully
     pub async fn disconnect(&mut self) -> Result<(), MorpheusError> {
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(()).await;
+        if let Some(session) = self.session.take() {
+            session.cancel();
         }
         *self.status.write().await = FeedStatus::Disconnected;
         Ok(())
     }
 }
 
+/// Connection loop's side of `ConnectionHandle`
+struct SessionControl {
+    outbound: mpsc::Receiver<String>,
+    reconnect: mpsc::Receiver<()>,
+    sessions: watch::Sender<u64>,
+}
+
+impl SessionControl {
+    /// Start a session: drop what was meant for the last one, then announce it
+    fn start_session(&mut self) {
+        while self.outbound.try_recv().is_ok() {}
+        while self.reconnect.try_recv().is_ok() {}
+        self.sessions.send_modify(|count| *count += 1);
+    }
+}
+
 /// Main connection loop with reconnection logic
 async fn connection_loop(
     config: ConnectionConfig,
     status: Arc<RwLock<FeedStatus>>,
     stats: Arc<RwLock<ConnectionStats>>,
     msg_tx: mpsc::Sender<Message>,
-    mut shutdown_rx: mpsc::Receiver<()>,
+    mut control: SessionControl,
+    cancel: CancellationToken,
 ) {
     let mut reconnect_attempt = 0u32;
-    let mut reconnect_delay = config.initial_reconnect_delay_ms;
+    let mut backoff = Backoff::from_config(&config);
+    let mut budget = ReconnectBudget::from_config(&config);
 
     loop {
         // Check for shutdown
-        if shutdown_rx.try_recv().is_ok() {
+        if cancel.is_cancelled() {
             info!("Connection loop received shutdown signal");
+            *status.write().await = FeedStatus::Disconnected;
             break;
         }
 
@@ -139,11 +394,13 @@ async fn connection_loop(
         info!("Connecting to WebSocket: {}", config.url);
 
         // Attempt connection with timeout
-        let connect_result = tokio::time::timeout(
-            Duration::from_millis(config.connect_timeout_ms),
-            connect_async(&config.url),
-        )
-        .await;
+        let connect_result = tokio::select! {
+            _ = cancel.cancelled() => continue,
+            result = tokio::time::timeout(
+                Duration::from_millis(config.connect_timeout_ms),
+                connect_async_with_config(&config.url, Some(config.websocket_config()), false),
+            ) => result,
+        };
 
         match connect_result {
             Ok(Ok((ws_stream, _response))) => {
@@ -158,7 +415,8 @@ async fn connection_loop(
 
                 // Reset reconnect state on successful connection
                 reconnect_attempt = 0;
-                reconnect_delay = config.initial_reconnect_delay_ms;
+                backoff.reset();
+                control.start_session();
 
                 // Run message loop
                 let disconnect_reason = message_loop(
@@ -166,13 +424,15 @@ async fn connection_loop(
                     &config,
                     Arc::clone(&stats),
                     msg_tx.clone(),
-                    &mut shutdown_rx,
+                    &mut control,
+                    &cancel,
                 )
                 .await;
 
                 match disconnect_reason {
                     DisconnectReason::Shutdown => {
                         info!("WebSocket disconnected by shutdown request");
+                        *status.write().await = FeedStatus::Disconnected;
                         break;
                     }
                     DisconnectReason::Error(e) => {
@@ -182,6 +442,15 @@ async fn connection_loop(
                     DisconnectReason::ServerClosed => {
                         info!("WebSocket closed by server");
                     }
+                    DisconnectReason::Requested => {
+                        info!("Reconnect requested");
+                    }
+                    DisconnectReason::PongTimeout => {
+                        warn!("No pong within {}ms, assuming half-open connection", config.pong_timeout_ms);
+                        let mut s = stats.write().await;
+                        s.errors += 1;
+                        s.pong_timeouts += 1;
+                    }
                 }
             }
             Ok(Err(e)) => {
@@ -202,17 +471,31 @@ async fn connection_loop(
             break;
         }
 
-        // Exponential backoff
+        // Out of budget: stay failed until the window frees a reconnect
+        while let Err(wait) = budget.try_acquire(Instant::now()) {
+            warn!(
+                "Reconnect budget exhausted ({} per {}ms), waiting {}ms",
+                config.reconnect_budget, config.reconnect_budget_window_ms, wait.as_millis()
+            );
+            *status.write().await = FeedStatus::Failed("Reconnect budget exhausted".to_string());
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = sleep(wait) => {}
+            }
+        }
+
+        // Exponential backoff with jitter
+        let reconnect_delay = backoff.next_delay_ms();
         info!(
             "Reconnecting in {}ms (attempt {})",
             reconnect_delay, reconnect_attempt
         );
         *status.write().await = FeedStatus::Reconnecting(reconnect_attempt);
 
-        sleep(Duration::from_millis(reconnect_delay)).await;
-
-        // Increase delay with exponential backoff, capped at max
-        reconnect_delay = (reconnect_delay * 2).min(config.max_reconnect_delay_ms);
+        tokio::select! {
+            _ = cancel.cancelled() => {}
+            _ = sleep(Duration::from_millis(reconnect_delay)) => {}
+        }
     }
 }
 
@@ -220,6 +503,10 @@ enum DisconnectReason {
     Shutdown,
     Error(String),
     ServerClosed,
+    /// A ping went unanswered past `pong_timeout_ms`
+    PongTimeout,
+    /// `ConnectionHandle::reconnect` was called
+    Requested,
 }
 
 /// Message loop - handles incoming messages and ping/pong
@@ -228,25 +515,50 @@ async fn message_loop(
     config: &ConnectionConfig,
     stats: Arc<RwLock<ConnectionStats>>,
     msg_tx: mpsc::Sender<Message>,
-    shutdown_rx: &mut mpsc::Receiver<()>,
+    control: &mut SessionControl,
+    cancel: &CancellationToken,
 ) -> DisconnectReason {
     let (mut write, mut read) = ws_stream.split();
     let mut ping_interval = tokio::time::interval(Duration::from_millis(config.ping_interval_ms));
+    // Deadline for a pong to the oldest unanswered ping
+    let mut pong_deadline: Option<Instant> = None;
 
     loop {
         tokio::select! {
             // Check for shutdown
-            _ = shutdown_rx.recv() => {
+            _ = cancel.cancelled() => {
                 debug!("Message loop received shutdown");
                 let _ = write.close().await;
                 return DisconnectReason::Shutdown;
             }
 
+            // The consumer gave up on this session
+            Some(()) = control.reconnect.recv() => {
+                let _ = write.close().await;
+                return DisconnectReason::Requested;
+            }
+
+            // Outgoing requests
+            Some(text) = control.outbound.recv() => {
+                if let Err(e) = write.send(Message::Text(text)).await {
+                    return DisconnectReason::Error(format!("Send failed: {}", e));
+                }
+            }
+
             // Ping interval for keep-alive
             _ = ping_interval.tick() => {
                 if let Err(e) = write.send(Message::Ping(vec![])).await {
                     return DisconnectReason::Error(format!("Ping failed: {}", e));
                 }
+                if config.pong_timeout_ms > 0 && pong_deadline.is_none() {
+                    pong_deadline = Some(Instant::now() + Duration::from_millis(config.pong_timeout_ms));
+                }
+            }
+
+            // Half-open connection: pings are sent but never answered
+            _ = sleep_until(pong_deadline.unwrap_or_else(Instant::now)), if pong_deadline.is_some() => {
+                // No close handshake: the peer is gone and a close could block
+                return DisconnectReason::PongTimeout;
             }
 
             // Incoming messages
@@ -255,6 +567,16 @@ async fn message_loop(
                     Some(Ok(message)) => {
                         match &message {
                             Message::Text(_) | Message::Binary(_) => {
+                                // Oversized messages arrive as `Error::Capacity` below
+                                if classify_message_size(message.len(), config.max_message_size)
+                                    == MessageSize::NearLimit
+                                {
+                                    warn!(
+                                        "Large message near size limit: {} bytes (max {})",
+                                        message.len(), config.max_message_size
+                                    );
+                                }
+
                                 stats.write().await.messages_received += 1;
                                 stats.write().await.last_message_at = Some(Instant::now());
 
@@ -270,13 +592,23 @@ async fn message_loop(
                             }
                             Message::Pong(_) => {
                                 // Keep-alive confirmed
+                                pong_deadline = None;
                             }
                             Message::Close(_) => {
                                 return DisconnectReason::ServerClosed;
                             }
-                            Message::Frame(_) => {}
+                            Message::Frame(_) => {
+                                // Raw frames are only produced when writing
+                                debug!("Ignoring raw frame");
+                            }
                         }
                     }
+                    Some(Err(tungstenite::Error::Capacity(e))) => {
+                        // Message or frame exceeded configured limits
+                        warn!("WebSocket message exceeds size limits: {}", e);
+                        stats.write().await.oversized_messages += 1;
+                        return DisconnectReason::Error(format!("Capacity exceeded: {}", e));
+                    }
                     Some(Err(e)) => {
                         return DisconnectReason::Error(format!("WebSocket error: {}", e));
                     }
@@ -289,28 +621,60 @@ async fn message_loop(
     }
 }
 
+/// Outcome of `ConnectionPool::connect_all`
+#[derive(Debug, Default)]
+pub struct PoolConnectResult {
+    /// Receivers of the connections that started, in pool order
+    pub receivers: Vec<mpsc::Receiver<Message>>,
+    /// Pool index and error of each connection that failed
+    pub errors: Vec<(usize, MorpheusError)>,
+}
+
+impl PoolConnectResult {
+    /// True if every connection started
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 /// Connection pool for managing multiple WebSocket connections
 pub struct ConnectionPool {
     connections: Vec<ManagedConnection>,
+    shutdown: CancellationToken,
 }
 
 impl ConnectionPool {
     pub fn new() -> Self {
         Self {
             connections: Vec::new(),
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Stop every connection added afterwards when `token` is cancelled
+    pub fn with_shutdown_token(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
     pub fn add(&mut self, config: ConnectionConfig) {
-        self.connections.push(ManagedConnection::new(config));
+        self.connections
+            .push(ManagedConnection::new(config).with_shutdown_token(self.shutdown.clone()));
     }
 
-    pub async fn connect_all(&mut self) -> Result<Vec<mpsc::Receiver<Message>>, MorpheusError> {
-        let mut receivers = Vec::new();
-        for conn in &mut self.connections {
-            receivers.push(conn.connect().await?);
+    /// Connect every connection, collecting failures instead of stopping at the first
+    pub async fn connect_all(&mut self) -> PoolConnectResult {
+        let mut result = PoolConnectResult::default();
+        for (index, conn) in self.connections.iter_mut().enumerate() {
+            match conn.connect().await {
+                Ok(receiver) => result.receivers.push(receiver),
+                Err(e) => {
+                    warn!("Connection {} ({}) failed: {}", index, conn.config.url, e);
+                    result.errors.push((index, e));
+                }
+            }
         }
-        Ok(receivers)
+        result
     }
 
     pub async fn disconnect_all(&mut self) -> Result<(), MorpheusError> {
@@ -338,6 +702,7 @@ impl Default for ConnectionPool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::greeting_server;
 
     #[test]
     fn test_connection_config_default() {
@@ -346,9 +711,260 @@ mod tests {
         assert_eq!(config.max_reconnect_delay_ms, 30000);
     }
 
+    fn delays(config: &ConnectionConfig, n: usize) -> Vec<u64> {
+        let mut backoff = Backoff::from_config(config);
+        (0..n).map(|_| backoff.next_delay_ms()).collect()
+    }
+
+    #[test]
+    fn test_seeded_backoff_is_reproducible() {
+        let config = ConnectionConfig {
+            rng_seed: Some(42),
+            ..Default::default()
+        };
+
+        let first = delays(&config, 8);
+        assert_eq!(first, delays(&config, 8));
+
+        let other = ConnectionConfig { rng_seed: Some(7), ..config.clone() };
+        assert_ne!(first, delays(&other, 8));
+
+        // Each delay stays within +/-20% of the doubling base, capped at max
+        let mut base = config.initial_reconnect_delay_ms;
+        for delay in first {
+            assert!(delay >= base * 80 / 100 && delay <= base * 120 / 100, "{} vs base {}", delay, base);
+            base = (base * 2).min(config.max_reconnect_delay_ms);
+        }
+    }
+
+    #[test]
+    fn test_backoff_without_jitter_and_reset() {
+        let config = ConnectionConfig {
+            reconnect_jitter_percent: 0,
+            max_reconnect_delay_ms: 5000,
+            ..Default::default()
+        };
+
+        let mut backoff = Backoff::from_config(&config);
+        let sequence: Vec<u64> = (0..5).map(|_| backoff.next_delay_ms()).collect();
+        assert_eq!(sequence, vec![1000, 2000, 4000, 5000, 5000]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay_ms(), 1000);
+    }
+
+    #[test]
+    fn test_websocket_size_limits() {
+        let config = ConnectionConfig {
+            max_message_size: 1 << 20,
+            max_frame_size: 256 << 10,
+            ..Default::default()
+        };
+
+        let ws = config.websocket_config();
+        assert_eq!(ws.max_message_size, Some(1 << 20));
+        assert_eq!(ws.max_frame_size, Some(256 << 10));
+    }
+
+    #[test]
+    fn test_large_message_near_limit() {
+        let max = 1 << 20;
+
+        // Synthetic log batch just under the limit
+        let payload = format!("{{\"data\":\"{}\"}}", "a".repeat(max - 16));
+        let message = Message::Text(payload);
+        assert!(message.len() <= max);
+        assert_eq!(classify_message_size(message.len(), max), MessageSize::NearLimit);
+
+        assert_eq!(classify_message_size(max / 2, max), MessageSize::Normal);
+    }
+
     #[test]
     fn test_connection_pool_creation() {
         let pool = ConnectionPool::new();
         assert!(pool.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_connect_all_partial_success() {
+        let url = greeting_server("hello").await;
+
+        let mut pool = ConnectionPool::new();
+        for url in [url.as_str(), "not a url", "http://example.com", url.as_str()] {
+            pool.add(ConnectionConfig { url: url.to_string(), ..Default::default() });
+        }
+
+        let mut result = pool.connect_all().await;
+        assert!(!result.is_complete());
+        assert_eq!(result.receivers.len(), 2);
+        let failed: Vec<usize> = result.errors.iter().map(|(index, _)| *index).collect();
+        assert_eq!(failed, vec![1, 2]);
+        assert!(result
+            .errors
+            .iter()
+            .all(|(_, e)| matches!(e, MorpheusError::ConnectionFailed(_))));
+
+        // The good connections are live despite the bad ones
+        for receiver in &mut result.receivers {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(message, Message::Text("hello".to_string()));
+        }
+
+        pool.disconnect_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_token_stops_all_connections() {
+        let url = greeting_server("hello").await;
+        let token = CancellationToken::new();
+        let mut pool = ConnectionPool::new().with_shutdown_token(token.clone());
+        for _ in 0..3 {
+            pool.add(ConnectionConfig { url: url.clone(), ..Default::default() });
+        }
+
+        let mut result = pool.connect_all().await;
+        for receiver in &mut result.receivers {
+            let greeting = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap();
+            assert!(greeting.is_some());
+        }
+
+        // One cancel ends every connection loop, closing its channel
+        token.cancel();
+        for receiver in &mut result.receivers {
+            let closed = tokio::time::timeout(Duration::from_secs(2), receiver.recv()).await.unwrap();
+            assert_eq!(closed, None);
+        }
+        for conn in &pool.connections {
+            assert_eq!(conn.status().await, FeedStatus::Disconnected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_is_capacity_error() {
+        let greeting: &'static str = Box::leak("a".repeat(4096).into_boxed_str());
+        let url = greeting_server(greeting).await;
+
+        let mut conn = ManagedConnection::new(ConnectionConfig {
+            url,
+            max_message_size: 1024,
+            max_frame_size: 1024,
+            initial_reconnect_delay_ms: 1_000,
+            ..Default::default()
+        });
+        let mut rx = conn.connect().await.unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while conn.stats().await.oversized_messages == 0 && Instant::now() < deadline {
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        // Rejected by tungstenite, never forwarded
+        assert_eq!(conn.stats().await.oversized_messages, 1);
+        assert!(rx.try_recv().is_err());
+        conn.disconnect().await.unwrap();
+    }
+
+    #[test]
+    fn test_reconnect_budget_recovers_after_window() {
+        let mut budget = ReconnectBudget::new(3, Duration::from_secs(300));
+        let start = Instant::now();
+
+        for i in 0..3 {
+            assert!(budget.try_acquire(start + Duration::from_secs(i * 10)).is_ok());
+        }
+        // Exhausted: the oldest reconnect frees up 300s after it was spent
+        assert_eq!(budget.try_acquire(start + Duration::from_secs(60)), Err(Duration::from_secs(240)));
+        assert!(budget.try_acquire(start + Duration::from_secs(299)).is_err());
+
+        assert!(budget.try_acquire(start + Duration::from_secs(300)).is_ok());
+        assert_eq!(budget.try_acquire(start + Duration::from_secs(301)), Err(Duration::from_secs(9)));
+
+        let mut unlimited = ReconnectBudget::new(0, Duration::from_secs(1));
+        assert!((0..100).all(|_| unlimited.try_acquire(start).is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_fails_until_window_refills() {
+        // Nothing listens here: every attempt is refused immediately
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut conn = ManagedConnection::new(ConnectionConfig {
+            url,
+            initial_reconnect_delay_ms: 5,
+            max_reconnect_delay_ms: 5,
+            reconnect_jitter_percent: 0,
+            reconnect_budget: 3,
+            reconnect_budget_window_ms: 400,
+            ..Default::default()
+        });
+        let _rx = conn.connect().await.unwrap();
+
+        let wait_for = |failed: bool| {
+            let status = Arc::clone(&conn.status);
+            async move {
+                let deadline = Instant::now() + Duration::from_secs(5);
+                loop {
+                    let current = status.read().await.clone();
+                    if matches!(current, FeedStatus::Failed(_)) == failed || Instant::now() > deadline {
+                        return current;
+                    }
+                    sleep(Duration::from_millis(5)).await;
+                }
+            }
+        };
+
+        let exhausted_at = Instant::now();
+        assert_eq!(wait_for(true).await, FeedStatus::Failed("Reconnect budget exhausted".to_string()));
+        let errors = conn.stats().await.errors;
+        assert_eq!(errors, 4, "initial attempt plus three budgeted reconnects");
+
+        // Retrying resumes once the window frees a reconnect
+        assert!(matches!(wait_for(false).await, FeedStatus::Reconnecting(_) | FeedStatus::Connecting));
+        assert!(exhausted_at.elapsed() >= Duration::from_millis(300));
+        conn.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pong_timeout_reconnects() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Completes the handshake, then never reads again: pings go unanswered
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let _ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    std::future::pending::<()>().await;
+                });
+            }
+        });
+
+        let mut conn = ManagedConnection::new(ConnectionConfig {
+            url,
+            ping_interval_ms: 20,
+            pong_timeout_ms: 50,
+            initial_reconnect_delay_ms: 10,
+            reconnect_jitter_percent: 0,
+            ..Default::default()
+        });
+        let _rx = conn.connect().await.unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while accepted.load(Ordering::SeqCst) < 2 && Instant::now() < deadline {
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        assert!(accepted.load(Ordering::SeqCst) >= 2, "no reconnect after pong timeout");
+        assert!(conn.stats().await.pong_timeouts >= 1);
+        conn.disconnect().await.unwrap();
+    }
 }
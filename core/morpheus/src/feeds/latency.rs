@@ -0,0 +1,128 @@
+//! Feed Latency Estimation
+//!
+//! Estimates feed latency as the delay between a block's timestamp and the
+//! local receipt of an event from that block, over a rolling window of
+//! samples. A rising p99 is the early sign of a degrading RPC/WebSocket
+//! provider, before it shows up as missed trades.
+//!
+//! Block timestamps have one-second resolution, so samples include up to
+//! a second of block-production offset; compare percentiles over time
+//! rather than reading single samples.
+
+use std::collections::VecDeque;
+
+/// Samples retained by default
+pub const DEFAULT_LATENCY_WINDOW: usize = 1_024;
+
+/// Latency percentiles over the current window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub p50_ms: u64,
+    pub p99_ms: u64,
+    pub samples: usize,
+}
+
+/// Rolling window of block-to-receipt delays
+#[derive(Debug, Clone)]
+pub struct LatencyEstimator {
+    window: usize,
+    samples: VecDeque<u64>,
+}
+
+impl LatencyEstimator {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record an event from a block stamped `block_timestamp_ms`, received
+    /// at `received_ms`; returns the sample
+    ///
+    /// A block "from the future" (local clock behind) counts as zero delay.
+    pub fn record(&mut self, block_timestamp_ms: u64, received_ms: u64) -> u64 {
+        let latency = received_ms.saturating_sub(block_timestamp_ms);
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+        latency
+    }
+
+    /// Nearest-rank percentile (0.0 - 100.0) of the window, in ms
+    pub fn percentile(&self, p: f64) -> Option<u64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+
+    /// p50 and p99 over the window, None before the first sample
+    pub fn stats(&self) -> Option<LatencyStats> {
+        Some(LatencyStats {
+            p50_ms: self.percentile(50.0)?,
+            p99_ms: self.percentile(99.0)?,
+            samples: self.samples.len(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+impl Default for LatencyEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK_MS: u64 = 1_700_000_000_000;
+
+    #[test]
+    fn test_percentiles_from_synthetic_timestamps() {
+        let mut estimator = LatencyEstimator::default();
+        assert_eq!(estimator.stats(), None);
+
+        // Latencies 1..=100 ms, recorded out of order
+        for latency in (1..=100u64).rev() {
+            assert_eq!(estimator.record(BLOCK_MS, BLOCK_MS + latency), latency);
+        }
+
+        let stats = estimator.stats().unwrap();
+        assert_eq!(stats, LatencyStats { p50_ms: 50, p99_ms: 99, samples: 100 });
+        assert_eq!(estimator.percentile(0.0), Some(1));
+        assert_eq!(estimator.percentile(100.0), Some(100));
+    }
+
+    #[test]
+    fn test_window_tracks_degradation() {
+        let mut estimator = LatencyEstimator::new(10);
+        for _ in 0..10 {
+            estimator.record(BLOCK_MS, BLOCK_MS + 200);
+        }
+        assert_eq!(estimator.stats().unwrap().p99_ms, 200);
+
+        // Provider slows down: old samples roll out of the window
+        for _ in 0..10 {
+            estimator.record(BLOCK_MS, BLOCK_MS + 3_000);
+        }
+        assert_eq!(estimator.len(), 10);
+        assert_eq!(estimator.stats().unwrap().p50_ms, 3_000);
+
+        // Clock skew never produces negative latency
+        assert_eq!(estimator.record(BLOCK_MS + 500, BLOCK_MS), 0);
+    }
+}
@@ -4,8 +4,20 @@
 
 pub mod connection;
 pub mod dex_feed;
+pub mod latency;
 pub mod bsc;
+pub mod aggregator;
+pub mod source;
+pub mod recorder;
+pub mod multicall;
+#[cfg(any(test, feature = "stub-feeds"))]
+pub mod stub;
 
-pub use connection::{ConnectionPool, ConnectionConfig, ManagedConnection, ConnectionStats};
-pub use dex_feed::{DexWebSocketFeed, PoolSubscription};
+pub use connection::{Backoff, ConnectionHandle, ConnectionPool, ConnectionConfig, ManagedConnection, ConnectionStats, MessageSize, PoolConnectResult, ReconnectBudget};
+pub use dex_feed::{DexWebSocketFeed, ParseErrorTolerance, PoolSubscription, V2Swap, DEFAULT_PARSE_ERROR_TOLERANCE, parse_swap_event, pool_event_signature, pool_event_topic, v2_swap_topic};
+pub use latency::{LatencyEstimator, LatencyStats, DEFAULT_LATENCY_WINDOW};
 pub use bsc::{BscPriceFeed, PancakeSwapFeed, BiswapFeed};
+pub use aggregator::{FeedAggregator, AggregatorConfig};
+pub use source::{FeedSource, HttpPollingSource, PriceSource, PriceStream, receiver_stream};
+pub use recorder::{FeedRecorder, RecorderConfig};
+pub use multicall::{warm_up_reserves_multicall, MULTICALL3_ADDRESS};
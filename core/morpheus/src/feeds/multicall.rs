@@ -0,0 +1,173 @@
+//! Batched reserve warm-up through Multicall3
+//!
+//! Fetching `getReserves()` with one `eth_call` per pool takes hundreds of
+//! round trips on a large pool list. Multicall3's `aggregate3` runs every
+//! call inside a single `eth_call`, with `allowFailure` set so a pool that
+//! isn't a V2 pair (or reverts) only loses its own result.
+
+use ethers::abi::{self, ParamType, Token};
+use ethers::core::types::transaction::eip2718::TypedTransaction;
+use ethers::core::types::{Address, Bytes, TransactionRequest, H160, U256};
+use ethers::providers::Middleware;
+use ethers::utils::id;
+
+use crate::MorpheusError;
+use super::dex_feed::{parse_reserves, PoolSubscription, GET_RESERVES_SELECTOR};
+
+/// Multicall3, deployed at the same address on most EVM chains
+pub const MULTICALL3_ADDRESS: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17, 0x39, 0x76, 0xca, 0x11,
+]);
+
+/// `aggregate3((address,bool,bytes)[])` signature
+const AGGREGATE3_SIGNATURE: &str = "aggregate3((address,bool,bytes)[])";
+
+/// Fetch every pool's reserves in one `aggregate3` call
+///
+/// Results are in pool order; `None` where the pool's call failed or
+/// returned something that isn't a V2 reserves tuple. Fails only if the
+/// multicall itself fails or its response can't be decoded.
+pub async fn warm_up_reserves_multicall<M: Middleware>(
+    pools: &[PoolSubscription],
+    multicall_address: Address,
+    provider: &M,
+) -> Result<Vec<Option<(U256, U256)>>, MorpheusError> {
+    if pools.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(multicall_address)
+        .data(encode_aggregate3(pools))
+        .into();
+    let data = provider
+        .call(&tx, None)
+        .await
+        .map_err(|e| MorpheusError::ConnectionFailed(format!("Multicall failed: {}", e)))?;
+
+    let results = decode_aggregate3(&data)?;
+    if results.len() != pools.len() {
+        return Err(MorpheusError::ParseError(format!(
+            "Multicall returned {} results for {} pools",
+            results.len(),
+            pools.len()
+        )));
+    }
+    Ok(results
+        .into_iter()
+        .map(|result| result.and_then(|data| parse_reserves(&data)))
+        .collect())
+}
+
+/// Calldata for `aggregate3` with a `getReserves()` call per pool
+fn encode_aggregate3(pools: &[PoolSubscription]) -> Bytes {
+    let calls = pools
+        .iter()
+        .map(|pool| {
+            Token::Tuple(vec![
+                Token::Address(pool.pool_address),
+                Token::Bool(true),
+                Token::Bytes(GET_RESERVES_SELECTOR.to_vec()),
+            ])
+        })
+        .collect();
+
+    let mut data = id(AGGREGATE3_SIGNATURE).to_vec();
+    data.extend(abi::encode(&[Token::Array(calls)]));
+    Bytes::from(data)
+}
+
+/// Decode `aggregate3`'s `(bool success, bytes returnData)[]`
+fn decode_aggregate3(data: &[u8]) -> Result<Vec<Option<Vec<u8>>>, MorpheusError> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes])));
+    let decoded = abi::decode(&[result_type], data)
+        .map_err(|e| MorpheusError::ParseError(format!("Invalid multicall response: {}", e)))?;
+
+    let Some(Token::Array(results)) = decoded.into_iter().next() else {
+        return Err(MorpheusError::ParseError("Invalid multicall response".to_string()));
+    };
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(true), Token::Bytes(data)] => Some(data.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::aggregate3_response;
+    use matrix_types::DexId;
+
+    fn reserves(reserve0: u64, reserve1: u64) -> Vec<u8> {
+        abi::encode(&[
+            Token::Uint(reserve0.into()),
+            Token::Uint(reserve1.into()),
+            Token::Uint(1_700_000_000u64.into()),
+        ])
+    }
+
+    #[test]
+    fn test_encodes_one_call_per_pool() {
+        assert_eq!(
+            format!("{:?}", MULTICALL3_ADDRESS),
+            "0xca11bde05977b3631167028862be2a173976ca11"
+        );
+
+        let pools: Vec<PoolSubscription> = (1..=3)
+            .map(|i| PoolSubscription {
+                pool_address: Address::from_low_u64_be(i),
+                token0: Address::repeat_byte(1),
+                token1: Address::repeat_byte(2),
+                dex: DexId::PancakeSwap,
+                fee_bps: None,
+            })
+            .collect();
+
+        let data = encode_aggregate3(&pools);
+        assert_eq!(data[..4], id(AGGREGATE3_SIGNATURE));
+        let call_type = ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes]);
+        let decoded = abi::decode(&[ParamType::Array(Box::new(call_type))], &data[4..]).unwrap();
+        let Token::Array(calls) = &decoded[0] else { panic!("not an array") };
+        assert_eq!(calls.len(), 3);
+        assert_eq!(
+            calls[1],
+            Token::Tuple(vec![
+                Token::Address(Address::from_low_u64_be(2)),
+                Token::Bool(true),
+                Token::Bytes(GET_RESERVES_SELECTOR.to_vec()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decodes_partial_failures() {
+        let response = aggregate3_response(&[
+            (true, reserves(1_000, 2_000)),
+            (false, Vec::new()),   // reverted
+            (true, vec![0x01; 4]), // not a V2 pair
+            (true, reserves(5, 7)),
+        ]);
+
+        let results: Vec<Option<(U256, U256)>> = decode_aggregate3(&response)
+            .unwrap()
+            .into_iter()
+            .map(|data| data.and_then(|data| parse_reserves(&data)))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                Some((U256::from(1_000u64), U256::from(2_000u64))),
+                None,
+                None,
+                Some((U256::from(5u64), U256::from(7u64))),
+            ]
+        );
+        assert!(decode_aggregate3(&[0xde, 0xad]).is_err());
+    }
+}
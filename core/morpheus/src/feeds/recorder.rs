@@ -0,0 +1,330 @@
+//! Feed Recorder
+//!
+//! Taps the `PriceUpdate` stream and appends each update as a JSON line to
+//! disk, so a live session can be replayed later. Recording never slows the
+//! feed: updates are handed to a writer thread over a bounded queue and
+//! dropped (and counted) when it is full. Output rotates into numbered
+//! segments by size or age.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use matrix_types::PriceUpdate;
+
+use crate::MorpheusError;
+
+/// Recorder configuration
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    /// Base path; segments are written as `<stem>-0000.<ext>`, `<stem>-0001.<ext>`, ...
+    pub path: PathBuf,
+    /// Rotate once a segment reaches this size (None = no size limit)
+    pub max_segment_bytes: Option<u64>,
+    /// Rotate once a segment has been open this long (None = no age limit)
+    pub max_segment_age_ms: Option<u64>,
+    /// Queue capacity between the feed and the writer thread
+    pub buffer_size: usize,
+}
+
+impl RecorderConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_segment_bytes: Some(256 * 1024 * 1024),
+            max_segment_age_ms: Some(60 * 60 * 1000),
+            buffer_size: 10000,
+        }
+    }
+
+    /// Path of segment `index`
+    pub fn segment_path(&self, index: u32) -> PathBuf {
+        let stem = self.path.file_stem().and_then(|s| s.to_str()).unwrap_or("feed");
+        let name = match self.path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => format!("{}-{:04}.{}", stem, index, ext),
+            None => format!("{}-{:04}", stem, index),
+        };
+        self.path.with_file_name(name)
+    }
+}
+
+/// Records price updates to rotating JSON-lines files
+pub struct FeedRecorder {
+    tx: Option<SyncSender<PriceUpdate>>,
+    writer: Option<JoinHandle<Result<Vec<PathBuf>, MorpheusError>>>,
+    recorded: Arc<AtomicU64>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FeedRecorder {
+    /// Open the first segment and start the writer thread
+    pub fn start(config: RecorderConfig) -> Result<Self, MorpheusError> {
+        let segment = Segment::open(&config, 0)?;
+        let (tx, rx) = sync_channel(config.buffer_size.max(1));
+        let recorded = Arc::new(AtomicU64::new(0));
+
+        info!("MORPHEUS: Recording feed to {}", segment.path.display());
+
+        let counter = Arc::clone(&recorded);
+        let writer = std::thread::Builder::new()
+            .name("feed-recorder".to_string())
+            .spawn(move || write_loop(config, segment, rx, counter))
+            .map_err(|e| MorpheusError::FeedError(format!("Spawn recorder: {}", e)))?;
+
+        Ok(Self {
+            tx: Some(tx),
+            writer: Some(writer),
+            recorded,
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Queue one update for writing; never blocks
+    ///
+    /// Returns false if the update was dropped.
+    pub fn record(&self, update: &PriceUpdate) -> bool {
+        let Some(tx) = &self.tx else {
+            return false;
+        };
+        match tx.try_send(update.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Record everything passing through `rx`, forwarding it unchanged
+    pub fn tap(self: &Arc<Self>, mut rx: mpsc::Receiver<PriceUpdate>, buffer_size: usize) -> mpsc::Receiver<PriceUpdate> {
+        let (tx, out_rx) = mpsc::channel(buffer_size);
+        let recorder = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Some(update) = rx.recv().await {
+                recorder.record(&update);
+                if tx.send(update).await.is_err() {
+                    break;
+                }
+            }
+        });
+        out_rx
+    }
+
+    /// Updates written to disk so far
+    pub fn recorded(&self) -> u64 {
+        self.recorded.load(Ordering::Relaxed)
+    }
+
+    /// Updates dropped because the writer fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Flush queued updates, close the current segment and return all segments written
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, MorpheusError> {
+        self.tx.take();
+        match self.writer.take() {
+            Some(writer) => writer
+                .join()
+                .map_err(|_| MorpheusError::FeedError("Recorder thread panicked".to_string()))?,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Read back every update in a recorded segment
+    pub fn replay(path: impl AsRef<Path>) -> Result<Vec<PriceUpdate>, MorpheusError> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| MorpheusError::FeedError(format!("Open {}: {}", path.display(), e)))?;
+
+        BufReader::new(file)
+            .lines()
+            .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+            .map(|line| {
+                let line = line.map_err(|e| MorpheusError::FeedError(e.to_string()))?;
+                serde_json::from_str(&line).map_err(|e| MorpheusError::ParseError(format!("Bad record: {}", e)))
+            })
+            .collect()
+    }
+}
+
+/// Open output file and how much has gone into it
+struct Segment {
+    path: PathBuf,
+    file: BufWriter<File>,
+    bytes: u64,
+    opened_at: Instant,
+}
+
+impl Segment {
+    fn open(config: &RecorderConfig, index: u32) -> Result<Self, MorpheusError> {
+        let path = config.segment_path(index);
+        let file = File::create(&path)
+            .map_err(|e| MorpheusError::FeedError(format!("Open {}: {}", path.display(), e)))?;
+        Ok(Self {
+            path,
+            file: BufWriter::new(file),
+            bytes: 0,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn is_full(&self, config: &RecorderConfig, next_len: u64) -> bool {
+        if self.bytes == 0 {
+            return false;
+        }
+        config.max_segment_bytes.is_some_and(|max| self.bytes + next_len > max)
+            || config
+                .max_segment_age_ms
+                .is_some_and(|max| self.opened_at.elapsed() >= Duration::from_millis(max))
+    }
+
+    fn write(&mut self, line: &[u8]) -> Result<(), MorpheusError> {
+        self.file
+            .write_all(line)
+            .map_err(|e| MorpheusError::FeedError(format!("Write {}: {}", self.path.display(), e)))?;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), MorpheusError> {
+        self.file
+            .flush()
+            .map_err(|e| MorpheusError::FeedError(format!("Flush {}: {}", self.path.display(), e)))
+    }
+}
+
+/// Writer thread: append queued updates, flushing whenever the queue drains
+fn write_loop(
+    config: RecorderConfig,
+    mut segment: Segment,
+    rx: Receiver<PriceUpdate>,
+    recorded: Arc<AtomicU64>,
+) -> Result<Vec<PathBuf>, MorpheusError> {
+    let mut segments = vec![segment.path.clone()];
+
+    while let Ok(first) = rx.recv() {
+        for update in std::iter::once(first).chain(rx.try_iter()) {
+            let mut line = match serde_json::to_vec(&update) {
+                Ok(line) => line,
+                Err(e) => {
+                    warn!("MORPHEUS: Skipping unserializable update: {}", e);
+                    continue;
+                }
+            };
+            line.push(b'\n');
+
+            if segment.is_full(&config, line.len() as u64) {
+                segment.flush()?;
+                segment = Segment::open(&config, segments.len() as u32)?;
+                segments.push(segment.path.clone());
+                info!("MORPHEUS: Rotated feed recording to {}", segment.path.display());
+            }
+
+            segment.write(&line)?;
+            recorded.fetch_add(1, Ordering::Relaxed);
+        }
+        segment.flush()?;
+    }
+
+    segment.flush()?;
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Address, U256};
+    use matrix_types::{ChainId, DexId};
+
+    fn update(i: u64) -> PriceUpdate {
+        PriceUpdate {
+            timestamp_ms: 1_700_000_000_000 + i,
+            chain: ChainId::Ethereum,
+            dex: DexId::SushiSwap,
+            pool: Address::from_low_u64_be(i + 1),
+            token0: Address::repeat_byte(1),
+            token1: Address::repeat_byte(2),
+            reserve0: U256::exp10(18) * (i + 1),
+            reserve1: U256::exp10(21) * (i + 2),
+            price: U256::MAX - i,
+            fee_bps: None,
+            provider: None,
+        }
+    }
+
+    fn same(a: &PriceUpdate, b: &PriceUpdate) -> bool {
+        serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+    }
+
+    fn temp_config(name: &str) -> RecorderConfig {
+        let dir = std::env::temp_dir().join(format!("morpheus-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        RecorderConfig::new(dir.join("feed.jsonl"))
+    }
+
+    #[tokio::test]
+    async fn test_recorded_updates_replay_identically() {
+        let config = temp_config("record");
+        let recorder = Arc::new(FeedRecorder::start(config.clone()).unwrap());
+
+        let (tx, rx) = mpsc::channel(16);
+        let mut out = recorder.tap(rx, 16);
+        let sent: Vec<_> = (0..5).map(update).collect();
+        for u in &sent {
+            tx.send(u.clone()).await.unwrap();
+            // Forwarded unchanged
+            assert!(same(&out.recv().await.unwrap(), u));
+        }
+        drop(tx);
+        assert!(out.recv().await.is_none());
+
+        // The tap task releases its handle once the input closes
+        while Arc::strong_count(&recorder) > 1 {
+            tokio::task::yield_now().await;
+        }
+        let recorder = Arc::try_unwrap(recorder).ok().unwrap();
+        assert_eq!(recorder.dropped(), 0);
+        let segments = recorder.finish().unwrap();
+        assert_eq!(segments, vec![config.segment_path(0)]);
+
+        let replayed = FeedRecorder::replay(&segments[0]).unwrap();
+        assert_eq!(replayed.len(), sent.len());
+        assert!(replayed.iter().zip(&sent).all(|(a, b)| same(a, b)));
+
+        std::fs::remove_dir_all(config.path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_rotates_by_size() {
+        let mut config = temp_config("rotate");
+        let line_len = serde_json::to_vec(&update(0)).unwrap().len() as u64 + 1;
+        // Two records per segment
+        config.max_segment_bytes = Some(line_len * 2 + line_len / 2);
+
+        let recorder = FeedRecorder::start(config.clone()).unwrap();
+        let sent: Vec<_> = (0..5).map(update).collect();
+        for u in &sent {
+            assert!(recorder.record(u));
+        }
+        let segments = recorder.finish().unwrap();
+        assert_eq!(segments.len(), 3);
+
+        let replayed: Vec<_> = segments
+            .iter()
+            .flat_map(|path| FeedRecorder::replay(path).unwrap())
+            .collect();
+        assert_eq!(FeedRecorder::replay(&segments[2]).unwrap().len(), 1);
+        assert!(replayed.iter().zip(&sent).all(|(a, b)| same(a, b)));
+
+        std::fs::remove_dir_all(config.path.parent().unwrap()).ok();
+    }
+}
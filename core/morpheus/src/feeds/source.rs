@@ -0,0 +1,251 @@
+//! Transport-agnostic price sources
+//!
+//! `PriceFeed` models a WebSocket lifecycle (connect, subscribe, disconnect).
+//! `PriceSource` only promises a stream of `PriceUpdate`s, so anything that
+//! can produce updates (gRPC, file replay, HTTP polling) can feed the same
+//! pipeline. Adapters are provided for existing feeds and for polling
+//! `getReserves()` over HTTP.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ethers::core::types::{Address, U256};
+use ethers::providers::{Http, Provider};
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use matrix_types::{ChainId, PriceUpdate};
+use crate::{MorpheusError, PriceFeed};
+use super::dex_feed::{endpoint_host, fetch_reserves, reserves_update, PoolSubscription};
+
+/// Stream of updates produced by a source
+pub type PriceStream = BoxStream<'static, PriceUpdate>;
+
+/// Anything that yields price updates, regardless of transport
+#[async_trait]
+pub trait PriceSource: Send {
+    /// Source identifier
+    fn id(&self) -> String;
+
+    /// Start producing updates
+    ///
+    /// The stream ends when the source is exhausted or stopped.
+    async fn start(&mut self) -> Result<PriceStream, MorpheusError>;
+
+    /// Release transport resources
+    async fn stop(&mut self) -> Result<(), MorpheusError> {
+        Ok(())
+    }
+}
+
+/// Adapts a `PriceFeed` (e.g. `DexWebSocketFeed`) into a `PriceSource`
+pub struct FeedSource {
+    feed: Box<dyn PriceFeed>,
+    buffer_size: usize,
+}
+
+impl FeedSource {
+    pub fn new(feed: Box<dyn PriceFeed>) -> Self {
+        Self {
+            feed,
+            buffer_size: 10_000,
+        }
+    }
+
+    /// Capacity of the channel between the feed and the stream
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size.max(1);
+        self
+    }
+
+    /// Wrapped feed
+    pub fn feed(&self) -> &dyn PriceFeed {
+        self.feed.as_ref()
+    }
+}
+
+#[async_trait]
+impl PriceSource for FeedSource {
+    fn id(&self) -> String {
+        self.feed.id()
+    }
+
+    async fn start(&mut self) -> Result<PriceStream, MorpheusError> {
+        self.feed.connect().await?;
+
+        let (tx, rx) = mpsc::channel(self.buffer_size);
+        self.feed.subscribe(tx).await?;
+
+        Ok(receiver_stream(rx))
+    }
+
+    async fn stop(&mut self) -> Result<(), MorpheusError> {
+        self.feed.disconnect().await
+    }
+}
+
+/// Polls `getReserves()` over HTTP and emits an update when reserves change
+pub struct HttpPollingSource {
+    chain: ChainId,
+    http_url: String,
+    pools: Vec<PoolSubscription>,
+    poll_interval_ms: u64,
+}
+
+impl HttpPollingSource {
+    pub fn new(chain: ChainId, http_url: impl Into<String>, pools: Vec<PoolSubscription>) -> Self {
+        Self {
+            chain,
+            http_url: http_url.into(),
+            pools,
+            poll_interval_ms: 1_000,
+        }
+    }
+
+    /// Time between polls
+    pub fn with_poll_interval(mut self, poll_interval_ms: u64) -> Self {
+        self.poll_interval_ms = poll_interval_ms.max(1);
+        self
+    }
+}
+
+/// Polling loop state carried between stream items
+struct PollState {
+    provider: Provider<Http>,
+    chain: ChainId,
+    pools: Vec<PoolSubscription>,
+    last: HashMap<Address, (U256, U256)>,
+    interval: tokio::time::Interval,
+    /// Provider name stamped on updates
+    endpoint: Option<String>,
+}
+
+impl PollState {
+    /// Wait for the next tick and return updates for pools that changed
+    async fn poll(&mut self) -> Vec<PriceUpdate> {
+        self.interval.tick().await;
+
+        let results = fetch_reserves(&self.provider, &self.pools).await;
+        let mut updates = Vec::new();
+        for (pool, reserves) in self.pools.iter().zip(results) {
+            let (reserve0, reserve1) = match reserves {
+                Some(r) => r,
+                None => {
+                    debug!("HTTP poll: no reserves for pool {:?}", pool.pool_address);
+                    continue;
+                }
+            };
+            if self.last.insert(pool.pool_address, (reserve0, reserve1)) != Some((reserve0, reserve1)) {
+                updates.push(PriceUpdate {
+                    provider: self.endpoint.clone(),
+                    ..reserves_update(self.chain, pool, reserve0, reserve1)
+                });
+            }
+        }
+        updates
+    }
+}
+
+#[async_trait]
+impl PriceSource for HttpPollingSource {
+    fn id(&self) -> String {
+        format!("{:?}-http-poll", self.chain)
+    }
+
+    async fn start(&mut self) -> Result<PriceStream, MorpheusError> {
+        let provider = Provider::<Http>::try_from(self.http_url.as_str())
+            .map_err(|e| MorpheusError::ConnectionFailed(format!("Invalid HTTP URL: {}", e)))?;
+
+        let mut interval = tokio::time::interval(Duration::from_millis(self.poll_interval_ms));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        info!(
+            "Polling {} pools every {}ms for {}",
+            self.pools.len(),
+            self.poll_interval_ms,
+            self.id()
+        );
+
+        let state = PollState {
+            provider,
+            chain: self.chain,
+            pools: self.pools.clone(),
+            last: HashMap::new(),
+            interval,
+            endpoint: endpoint_host(&self.http_url),
+        };
+
+        Ok(stream::unfold(state, |mut state| async move {
+            let updates = state.poll().await;
+            Some((stream::iter(updates), state))
+        })
+        .flatten()
+        .boxed())
+    }
+}
+
+/// Stream the contents of a channel until every sender is dropped
+pub fn receiver_stream(rx: mpsc::Receiver<PriceUpdate>) -> PriceStream {
+    stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|update| (update, rx)) }).boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feeds::stub::StaticFeed;
+    use crate::test_support::{mock_rpc, reserves_hex};
+    use crate::FeedStatus;
+    use matrix_types::DexId;
+
+    fn pool(byte: u8) -> PoolSubscription {
+        PoolSubscription {
+            pool_address: Address::repeat_byte(byte),
+            token0: Address::repeat_byte(1),
+            token1: Address::repeat_byte(2),
+            dex: DexId::PancakeSwap,
+            fee_bps: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_polling_emits_changed_reserves() {
+        let rpc = mock_rpc(vec![
+            (Address::repeat_byte(0xa), Some(reserves_hex(1_000, 2_000))),
+            (Address::repeat_byte(0xb), None), // reverts
+        ])
+        .await;
+
+        let mut source = HttpPollingSource::new(ChainId::Bsc, rpc.url(), vec![pool(0xa), pool(0xb)])
+            .with_poll_interval(10);
+        let mut updates = source.start().await.unwrap();
+
+        let first = updates.next().await.unwrap();
+        assert_eq!(first.pool, Address::repeat_byte(0xa));
+        assert_eq!(first.chain, ChainId::Bsc);
+        assert_eq!(first.price, U256::exp10(18) * 2);
+        assert_eq!(first.provider, endpoint_host(&rpc.url()));
+
+        // Unchanged reserves are not re-emitted
+        let next = tokio::time::timeout(Duration::from_millis(100), updates.next()).await;
+        assert!(next.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_feed_source_adapts_price_feed() {
+        let updates: Vec<PriceUpdate> = (1..=3)
+            .map(|i| reserves_update(ChainId::Bsc, &pool(i), U256::from(i), U256::from(2 * i)))
+            .collect();
+        let mut source = FeedSource::new(Box::new(StaticFeed::new(updates)));
+
+        let stream = source.start().await.unwrap();
+        assert_eq!(source.feed().status(), FeedStatus::Connected);
+
+        let pools: Vec<Address> = stream.map(|u| u.pool).collect().await;
+        assert_eq!(pools, vec![pool(1).pool_address, pool(2).pool_address, pool(3).pool_address]);
+
+        source.stop().await.unwrap();
+        assert_eq!(source.feed().status(), FeedStatus::Disconnected);
+    }
+}
@@ -0,0 +1,107 @@
+//! Stub feeds
+//!
+//! `PriceFeed`s with scripted behaviour for exercising code that consumes
+//! feeds without a network: one that pushes fixed updates on subscribe and
+//! one that reports fixed errors on connect.
+//!
+//! Enabled in tests and with the `stub-feeds` feature.
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use matrix_types::PriceUpdate;
+use crate::{FeedError, FeedErrorKind, FeedStatus, MorpheusError, PriceFeed};
+
+/// Feed that pushes fixed updates on subscribe
+pub struct StaticFeed {
+    updates: Vec<PriceUpdate>,
+    status: FeedStatus,
+}
+
+impl StaticFeed {
+    pub fn new(updates: Vec<PriceUpdate>) -> Self {
+        Self {
+            updates,
+            status: FeedStatus::Disconnected,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for StaticFeed {
+    fn id(&self) -> String {
+        "static".to_string()
+    }
+
+    async fn connect(&mut self) -> Result<(), MorpheusError> {
+        self.status = FeedStatus::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), MorpheusError> {
+        self.status = FeedStatus::Disconnected;
+        Ok(())
+    }
+
+    fn status(&self) -> FeedStatus {
+        self.status.clone()
+    }
+
+    async fn subscribe(&self, tx: mpsc::Sender<PriceUpdate>) -> Result<(), MorpheusError> {
+        for update in &self.updates {
+            if tx.send(update.clone()).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Feed that reports a fixed list of errors when connected
+pub struct ErroringFeed {
+    id: String,
+    errors: Vec<(FeedErrorKind, &'static str)>,
+    error_tx: Option<mpsc::Sender<FeedError>>,
+}
+
+impl ErroringFeed {
+    pub fn new(id: impl Into<String>, errors: Vec<(FeedErrorKind, &'static str)>) -> Self {
+        Self {
+            id: id.into(),
+            errors,
+            error_tx: None,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for ErroringFeed {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    async fn connect(&mut self) -> Result<(), MorpheusError> {
+        if let Some(tx) = &self.error_tx {
+            for (kind, message) in &self.errors {
+                let _ = tx.send(FeedError::new(&self.id, *kind, *message)).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), MorpheusError> {
+        Ok(())
+    }
+
+    fn status(&self) -> FeedStatus {
+        FeedStatus::Connected
+    }
+
+    async fn subscribe(&self, _tx: mpsc::Sender<PriceUpdate>) -> Result<(), MorpheusError> {
+        Ok(())
+    }
+
+    fn set_error_sender(&mut self, tx: mpsc::Sender<FeedError>) {
+        self.error_tx = Some(tx);
+    }
+}
@@ -13,16 +13,28 @@ use async_trait::async_trait;
 use matrix_types::{ChainId, DexId, PriceUpdate};
 use thiserror::Error;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 // WebSocket feed implementations
 pub mod feeds;
 
+// Token metadata cache
+pub mod tokens;
+
+#[cfg(test)]
+mod test_support;
+
 // Re-export commonly used types
 pub use feeds::{
-    ConnectionPool, ConnectionConfig,
+    ConnectionPool, ConnectionConfig, PoolConnectResult,
     DexWebSocketFeed, PoolSubscription,
     BscPriceFeed, PancakeSwapFeed, BiswapFeed,
+    FeedAggregator, AggregatorConfig,
+    LatencyEstimator, LatencyStats,
+    FeedSource, HttpPollingSource, PriceSource, PriceStream,
+    FeedRecorder, RecorderConfig,
 };
+pub use tokens::{TokenMetadata, TokenMetadataSource, TokenRegistry, DEFAULT_DECIMALS, DEFAULT_FAILURE_TTL};
 
 /// Morpheus errors
 #[derive(Error, Debug)]
@@ -40,6 +52,63 @@ pub enum MorpheusError {
     ParseError(String),
 }
 
+/// Category of a feed error, used as the `error_type` metric label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedErrorKind {
+    Connection,
+    Subscription,
+    Parse,
+    Channel,
+    Other,
+}
+
+impl FeedErrorKind {
+    /// Metric label for this kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedErrorKind::Connection => "connection",
+            FeedErrorKind::Subscription => "subscription",
+            FeedErrorKind::Parse => "parse",
+            FeedErrorKind::Channel => "channel",
+            FeedErrorKind::Other => "other",
+        }
+    }
+}
+
+/// Structured error reported by a feed on its error channel
+#[derive(Debug, Clone)]
+pub struct FeedError {
+    pub feed_id: String,
+    pub kind: FeedErrorKind,
+    pub message: String,
+    pub timestamp_ms: u64,
+}
+
+impl FeedError {
+    pub fn new(feed_id: &str, kind: FeedErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            feed_id: feed_id.to_string(),
+            kind,
+            message: message.into(),
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        }
+    }
+
+    /// Build from a `MorpheusError`, deriving the kind from its variant
+    pub fn from_error(feed_id: &str, error: &MorpheusError) -> Self {
+        let kind = match error {
+            MorpheusError::ConnectionFailed(_) => FeedErrorKind::Connection,
+            MorpheusError::SubscriptionFailed(_) => FeedErrorKind::Subscription,
+            MorpheusError::ParseError(_) => FeedErrorKind::Parse,
+            MorpheusError::FeedError(_) => FeedErrorKind::Other,
+        };
+        Self::new(feed_id, kind, error.to_string())
+    }
+}
+
 /// Feed configuration
 #[derive(Debug, Clone)]
 pub struct FeedConfig {
@@ -48,6 +117,8 @@ pub struct FeedConfig {
     pub websocket_url: String,
     pub reconnect_delay_ms: u64,
     pub max_reconnect_attempts: u32,
+    /// HTTP RPC used to warm up reserves on connect (None = wait for Sync)
+    pub http_url: Option<String>,
 }
 
 /// Feed status
@@ -77,12 +148,20 @@ pub trait PriceFeed: Send + Sync {
 
     /// Subscribe to price updates
     async fn subscribe(&self, tx: mpsc::Sender<PriceUpdate>) -> Result<(), MorpheusError>;
+
+    /// Set the channel for structured error reports (ignored by default)
+    fn set_error_sender(&mut self, _tx: mpsc::Sender<FeedError>) {}
+
+    /// Stop the feed's connection when `token` is cancelled (ignored by default)
+    fn set_shutdown_token(&mut self, _token: CancellationToken) {}
 }
 
 /// Morpheus market data coordinator
 pub struct Morpheus {
     feeds: Vec<Box<dyn PriceFeed>>,
     status: FeedStatus,
+    /// System-wide shutdown, handed to every feed
+    shutdown: CancellationToken,
 }
 
 impl Morpheus {
@@ -91,12 +170,22 @@ impl Morpheus {
         Self {
             feeds: Vec::new(),
             status: FeedStatus::Disconnected,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Stop every feed, present and future, when `token` is cancelled
+    pub fn set_shutdown_token(&mut self, token: CancellationToken) {
+        for feed in &mut self.feeds {
+            feed.set_shutdown_token(token.clone());
         }
+        self.shutdown = token;
     }
 
     /// Add a price feed
-    pub fn add_feed(&mut self, feed: Box<dyn PriceFeed>) {
+    pub fn add_feed(&mut self, mut feed: Box<dyn PriceFeed>) {
         tracing::info!("MORPHEUS: Adding feed '{}'", feed.id());
+        feed.set_shutdown_token(self.shutdown.clone());
         self.feeds.push(feed);
     }
 
@@ -0,0 +1,104 @@
+//! Shared test fixtures
+//!
+//! Stand-ins for the node endpoints feeds talk to, used by tests across
+//! the feed modules.
+
+use ethers::abi::{self, Token};
+use ethers::core::types::Address;
+use futures_util::{SinkExt, StreamExt};
+use matrix_types::mock_rpc::{rpc_error, rpc_result, MockRpcServer};
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::Message;
+
+/// JSON-RPC node answering `eth_call`s by target address
+///
+/// Calls to an address mapped to `Some(result)` return it; anything else
+/// reverts. The node stops when the returned server is dropped.
+pub(crate) async fn mock_rpc(results: Vec<(Address, Option<String>)>) -> MockRpcServer {
+    MockRpcServer::start(move |request| {
+        let to: Address = serde_json::from_value(request["params"][0]["to"].clone()).unwrap();
+        match results.iter().find(|(a, _)| *a == to).and_then(|(_, r)| r.clone()) {
+            Some(result) => rpc_result(request, Value::String(result)),
+            None => rpc_error(request, -32000, "execution reverted"),
+        }
+    })
+    .await
+    .unwrap()
+}
+
+/// Hex `getReserves()` return data
+pub(crate) fn reserves_hex(reserve0: u128, reserve1: u128) -> String {
+    format!("0x{:064x}{:064x}{:064x}", reserve0, reserve1, 1_700_000_000u64)
+}
+
+/// ABI-encoded `aggregate3` return data for the given call outcomes
+pub(crate) fn aggregate3_response(results: &[(bool, Vec<u8>)]) -> Vec<u8> {
+    let results = results
+        .iter()
+        .map(|(success, data)| Token::Tuple(vec![Token::Bool(*success), Token::Bytes(data.clone())]))
+        .collect();
+    abi::encode(&[Token::Array(results)])
+}
+
+/// WebSocket server that sends `greeting` to every client, returning its URL
+pub(crate) async fn greeting_server(greeting: &'static str) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                ws.send(Message::Text(greeting.to_string())).await.unwrap();
+                while let Some(Ok(_)) = ws.next().await {}
+            });
+        }
+    });
+
+    url
+}
+
+/// Node that rejects every `eth_subscribe` on its first `rejected_sessions`
+/// connections, then accepts and pushes `notification`
+///
+/// Returns its URL and the index of the connection each subscribe arrived on.
+pub(crate) async fn subscription_node(
+    rejected_sessions: usize,
+    notification: String,
+) -> (String, tokio::sync::mpsc::UnboundedReceiver<usize>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (subscribes_tx, subscribes_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut session = 0;
+        while let Ok((stream, _)) = listener.accept().await {
+            let (subscribes_tx, notification) = (subscribes_tx.clone(), notification.clone());
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(message)) = ws.next().await {
+                    let Message::Text(text) = message else { continue };
+                    let request: Value = serde_json::from_str(&text).unwrap();
+                    if request["method"] != "eth_subscribe" {
+                        continue;
+                    }
+                    let _ = subscribes_tx.send(session);
+                    let reply = if session < rejected_sessions {
+                        rpc_error(&request, -32005, "too many subscriptions")
+                    } else {
+                        rpc_result(&request, Value::String("0xsub".to_string()))
+                    };
+                    if ws.send(Message::Text(reply.to_string())).await.is_err() {
+                        return;
+                    }
+                    if session >= rejected_sessions {
+                        let _ = ws.send(Message::Text(notification.clone())).await;
+                    }
+                }
+            });
+            session += 1;
+        }
+    });
+
+    (url, subscribes_rx)
+}
@@ -0,0 +1,393 @@
+//! Token Metadata Registry
+//!
+//! Caches ERC-20 `decimals()` / `symbol()` per token address so price math
+//! stops assuming 18 decimals. Misses are fetched once via `eth_call` and
+//! memoized; the registry is cheap to clone and share across agents.
+//! Background fetches are deduplicated per token, and failed lookups are
+//! not retried until `failure_ttl` passes. The registry also implements
+//! hotpath's `TokenDecimals` for the scanner.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::{DashMap, DashSet};
+use ethers::core::types::transaction::eip2718::TypedTransaction;
+use ethers::core::types::{Address, Bytes, TransactionRequest, U256};
+use ethers::providers::Middleware;
+use tracing::{debug, warn};
+
+use crate::MorpheusError;
+
+/// Decimals assumed until a token's metadata is known
+pub const DEFAULT_DECIMALS: u8 = 18;
+
+/// How long a failed lookup is remembered before it is retried
+pub const DEFAULT_FAILURE_TTL: Duration = Duration::from_secs(300);
+
+/// `decimals()` selector
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+/// `symbol()` selector
+const SYMBOL_SELECTOR: [u8; 4] = [0x95, 0xd8, 0x9b, 0x41];
+
+/// ERC-20 metadata for one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub address: Address,
+    pub decimals: u8,
+    pub symbol: String,
+}
+
+/// Where token metadata is fetched from on a cache miss
+#[async_trait]
+pub trait TokenMetadataSource: Send + Sync {
+    async fn fetch(&self, token: Address) -> Result<TokenMetadata, MorpheusError>;
+}
+
+#[async_trait]
+impl<M: Middleware> TokenMetadataSource for M {
+    async fn fetch(&self, token: Address) -> Result<TokenMetadata, MorpheusError> {
+        let call = |selector: [u8; 4]| -> TypedTransaction {
+            TransactionRequest::new()
+                .to(token)
+                .data(Bytes::from(selector.to_vec()))
+                .into()
+        };
+
+        let data = self
+            .call(&call(DECIMALS_SELECTOR), None)
+            .await
+            .map_err(|e| MorpheusError::FeedError(format!("decimals() on {:?} failed: {}", token, e)))?;
+        let decimals = parse_decimals(&data)
+            .ok_or_else(|| MorpheusError::ParseError(format!("Bad decimals() return for {:?}", token)))?;
+
+        // Some tokens have no (or a non-standard) symbol; that shouldn't block pricing
+        let symbol = match self.call(&call(SYMBOL_SELECTOR), None).await {
+            Ok(data) => parse_symbol(&data).unwrap_or_default(),
+            Err(e) => {
+                debug!("symbol() on {:?} failed: {}", token, e);
+                String::new()
+            }
+        };
+
+        Ok(TokenMetadata { address: token, decimals, symbol })
+    }
+}
+
+/// Decode a `uint8` return value
+fn parse_decimals(data: &[u8]) -> Option<u8> {
+    if data.len() < 32 {
+        return None;
+    }
+    let value = U256::from_big_endian(&data[0..32]);
+    (value <= U256::from(u8::MAX)).then(|| value.as_u32() as u8)
+}
+
+/// Decode a `string` return value, or a `bytes32` one (e.g. MKR)
+fn parse_symbol(data: &[u8]) -> Option<String> {
+    if data.len() == 32 {
+        let end = data.iter().position(|b| *b == 0).unwrap_or(32);
+        return String::from_utf8(data[..end].to_vec()).ok();
+    }
+    if data.len() < 64 {
+        return None;
+    }
+    let offset = U256::from_big_endian(&data[0..32]);
+    if offset > U256::from(data.len()) {
+        return None;
+    }
+    let offset = offset.as_usize();
+    let len = U256::from_big_endian(data.get(offset..offset + 32)?);
+    if len > U256::from(data.len()) {
+        return None;
+    }
+    let start = offset + 32;
+    let bytes = data.get(start..start + len.as_usize())?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+/// Shared, memoizing token metadata cache
+#[derive(Clone)]
+pub struct TokenRegistry {
+    source: Arc<dyn TokenMetadataSource>,
+    cache: Arc<DashMap<Address, TokenMetadata>>,
+    /// Tokens with a background fetch running
+    in_flight: Arc<DashSet<Address>>,
+    /// When each token's last lookup failed
+    failures: Arc<DashMap<Address, Instant>>,
+    failure_ttl: Duration,
+}
+
+impl TokenRegistry {
+    pub fn new(source: Arc<dyn TokenMetadataSource>) -> Self {
+        Self {
+            source,
+            cache: Arc::new(DashMap::new()),
+            in_flight: Arc::new(DashSet::new()),
+            failures: Arc::new(DashMap::new()),
+            failure_ttl: DEFAULT_FAILURE_TTL,
+        }
+    }
+
+    /// Remember failed lookups for `ttl` before retrying
+    pub fn with_failure_ttl(mut self, ttl: Duration) -> Self {
+        self.failure_ttl = ttl;
+        self
+    }
+
+    /// Seed metadata without fetching (e.g. from config)
+    pub fn insert(&self, metadata: TokenMetadata) {
+        self.cache.insert(metadata.address, metadata);
+    }
+
+    /// Cached metadata, without fetching
+    pub fn cached(&self, token: &Address) -> Option<TokenMetadata> {
+        self.cache.get(token).map(|m| m.clone())
+    }
+
+    /// Whether `token`'s last lookup failed less than `failure_ttl` ago
+    fn recently_failed(&self, token: &Address) -> bool {
+        match self.failures.get(token).map(|failed_at| failed_at.elapsed()) {
+            Some(age) if age < self.failure_ttl => true,
+            Some(_) => {
+                self.failures.remove(token);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Metadata for `token`, fetching and caching it on a miss
+    ///
+    /// Fails without fetching while an earlier failure is remembered.
+    pub async fn get(&self, token: Address) -> Result<TokenMetadata, MorpheusError> {
+        if let Some(metadata) = self.cached(&token) {
+            return Ok(metadata);
+        }
+        if self.recently_failed(&token) {
+            return Err(MorpheusError::FeedError(format!("Token metadata for {:?} recently failed", token)));
+        }
+
+        match self.source.fetch(token).await {
+            Ok(metadata) => {
+                debug!("Token {:?}: {} ({} decimals)", token, metadata.symbol, metadata.decimals);
+                self.cache.insert(token, metadata.clone());
+                Ok(metadata)
+            }
+            Err(e) => {
+                self.failures.insert(token, Instant::now());
+                Err(e)
+            }
+        }
+    }
+
+    /// Cached decimals, or `DEFAULT_DECIMALS` while a fetch runs in the background
+    ///
+    /// For synchronous callers (normalizers) that can't await a fetch.
+    pub fn decimals_or_default(&self, token: &Address) -> u8 {
+        if let Some(metadata) = self.cache.get(token) {
+            return metadata.decimals;
+        }
+        self.prefetch(*token);
+        DEFAULT_DECIMALS
+    }
+
+    /// Fetch `token` in the background if a Tokio runtime is available
+    ///
+    /// No-op while a fetch for it is already running or it recently failed.
+    pub fn prefetch(&self, token: Address) {
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+        if self.recently_failed(&token) || !self.in_flight.insert(token) {
+            return;
+        }
+        let registry = self.clone();
+        handle.spawn(async move {
+            if let Err(e) = registry.get(token).await {
+                warn!("Token metadata fetch for {:?} failed: {}", token, e);
+            }
+            registry.in_flight.remove(&token);
+        });
+    }
+
+    /// Number of cached tokens
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+/// Cached decimals for the scanner; misses are prefetched
+impl hotpath::TokenDecimals for TokenRegistry {
+    fn decimals(&self, token: &Address) -> Option<u8> {
+        let decimals = self.cache.get(token).map(|metadata| metadata.decimals);
+        if decimals.is_none() {
+            self.prefetch(*token);
+        }
+        decimals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::Provider;
+
+    fn abi_string(value: &str) -> Bytes {
+        let mut data = vec![0u8; 64];
+        data[31] = 0x20;
+        data[63] = value.len() as u8;
+        let mut padded = value.as_bytes().to_vec();
+        padded.resize(32, 0);
+        data.extend(padded);
+        Bytes::from(data)
+    }
+
+    fn abi_uint(value: u8) -> Bytes {
+        let mut data = vec![0u8; 32];
+        data[31] = value;
+        Bytes::from(data)
+    }
+
+    #[tokio::test]
+    async fn test_registry_fetches_once() {
+        let usdc = Address::repeat_byte(0xaa);
+        let (provider, mock) = Provider::mocked();
+        // Responses are popped last-in first-out: decimals() is called first
+        mock.push::<Bytes, _>(abi_string("USDC")).unwrap();
+        mock.push::<Bytes, _>(abi_uint(6)).unwrap();
+
+        let registry = TokenRegistry::new(Arc::new(provider));
+        assert!(registry.cached(&usdc).is_none());
+
+        let metadata = registry.get(usdc).await.unwrap();
+        assert_eq!(metadata.decimals, 6);
+        assert_eq!(metadata.symbol, "USDC");
+
+        // Served from cache: no further responses are queued, so a second
+        // fetch would fail
+        let again = registry.get(usdc).await.unwrap();
+        assert_eq!(again, metadata);
+        assert_eq!(registry.decimals_or_default(&usdc), 6);
+        assert_eq!(registry.len(), 1);
+    }
+
+    /// Source that counts fetches, optionally slow or failing
+    #[derive(Default)]
+    struct CountingSource {
+        fetches: std::sync::atomic::AtomicUsize,
+        delay: Duration,
+        fail: bool,
+    }
+
+    impl CountingSource {
+        fn fetches(&self) -> usize {
+            self.fetches.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl TokenMetadataSource for CountingSource {
+        async fn fetch(&self, token: Address) -> Result<TokenMetadata, MorpheusError> {
+            self.fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(MorpheusError::FeedError("execution reverted".into()));
+            }
+            Ok(TokenMetadata { address: token, decimals: 6, symbol: "USDC".into() })
+        }
+    }
+
+    /// Wait for background fetches to settle
+    async fn settle(registry: &TokenRegistry) {
+        for _ in 0..200 {
+            if registry.in_flight.is_empty() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decimals_default_until_prefetched() {
+        use hotpath::TokenDecimals;
+
+        let source = Arc::new(CountingSource::default());
+        let registry = TokenRegistry::new(source.clone());
+        let usdc = Address::repeat_byte(0xaa);
+
+        // Miss: default now, fetch in the background
+        assert_eq!(registry.decimals_or_default(&usdc), DEFAULT_DECIMALS);
+        settle(&registry).await;
+        assert_eq!(registry.decimals_or_default(&usdc), 6);
+        assert_eq!(source.fetches(), 1);
+
+        // Seeded tokens never fetch
+        let weth = Address::repeat_byte(0xee);
+        registry.insert(TokenMetadata { address: weth, decimals: 18, symbol: "WETH".into() });
+        assert_eq!(registry.get(weth).await.unwrap().symbol, "WETH");
+        assert_eq!(source.fetches(), 1);
+
+        // The scanner sees cached decimals only
+        assert_eq!(TokenDecimals::decimals(&registry, &usdc), Some(6));
+        assert_eq!(TokenDecimals::decimals(&registry, &Address::repeat_byte(0xbb)), None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_fetch_once() {
+        let source = Arc::new(CountingSource { delay: Duration::from_millis(20), ..Default::default() });
+        let registry = TokenRegistry::new(source.clone());
+        let usdc = Address::repeat_byte(0xaa);
+
+        // A burst of normalizations before the first fetch returns
+        for _ in 0..50 {
+            assert_eq!(registry.decimals_or_default(&usdc), DEFAULT_DECIMALS);
+        }
+        settle(&registry).await;
+
+        assert_eq!(registry.decimals_or_default(&usdc), 6);
+        assert_eq!(source.fetches(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_lookup_cached_until_ttl() {
+        let source = Arc::new(CountingSource { fail: true, ..Default::default() });
+        let registry = TokenRegistry::new(source.clone()).with_failure_ttl(Duration::from_millis(50));
+        let token = Address::repeat_byte(0xaa);
+
+        assert_eq!(registry.decimals_or_default(&token), DEFAULT_DECIMALS);
+        settle(&registry).await;
+        assert_eq!(source.fetches(), 1);
+
+        // Within the TTL neither misses nor direct lookups hit the node
+        for _ in 0..10 {
+            assert_eq!(registry.decimals_or_default(&token), DEFAULT_DECIMALS);
+        }
+        assert!(registry.get(token).await.is_err());
+        settle(&registry).await;
+        assert_eq!(source.fetches(), 1);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(registry.get(token).await.is_err());
+        assert_eq!(source.fetches(), 2);
+    }
+
+    #[test]
+    fn test_parse_symbol_variants() {
+        assert_eq!(parse_symbol(&abi_string("DAI")).as_deref(), Some("DAI"));
+
+        let mut bytes32 = b"MKR".to_vec();
+        bytes32.resize(32, 0);
+        assert_eq!(parse_symbol(&bytes32).as_deref(), Some("MKR"));
+
+        assert_eq!(parse_symbol(&[0u8; 10]), None);
+        assert_eq!(parse_decimals(&abi_uint(8)), Some(8));
+        assert_eq!(parse_decimals(&[0u8; 4]), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Opportunity Batching
+//!
+//! When feeds update rapidly the scanner can report a new "best"
+//! opportunity every few milliseconds; executing each one thrashes the
+//! executor and the relays. The batcher collects opportunities for a fixed
+//! window and forwards only the most profitable one when the window closes,
+//! trading up to one window of latency for far fewer submissions.
+
+use std::time::Duration;
+
+use matrix_types::Opportunity;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+
+/// Default collection window
+pub const DEFAULT_BATCH_WINDOW_MS: u64 = 50;
+
+/// Keeps the best opportunity seen in the current window
+#[derive(Debug)]
+pub struct OpportunityBatcher {
+    window_ms: u64,
+    /// Start of the open window, if any opportunity arrived in it
+    window_start_ms: Option<u64>,
+    best: Option<Opportunity>,
+    /// Opportunities dropped because a better one arrived in their window
+    superseded: u64,
+}
+
+impl OpportunityBatcher {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            window_start_ms: None,
+            best: None,
+            superseded: 0,
+        }
+    }
+
+    /// Add an opportunity seen at `now_ms`
+    ///
+    /// Opens a window if none is open. Returns the previous window's best
+    /// if that window had already closed.
+    pub fn offer(&mut self, opportunity: Opportunity, now_ms: u64) -> Option<Opportunity> {
+        let closed = self.poll(now_ms);
+
+        self.window_start_ms.get_or_insert(now_ms);
+        match &self.best {
+            Some(best) if best.profit_wei >= opportunity.profit_wei => self.superseded += 1,
+            Some(_) => {
+                self.superseded += 1;
+                self.best = Some(opportunity);
+            }
+            None => self.best = Some(opportunity),
+        }
+
+        closed
+    }
+
+    /// The open window's best, if the window has closed by `now_ms`
+    pub fn poll(&mut self, now_ms: u64) -> Option<Opportunity> {
+        match self.deadline_ms() {
+            Some(deadline) if now_ms >= deadline => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// Close the open window now, returning its best
+    pub fn flush(&mut self) -> Option<Opportunity> {
+        self.window_start_ms = None;
+        self.best.take()
+    }
+
+    /// When the open window closes (None if no window is open)
+    pub fn deadline_ms(&self) -> Option<u64> {
+        self.window_start_ms.map(|start| start.saturating_add(self.window_ms))
+    }
+
+    /// Opportunities dropped in favour of a better one in the same window
+    pub fn superseded(&self) -> u64 {
+        self.superseded
+    }
+}
+
+impl Default for OpportunityBatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_BATCH_WINDOW_MS)
+    }
+}
+
+/// Forward the best opportunity of each window from `rx` to `tx`
+///
+/// Runs until `rx` closes (flushing the open window) or `tx` is dropped.
+pub async fn run_batcher(
+    mut batcher: OpportunityBatcher,
+    mut rx: mpsc::Receiver<Opportunity>,
+    tx: mpsc::Sender<Opportunity>,
+) {
+    let epoch = Instant::now();
+    let now_ms = || epoch.elapsed().as_millis() as u64;
+
+    loop {
+        let deadline = batcher
+            .deadline_ms()
+            .map(|ms| epoch + Duration::from_millis(ms));
+
+        let ready = tokio::select! {
+            received = rx.recv() => match received {
+                Some(opportunity) => batcher.offer(opportunity, now_ms()),
+                None => {
+                    if let Some(best) = batcher.flush() {
+                        let _ = tx.send(best).await;
+                    }
+                    return;
+                }
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                batcher.flush()
+            }
+        };
+
+        if let Some(best) = ready {
+            tracing::debug!(
+                "NEO: Forwarding opportunity {} ({} superseded so far)",
+                best.id,
+                batcher.superseded()
+            );
+            if tx.send(best).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+    use matrix_types::ChainId;
+
+    fn opportunity(id: u64, profit: u64) -> Opportunity {
+        Opportunity {
+            id,
+            timestamp_ms: 1_700_000_000_000,
+            chain: ChainId::Ethereum,
+            profit_wei: U256::from(profit),
+            gas_estimate: 300_000,
+            path: Vec::new(),
+            flash_loan_token: Default::default(),
+            flash_loan_amount: U256::exp10(18),
+        }
+    }
+
+    #[test]
+    fn test_only_best_in_window_forwarded() {
+        let mut batcher = OpportunityBatcher::new(50);
+
+        assert!(batcher.offer(opportunity(1, 100), 0).is_none());
+        assert!(batcher.offer(opportunity(2, 300), 10).is_none());
+        assert!(batcher.offer(opportunity(3, 200), 49).is_none());
+        assert!(batcher.poll(49).is_none());
+
+        let best = batcher.poll(50).unwrap();
+        assert_eq!(best.id, 2);
+        assert_eq!(batcher.superseded(), 2);
+        assert!(batcher.poll(1_000).is_none());
+        assert_eq!(batcher.deadline_ms(), None);
+    }
+
+    #[test]
+    fn test_late_offer_closes_previous_window() {
+        let mut batcher = OpportunityBatcher::new(50);
+        batcher.offer(opportunity(1, 100), 0);
+
+        // Arrives after the first window closed: it emits that window's
+        // best and opens a new window of its own
+        let closed = batcher.offer(opportunity(2, 50), 70).unwrap();
+        assert_eq!(closed.id, 1);
+        assert_eq!(batcher.deadline_ms(), Some(120));
+        assert_eq!(batcher.flush().unwrap().id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_batcher_forwards_best_per_window() {
+        let (in_tx, in_rx) = mpsc::channel(16);
+        let (out_tx, mut out_rx) = mpsc::channel(16);
+        // Queued before the batcher starts, so all land in its first window
+        for (id, profit) in [(1, 10), (2, 40), (3, 20)] {
+            in_tx.send(opportunity(id, profit)).await.unwrap();
+        }
+        let handle = tokio::spawn(run_batcher(OpportunityBatcher::new(30), in_rx, out_tx));
+        let first = tokio::time::timeout(Duration::from_secs(5), out_rx.recv()).await.unwrap().unwrap();
+        assert_eq!(first.id, 2);
+
+        // Closing the input flushes the open window
+        in_tx.send(opportunity(4, 5)).await.unwrap();
+        drop(in_tx);
+        handle.await.unwrap();
+        let ids: Vec<u64> = std::iter::from_fn(|| out_rx.try_recv().ok()).map(|o| o.id).collect();
+        assert_eq!(ids, vec![4]);
+    }
+}
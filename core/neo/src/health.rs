@@ -0,0 +1,178 @@
+//! Health-check HTTP endpoint
+//!
+//! Serves `MonitoringConfig.health_check_port` for Kubernetes liveness and
+//! readiness probes: 200 when every registered agent is `Running`, 503 with
+//! the unhealthy agents' `AgentHealth` otherwise. Any path is accepted.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use matrix_types::AgentHealth;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{Neo, NeoError};
+
+/// Largest request head read before answering
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Bind `addr` and answer health probes from `neo` in the background
+///
+/// Returns the bound address (useful with port 0). The listener closes on
+/// `Neo::shutdown`.
+pub async fn serve_health(addr: SocketAddr, neo: Arc<Neo>) -> Result<SocketAddr, NeoError> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| NeoError::SupervisionError(format!("Health check bind {} failed: {}", addr, e)))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| NeoError::SupervisionError(e.to_string()))?;
+
+    tracing::info!("NEO: Health check listening on {}", local_addr);
+
+    let shutdown = neo.shutdown_token();
+    tokio::spawn(async move {
+        loop {
+            let accepted = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                accepted = listener.accept() => accepted,
+            };
+            match accepted {
+                Ok((stream, _)) => {
+                    let neo = Arc::clone(&neo);
+                    tokio::spawn(async move {
+                        if let Err(e) = respond(stream, &neo).await {
+                            tracing::debug!("NEO: Health check connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => tracing::warn!("NEO: Health check accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(local_addr)
+}
+
+/// Status code and JSON body for the current agent health
+pub fn health_response(neo: &Neo) -> (u16, String) {
+    let agents = neo.agent_health();
+    let unhealthy: Vec<&AgentHealth> = agents
+        .iter()
+        .filter(|a| a.status != matrix_types::AgentStatus::Running)
+        .collect();
+
+    if unhealthy.is_empty() {
+        (200, json!({ "status": "ok", "agents": agents.len() }).to_string())
+    } else {
+        (503, json!({ "status": "unhealthy", "unhealthy": unhealthy }).to_string())
+    }
+}
+
+async fn respond(mut stream: TcpStream, neo: &Neo) -> std::io::Result<()> {
+    // Read the request head; the body (if any) is ignored
+    let mut buf = Vec::with_capacity(512);
+    let mut chunk = [0u8; 512];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let (code, body) = health_response(neo);
+    let reason = if code == 200 { "OK" } else { "Service Unavailable" };
+    let reply = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(reply.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Agent, AgentStatus};
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    /// Agent whose status is controlled by the test
+    struct ToggleAgent {
+        name: String,
+        status: Arc<Mutex<AgentStatus>>,
+    }
+
+    #[async_trait]
+    impl Agent for ToggleAgent {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn start(&mut self) -> Result<(), NeoError> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), NeoError> {
+            Ok(())
+        }
+
+        fn status(&self) -> AgentStatus {
+            self.status.lock().clone()
+        }
+
+        async fn health_check(&self) -> bool {
+            *self.status.lock() == AgentStatus::Running
+        }
+    }
+
+    async fn get(addr: SocketAddr) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let code = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        (code, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_reports_failed_agent() {
+        let neo = Arc::new(Neo::new());
+        let dozer_status = Arc::new(Mutex::new(AgentStatus::Running));
+        neo.register(Box::new(ToggleAgent {
+            name: "morpheus".to_string(),
+            status: Arc::new(Mutex::new(AgentStatus::Running)),
+        }));
+        neo.register(Box::new(ToggleAgent {
+            name: "dozer".to_string(),
+            status: dozer_status.clone(),
+        }));
+
+        let addr = serve_health("127.0.0.1:0".parse().unwrap(), neo.clone()).await.unwrap();
+
+        let (code, body) = get(addr).await;
+        assert_eq!(code, 200);
+        assert_eq!(body["agents"], 2);
+
+        *dozer_status.lock() = AgentStatus::Failed("feed disconnected".to_string());
+        let (code, body) = get(addr).await;
+        assert_eq!(code, 503);
+        let unhealthy = body["unhealthy"].as_array().unwrap();
+        assert_eq!(unhealthy.len(), 1);
+        assert_eq!(unhealthy[0]["name"], "dozer");
+        assert_eq!(unhealthy[0]["status"], "Failed");
+
+        *dozer_status.lock() = AgentStatus::Running;
+        assert_eq!(get(addr).await.0, 200);
+    }
+}
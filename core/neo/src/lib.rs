@@ -10,8 +10,30 @@
 //! - Handle failover and recovery
 //! - Route opportunities to execution
 
+pub mod batch;
+pub mod health;
+pub mod queue;
+pub mod recent;
+pub mod routing;
+pub mod sink;
+pub mod validation;
+
 use async_trait::async_trait;
+use matrix_config::AgentConfig;
+use matrix_types::{AgentHealth, ExecutionResult, Opportunity, SafeMode};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+pub use batch::{run_batcher, OpportunityBatcher, DEFAULT_BATCH_WINDOW_MS};
+pub use health::{health_response, serve_health};
+pub use queue::{ExecutionQueue, ExecutionQueueConfig, DEFAULT_MAX_PER_BLOCK, DEFAULT_QUEUE_CAPACITY};
+pub use recent::{RecentOpportunities, DEFAULT_RECENT_CAPACITY};
+pub use routing::{OpportunityRouter, RejectReason, RouterConfig};
+pub use sink::{JsonlSink, NoopSink, ResultSink};
+pub use validation::{ValidationOutcome, ValidationPool, ValidationPoolConfig};
+
+/// Restarts attempted for an agent that fails to start
+pub const DEFAULT_MAX_RESTARTS: u32 = 3;
 
 /// NEO agent errors
 #[derive(Error, Debug)]
@@ -36,6 +58,18 @@ pub enum AgentStatus {
     Failed(String),
 }
 
+impl From<&AgentStatus> for matrix_types::AgentStatus {
+    fn from(status: &AgentStatus) -> Self {
+        match status {
+            AgentStatus::Starting => matrix_types::AgentStatus::Starting,
+            AgentStatus::Running => matrix_types::AgentStatus::Running,
+            AgentStatus::Stopping => matrix_types::AgentStatus::Stopping,
+            AgentStatus::Stopped => matrix_types::AgentStatus::Stopped,
+            AgentStatus::Failed(_) => matrix_types::AgentStatus::Failed,
+        }
+    }
+}
+
 /// Agent trait - all Matrix agents implement this
 #[async_trait]
 pub trait Agent: Send + Sync {
@@ -53,23 +87,72 @@ pub trait Agent: Send + Sync {
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Heartbeat, error count and metrics for reporting
+    ///
+    /// Agents with a `HealthReporter` should override this; the default
+    /// reports only the status, with no heartbeat (0).
+    fn health(&self) -> AgentHealth {
+        AgentHealth {
+            name: self.name().to_string(),
+            status: (&self.status()).into(),
+            last_heartbeat_ms: 0,
+            error_count: 0,
+            metrics: Default::default(),
+        }
+    }
 }
 
 /// NEO orchestrator
 pub struct Neo {
     agents: dashmap::DashMap<String, Box<dyn Agent>>,
+    /// Instance names registered under each scaled agent
+    instance_groups: dashmap::DashMap<String, Vec<String>>,
     status: AgentStatus,
+    recent_opportunities: RecentOpportunities,
+    /// Validated opportunities awaiting execution, best score first
+    execution_queue: parking_lot::Mutex<ExecutionQueue>,
+    result_sink: Box<dyn ResultSink>,
+    safe_mode: SafeMode,
+    shutdown: CancellationToken,
+    /// Restarts allowed per agent before it is left failed
+    max_restarts: u32,
+    /// Restarts performed per agent
+    restarts: dashmap::DashMap<String, u32>,
 }
 
 impl Neo {
     pub fn new() -> Self {
+        Self::with_opportunity_history(DEFAULT_RECENT_CAPACITY)
+    }
+
+    /// Create with a custom recent-opportunity history size
+    pub fn with_opportunity_history(capacity: usize) -> Self {
         tracing::info!("NEO: The One awakens...");
         Self {
             agents: dashmap::DashMap::new(),
+            instance_groups: dashmap::DashMap::new(),
             status: AgentStatus::Starting,
+            recent_opportunities: RecentOpportunities::new(capacity),
+            execution_queue: parking_lot::Mutex::new(ExecutionQueue::default()),
+            result_sink: Box::new(NoopSink),
+            safe_mode: SafeMode::new(),
+            shutdown: CancellationToken::new(),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            restarts: dashmap::DashMap::new(),
         }
     }
 
+    /// Restarts allowed per agent before it is left failed
+    pub fn set_max_restarts(&mut self, max_restarts: u32) {
+        self.max_restarts = max_restarts;
+    }
+
+    /// Restarts performed for an agent so far
+    pub fn restarts(&self, name: &str) -> u32 {
+        self.restarts.get(name).map(|count| *count).unwrap_or(0)
+    }
+
     /// Register an agent
     pub fn register(&self, agent: Box<dyn Agent>) {
         let name = agent.name().to_string();
@@ -77,17 +160,245 @@ impl Neo {
         self.agents.insert(name, agent);
     }
 
-    /// Start all agents
+    /// Register `config.instances` copies of an agent as `"{name}-{i}"`
+    ///
+    /// `factory` builds each copy from its instance name. Disabled agents
+    /// register nothing; re-registering replaces the previous instances.
+    /// Returns the number of instances registered.
+    pub fn register_instances<F>(&self, name: &str, config: &AgentConfig, factory: F) -> usize
+    where
+        F: Fn(&str) -> Box<dyn Agent>,
+    {
+        if let Some((_, old)) = self.instance_groups.remove(name) {
+            for instance in old {
+                self.agents.remove(&instance);
+            }
+        }
+        if !config.enabled {
+            tracing::info!("NEO: Agent '{}' disabled, not registering", name);
+            return 0;
+        }
+
+        let instances: Vec<String> = (0..config.instances).map(|i| format!("{}-{}", name, i)).collect();
+        tracing::info!("NEO: Registering {} instance(s) of agent '{}'", instances.len(), name);
+        for instance in &instances {
+            self.agents.insert(instance.clone(), factory(instance));
+        }
+        let count = instances.len();
+        self.instance_groups.insert(name.to_string(), instances);
+        count
+    }
+
+    /// Instance names registered under a scaled agent, in order
+    pub fn instances(&self, name: &str) -> Vec<String> {
+        self.instance_groups
+            .get(name)
+            .map(|group| group.clone())
+            .unwrap_or_default()
+    }
+
+    /// Combined health of a scaled agent's instances
+    ///
+    /// `Running` when every instance runs, `Degraded` when only some do;
+    /// otherwise `Failed` if any instance failed, else the first instance's
+    /// status. The heartbeat is the stalest instance's; the error count
+    /// adds instances not running to every instance's own errors. `None` if
+    /// `name` was never registered with instances.
+    pub fn instance_health(&self, name: &str) -> Option<AgentHealth> {
+        let group = self.instance_groups.get(name)?;
+        let instances: Vec<AgentHealth> = group
+            .iter()
+            .filter_map(|instance| self.agents.get(instance).map(|agent| agent.health()))
+            .collect();
+        let statuses: Vec<matrix_types::AgentStatus> = instances.iter().map(|h| h.status.clone()).collect();
+
+        let running = statuses
+            .iter()
+            .filter(|s| **s == matrix_types::AgentStatus::Running)
+            .count();
+        let status = if !statuses.is_empty() && running == statuses.len() {
+            matrix_types::AgentStatus::Running
+        } else if running > 0 {
+            matrix_types::AgentStatus::Degraded
+        } else if statuses.contains(&matrix_types::AgentStatus::Failed) {
+            matrix_types::AgentStatus::Failed
+        } else {
+            statuses.first().cloned().unwrap_or(matrix_types::AgentStatus::Stopped)
+        };
+
+        let mut metrics = std::collections::HashMap::new();
+        metrics.insert("instances".to_string(), statuses.len() as f64);
+        metrics.insert("instances_running".to_string(), running as f64);
+
+        Some(AgentHealth {
+            name: name.to_string(),
+            status,
+            last_heartbeat_ms: instances.iter().map(|h| h.last_heartbeat_ms).min().unwrap_or(0),
+            error_count: (statuses.len() - running) as u64 + instances.iter().map(|h| h.error_count).sum::<u64>(),
+            metrics,
+        })
+    }
+
+    /// Health of every registered agent, sorted by name
+    pub fn agent_health(&self) -> Vec<AgentHealth> {
+        let mut health: Vec<AgentHealth> = self
+            .agents
+            .iter()
+            .map(|entry| AgentHealth {
+                name: entry.key().clone(),
+                ..entry.value().health()
+            })
+            .collect();
+        health.sort_by(|a, b| a.name.cmp(&b.name));
+        health
+    }
+
+    /// Start every agent not already running, restarting failures
+    ///
+    /// An agent whose `start` fails is stopped and started again, up to
+    /// `max_restarts` times. Agents that still fail are left registered
+    /// (reporting their failure) and named in the returned error.
     pub async fn start_all(&mut self) -> Result<(), NeoError> {
         tracing::info!("NEO: Starting all agents...");
-        self.status = AgentStatus::Running;
-        // Implementation will iterate and start each agent
-        Ok(())
+        let mut names: Vec<String> = self.agents.iter().map(|entry| entry.key().clone()).collect();
+        names.sort();
+
+        let mut failed = Vec::new();
+        for name in names {
+            // Take the agent out so no map lock is held across `start`
+            let Some((name, mut agent)) = self.agents.remove(&name) else {
+                continue;
+            };
+            if agent.status() != AgentStatus::Running && !self.supervise_start(&name, agent.as_mut()).await {
+                failed.push(name.clone());
+            }
+            self.agents.insert(name, agent);
+        }
+
+        if failed.is_empty() {
+            self.status = AgentStatus::Running;
+            Ok(())
+        } else {
+            let message = format!("Agents failed to start: {}", failed.join(", "));
+            self.status = AgentStatus::Failed(message.clone());
+            Err(NeoError::SupervisionError(message))
+        }
+    }
+
+    /// Start `agent`, restarting it on failure; true once it started
+    async fn supervise_start(&self, name: &str, agent: &mut dyn Agent) -> bool {
+        let mut attempt = 0;
+        loop {
+            match agent.start().await {
+                Ok(()) => {
+                    tracing::info!("NEO: Agent '{}' started", name);
+                    return true;
+                }
+                Err(e) if attempt < self.max_restarts => {
+                    attempt += 1;
+                    *self.restarts.entry(name.to_string()).or_insert(0) += 1;
+                    tracing::warn!(
+                        "NEO: Agent '{}' failed to start ({}), restart {}/{}",
+                        name, e, attempt, self.max_restarts
+                    );
+                    if let Err(e) = agent.stop().await {
+                        tracing::warn!("NEO: Agent '{}' failed to stop before restart: {}", name, e);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("NEO: Agent '{}' failed to start after {} restart(s): {}", name, attempt, e);
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Record a detected opportunity for post-mortem queries
+    pub fn record_opportunity(&self, opportunity: Opportunity) {
+        tracing::debug!("NEO: Recording opportunity {}", opportunity.id);
+        self.recent_opportunities.push(opportunity);
+    }
+
+    /// Most recent `n` detected opportunities, newest first
+    pub fn recent(&self, n: usize) -> Vec<Opportunity> {
+        self.recent_opportunities.recent(n)
+    }
+
+    /// Replace the execution queue's capacity and per-block cap
+    ///
+    /// Anything still queued is discarded.
+    pub fn set_execution_queue_config(&mut self, config: ExecutionQueueConfig) {
+        self.execution_queue = parking_lot::Mutex::new(ExecutionQueue::new(config));
+    }
+
+    /// Queue a validated opportunity for execution, ranked by `score`
+    pub fn enqueue_execution(&self, opportunity: Opportunity, score: f64) {
+        if let Some(dropped) = self.execution_queue.lock().push(opportunity, score) {
+            tracing::debug!("NEO: Execution queue full, dropped opportunity {}", dropped.id);
+        }
+    }
+
+    /// Opportunities to execute in `block`, highest score first, within
+    /// the per-block cap
+    pub fn next_executions(&self, block: u64) -> Vec<Opportunity> {
+        self.execution_queue.lock().drain_block(block)
+    }
+
+    /// Set where execution results are persisted (default: discarded)
+    pub fn set_result_sink(&mut self, sink: Box<dyn ResultSink>) {
+        self.result_sink = sink;
+    }
+
+    /// Persist an execution result; called after each execution
+    ///
+    /// Sink failures are logged, not propagated: losing an audit record
+    /// must not stall trading.
+    pub fn record_execution(&self, result: &ExecutionResult) {
+        tracing::info!(
+            "NEO: Execution {} for opportunity {} (success={})",
+            result.tx_hash, result.opportunity_id, result.success
+        );
+        if let Err(e) = self.result_sink.record(result) {
+            tracing::error!("NEO: Failed to persist execution result: {}", e);
+        }
+    }
+
+    /// Handle to the safe mode switch, to share with executors
+    pub fn safe_mode(&self) -> SafeMode {
+        self.safe_mode.clone()
+    }
+
+    /// Turn safe mode on or off: detection keeps running, execution stops
+    pub fn set_safe_mode(&self, enabled: bool) {
+        if enabled {
+            tracing::warn!("NEO: Entering safe mode - execution disabled");
+            self.safe_mode.enable();
+        } else {
+            tracing::info!("NEO: Leaving safe mode - execution enabled");
+            self.safe_mode.disable();
+        }
+    }
+
+    /// Token for a long-running task to select on; cancelled by `shutdown`
+    ///
+    /// Pass it to `ManagedConnection::with_shutdown_token`,
+    /// `FeedProcessor::set_shutdown_token`, and any spawned loop.
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.child_token()
+    }
+
+    /// Signal every task holding a shutdown token to exit
+    pub fn shutdown(&self) {
+        if !self.shutdown.is_cancelled() {
+            tracing::info!("NEO: Signalling system-wide shutdown");
+            self.shutdown.cancel();
+        }
     }
 
     /// Stop all agents
     pub async fn stop_all(&mut self) -> Result<(), NeoError> {
         tracing::info!("NEO: Stopping all agents...");
+        self.shutdown();
         self.status = AgentStatus::Stopped;
         Ok(())
     }
@@ -102,10 +413,365 @@ impl Default for Neo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use matrix_types::HealthReporter;
+    use std::sync::Arc;
 
     #[test]
     fn test_neo_creation() {
         let neo = Neo::new();
         assert_eq!(neo.status, AgentStatus::Starting);
     }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_all_tasks() {
+        use std::time::Duration;
+
+        let mut neo = Neo::new();
+        let (batch_tx, batch_rx) = tokio::sync::mpsc::channel(8);
+        let (out_tx, _out_rx) = tokio::sync::mpsc::channel(8);
+
+        // Idle loops of different shapes, each selecting on its token
+        let mut tasks = Vec::new();
+        for period_ms in [1, 10, 1_000] {
+            let token = neo.shutdown_token();
+            tasks.push(tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(Duration::from_millis(period_ms));
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = ticks.tick() => {}
+                    }
+                }
+            }));
+        }
+        let token = neo.shutdown_token();
+        tasks.push(tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = run_batcher(OpportunityBatcher::default(), batch_rx, out_tx) => {}
+            }
+        }));
+
+        neo.stop_all().await.unwrap();
+        for task in tasks {
+            tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+        }
+        drop(batch_tx);
+
+        // Tokens handed out after shutdown are already cancelled
+        assert!(neo.shutdown_token().is_cancelled());
+    }
+
+    struct TestAgent {
+        name: String,
+        status: AgentStatus,
+    }
+
+    #[async_trait]
+    impl Agent for TestAgent {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn start(&mut self) -> Result<(), NeoError> {
+            self.status = AgentStatus::Running;
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), NeoError> {
+            self.status = AgentStatus::Stopped;
+            Ok(())
+        }
+
+        fn status(&self) -> AgentStatus {
+            self.status.clone()
+        }
+
+        async fn health_check(&self) -> bool {
+            self.status == AgentStatus::Running
+        }
+    }
+
+    /// Agent that starts `Stopped` and fails its first `failures` starts
+    struct FlakyAgent {
+        name: String,
+        status: AgentStatus,
+        failures: u32,
+    }
+
+    #[async_trait]
+    impl Agent for FlakyAgent {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn start(&mut self) -> Result<(), NeoError> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                self.status = AgentStatus::Failed("rpc down".to_string());
+                return Err(NeoError::StateError("rpc down".to_string()));
+            }
+            self.status = AgentStatus::Running;
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), NeoError> {
+            self.status = AgentStatus::Stopped;
+            Ok(())
+        }
+
+        fn status(&self) -> AgentStatus {
+            self.status.clone()
+        }
+
+        async fn health_check(&self) -> bool {
+            self.status == AgentStatus::Running
+        }
+    }
+
+    fn flaky(name: &str, failures: u32) -> Box<dyn Agent> {
+        Box::new(FlakyAgent {
+            name: name.to_string(),
+            status: AgentStatus::Stopped,
+            failures,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_start_all_restarts_failed_agent() {
+        let mut neo = Neo::new();
+        neo.register(flaky("morpheus", 1));
+        neo.register(flaky("dozer", 0));
+        assert!(neo.agent_health().iter().all(|h| h.status == matrix_types::AgentStatus::Stopped));
+        // Without a reporter, an agent never claims a heartbeat
+        assert!(neo.agent_health().iter().all(|h| h.last_heartbeat_ms == 0));
+
+        neo.start_all().await.unwrap();
+        assert_eq!(neo.status, AgentStatus::Running);
+        assert!(neo.agent_health().iter().all(|h| h.status == matrix_types::AgentStatus::Running));
+        assert_eq!(neo.restarts("morpheus"), 1);
+        assert_eq!(neo.restarts("dozer"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_start_all_gives_up_after_max_restarts() {
+        let mut neo = Neo::new();
+        neo.set_max_restarts(2);
+        neo.register(flaky("trinity", 10));
+        neo.register(flaky("dozer", 0));
+
+        let err = neo.start_all().await.unwrap_err();
+        assert!(matches!(err, NeoError::SupervisionError(ref m) if m.contains("trinity") && !m.contains("dozer")));
+        assert!(matches!(neo.status, AgentStatus::Failed(_)));
+        assert_eq!(neo.restarts("trinity"), 2);
+
+        // The failed agent stays registered and reports its failure
+        let health = neo.agent_health();
+        assert_eq!(health[1].name, "trinity");
+        assert_eq!(health[1].status, matrix_types::AgentStatus::Failed);
+        assert_eq!(health[0].status, matrix_types::AgentStatus::Running);
+    }
+
+    #[test]
+    fn test_register_scaled_instances() {
+        let neo = Neo::new();
+        let config = AgentConfig {
+            instances: 3,
+            ..Default::default()
+        };
+
+        let count = neo.register_instances("morpheus", &config, |name| {
+            Box::new(TestAgent {
+                name: name.to_string(),
+                status: AgentStatus::Running,
+            })
+        });
+        assert_eq!(count, 3);
+        assert_eq!(neo.instances("morpheus"), vec!["morpheus-0", "morpheus-1", "morpheus-2"]);
+
+        let names: Vec<String> = neo.agent_health().into_iter().map(|h| h.name).collect();
+        assert_eq!(names, vec!["morpheus-0", "morpheus-1", "morpheus-2"]);
+
+        let health = neo.instance_health("morpheus").unwrap();
+        assert_eq!(health.status, matrix_types::AgentStatus::Running);
+        assert_eq!(health.metrics["instances"], 3.0);
+
+        // Scaling down replaces the old instances
+        let config = AgentConfig {
+            instances: 1,
+            ..Default::default()
+        };
+        neo.register_instances("morpheus", &config, |name| {
+            Box::new(TestAgent {
+                name: name.to_string(),
+                status: AgentStatus::Running,
+            })
+        });
+        assert_eq!(neo.agent_health().len(), 1);
+        assert!(neo.instance_health("dozer").is_none());
+    }
+
+    #[test]
+    fn test_instance_health_degrades_when_one_fails() {
+        let neo = Neo::new();
+        let config = AgentConfig {
+            instances: 3,
+            ..Default::default()
+        };
+        neo.register_instances("dozer", &config, |name| {
+            let status = if name == "dozer-1" {
+                AgentStatus::Failed("rpc down".to_string())
+            } else {
+                AgentStatus::Running
+            };
+            Box::new(TestAgent {
+                name: name.to_string(),
+                status,
+            })
+        });
+
+        let health = neo.instance_health("dozer").unwrap();
+        assert_eq!(health.status, matrix_types::AgentStatus::Degraded);
+        assert_eq!(health.error_count, 1);
+        assert_eq!(health.metrics["instances_running"], 2.0);
+    }
+
+    /// Agent reporting through a shared `HealthReporter`
+    struct ReportingAgent {
+        reporter: Arc<HealthReporter>,
+    }
+
+    #[async_trait]
+    impl Agent for ReportingAgent {
+        fn name(&self) -> &str {
+            "trinity"
+        }
+
+        async fn start(&mut self) -> Result<(), NeoError> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), NeoError> {
+            Ok(())
+        }
+
+        fn status(&self) -> AgentStatus {
+            AgentStatus::Running
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+
+        fn health(&self) -> AgentHealth {
+            self.reporter.report((&self.status()).into())
+        }
+    }
+
+    #[test]
+    fn test_agent_health_reflects_counters() {
+        let neo = Neo::new();
+        let reporters: Vec<Arc<HealthReporter>> = (0..2).map(|_| Arc::new(HealthReporter::new("trinity"))).collect();
+        let config = AgentConfig {
+            instances: 2,
+            ..Default::default()
+        };
+        neo.register_instances("trinity", &config, |name| {
+            let i: usize = name.rsplit('-').next().unwrap().parse().unwrap();
+            Box::new(ReportingAgent {
+                reporter: Arc::clone(&reporters[i]),
+            })
+        });
+
+        let before = chrono::Utc::now().timestamp_millis() as u64;
+        reporters[0].heartbeat();
+        reporters[0].record_error();
+        reporters[1].heartbeat_at(before - 60_000);
+        reporters[1].record_error();
+        reporters[1].record_error();
+
+        let health = neo.agent_health();
+        assert_eq!(health[0].name, "trinity-0");
+        assert_eq!(health[0].error_count, 1);
+        assert!(health[0].last_heartbeat_ms >= before);
+
+        // The group reports its stalest heartbeat and every instance's errors
+        let group = neo.instance_health("trinity").unwrap();
+        assert_eq!(group.status, matrix_types::AgentStatus::Running);
+        assert_eq!(group.error_count, 3);
+        assert_eq!(group.last_heartbeat_ms, before - 60_000);
+    }
+
+    #[test]
+    fn test_recent_opportunities() {
+        let neo = Neo::with_opportunity_history(2);
+        for id in 1..=3 {
+            neo.record_opportunity(Opportunity {
+                id,
+                timestamp_ms: id,
+                chain: matrix_types::ChainId::Bsc,
+                profit_wei: Default::default(),
+                gas_estimate: 0,
+                path: Vec::new(),
+                flash_loan_token: Default::default(),
+                flash_loan_amount: Default::default(),
+            });
+        }
+
+        let ids: Vec<u64> = neo.recent(5).iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_execution_queue_by_score_per_block() {
+        let mut neo = Neo::new();
+        neo.set_execution_queue_config(ExecutionQueueConfig {
+            capacity: 8,
+            max_per_block: 2,
+        });
+        for (id, score) in [(1, 1.5), (2, 4.0), (3, 0.5), (4, 3.0)] {
+            neo.enqueue_execution(
+                Opportunity {
+                    id,
+                    timestamp_ms: id,
+                    chain: matrix_types::ChainId::Bsc,
+                    profit_wei: Default::default(),
+                    gas_estimate: 0,
+                    path: Vec::new(),
+                    flash_loan_token: Default::default(),
+                    flash_loan_amount: Default::default(),
+                },
+                score,
+            );
+        }
+
+        let ids = |block| neo.next_executions(block).iter().map(|o| o.id).collect::<Vec<u64>>();
+        assert_eq!(ids(50), vec![2, 4]);
+        assert!(ids(50).is_empty());
+        assert_eq!(ids(51), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_record_execution_uses_sink() {
+        let path = std::env::temp_dir().join(format!("neo-executions-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut neo = Neo::new();
+        neo.set_result_sink(Box::new(JsonlSink::open(&path).unwrap()));
+        for id in 1..=2 {
+            neo.record_execution(&ExecutionResult {
+                opportunity_id: id,
+                tx_hash: Default::default(),
+                success: true,
+                actual_profit: Default::default(),
+                gas_used: 0,
+                block_number: 0,
+                timestamp_ms: id,
+            });
+        }
+
+        let ids: Vec<u64> = JsonlSink::replay(&path).unwrap().iter().map(|r| r.opportunity_id).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ids, vec![1, 2]);
+    }
 }
@@ -0,0 +1,224 @@
+//! Execution Queue
+//!
+//! Validated opportunities often compete for the same block. Executing
+//! them first-come lets a mediocre early arrival take the slot from a
+//! better one a few milliseconds behind it. The queue holds them ordered
+//! by score and hands out at most `max_per_block` per block, best first.
+//! When full, the lowest-scored entry makes way for a better arrival.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use matrix_types::Opportunity;
+
+/// Default number of opportunities held
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+
+/// Default executions per block
+pub const DEFAULT_MAX_PER_BLOCK: usize = 1;
+
+/// Queue bounds
+#[derive(Debug, Clone)]
+pub struct ExecutionQueueConfig {
+    /// Opportunities held at once
+    pub capacity: usize,
+    /// Opportunities handed out per block
+    pub max_per_block: usize,
+}
+
+impl Default for ExecutionQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUEUE_CAPACITY,
+            max_per_block: DEFAULT_MAX_PER_BLOCK,
+        }
+    }
+}
+
+/// Queued opportunity; highest score first, then earliest queued
+#[derive(Debug)]
+struct Queued {
+    score: f64,
+    seq: u64,
+    opportunity: Opportunity,
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+/// Bounded, score-ordered queue with a per-block execution cap
+#[derive(Debug)]
+pub struct ExecutionQueue {
+    config: ExecutionQueueConfig,
+    heap: BinaryHeap<Queued>,
+    next_seq: u64,
+    /// Block being executed and how many were handed out for it
+    block: Option<u64>,
+    taken_in_block: usize,
+    /// Opportunities turned away or evicted while full
+    dropped: u64,
+}
+
+impl ExecutionQueue {
+    pub fn new(config: ExecutionQueueConfig) -> Self {
+        Self {
+            config,
+            heap: BinaryHeap::new(),
+            next_seq: 0,
+            block: None,
+            taken_in_block: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Queue `opportunity` with `score` (higher executes first)
+    ///
+    /// When full, the lowest-scored entry is evicted if `score` beats it;
+    /// otherwise `opportunity` itself is turned away. Returns whichever
+    /// was dropped.
+    pub fn push(&mut self, opportunity: Opportunity, score: f64) -> Option<Opportunity> {
+        let queued = Queued {
+            score,
+            seq: self.next_seq,
+            opportunity,
+        };
+        self.next_seq += 1;
+
+        if self.heap.len() < self.config.capacity {
+            self.heap.push(queued);
+            return None;
+        }
+
+        self.dropped += 1;
+        let mut entries = std::mem::take(&mut self.heap).into_vec();
+        let lowest = (0..entries.len()).min_by(|&a, &b| entries[a].cmp(&entries[b]));
+        let dropped = match lowest {
+            Some(i) if queued > entries[i] => std::mem::replace(&mut entries[i], queued),
+            _ => queued,
+        };
+        self.heap = entries.into();
+        Some(dropped.opportunity)
+    }
+
+    /// Best queued opportunity, if `block`'s cap isn't reached yet
+    pub fn pop(&mut self, block: u64) -> Option<Opportunity> {
+        if self.block != Some(block) {
+            self.block = Some(block);
+            self.taken_in_block = 0;
+        }
+        if self.taken_in_block >= self.config.max_per_block {
+            return None;
+        }
+
+        let queued = self.heap.pop()?;
+        self.taken_in_block += 1;
+        Some(queued.opportunity)
+    }
+
+    /// Everything `block` may still execute, best first
+    pub fn drain_block(&mut self, block: u64) -> Vec<Opportunity> {
+        std::iter::from_fn(|| self.pop(block)).collect()
+    }
+
+    /// Opportunities turned away or evicted because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl Default for ExecutionQueue {
+    fn default() -> Self {
+        Self::new(ExecutionQueueConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Address, U256};
+    use matrix_types::ChainId;
+
+    fn opportunity(id: u64) -> Opportunity {
+        Opportunity {
+            id,
+            timestamp_ms: 1_700_000_000_000 + id,
+            chain: ChainId::Ethereum,
+            profit_wei: U256::from(id),
+            gas_estimate: 250_000,
+            path: Vec::new(),
+            flash_loan_token: Address::zero(),
+            flash_loan_amount: U256::zero(),
+        }
+    }
+
+    fn ids(opportunities: &[Opportunity]) -> Vec<u64> {
+        opportunities.iter().map(|o| o.id).collect()
+    }
+
+    #[test]
+    fn test_highest_score_first_within_block_cap() {
+        let mut queue = ExecutionQueue::new(ExecutionQueueConfig {
+            capacity: 10,
+            max_per_block: 2,
+        });
+        for (id, score) in [(1, 0.2), (2, 0.9), (3, 0.5), (4, 0.9), (5, 0.1)] {
+            assert!(queue.push(opportunity(id), score).is_none());
+        }
+
+        // Ties go to the earlier arrival
+        assert_eq!(ids(&queue.drain_block(100)), vec![2, 4]);
+        assert!(queue.pop(100).is_none());
+        assert_eq!(queue.len(), 3);
+
+        // A new block gets a fresh budget
+        assert_eq!(ids(&queue.drain_block(101)), vec![3, 1]);
+        assert_eq!(ids(&queue.drain_block(102)), vec![5]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_full_queue_keeps_best() {
+        let mut queue = ExecutionQueue::new(ExecutionQueueConfig {
+            capacity: 3,
+            max_per_block: 10,
+        });
+        for (id, score) in [(1, 0.5), (2, 0.3), (3, 0.7)] {
+            queue.push(opportunity(id), score);
+        }
+
+        // Worse than everything queued: turned away
+        assert_eq!(queue.push(opportunity(4), 0.1).map(|o| o.id), Some(4));
+        // Better than the lowest: evicts it
+        assert_eq!(queue.push(opportunity(5), 0.6).map(|o| o.id), Some(2));
+        assert_eq!(queue.dropped(), 2);
+
+        assert_eq!(ids(&queue.drain_block(1)), vec![3, 5, 1]);
+    }
+}
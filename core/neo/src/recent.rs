@@ -0,0 +1,122 @@
+//! Recent Opportunities
+//!
+//! Fixed-capacity ring buffer of recently detected opportunities.
+//! Kept in memory for post-mortems ("why didn't this trade fire?")
+//! without needing a database.
+
+use matrix_types::Opportunity;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// Default number of opportunities retained
+pub const DEFAULT_RECENT_CAPACITY: usize = 256;
+
+/// Bounded, newest-first history of detected opportunities
+pub struct RecentOpportunities {
+    capacity: usize,
+    buffer: Mutex<VecDeque<Opportunity>>,
+}
+
+impl RecentOpportunities {
+    /// Create a buffer holding at most `capacity` opportunities
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buffer: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Record an opportunity, evicting the oldest when full
+    pub fn push(&self, opportunity: Opportunity) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut buffer = self.buffer.lock();
+        if buffer.len() == self.capacity {
+            buffer.pop_back();
+        }
+        buffer.push_front(opportunity);
+    }
+
+    /// Up to `n` most recent opportunities, newest first
+    pub fn recent(&self, n: usize) -> Vec<Opportunity> {
+        self.buffer.lock().iter().take(n).cloned().collect()
+    }
+
+    /// Maximum number of retained opportunities
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.lock().is_empty()
+    }
+
+    /// Drop all recorded opportunities
+    pub fn clear(&self) {
+        self.buffer.lock().clear();
+    }
+}
+
+impl Default for RecentOpportunities {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_types::ChainId;
+
+    fn opportunity(id: u64) -> Opportunity {
+        Opportunity {
+            id,
+            timestamp_ms: 1_700_000_000_000 + id,
+            chain: ChainId::Ethereum,
+            profit_wei: Default::default(),
+            gas_estimate: 300_000,
+            path: Vec::new(),
+            flash_loan_token: Default::default(),
+            flash_loan_amount: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_capacity_respected() {
+        let recent = RecentOpportunities::new(3);
+        for id in 1..=5 {
+            recent.push(opportunity(id));
+        }
+
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent.capacity(), 3);
+
+        let ids: Vec<u64> = recent.recent(10).iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![5, 4, 3]);
+    }
+
+    #[test]
+    fn test_newest_first() {
+        let recent = RecentOpportunities::new(8);
+        for id in 1..=4 {
+            recent.push(opportunity(id));
+        }
+
+        let ids: Vec<u64> = recent.recent(2).iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![4, 3]);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let recent = RecentOpportunities::new(0);
+        recent.push(opportunity(1));
+        assert!(recent.is_empty());
+        assert!(recent.recent(1).is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+//! Opportunity routing checks
+//!
+//! Gatekeeps opportunities before they reach execution and reports every
+//! rejection as a single `RejectReason`, so "why aren't we trading?" can be
+//! answered from one counter per reason instead of scattered error strings.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use cypher::{Cypher, CypherError};
+use ethers::types::U256;
+use matrix_metrics::ArbitrageMetrics;
+use matrix_config::ChainConfig;
+use matrix_types::{ChainId, GasPrice, Opportunity};
+use parking_lot::Mutex;
+use seraph::SeraphError;
+
+/// Why an opportunity was not executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectReason {
+    RiskLimit,
+    CircuitBreaker,
+    Cooldown,
+    InsufficientProfit,
+    Slippage,
+    GasTooHigh,
+    SimulationReverted,
+    Expired,
+    Duplicate,
+}
+
+impl RejectReason {
+    pub const ALL: [RejectReason; 9] = [
+        RejectReason::RiskLimit,
+        RejectReason::CircuitBreaker,
+        RejectReason::Cooldown,
+        RejectReason::InsufficientProfit,
+        RejectReason::Slippage,
+        RejectReason::GasTooHigh,
+        RejectReason::SimulationReverted,
+        RejectReason::Expired,
+        RejectReason::Duplicate,
+    ];
+
+    /// Metric label for this reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectReason::RiskLimit => "risk_limit",
+            RejectReason::CircuitBreaker => "circuit_breaker",
+            RejectReason::Cooldown => "cooldown",
+            RejectReason::InsufficientProfit => "insufficient_profit",
+            RejectReason::Slippage => "slippage",
+            RejectReason::GasTooHigh => "gas_too_high",
+            RejectReason::SimulationReverted => "simulation_reverted",
+            RejectReason::Expired => "expired",
+            RejectReason::Duplicate => "duplicate",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&CypherError> for RejectReason {
+    fn from(error: &CypherError) -> Self {
+        match error {
+            CypherError::PositionLimitExceeded(_)
+            | CypherError::ExposureLimitExceeded { .. }
+            | CypherError::RiskCheckFailed(_) => RejectReason::RiskLimit,
+            CypherError::CircuitBreakerTriggered(_) => RejectReason::CircuitBreaker,
+            CypherError::CooldownActive { .. } => RejectReason::Cooldown,
+        }
+    }
+}
+
+impl From<&SeraphError> for RejectReason {
+    fn from(error: &SeraphError) -> Self {
+        match error {
+            // Costs too large to represent can't leave a profit either
+            SeraphError::InsufficientProfit { .. } | SeraphError::Overflow(_) => RejectReason::InsufficientProfit,
+            SeraphError::SlippageExceeded { .. } => RejectReason::Slippage,
+            SeraphError::SimulationFailed(_)
+            | SeraphError::ValidationFailed(_)
+            | SeraphError::GasEstimationFailed(_)
+            | SeraphError::StateAccessError(_) => RejectReason::SimulationReverted,
+        }
+    }
+}
+
+/// Routing configuration
+#[derive(Debug, Clone)]
+pub struct RouterConfig {
+    /// Opportunities older than this are expired, unless their chain has
+    /// its own entry in `chain_max_age_ms`
+    pub max_age_ms: u64,
+    /// Per-chain expiry, from each chain's opportunity TTL
+    pub chain_max_age_ms: HashMap<ChainId, u64>,
+    /// Minimum expected profit in wei
+    pub min_profit_wei: U256,
+    /// Opportunity ids remembered for duplicate detection
+    pub dedup_capacity: usize,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            max_age_ms: 2_000,
+            chain_max_age_ms: HashMap::new(),
+            min_profit_wei: U256::exp10(15), // 0.001 ETH
+            dedup_capacity: 4_096,
+        }
+    }
+}
+
+impl RouterConfig {
+    /// Expire opportunities after each chain's `opportunity_ttl_ms()`
+    ///
+    /// Chains with an unknown `chain_id` keep the global `max_age_ms`.
+    pub fn with_chains<'a>(mut self, chains: impl IntoIterator<Item = &'a ChainConfig>) -> Self {
+        for config in chains {
+            if let Some(chain) = config.chain() {
+                self.chain_max_age_ms.insert(chain, config.opportunity_ttl_ms());
+            }
+        }
+        self
+    }
+
+    /// Age after which an opportunity on `chain` is expired
+    pub fn max_age_ms(&self, chain: ChainId) -> u64 {
+        self.chain_max_age_ms.get(&chain).copied().unwrap_or(self.max_age_ms)
+    }
+}
+
+/// Pre-execution gate that counts rejections by reason
+pub struct OpportunityRouter {
+    config: RouterConfig,
+    seen: Mutex<(HashSet<u64>, VecDeque<u64>)>,
+    rejections: [AtomicU64; RejectReason::ALL.len()],
+    metrics: Option<Arc<ArbitrageMetrics>>,
+}
+
+impl OpportunityRouter {
+    pub fn new(config: RouterConfig) -> Self {
+        Self {
+            config,
+            seen: Mutex::new((HashSet::new(), VecDeque::new())),
+            rejections: Default::default(),
+            metrics: None,
+        }
+    }
+
+    /// Report rejections to `opportunities_rejected`
+    pub fn with_metrics(mut self, metrics: Arc<ArbitrageMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Run routing checks against current risk state
+    ///
+    /// Checks, in order: duplicate, expiry, Cypher trading state, gas price,
+    /// expected profit, Cypher position limits.
+    pub fn check(
+        &self,
+        opportunity: &Opportunity,
+        cypher: &Cypher,
+        gas_price: U256,
+        now_ms: u64,
+    ) -> Result<(), RejectReason> {
+        if !self.mark_seen(opportunity.id) {
+            return Err(self.reject(RejectReason::Duplicate));
+        }
+
+        if now_ms.saturating_sub(opportunity.timestamp_ms) > self.config.max_age_ms(opportunity.chain) {
+            return Err(self.reject(RejectReason::Expired));
+        }
+
+        if let Err(e) = cypher.can_trade(now_ms) {
+            return Err(self.reject_error(&e));
+        }
+
+        if GasPrice::from_wei(gas_price) > cypher.limits().max_gas_price {
+            return Err(self.reject(RejectReason::GasTooHigh));
+        }
+
+        if opportunity.profit_wei < self.config.min_profit_wei {
+            return Err(self.reject(RejectReason::InsufficientProfit));
+        }
+
+        if let Err(e) = cypher.check_position(opportunity.flash_loan_amount) {
+            return Err(self.reject_error(&e));
+        }
+
+        Ok(())
+    }
+
+    /// Count a rejection and return it
+    pub fn reject(&self, reason: RejectReason) -> RejectReason {
+        tracing::debug!("NEO: Opportunity rejected ({})", reason);
+        self.rejections[reason.index()].fetch_add(1, Ordering::Relaxed);
+        if let Some(metrics) = &self.metrics {
+            metrics.opportunities_rejected.with_label_values(&[reason.as_str()]).inc();
+        }
+        reason
+    }
+
+    /// Count a rejection derived from an agent error
+    pub fn reject_error<E>(&self, error: &E) -> RejectReason
+    where
+        for<'a> RejectReason: From<&'a E>,
+    {
+        self.reject(RejectReason::from(error))
+    }
+
+    /// Rejections counted for a reason
+    pub fn rejections(&self, reason: RejectReason) -> u64 {
+        self.rejections[reason.index()].load(Ordering::Relaxed)
+    }
+
+    /// Remember an id; false if it was already seen
+    fn mark_seen(&self, id: u64) -> bool {
+        let mut guard = self.seen.lock();
+        let (ids, order) = &mut *guard;
+        if !ids.insert(id) {
+            return false;
+        }
+        order.push_back(id);
+        while order.len() > self.config.dedup_capacity {
+            if let Some(oldest) = order.pop_front() {
+                ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+impl Default for OpportunityRouter {
+    fn default() -> Self {
+        Self::new(RouterConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cypher::RiskLimits;
+    use ethers::types::Address;
+    use matrix_types::ChainId;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn opportunity(id: u64) -> Opportunity {
+        Opportunity {
+            id,
+            timestamp_ms: NOW,
+            chain: ChainId::Ethereum,
+            profit_wei: U256::exp10(16),
+            gas_estimate: 300_000,
+            path: Vec::new(),
+            flash_loan_token: Address::zero(),
+            flash_loan_amount: U256::exp10(18),
+        }
+    }
+
+    fn gwei(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(9)
+    }
+
+    #[test]
+    fn test_routing_rejections_map_to_reasons() {
+        let router = OpportunityRouter::default();
+        let cypher = Cypher::with_default_limits();
+
+        assert_eq!(router.check(&opportunity(1), &cypher, gwei(20), NOW), Ok(()));
+        assert_eq!(router.check(&opportunity(1), &cypher, gwei(20), NOW), Err(RejectReason::Duplicate));
+        assert_eq!(router.check(&opportunity(2), &cypher, gwei(20), NOW + 5_000), Err(RejectReason::Expired));
+        assert_eq!(router.check(&opportunity(3), &cypher, gwei(500), NOW), Err(RejectReason::GasTooHigh));
+
+        let thin = Opportunity { profit_wei: U256::from(1u64), ..opportunity(4) };
+        assert_eq!(router.check(&thin, &cypher, gwei(20), NOW), Err(RejectReason::InsufficientProfit));
+
+        let huge = Opportunity { flash_loan_amount: U256::exp10(18) * 1_000, ..opportunity(5) };
+        assert_eq!(router.check(&huge, &cypher, gwei(20), NOW), Err(RejectReason::RiskLimit));
+
+        cypher.set_cooldown(NOW);
+        assert_eq!(router.check(&opportunity(6), &cypher, gwei(20), NOW), Err(RejectReason::Cooldown));
+
+        let mut halted = Cypher::new(RiskLimits::default());
+        halted.trigger_circuit_breaker("test");
+        assert_eq!(router.check(&opportunity(7), &halted, gwei(20), NOW), Err(RejectReason::CircuitBreaker));
+
+        for reason in [
+            RejectReason::Duplicate,
+            RejectReason::Expired,
+            RejectReason::GasTooHigh,
+            RejectReason::InsufficientProfit,
+            RejectReason::RiskLimit,
+            RejectReason::Cooldown,
+            RejectReason::CircuitBreaker,
+        ] {
+            assert_eq!(router.rejections(reason), 1, "{}", reason);
+        }
+    }
+
+    #[test]
+    fn test_seraph_errors_map_to_reasons() {
+        let cases = [
+            (SeraphError::SimulationFailed("revert".into()), RejectReason::SimulationReverted),
+            (SeraphError::ValidationFailed("approval".into()), RejectReason::SimulationReverted),
+            (
+                SeraphError::InsufficientProfit { expected: U256::one(), actual: U256::zero() },
+                RejectReason::InsufficientProfit,
+            ),
+            (SeraphError::SlippageExceeded { max_bps: 50, actual_bps: 80 }, RejectReason::Slippage),
+        ];
+
+        let router = OpportunityRouter::default();
+        for (error, expected) in &cases {
+            assert_eq!(router.reject_error(error), *expected);
+        }
+        assert_eq!(router.rejections(RejectReason::SimulationReverted), 2);
+        assert_eq!(router.rejections(RejectReason::Slippage), 1);
+    }
+
+    #[test]
+    fn test_rejections_reported_to_metrics() {
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(ArbitrageMetrics::new(&registry).unwrap());
+        let router = OpportunityRouter::default().with_metrics(metrics.clone());
+
+        router.reject(RejectReason::GasTooHigh);
+        router.reject(RejectReason::GasTooHigh);
+        router.reject(RejectReason::Expired);
+
+        let count = |reason: RejectReason| metrics.opportunities_rejected.with_label_values(&[reason.as_str()]).get();
+        assert_eq!(count(RejectReason::GasTooHigh), 2);
+        assert_eq!(count(RejectReason::Expired), 1);
+        assert_eq!(count(RejectReason::Slippage), 0);
+
+        // Labels are unique per variant
+        let labels: HashSet<&str> = RejectReason::ALL.iter().map(|r| r.as_str()).collect();
+        assert_eq!(labels.len(), RejectReason::ALL.len());
+    }
+
+    #[test]
+    fn test_dedup_capacity_bounded() {
+        let router = OpportunityRouter::new(RouterConfig { dedup_capacity: 2, ..Default::default() });
+        assert!(router.mark_seen(1));
+        assert!(router.mark_seen(2));
+        assert!(router.mark_seen(3));
+        // 1 was evicted and is accepted again
+        assert!(router.mark_seen(1));
+        assert!(!router.mark_seen(3));
+    }
+
+    fn chain_config(chain_id: u64, block_time_ms: u64) -> ChainConfig {
+        ChainConfig {
+            name: format!("chain-{}", chain_id),
+            chain_id,
+            rpc_url: String::new(),
+            ws_url: String::new(),
+            flashloan_provider: "aave".to_string(),
+            flash_loan_providers: Vec::new(),
+            flash_loan_contract: String::new(),
+            block_time_ms,
+            gas_limit: 1_000_000,
+            priority_fee_gwei: 2,
+            base_gas: 150_000,
+            per_hop_gas: 100_000,
+            l1_data_fee_multiplier: None,
+            opportunity_ttl_ms: None,
+            price_staleness_ms: None,
+            min_confirmations: 1,
+        }
+    }
+
+    #[test]
+    fn test_expiry_follows_chain_ttl() {
+        let chains = [chain_config(1, 12_000), chain_config(42161, 250)];
+        let router = OpportunityRouter::new(RouterConfig::default().with_chains(&chains));
+        let cypher = Cypher::with_default_limits();
+
+        let on = |id: u64, chain: ChainId| Opportunity { chain, ..opportunity(id) };
+
+        // 5s old: within Ethereum's two blocks, far past Arbitrum's
+        assert_eq!(router.check(&on(1, ChainId::Ethereum), &cypher, gwei(20), NOW + 5_000), Ok(()));
+        assert_eq!(
+            router.check(&on(2, ChainId::Arbitrum), &cypher, gwei(20), NOW + 5_000),
+            Err(RejectReason::Expired)
+        );
+        assert_eq!(router.check(&on(3, ChainId::Arbitrum), &cypher, gwei(20), NOW + 400), Ok(()));
+        // Chains without a config keep the global default
+        assert_eq!(
+            router.check(&on(4, ChainId::Base), &cypher, gwei(20), NOW + 5_000),
+            Err(RejectReason::Expired)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Execution Result Sinks
+//!
+//! Durable trade log written after each execution. The JSONL sink appends
+//! one serialized `ExecutionResult` per line, so the log can be replayed
+//! or loaded into analysis tools without a database.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use matrix_types::ExecutionResult;
+use parking_lot::Mutex;
+
+use crate::NeoError;
+
+/// Destination for executed trade results
+pub trait ResultSink: Send + Sync {
+    /// Persist one execution result
+    fn record(&self, result: &ExecutionResult) -> Result<(), NeoError>;
+}
+
+/// Sink that discards results (the default)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSink;
+
+impl ResultSink for NoopSink {
+    fn record(&self, _result: &ExecutionResult) -> Result<(), NeoError> {
+        Ok(())
+    }
+}
+
+/// Appends results to a JSON-lines file
+pub struct JsonlSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlSink {
+    /// Open `path` for appending, creating it if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NeoError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| NeoError::StateError(format!("Open {}: {}", path.display(), e)))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read back every result in a JSONL trade log
+    pub fn replay(path: impl AsRef<Path>) -> Result<Vec<ExecutionResult>, NeoError> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| NeoError::StateError(format!("Open {}: {}", path.display(), e)))?;
+
+        BufReader::new(file)
+            .lines()
+            .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+            .map(|line| {
+                let line = line.map_err(|e| NeoError::StateError(e.to_string()))?;
+                serde_json::from_str(&line).map_err(|e| NeoError::StateError(format!("Bad record: {}", e)))
+            })
+            .collect()
+    }
+}
+
+impl ResultSink for JsonlSink {
+    fn record(&self, result: &ExecutionResult) -> Result<(), NeoError> {
+        let mut line = serde_json::to_vec(result).map_err(|e| NeoError::StateError(e.to_string()))?;
+        line.push(b'\n');
+
+        // One write per record so concurrent writers never interleave lines
+        let mut file = self.file.lock();
+        file.write_all(&line)
+            .and_then(|_| file.flush())
+            .map_err(|e| NeoError::StateError(format!("Write {}: {}", self.path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+
+    fn result(id: u64, success: bool) -> ExecutionResult {
+        ExecutionResult {
+            opportunity_id: id,
+            tx_hash: H256::from_low_u64_be(id),
+            success,
+            actual_profit: U256::exp10(16) * id,
+            gas_used: 250_000,
+            block_number: 18_000_000 + id,
+            timestamp_ms: 1_700_000_000_000 + id,
+        }
+    }
+
+    #[test]
+    fn test_jsonl_sink_appends_and_round_trips() {
+        let path = std::env::temp_dir().join(format!("neo-trades-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let sink = JsonlSink::open(&path).unwrap();
+        sink.record(&result(1, true)).unwrap();
+        sink.record(&result(2, false)).unwrap();
+        drop(sink);
+
+        // Reopening appends rather than truncating
+        let sink = JsonlSink::open(&path).unwrap();
+        sink.record(&result(3, true)).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+
+        let replayed = JsonlSink::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed.len(), 3);
+        for (record, id) in replayed.iter().zip(1..) {
+            let expected = result(id, id != 2);
+            assert_eq!(record.opportunity_id, expected.opportunity_id);
+            assert_eq!(record.tx_hash, expected.tx_hash);
+            assert_eq!(record.success, expected.success);
+            assert_eq!(record.actual_profit, expected.actual_profit);
+            assert_eq!(record.block_number, expected.block_number);
+        }
+    }
+
+    #[test]
+    fn test_noop_sink() {
+        assert!(NoopSink.record(&result(1, true)).is_ok());
+    }
+}
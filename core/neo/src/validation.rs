@@ -0,0 +1,190 @@
+//! Parallel Opportunity Validation
+//!
+//! Bounded worker pool that validates many opportunities concurrently
+//! through SERAPH. When several opportunities land in the same block,
+//! results stream back as they complete so the most profitable valid
+//! one can be chosen without waiting on a serial pass.
+
+use seraph::{SeraphError, ValidationRequest, ValidationResult, Validator};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+
+/// Worker pool configuration
+#[derive(Debug, Clone)]
+pub struct ValidationPoolConfig {
+    /// Maximum validations running at once
+    pub max_concurrency: usize,
+}
+
+impl Default for ValidationPoolConfig {
+    fn default() -> Self {
+        Self { max_concurrency: 4 }
+    }
+}
+
+/// Outcome of one validation, tagged with its request index
+pub type ValidationOutcome = (usize, Result<ValidationResult, SeraphError>);
+
+/// Bounded pool of concurrent SERAPH validations
+pub struct ValidationPool {
+    validator: Arc<dyn Validator>,
+    semaphore: Arc<Semaphore>,
+    config: ValidationPoolConfig,
+}
+
+impl ValidationPool {
+    pub fn new(validator: Arc<dyn Validator>, config: ValidationPoolConfig) -> Self {
+        let permits = config.max_concurrency.max(1);
+        tracing::info!("NEO: Validation pool ready with {} workers", permits);
+        Self {
+            validator,
+            semaphore: Arc::new(Semaphore::new(permits)),
+            config,
+        }
+    }
+
+    /// Validate all requests, streaming results in completion order
+    pub fn validate_all(&self, requests: Vec<ValidationRequest>) -> mpsc::Receiver<ValidationOutcome> {
+        let (tx, rx) = mpsc::channel(requests.len().max(1));
+
+        for (index, request) in requests.into_iter().enumerate() {
+            let validator = Arc::clone(&self.validator);
+            let semaphore = Arc::clone(&self.semaphore);
+            let tx = tx.clone();
+
+            tokio::spawn(async move {
+                let _permit = match semaphore.acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(_) => return, // Pool shut down
+                };
+                let result = validator.validate(&request).await;
+                let _ = tx.send((index, result)).await;
+            });
+        }
+
+        rx
+    }
+
+    /// Validate all requests and return the most profitable valid one
+    pub async fn best(&self, requests: Vec<ValidationRequest>) -> Option<(usize, ValidationResult)> {
+        let mut rx = self.validate_all(requests);
+        let mut best: Option<(usize, ValidationResult)> = None;
+
+        while let Some((index, result)) = rx.recv().await {
+            match result {
+                Ok(result) if result.is_valid => {
+                    let better = best
+                        .as_ref()
+                        .map(|(_, b)| result.net_profit > b.net_profit)
+                        .unwrap_or(true);
+                    if better {
+                        best = Some((index, result));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!("NEO: Validation {} rejected: {}", index, e),
+            }
+        }
+
+        best
+    }
+
+    /// Configured concurrency limit
+    pub fn max_concurrency(&self) -> usize {
+        self.config.max_concurrency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use ethers::types::{Address, Bytes, U256};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Mock validator that tracks peak concurrency
+    struct MockValidator {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Validator for MockValidator {
+        async fn validate(&self, request: &ValidationRequest) -> Result<ValidationResult, SeraphError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(ValidationResult {
+                is_valid: true,
+                simulated_profit: request.expected_profit,
+                gas_used: request.gas_limit,
+                net_profit: request.expected_profit,
+                slippage_bps: 0,
+                state_changes: Vec::new(),
+                warnings: Vec::new(),
+                errors: Vec::new(),
+            })
+        }
+
+        async fn simulate(&self, request: &ValidationRequest) -> Result<U256, SeraphError> {
+            Ok(request.expected_profit)
+        }
+
+        async fn estimate_gas(&self, request: &ValidationRequest) -> Result<u64, SeraphError> {
+            Ok(request.gas_limit)
+        }
+    }
+
+    fn request(profit: u64) -> ValidationRequest {
+        ValidationRequest {
+            from: Address::zero(),
+            to: Address::zero(),
+            value: U256::zero(),
+            data: Bytes::default(),
+            gas_limit: 300_000,
+            gas_price: U256::from(1_000_000_000u64),
+            expected_profit: U256::from(profit),
+            max_slippage_bps: 50,
+            flash_loan_amount: U256::zero(),
+            flash_loan_premium_bps: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_bounded() {
+        let validator = Arc::new(MockValidator {
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let pool = ValidationPool::new(validator.clone(), ValidationPoolConfig { max_concurrency: 3 });
+
+        let mut rx = pool.validate_all((1..=10).map(request).collect());
+        let mut completed = 0;
+        while rx.recv().await.is_some() {
+            completed += 1;
+        }
+
+        assert_eq!(completed, 10);
+        assert!(validator.peak.load(Ordering::SeqCst) <= 3);
+        assert!(validator.peak.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_best_picks_most_profitable() {
+        let validator = Arc::new(MockValidator {
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let pool = ValidationPool::new(validator, ValidationPoolConfig::default());
+
+        let (index, result) = pool
+            .best(vec![request(5), request(50), request(20)])
+            .await
+            .expect("a valid opportunity");
+        assert_eq!(index, 1);
+        assert_eq!(result.net_profit, U256::from(50u64));
+    }
+}
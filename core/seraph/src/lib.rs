@@ -10,10 +10,15 @@
 //! - Check slippage within limits
 //! - Validate all safety conditions
 
+pub mod profit_floor;
+
 use async_trait::async_trait;
 use ethers::types::{Address, U256, Bytes, H256};
+use matrix_types::GasPrice;
 use thiserror::Error;
 
+pub use profit_floor::ProfitFloor;
+
 /// Seraph validation errors
 #[derive(Error, Debug)]
 pub enum SeraphError {
@@ -34,6 +39,9 @@ pub enum SeraphError {
 
     #[error("State access error: {0}")]
     StateAccessError(String),
+
+    #[error("Arithmetic overflow: {0}")]
+    Overflow(String),
 }
 
 /// Transaction to validate
@@ -47,6 +55,8 @@ pub struct ValidationRequest {
     pub gas_price: U256,
     pub expected_profit: U256,
     pub max_slippage_bps: u64,
+    pub flash_loan_amount: U256,
+    pub flash_loan_premium_bps: u64, // e.g. 9 for Aave (0.09%), 0 for Balancer
 }
 
 /// Validation result
@@ -76,10 +86,12 @@ pub struct StateChange {
 pub struct SafetyConfig {
     pub min_profit_wei: U256,
     pub max_slippage_bps: u64,
-    pub max_gas_price: U256,
+    pub max_gas_price: GasPrice,
     pub max_position_size: U256,
     pub allowed_tokens: Vec<Address>,
     pub blocked_addresses: Vec<Address>,
+    /// Scale the minimum profit with gas cost (None = fixed `min_profit_wei`)
+    pub profit_floor: Option<ProfitFloor>,
 }
 
 impl Default for SafetyConfig {
@@ -87,14 +99,55 @@ impl Default for SafetyConfig {
         Self {
             min_profit_wei: U256::from(1_000_000_000_000_000u64), // 0.001 ETH
             max_slippage_bps: 100,                                 // 1%
-            max_gas_price: U256::from(500_000_000_000u64),        // 500 gwei
+            max_gas_price: GasPrice::from_gwei(500),
             max_position_size: U256::from(100u64) * U256::exp10(18), // 100 ETH
             allowed_tokens: Vec::new(),
             blocked_addresses: Vec::new(),
+            profit_floor: None,
         }
     }
 }
 
+/// Token allowance the executor needs before a trade
+#[derive(Debug, Clone)]
+pub struct AllowanceRequirement {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub amount: U256,
+}
+
+/// Flash loan repayment the executor must be able to cover
+#[derive(Debug, Clone)]
+pub struct RepaymentRequirement {
+    pub token: Address,
+    pub borrower: Address,
+    pub amount: U256,
+    pub premium_bps: u64,
+}
+
+/// Read-only token state for pre-trade checks
+///
+/// Implementations decide which state they read: an RPC node at the
+/// latest block, or a simulated post-trade state once one is available.
+#[async_trait]
+pub trait TokenStateReader: Send + Sync {
+    /// ERC20 `allowance(owner, spender)`
+    async fn allowance(&self, token: Address, owner: Address, spender: Address) -> Result<U256, SeraphError>;
+
+    /// ERC20 `balanceOf(owner)`
+    async fn balance_of(&self, token: Address, owner: Address) -> Result<U256, SeraphError>;
+}
+
+/// Flash loan premium owed on repayment: `amount * premium_bps / 10000`
+///
+/// None if `amount * premium_bps` overflows.
+pub fn flash_loan_premium(amount: U256, premium_bps: u64) -> Option<U256> {
+    amount
+        .checked_mul(U256::from(premium_bps))
+        .map(|scaled| scaled / U256::from(10000u64))
+}
+
 /// Transaction validator trait
 #[async_trait]
 pub trait Validator: Send + Sync {
@@ -127,10 +180,10 @@ impl Seraph {
     /// Perform pre-flight safety checks
     pub fn pre_flight_check(&self, request: &ValidationRequest) -> Result<(), SeraphError> {
         // Check gas price
-        if request.gas_price > self.config.max_gas_price {
+        if GasPrice::from_wei(request.gas_price) > self.config.max_gas_price {
             return Err(SeraphError::ValidationFailed(format!(
                 "Gas price {} exceeds max {}",
-                request.gas_price, self.config.max_gas_price
+                GasPrice::from_wei(request.gas_price), self.config.max_gas_price
             )));
         }
 
@@ -152,19 +205,99 @@ impl Seraph {
         Ok(())
     }
 
+    /// Verify every required token approval is in place
+    pub async fn check_allowances(
+        &self,
+        state: &dyn TokenStateReader,
+        requirements: &[AllowanceRequirement],
+    ) -> Result<(), SeraphError> {
+        for req in requirements {
+            let allowance = state.allowance(req.token, req.owner, req.spender).await?;
+            if allowance < req.amount {
+                return Err(SeraphError::ValidationFailed(format!(
+                    "Missing approval: token {:?} owner {:?} spender {:?} (allowance {}, required {})",
+                    req.token, req.owner, req.spender, allowance, req.amount
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the borrower can repay the flash loan plus premium
+    pub async fn check_repayment(
+        &self,
+        state: &dyn TokenStateReader,
+        repayment: &RepaymentRequirement,
+    ) -> Result<(), SeraphError> {
+        let premium = flash_loan_premium(repayment.amount, repayment.premium_bps)
+            .ok_or_else(|| SeraphError::Overflow(format!("Flash loan premium on {}", repayment.amount)))?;
+        let owed = repayment.amount.checked_add(premium).ok_or_else(|| {
+            SeraphError::Overflow(format!("Flash loan repayment of {} plus premium {}", repayment.amount, premium))
+        })?;
+        let balance = state.balance_of(repayment.token, repayment.borrower).await?;
+
+        if balance < owed {
+            return Err(SeraphError::ValidationFailed(format!(
+                "Flash loan repayment would fail: borrower {:?} holds {} of token {:?}, owes {}",
+                repayment.borrower, balance, repayment.token, owed
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Run allowance and repayment checks against simulated state
+    pub async fn pre_trade_check(
+        &self,
+        state: &dyn TokenStateReader,
+        allowances: &[AllowanceRequirement],
+        repayment: &RepaymentRequirement,
+    ) -> Result<(), SeraphError> {
+        self.check_allowances(state, allowances).await?;
+        self.check_repayment(state, repayment).await
+    }
+
     /// Validate profit meets minimum threshold
     pub fn validate_profit(&self, profit: U256, gas_cost: U256) -> Result<U256, SeraphError> {
+        self.validate_profit_with_premium(profit, gas_cost, U256::zero(), 0)
+    }
+
+    /// Minimum net profit for a trade costing `gas_cost`
+    ///
+    /// The gas-relative floor when configured, else `min_profit_wei`.
+    pub fn min_profit_for(&self, gas_cost: U256) -> U256 {
+        match &self.config.profit_floor {
+            Some(floor) => floor.floor(gas_cost),
+            None => self.config.min_profit_wei,
+        }
+    }
+
+    /// Validate profit after gas and the flash loan premium
+    pub fn validate_profit_with_premium(
+        &self,
+        profit: U256,
+        gas_cost: U256,
+        loan_amount: U256,
+        premium_bps: u64,
+    ) -> Result<U256, SeraphError> {
+        let min_profit = self.min_profit_for(gas_cost);
+        let gas_cost = flash_loan_premium(loan_amount, premium_bps)
+            .and_then(|premium| gas_cost.checked_add(premium))
+            .ok_or_else(|| {
+                SeraphError::Overflow(format!("Gas cost {} plus premium on {}", gas_cost, loan_amount))
+            })?;
         if profit <= gas_cost {
             return Err(SeraphError::InsufficientProfit {
-                expected: self.config.min_profit_wei,
+                expected: min_profit,
                 actual: U256::zero(),
             });
         }
 
         let net_profit = profit - gas_cost;
-        if net_profit < self.config.min_profit_wei {
+        if net_profit < min_profit {
             return Err(SeraphError::InsufficientProfit {
-                expected: self.config.min_profit_wei,
+                expected: min_profit,
                 actual: net_profit,
             });
         }
@@ -173,13 +306,27 @@ impl Seraph {
     }
 
     /// Validate slippage within limits
+    ///
+    /// A zero `expected` amount has no defined slippage and is rejected.
     pub fn validate_slippage(&self, expected: U256, actual: U256) -> Result<u64, SeraphError> {
-        if actual >= expected {
-            return Ok(0);
+        if expected.is_zero() {
+            return Err(SeraphError::ValidationFailed(
+                "Expected output is zero, slippage undefined".to_string(),
+            ));
         }
 
-        let diff = expected - actual;
-        let slippage_bps = (diff * U256::from(10000u64) / expected).as_u64();
+        let diff = match expected.checked_sub(actual) {
+            Some(diff) if !diff.is_zero() => diff,
+            _ => return Ok(0),
+        };
+
+        // diff < expected, so the ratio is below 10000 bps; only the scaled
+        // numerator can overflow, for amounts near U256::MAX
+        let slippage_bps = match diff.checked_mul(U256::from(10000u64)) {
+            Some(scaled) => scaled / expected,
+            None => diff / (expected / U256::from(10000u64)),
+        }
+        .as_u64();
 
         if slippage_bps > self.config.max_slippage_bps {
             return Err(SeraphError::SlippageExceeded {
@@ -206,6 +353,29 @@ impl Seraph {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+
+    /// Mock token state keyed by (token, owner[, spender])
+    #[derive(Default)]
+    struct MockState {
+        allowances: HashMap<(Address, Address, Address), U256>,
+        balances: HashMap<(Address, Address), U256>,
+    }
+
+    #[async_trait]
+    impl TokenStateReader for MockState {
+        async fn allowance(&self, token: Address, owner: Address, spender: Address) -> Result<U256, SeraphError> {
+            Ok(self.allowances.get(&(token, owner, spender)).copied().unwrap_or_default())
+        }
+
+        async fn balance_of(&self, token: Address, owner: Address) -> Result<U256, SeraphError> {
+            Ok(self.balances.get(&(token, owner)).copied().unwrap_or_default())
+        }
+    }
+
+    fn addr(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
 
     #[test]
     fn test_seraph_creation() {
@@ -229,6 +399,114 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_flash_loan_premium() {
+        let amount = U256::from(100u64) * U256::exp10(18); // 100 ETH
+        assert_eq!(flash_loan_premium(amount, 0), Some(U256::zero()));
+        assert_eq!(flash_loan_premium(amount, 9), Some(U256::from(90_000_000_000_000_000u64))); // 0.09 ETH
+        assert_eq!(flash_loan_premium(U256::MAX, 9), None);
+    }
+
+    #[test]
+    fn test_profit_validation_with_premium() {
+        let seraph = Seraph::with_default_config();
+
+        let loan = U256::from(100u64) * U256::exp10(18);    // 100 ETH
+        let profit = U256::from(90_000_000_000_000_000u64); // 0.09 ETH
+        let gas = U256::from(5_000_000_000_000_000u64);     // 0.005 ETH
+
+        // Profitable with a 0% premium (Balancer)
+        let result = seraph.validate_profit_with_premium(profit, gas, loan, 0);
+        assert_eq!(result.unwrap(), U256::from(85_000_000_000_000_000u64));
+
+        // Unprofitable with a 0.09% premium (Aave)
+        let result = seraph.validate_profit_with_premium(profit, gas, loan, 9);
+        assert!(matches!(result, Err(SeraphError::InsufficientProfit { .. })));
+
+        // Overflowing costs are an error, not a wrapped-around pass
+        let result = seraph.validate_profit_with_premium(profit, U256::MAX, loan, 9);
+        assert!(matches!(result, Err(SeraphError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_gas_relative_floor_in_profit_validation() {
+        let seraph = Seraph::new(SafetyConfig {
+            profit_floor: Some(ProfitFloor::default()),
+            ..Default::default()
+        });
+        let profit = U256::from(20_000_000_000_000_000u64); // 0.02 ETH
+        let gwei = U256::exp10(9);
+
+        // 300k gas at 20 gwei (0.006 ETH): floor 0.012 ETH, net 0.014 passes
+        let cheap_gas = gwei * U256::from(20u64) * U256::from(300_000u64);
+        assert_eq!(seraph.min_profit_for(cheap_gas), U256::from(12_000_000_000_000_000u64));
+        assert!(seraph.validate_profit(profit, cheap_gas).is_ok());
+
+        // Base fee rises to 30 gwei (0.009 ETH): floor 0.018 ETH, net 0.011 fails
+        let busy_gas = gwei * U256::from(30u64) * U256::from(300_000u64);
+        match seraph.validate_profit(profit, busy_gas) {
+            Err(SeraphError::InsufficientProfit { expected, actual }) => {
+                assert_eq!(expected, U256::from(18_000_000_000_000_000u64));
+                assert_eq!(actual, U256::from(11_000_000_000_000_000u64));
+            }
+            other => panic!("expected insufficient profit, got {:?}", other),
+        }
+
+        // Without a floor the fixed 0.001 ETH minimum applies
+        assert_eq!(Seraph::with_default_config().min_profit_for(busy_gas), U256::exp10(15));
+    }
+
+    #[tokio::test]
+    async fn test_missing_allowance() {
+        let seraph = Seraph::with_default_config();
+        let (weth, usdc, executor, router) = (addr(1), addr(2), addr(10), addr(20));
+        let amount = U256::exp10(18);
+
+        let mut state = MockState::default();
+        state.allowances.insert((weth, executor, router), amount);
+
+        let requirements = vec![
+            AllowanceRequirement { token: weth, owner: executor, spender: router, amount },
+            AllowanceRequirement { token: usdc, owner: executor, spender: router, amount },
+        ];
+
+        let err = seraph.check_allowances(&state, &requirements).await.unwrap_err();
+        match err {
+            SeraphError::ValidationFailed(msg) => {
+                assert!(msg.contains("Missing approval"));
+                assert!(msg.contains(&format!("{:?}", usdc)));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // Granting the approval clears the check
+        state.allowances.insert((usdc, executor, router), amount);
+        assert!(seraph.check_allowances(&state, &requirements).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_repayment_includes_premium() {
+        let seraph = Seraph::with_default_config();
+        let (weth, executor) = (addr(1), addr(10));
+        let amount = U256::from(100u64) * U256::exp10(18);
+
+        // Holds the principal but not the 0.09% premium
+        let mut state = MockState::default();
+        state.balances.insert((weth, executor), amount);
+
+        let repayment = RepaymentRequirement { token: weth, borrower: executor, amount, premium_bps: 9 };
+        let err = seraph.pre_trade_check(&state, &[], &repayment).await.unwrap_err();
+        assert!(matches!(err, SeraphError::ValidationFailed(ref msg) if msg.contains("repayment")));
+
+        let free = RepaymentRequirement { premium_bps: 0, ..repayment.clone() };
+        assert!(seraph.pre_trade_check(&state, &[], &free).await.is_ok());
+
+        // Principal plus premium past U256::MAX is an error, not a wrap
+        let huge = RepaymentRequirement { amount: U256::MAX - 1, premium_bps: 1, ..repayment };
+        let err = seraph.check_repayment(&state, &huge).await.unwrap_err();
+        assert!(matches!(err, SeraphError::Overflow(_)));
+    }
+
     #[test]
     fn test_slippage_validation() {
         let seraph = Seraph::with_default_config();
@@ -250,4 +528,30 @@ mod tests {
         let result = seraph.validate_slippage(expected, actual_very_low);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_slippage_degenerate_amounts() {
+        let seraph = Seraph::with_default_config();
+
+        // Zero expected output: rejected instead of dividing by zero
+        for actual in [0u64, 1, 1000] {
+            let result = seraph.validate_slippage(U256::zero(), U256::from(actual));
+            assert!(matches!(result, Err(SeraphError::ValidationFailed(_))));
+        }
+
+        // Expected far below the 10000 multiplier: still exact bps
+        assert_eq!(seraph.validate_slippage(U256::from(1u64), U256::one()).unwrap(), 0);
+        assert!(matches!(
+            seraph.validate_slippage(U256::from(3u64), U256::from(1u64)),
+            Err(SeraphError::SlippageExceeded { actual_bps: 6666, .. })
+        ));
+        assert!(matches!(
+            seraph.validate_slippage(U256::one(), U256::zero()),
+            Err(SeraphError::SlippageExceeded { actual_bps: 10000, .. })
+        ));
+
+        // Amounts near U256::MAX don't overflow the scaled difference
+        let result = seraph.validate_slippage(U256::MAX, U256::MAX - U256::MAX / 1000);
+        assert_eq!(result.unwrap(), 10);
+    }
 }
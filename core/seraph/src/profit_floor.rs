@@ -0,0 +1,85 @@
+//! Gas-relative profit floor
+//!
+//! A fixed minimum profit is too strict when gas is cheap and too loose
+//! when it spikes. The floor here scales with the trade's gas cost, which
+//! callers price at the current base fee, so it tracks base fee moves
+//! without extra state. Fixed bounds keep it sane at the extremes.
+
+use ethers::types::U256;
+
+/// Required net profit as a multiple of gas cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfitFloor {
+    /// Multiple of gas cost in basis points (20000 = 2x)
+    pub gas_multiple_bps: u64,
+    /// Never require less than this
+    pub min_floor_wei: U256,
+    /// Never require more than this (None = uncapped)
+    pub max_floor_wei: Option<U256>,
+}
+
+impl Default for ProfitFloor {
+    fn default() -> Self {
+        Self {
+            gas_multiple_bps: 20_000,                                // 2x gas
+            min_floor_wei: U256::from(100_000_000_000_000u64),       // 0.0001 ETH
+            max_floor_wei: None,
+        }
+    }
+}
+
+impl ProfitFloor {
+    /// Minimum net profit for a trade costing `gas_cost_wei`
+    pub fn floor(&self, gas_cost_wei: U256) -> U256 {
+        let scaled = gas_cost_wei.saturating_mul(U256::from(self.gas_multiple_bps)) / U256::from(10_000u64);
+        let floor = scaled.max(self.min_floor_wei);
+        match self.max_floor_wei {
+            Some(max) => floor.min(max.max(self.min_floor_wei)),
+            None => floor,
+        }
+    }
+
+    /// Floor for `gas_units` priced at `base_fee + priority_fee` per gas
+    pub fn floor_at(&self, gas_units: u64, base_fee: U256, priority_fee: U256) -> U256 {
+        let gas_price = base_fee.saturating_add(priority_fee);
+        self.floor(gas_price.saturating_mul(U256::from(gas_units)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gwei(n: u64) -> U256 {
+        U256::from(n) * U256::exp10(9)
+    }
+
+    #[test]
+    fn test_floor_rises_with_gas_price() {
+        let floor = ProfitFloor::default();
+        let gas_units = 300_000;
+
+        // 300k gas at 10 / 50 / 200 gwei: 0.003 / 0.015 / 0.06 ETH, doubled
+        let cheap = floor.floor_at(gas_units, gwei(9), gwei(1));
+        let busy = floor.floor_at(gas_units, gwei(48), gwei(2));
+        let spike = floor.floor_at(gas_units, gwei(195), gwei(5));
+        assert_eq!(cheap, U256::exp10(15) * U256::from(6u64));
+        assert_eq!(busy, U256::exp10(15) * U256::from(30u64));
+        assert_eq!(spike, U256::exp10(15) * U256::from(120u64));
+        assert!(cheap < busy && busy < spike);
+    }
+
+    #[test]
+    fn test_floor_bounds() {
+        let floor = ProfitFloor {
+            max_floor_wei: Some(U256::exp10(17)), // 0.1 ETH
+            ..Default::default()
+        };
+
+        // Near-free gas still requires the minimum
+        assert_eq!(floor.floor(U256::from(1_000u64)), floor.min_floor_wei);
+        // Extreme spikes are capped
+        assert_eq!(floor.floor_at(1_000_000, gwei(5_000), U256::zero()), U256::exp10(17));
+        assert_eq!(floor.floor(U256::MAX), U256::exp10(17));
+    }
+}
@@ -6,11 +6,16 @@
 //! - Environment variables
 //! - Runtime overrides
 
+use matrix_types::{ChainId, FlashLoanProvider, GasPrice};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
+mod summary;
+
+pub use summary::{ConfigChange, REDACTED};
+
 /// Configuration errors
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -37,11 +42,153 @@ pub struct ChainConfig {
     pub chain_id: u64,
     pub rpc_url: String,
     pub ws_url: String,
+    /// Single provider, used when `flash_loan_providers` is empty
     pub flashloan_provider: String,
+    /// Providers allowed on this chain
+    #[serde(default)]
+    pub flash_loan_providers: Vec<FlashLoanProvider>,
     pub flash_loan_contract: String,
     pub block_time_ms: u64,
     pub gas_limit: u64,
     pub priority_fee_gwei: u64,
+    /// Fixed gas per arbitrage (flash loan, callback, repayment)
+    #[serde(default = "default_base_gas")]
+    pub base_gas: u64,
+    /// Additional gas per swap hop
+    #[serde(default = "default_per_hop_gas")]
+    pub per_hop_gas: u64,
+    /// L2 only: multiplier applied to the L1 data fee (None on L1)
+    #[serde(default)]
+    pub l1_data_fee_multiplier: Option<f64>,
+    /// Opportunity time-to-live override (None = from block time)
+    #[serde(default)]
+    pub opportunity_ttl_ms: Option<u64>,
+    /// Price staleness threshold override (None = from block time)
+    #[serde(default)]
+    pub price_staleness_ms: Option<u64>,
+    /// Blocks (including the inclusion block) before realized profit is
+    /// booked; raise on reorg-prone chains
+    #[serde(default = "default_min_confirmations")]
+    pub min_confirmations: u64,
+}
+
+/// Confirmations before realized profit is booked by default: the
+/// inclusion block alone
+pub const DEFAULT_MIN_CONFIRMATIONS: u64 = 1;
+
+/// Blocks an opportunity stays valid for by default
+pub const OPPORTUNITY_TTL_BLOCKS: u64 = 2;
+
+/// Blocks a price may lag before it is stale by default (tolerates one
+/// missed block from a feed)
+pub const PRICE_STALENESS_BLOCKS: u64 = 3;
+
+fn default_base_gas() -> u64 {
+    150_000
+}
+
+fn default_per_hop_gas() -> u64 {
+    100_000
+}
+
+fn default_min_confirmations() -> u64 {
+    DEFAULT_MIN_CONFIRMATIONS
+}
+
+impl ChainConfig {
+    /// Estimated gas units for an arbitrage with `hops` swaps
+    pub fn estimate_gas(&self, hops: u64) -> u64 {
+        self.base_gas.saturating_add(hops.saturating_mul(self.per_hop_gas))
+    }
+
+    /// Estimated total cost in wei: execution gas plus scaled L1 data fee
+    ///
+    /// `l1_data_fee_wei` is ignored on L1 chains.
+    pub fn estimate_gas_cost_wei(&self, hops: u64, gas_price_wei: u128, l1_data_fee_wei: u128) -> u128 {
+        let execution = (self.estimate_gas(hops) as u128).saturating_mul(gas_price_wei);
+        let data_fee = match self.l1_data_fee_multiplier {
+            Some(multiplier) => (l1_data_fee_wei as f64 * multiplier) as u128,
+            None => 0,
+        };
+        execution.saturating_add(data_fee)
+    }
+
+    /// Whether this chain pays an L1 data fee
+    pub fn is_l2(&self) -> bool {
+        self.l1_data_fee_multiplier.is_some()
+    }
+
+    /// Known chain for `chain_id`, if any
+    pub fn chain(&self) -> Option<ChainId> {
+        ChainId::try_from(self.chain_id).ok()
+    }
+
+    /// How long an opportunity stays actionable
+    ///
+    /// `OPPORTUNITY_TTL_BLOCKS` block times unless overridden.
+    pub fn opportunity_ttl_ms(&self) -> u64 {
+        self.opportunity_ttl_ms
+            .unwrap_or_else(|| self.block_time_ms.saturating_mul(OPPORTUNITY_TTL_BLOCKS))
+    }
+
+    /// Age after which a pool price is stale
+    ///
+    /// `PRICE_STALENESS_BLOCKS` block times unless overridden.
+    pub fn price_staleness_ms(&self) -> u64 {
+        self.price_staleness_ms
+            .unwrap_or_else(|| self.block_time_ms.saturating_mul(PRICE_STALENESS_BLOCKS))
+    }
+
+    /// Flash loan providers allowed on this chain
+    ///
+    /// Falls back to the single `flashloan_provider` when no list is set.
+    pub fn flash_loan_providers(&self) -> Result<Vec<FlashLoanProvider>, ConfigError> {
+        if !self.flash_loan_providers.is_empty() {
+            return Ok(self.flash_loan_providers.clone());
+        }
+        self.flashloan_provider
+            .parse()
+            .map(|provider| vec![provider])
+            .map_err(|e| ConfigError::InvalidValue(format!("{}: {}", self.name, e)))
+    }
+
+    /// Validate gas, timing and flash loan settings
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.block_time_ms == 0 {
+            return Err(ConfigError::InvalidValue(format!("{}: block_time_ms must be positive", self.name)));
+        }
+
+        if self.base_gas == 0 {
+            return Err(ConfigError::InvalidValue(format!("{}: base_gas must be positive", self.name)));
+        }
+
+        if self.per_hop_gas == 0 {
+            return Err(ConfigError::InvalidValue(format!("{}: per_hop_gas must be positive", self.name)));
+        }
+
+        if self.min_confirmations == 0 {
+            return Err(ConfigError::InvalidValue(format!(
+                "{}: min_confirmations must be at least 1",
+                self.name
+            )));
+        }
+
+        if let Some(multiplier) = self.l1_data_fee_multiplier {
+            if !multiplier.is_finite() || multiplier <= 0.0 {
+                return Err(ConfigError::InvalidValue(format!(
+                    "{}: l1_data_fee_multiplier must be positive",
+                    self.name
+                )));
+            }
+        }
+
+        let providers = self.flash_loan_providers()?;
+        if providers.contains(&FlashLoanProvider::Dydx) && self.chain_id != 1 {
+            return Err(ConfigError::InvalidValue(format!("{}: dydx is only available on Ethereum", self.name)));
+        }
+
+        Ok(())
+    }
 }
 
 /// DEX configuration
@@ -50,10 +197,39 @@ pub struct DexConfig {
     pub name: String,
     pub router_address: String,
     pub factory_address: String,
+    /// Fee for pools that don't name a tier
     pub fee_bps: u64,
+    /// Fee tiers pools may use (V3-style DEXs, e.g. [5, 30, 100]); empty for V2
+    #[serde(default)]
+    pub fee_tiers: Vec<u64>,
     pub supported_chains: Vec<u64>,
 }
 
+impl DexConfig {
+    /// Fee for a pool on fee tier `tier`, or the DEX default for `None`
+    pub fn pool_fee_bps(&self, tier: Option<u64>) -> Result<u64, ConfigError> {
+        match tier {
+            None => Ok(self.fee_bps),
+            Some(tier) if self.fee_tiers.contains(&tier) => Ok(tier),
+            Some(tier) => Err(ConfigError::InvalidValue(format!(
+                "{}: no {} bps fee tier (tiers: {:?})",
+                self.name, tier, self.fee_tiers
+            ))),
+        }
+    }
+
+    /// Check fees are below 100%
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(fee) = std::iter::once(&self.fee_bps)
+            .chain(&self.fee_tiers)
+            .find(|fee| **fee >= 10_000)
+        {
+            return Err(ConfigError::InvalidValue(format!("{}: fee of {} bps is not below 100%", self.name, fee)));
+        }
+        Ok(())
+    }
+}
+
 /// RPC provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RpcConfig {
@@ -76,7 +252,9 @@ pub struct RiskConfig {
     pub max_daily_loss_eth: f64,
     pub min_profit_eth: f64,
     pub max_slippage_bps: u64,
-    pub max_gas_price_gwei: u64,
+    /// Written as `max_gas_price_gwei` in config files
+    #[serde(rename = "max_gas_price_gwei")]
+    pub max_gas_price: GasPrice,
     pub failure_cooldown_ms: u64,
 }
 
@@ -90,7 +268,7 @@ impl Default for RiskConfig {
             max_daily_loss_eth: 20.0,
             min_profit_eth: 0.001,
             max_slippage_bps: 100,
-            max_gas_price_gwei: 300,
+            max_gas_price: GasPrice::from_gwei(300),
             failure_cooldown_ms: 5000,
         }
     }
@@ -211,8 +389,9 @@ impl MatrixConfig {
         }
 
         if let Ok(val) = std::env::var("MATRIX_MAX_GAS_PRICE_GWEI") {
-            self.risk.max_gas_price_gwei = val
+            self.risk.max_gas_price = val
                 .parse()
+                .map(GasPrice::from_gwei_f64)
                 .map_err(|_| ConfigError::InvalidValue("MATRIX_MAX_GAS_PRICE_GWEI".to_string()))?;
         }
 
@@ -246,6 +425,14 @@ impl MatrixConfig {
             return Err(ConfigError::MissingRequired("No chains configured".to_string()));
         }
 
+        for chain in self.chains.values() {
+            chain.validate()?;
+        }
+
+        for dex in self.dexes.values() {
+            dex.validate()?;
+        }
+
         // Validate RPC providers
         if self.rpc_providers.is_empty() {
             return Err(ConfigError::MissingRequired("No RPC providers configured".to_string()));
@@ -337,10 +524,211 @@ mod tests {
         assert_eq!(config.environment, "staging");
     }
 
+    fn chain(name: &str, l1_data_fee_multiplier: Option<f64>) -> ChainConfig {
+        ChainConfig {
+            name: name.to_string(),
+            chain_id: 1,
+            rpc_url: String::new(),
+            ws_url: String::new(),
+            flashloan_provider: "aave".to_string(),
+            flash_loan_providers: Vec::new(),
+            flash_loan_contract: String::new(),
+            block_time_ms: 12_000,
+            gas_limit: 1_000_000,
+            priority_fee_gwei: 2,
+            base_gas: 150_000,
+            per_hop_gas: 100_000,
+            l1_data_fee_multiplier,
+            opportunity_ttl_ms: None,
+            price_staleness_ms: None,
+            min_confirmations: 1,
+        }
+    }
+
+    #[test]
+    fn test_gas_estimate_by_hops() {
+        let ethereum = chain("ethereum", None);
+        let arbitrum = ChainConfig {
+            base_gas: 400_000,
+            per_hop_gas: 250_000,
+            ..chain("arbitrum", Some(1.5))
+        };
+
+        assert_eq!(ethereum.estimate_gas(2), 350_000);
+        assert_eq!(ethereum.estimate_gas(3), 450_000);
+        assert_eq!(arbitrum.estimate_gas(2), 900_000);
+        assert_eq!(arbitrum.estimate_gas(3), 1_150_000);
+
+        // L1 ignores the data fee; L2 scales it
+        let gwei = 1_000_000_000u128;
+        assert!(!ethereum.is_l2());
+        assert_eq!(ethereum.estimate_gas_cost_wei(2, 20 * gwei, 1_000), 350_000 * 20 * gwei);
+        assert!(arbitrum.is_l2());
+        assert_eq!(
+            arbitrum.estimate_gas_cost_wei(3, gwei / 10, 1_000_000),
+            1_150_000 * (gwei / 10) + 1_500_000
+        );
+    }
+
+    #[test]
+    fn test_chain_gas_validation() {
+        assert!(chain("ethereum", None).validate().is_ok());
+        assert!(chain("base", Some(1.0)).validate().is_ok());
+        assert!(ChainConfig { base_gas: 0, ..chain("ethereum", None) }.validate().is_err());
+        assert!(ChainConfig { per_hop_gas: 0, ..chain("ethereum", None) }.validate().is_err());
+        assert!(chain("base", Some(0.0)).validate().is_err());
+        assert!(chain("base", Some(f64::NAN)).validate().is_err());
+        assert!(ChainConfig { min_confirmations: 0, ..chain("ethereum", None) }.validate().is_err());
+
+        let config = ConfigBuilder::new()
+            .add_chain("ethereum", ChainConfig { per_hop_gas: 0, ..chain("ethereum", None) })
+            .add_rpc(RpcConfig {
+                name: "primary".to_string(),
+                http_url: String::new(),
+                ws_url: String::new(),
+                api_key: None,
+                priority: 0,
+                max_retries: 3,
+                timeout_ms: 1_000,
+            })
+            .build();
+        assert!(matches!(config.validate(), Err(ConfigError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_ttl_defaults_follow_block_time() {
+        let ethereum = chain("ethereum", None);
+        let arbitrum = ChainConfig {
+            block_time_ms: 250,
+            ..chain("arbitrum", Some(1.5))
+        };
+
+        assert_eq!(ethereum.opportunity_ttl_ms(), 24_000);
+        assert_eq!(ethereum.price_staleness_ms(), 36_000);
+        assert_eq!(arbitrum.opportunity_ttl_ms(), 500);
+        assert_eq!(arbitrum.price_staleness_ms(), 750);
+        assert!(arbitrum.opportunity_ttl_ms() * 10 < ethereum.opportunity_ttl_ms());
+        assert_eq!(ethereum.chain(), Some(ChainId::Ethereum));
+        assert_eq!(ChainConfig { chain_id: 42161, ..arbitrum.clone() }.chain(), Some(ChainId::Arbitrum));
+        assert_eq!(ChainConfig { chain_id: 7, ..arbitrum.clone() }.chain(), None);
+
+        // Explicit values win over block time
+        let tuned = ChainConfig {
+            opportunity_ttl_ms: Some(1_000),
+            price_staleness_ms: Some(2_000),
+            ..arbitrum.clone()
+        };
+        assert_eq!(tuned.opportunity_ttl_ms(), 1_000);
+        assert_eq!(tuned.price_staleness_ms(), 2_000);
+
+        assert!(ChainConfig { block_time_ms: 0, ..arbitrum }.validate().is_err());
+    }
+
+    #[test]
+    fn test_gas_fields_default_when_omitted() {
+        let chain: ChainConfig = toml::from_str(
+            r#"
+            name = "ethereum"
+            chain_id = 1
+            rpc_url = ""
+            ws_url = ""
+            flashloan_provider = "aave"
+            flash_loan_contract = ""
+            block_time_ms = 12000
+            gas_limit = 1000000
+            priority_fee_gwei = 2
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(chain.base_gas, 150_000);
+        assert_eq!(chain.per_hop_gas, 100_000);
+        assert!(chain.l1_data_fee_multiplier.is_none());
+        assert!(chain.opportunity_ttl_ms.is_none());
+        assert_eq!(chain.opportunity_ttl_ms(), 24_000);
+        assert_eq!(chain.min_confirmations, 1);
+        assert_eq!(chain.flash_loan_providers().unwrap(), vec![FlashLoanProvider::Aave]);
+    }
+
+    #[test]
+    fn test_flash_loan_provider_list() {
+        let arbitrum: ChainConfig = toml::from_str(
+            r#"
+            name = "arbitrum"
+            chain_id = 42161
+            rpc_url = ""
+            ws_url = ""
+            flashloan_provider = "aave"
+            flash_loan_providers = ["balancer", "aave"]
+            flash_loan_contract = ""
+            block_time_ms = 250
+            gas_limit = 1000000
+            priority_fee_gwei = 0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            arbitrum.flash_loan_providers().unwrap(),
+            vec![FlashLoanProvider::Balancer, FlashLoanProvider::Aave]
+        );
+        assert!(arbitrum.validate().is_ok());
+
+        // dYdX only exists on mainnet; unknown names are rejected
+        let dydx = ChainConfig { flash_loan_providers: vec![FlashLoanProvider::Dydx], ..arbitrum };
+        assert!(dydx.validate().is_err());
+        assert!(ChainConfig { chain_id: 1, ..dydx }.validate().is_ok());
+        let unknown = ChainConfig { flashloan_provider: "uniswap".to_string(), ..chain("ethereum", None) };
+        assert!(unknown.validate().is_err());
+    }
+
     #[test]
     fn test_risk_defaults() {
         let risk = RiskConfig::default();
         assert_eq!(risk.max_slippage_bps, 100);
         assert_eq!(risk.max_concurrent_positions, 5);
+        assert_eq!(risk.max_gas_price, GasPrice::from_gwei(300));
+    }
+
+    #[test]
+    fn test_max_gas_price_read_as_gwei() {
+        let risk: RiskConfig = toml::from_str(
+            r#"
+            max_position_size_eth = 50.0
+            max_total_exposure_eth = 200.0
+            max_concurrent_positions = 5
+            max_hourly_loss_eth = 5.0
+            max_daily_loss_eth = 20.0
+            min_profit_eth = 0.001
+            max_slippage_bps = 100
+            max_gas_price_gwei = 3
+            failure_cooldown_ms = 5000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(risk.max_gas_price, GasPrice::from_gwei(3));
+    }
+
+    #[test]
+    fn test_dex_fee_tiers() {
+        let uniswap: DexConfig = toml::from_str(
+            r#"
+            name = "uniswap_v3"
+            router_address = "0xE592427A0AEce92De3Edee1F18E0157C05861564"
+            factory_address = "0x1F98431c8aD98523631AE4a59f267346ea31F984"
+            fee_bps = 30
+            fee_tiers = [5, 30, 100]
+            supported_chains = [1, 42161]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(uniswap.pool_fee_bps(Some(5)).unwrap(), 5);
+        assert_eq!(uniswap.pool_fee_bps(None).unwrap(), 30);
+        assert!(uniswap.pool_fee_bps(Some(1)).is_err());
+        assert!(uniswap.validate().is_ok());
+
+        // V2-style DEXs omit the tiers and only have the default fee
+        let sushi = DexConfig { fee_tiers: Vec::new(), ..uniswap.clone() };
+        assert!(sushi.pool_fee_bps(Some(5)).is_err());
+        assert!(DexConfig { fee_tiers: vec![10_000], ..uniswap }.validate().is_err());
     }
 }
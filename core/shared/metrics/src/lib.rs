@@ -3,11 +3,45 @@
 //! Provides Prometheus-compatible metrics collection for all agents
 //! and system components.
 
+use matrix_types::SignedWei;
+use prometheus::core::Collector;
 use prometheus::{
     Counter, CounterVec, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec,
     IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
 };
-use std::sync::OnceLock;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use thiserror::Error;
+
+/// Metrics setup errors
+#[derive(Error, Debug)]
+pub enum MetricsError {
+    #[error("Failed to register {metric}: {source}")]
+    Registration {
+        metric: String,
+        #[source]
+        source: prometheus::Error,
+    },
+
+    #[error("Metrics missing from registry: {}", .0.join(", "))]
+    Missing(Vec<String>),
+}
+
+/// Register `collector`, naming it in the error instead of dropping the failure
+fn register(registry: &Registry, collector: Box<dyn Collector>) -> Result<(), MetricsError> {
+    let metric = collector_name(collector.as_ref());
+    registry
+        .register(collector)
+        .map_err(|source| MetricsError::Registration { metric, source })
+}
+
+fn collector_name(collector: &dyn Collector) -> String {
+    collector
+        .desc()
+        .first()
+        .map(|desc| desc.fq_name.clone())
+        .unwrap_or_default()
+}
 
 /// Global metrics registry
 static REGISTRY: OnceLock<Registry> = OnceLock::new();
@@ -47,7 +81,7 @@ pub struct AgentMetrics {
 }
 
 impl AgentMetrics {
-    pub fn new(registry: &Registry) -> Self {
+    pub fn new(registry: &Registry) -> Result<Self, MetricsError> {
         let status = IntGaugeVec::new(
             Opts::new("matrix_agent_status", "Agent status (0=stopped, 1=starting, 2=running, 3=stopping, 4=failed)"),
             &["agent"],
@@ -74,22 +108,104 @@ impl AgentMetrics {
             &["agent"],
         ).expect("Failed to create agent_processing metric");
 
-        registry.register(Box::new(status.clone())).ok();
-        registry.register(Box::new(uptime_seconds.clone())).ok();
-        registry.register(Box::new(error_count.clone())).ok();
-        registry.register(Box::new(message_count.clone())).ok();
-        registry.register(Box::new(processing_time.clone())).ok();
-
-        Self {
+        let metrics = Self {
             status,
             uptime_seconds,
             error_count,
             message_count,
             processing_time,
+        };
+        for collector in metrics.collectors() {
+            register(registry, collector)?;
+        }
+        Ok(metrics)
+    }
+
+    /// Every collector in this group, for registration and `verify`
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.status.clone()),
+            Box::new(self.uptime_seconds.clone()),
+            Box::new(self.error_count.clone()),
+            Box::new(self.message_count.clone()),
+            Box::new(self.processing_time.clone()),
+        ]
+    }
+}
+
+/// Canonical `reason` label for `ArbitrageMetrics.execution_failed`
+///
+/// Record failures through `record_execution_failed` so the label set
+/// stays closed instead of fragmenting on free-form error strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureReason {
+    GasTooHigh,
+    Reverted,
+    Slippage,
+    NotIncluded,
+    NonceError,
+    Timeout,
+    Underpriced,
+}
+
+impl FailureReason {
+    pub const ALL: [FailureReason; 7] = [
+        FailureReason::GasTooHigh,
+        FailureReason::Reverted,
+        FailureReason::Slippage,
+        FailureReason::NotIncluded,
+        FailureReason::NonceError,
+        FailureReason::Timeout,
+        FailureReason::Underpriced,
+    ];
+
+    /// Metric label for this reason
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            FailureReason::GasTooHigh => "gas_too_high",
+            FailureReason::Reverted => "reverted",
+            FailureReason::Slippage => "slippage",
+            FailureReason::NotIncluded => "not_included",
+            FailureReason::NonceError => "nonce_error",
+            FailureReason::Timeout => "timeout",
+            FailureReason::Underpriced => "underpriced",
+        }
+    }
+
+    /// Best-effort classification of a node or relay error message
+    ///
+    /// Returns None when nothing recognisable matches; callers pick the
+    /// fallback that fits the stage that failed.
+    pub fn classify(message: &str) -> Option<FailureReason> {
+        let message = message.to_ascii_lowercase();
+        let has = |needle: &str| message.contains(needle);
+
+        if has("nonce") {
+            Some(FailureReason::NonceError)
+        } else if has("underpriced") {
+            Some(FailureReason::Underpriced)
+        } else if has("slippage") || has("insufficient_output_amount") || has("too little received") {
+            Some(FailureReason::Slippage)
+        } else if has("revert") {
+            Some(FailureReason::Reverted)
+        } else if has("timeout") || has("timed out") || has("not confirmed") {
+            Some(FailureReason::Timeout)
+        } else if has("fee cap") || has("gas price too high") || has("exceeds the configured cap") {
+            Some(FailureReason::GasTooHigh)
+        } else if has("not included") || has("dropped") {
+            Some(FailureReason::NotIncluded)
+        } else {
+            None
         }
     }
 }
 
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_label())
+    }
+}
+
 /// Arbitrage metrics
 pub struct ArbitrageMetrics {
     pub opportunities_detected: IntCounterVec,
@@ -101,10 +217,15 @@ pub struct ArbitrageMetrics {
     pub latency: HistogramVec,
     pub active_positions: IntGaugeVec,
     pub total_exposure: GaugeVec,
+    pub opportunities_rejected: IntCounterVec,
+    /// Cumulative realized profit in USD; no series until a price is known
+    pub profit_usd: GaugeVec,
+    /// Cumulative realized profit in ETH allocated to each split bucket
+    pub profit_allocated_eth: GaugeVec,
 }
 
 impl ArbitrageMetrics {
-    pub fn new(registry: &Registry) -> Self {
+    pub fn new(registry: &Registry) -> Result<Self, MetricsError> {
         let opportunities_detected = IntCounterVec::new(
             Opts::new("matrix_opportunities_detected_total", "Total arbitrage opportunities detected"),
             &["chain", "dex_pair"],
@@ -153,17 +274,22 @@ impl ArbitrageMetrics {
             &["chain"],
         ).expect("Failed to create total_exposure metric");
 
-        registry.register(Box::new(opportunities_detected.clone())).ok();
-        registry.register(Box::new(opportunities_executed.clone())).ok();
-        registry.register(Box::new(execution_success.clone())).ok();
-        registry.register(Box::new(execution_failed.clone())).ok();
-        registry.register(Box::new(profit_eth.clone())).ok();
-        registry.register(Box::new(gas_used.clone())).ok();
-        registry.register(Box::new(latency.clone())).ok();
-        registry.register(Box::new(active_positions.clone())).ok();
-        registry.register(Box::new(total_exposure.clone())).ok();
-
-        Self {
+        let opportunities_rejected = IntCounterVec::new(
+            Opts::new("matrix_opportunities_rejected_total", "Opportunities rejected before execution"),
+            &["reason"],
+        ).expect("Failed to create opportunities_rejected metric");
+
+        let profit_usd = GaugeVec::new(
+            Opts::new("matrix_realized_profit_usd", "Cumulative realized profit in USD"),
+            &["chain"],
+        ).expect("Failed to create profit_usd metric");
+
+        let profit_allocated_eth = GaugeVec::new(
+            Opts::new("matrix_profit_allocated_eth", "Cumulative realized profit allocated per bucket in ETH"),
+            &["chain", "bucket"],
+        ).expect("Failed to create profit_allocated_eth metric");
+
+        let metrics = Self {
             opportunities_detected,
             opportunities_executed,
             execution_success,
@@ -173,21 +299,87 @@ impl ArbitrageMetrics {
             latency,
             active_positions,
             total_exposure,
+            opportunities_rejected,
+            profit_usd,
+            profit_allocated_eth,
+        };
+        for collector in metrics.collectors() {
+            register(registry, collector)?;
         }
+        Ok(metrics)
+    }
+
+    /// Every collector in this group, for registration and `verify`
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.opportunities_detected.clone()),
+            Box::new(self.opportunities_executed.clone()),
+            Box::new(self.execution_success.clone()),
+            Box::new(self.execution_failed.clone()),
+            Box::new(self.profit_eth.clone()),
+            Box::new(self.gas_used.clone()),
+            Box::new(self.latency.clone()),
+            Box::new(self.active_positions.clone()),
+            Box::new(self.total_exposure.clone()),
+            Box::new(self.opportunities_rejected.clone()),
+            Box::new(self.profit_usd.clone()),
+            Box::new(self.profit_allocated_eth.clone()),
+        ]
+    }
+
+    /// Count a failed execution under its canonical reason
+    pub fn record_execution_failed(&self, chain: &str, reason: FailureReason) {
+        self.execution_failed.with_label_values(&[chain, reason.as_label()]).inc();
+    }
+
+    /// Add a trade's USD profit, if it could be priced
+    ///
+    /// Trades without a USD value are skipped, so the gauge undercounts
+    /// rather than mixing in native units.
+    pub fn record_profit_usd(&self, chain: &str, usd: Option<f64>) {
+        match usd {
+            Some(usd) => self.profit_usd.with_label_values(&[chain]).add(usd),
+            None => tracing::debug!("METRICS: No USD price for {} profit, skipped", chain),
+        }
+    }
+
+    /// Add the share of a trade's profit allocated to `bucket`
+    pub fn record_profit_allocation(&self, chain: &str, bucket: &str, eth: f64) {
+        self.profit_allocated_eth.with_label_values(&[chain, bucket]).add(eth);
     }
 }
 
+/// `pool` label value for updates not tracked per pool
+pub const AGGREGATED_POOL_LABEL: &str = "_aggregated";
+
+/// How per-pool market metrics are labeled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolLabelPolicy {
+    /// One series per pool
+    PerPool,
+    /// Per-pool series for the first `n` pools seen; the rest are
+    /// aggregated per dex under `AGGREGATED_POOL_LABEL`
+    Limit(usize),
+    /// Always aggregate per dex
+    Drop,
+}
+
 /// Market data metrics
+///
+/// Use `record_price_update` / `set_price_staleness` rather than the
+/// vectors directly so the pool label policy caps cardinality.
 pub struct MarketMetrics {
     pub price_updates: IntCounterVec,
     pub feed_status: IntGaugeVec,
     pub feed_latency: HistogramVec,
     pub price_staleness: GaugeVec,
     pub reconnect_count: IntCounterVec,
+    pool_labels: PoolLabelPolicy,
+    tracked_pools: Mutex<HashSet<String>>,
 }
 
 impl MarketMetrics {
-    pub fn new(registry: &Registry) -> Self {
+    pub fn new(registry: &Registry) -> Result<Self, MetricsError> {
         let price_updates = IntCounterVec::new(
             Opts::new("matrix_price_updates_total", "Total price updates received"),
             &["chain", "dex", "pool"],
@@ -214,19 +406,74 @@ impl MarketMetrics {
             &["chain", "dex"],
         ).expect("Failed to create reconnect_count metric");
 
-        registry.register(Box::new(price_updates.clone())).ok();
-        registry.register(Box::new(feed_status.clone())).ok();
-        registry.register(Box::new(feed_latency.clone())).ok();
-        registry.register(Box::new(price_staleness.clone())).ok();
-        registry.register(Box::new(reconnect_count.clone())).ok();
-
-        Self {
+        let metrics = Self {
             price_updates,
             feed_status,
             feed_latency,
             price_staleness,
             reconnect_count,
+            pool_labels: PoolLabelPolicy::PerPool,
+            tracked_pools: Mutex::new(HashSet::new()),
+        };
+        for collector in metrics.collectors() {
+            register(registry, collector)?;
         }
+        Ok(metrics)
+    }
+
+    /// Every collector in this group, for registration and `verify`
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.price_updates.clone()),
+            Box::new(self.feed_status.clone()),
+            Box::new(self.feed_latency.clone()),
+            Box::new(self.price_staleness.clone()),
+            Box::new(self.reconnect_count.clone()),
+        ]
+    }
+
+    /// Set how pools are labeled (default: one series per pool)
+    pub fn with_pool_label_policy(mut self, policy: PoolLabelPolicy) -> Self {
+        self.pool_labels = policy;
+        self
+    }
+
+    /// Count a price update for a pool
+    pub fn record_price_update(&self, chain: &str, dex: &str, pool: &str) {
+        let pool = self.pool_label(pool);
+        self.price_updates.with_label_values(&[chain, dex, pool]).inc();
+    }
+
+    /// Set a pool's price staleness
+    ///
+    /// Aggregated pools share one gauge, so it holds the latest value set.
+    pub fn set_price_staleness(&self, chain: &str, dex: &str, pool: &str, seconds: f64) {
+        let pool = self.pool_label(pool);
+        self.price_staleness.with_label_values(&[chain, dex, pool]).set(seconds);
+    }
+
+    /// Pools currently given their own series
+    pub fn tracked_pool_count(&self) -> usize {
+        self.tracked_pools.lock().unwrap().len()
+    }
+
+    /// `pool` label value under the current policy
+    fn pool_label<'a>(&self, pool: &'a str) -> &'a str {
+        let limit = match self.pool_labels {
+            PoolLabelPolicy::PerPool => return pool,
+            PoolLabelPolicy::Drop => return AGGREGATED_POOL_LABEL,
+            PoolLabelPolicy::Limit(limit) => limit,
+        };
+
+        let mut tracked = self.tracked_pools.lock().unwrap();
+        if tracked.contains(pool) {
+            return pool;
+        }
+        if tracked.len() < limit {
+            tracked.insert(pool.to_string());
+            return pool;
+        }
+        AGGREGATED_POOL_LABEL
     }
 }
 
@@ -241,7 +488,7 @@ pub struct RiskMetrics {
 }
 
 impl RiskMetrics {
-    pub fn new(registry: &Registry) -> Self {
+    pub fn new(registry: &Registry) -> Result<Self, MetricsError> {
         let circuit_breaker_status = IntGauge::new(
             "matrix_circuit_breaker_status",
             "Circuit breaker status (0=closed, 1=half-open, 2=open)",
@@ -272,21 +519,36 @@ impl RiskMetrics {
             "Whether cooldown is currently active (0/1)",
         ).expect("Failed to create cooldown_active metric");
 
-        registry.register(Box::new(circuit_breaker_status.clone())).ok();
-        registry.register(Box::new(hourly_pnl_eth.clone())).ok();
-        registry.register(Box::new(daily_pnl_eth.clone())).ok();
-        registry.register(Box::new(max_drawdown.clone())).ok();
-        registry.register(Box::new(position_count.clone())).ok();
-        registry.register(Box::new(cooldown_active.clone())).ok();
-
-        Self {
+        let metrics = Self {
             circuit_breaker_status,
             hourly_pnl_eth,
             daily_pnl_eth,
             max_drawdown,
             position_count,
             cooldown_active,
+        };
+        for collector in metrics.collectors() {
+            register(registry, collector)?;
         }
+        Ok(metrics)
+    }
+
+    /// Every collector in this group, for registration and `verify`
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.circuit_breaker_status.clone()),
+            Box::new(self.hourly_pnl_eth.clone()),
+            Box::new(self.daily_pnl_eth.clone()),
+            Box::new(self.max_drawdown.clone()),
+            Box::new(self.position_count.clone()),
+            Box::new(self.cooldown_active.clone()),
+        ]
+    }
+
+    /// Publish hourly and daily PnL in ETH
+    pub fn set_pnl(&self, hourly: SignedWei, daily: SignedWei) {
+        self.hourly_pnl_eth.set(hourly.to_eth_f64());
+        self.daily_pnl_eth.set(daily.to_eth_f64());
     }
 }
 
@@ -299,7 +561,7 @@ pub struct SystemMetrics {
 }
 
 impl SystemMetrics {
-    pub fn new(registry: &Registry) -> Self {
+    pub fn new(registry: &Registry) -> Result<Self, MetricsError> {
         let cpu_usage = Gauge::new(
             "matrix_cpu_usage_percent",
             "CPU usage percentage",
@@ -320,17 +582,26 @@ impl SystemMetrics {
             &["type"],
         ).expect("Failed to create open_connections metric");
 
-        registry.register(Box::new(cpu_usage.clone())).ok();
-        registry.register(Box::new(memory_usage.clone())).ok();
-        registry.register(Box::new(goroutines.clone())).ok();
-        registry.register(Box::new(open_connections.clone())).ok();
-
-        Self {
+        let metrics = Self {
             cpu_usage,
             memory_usage,
             goroutines,
             open_connections,
+        };
+        for collector in metrics.collectors() {
+            register(registry, collector)?;
         }
+        Ok(metrics)
+    }
+
+    /// Every collector in this group, for registration and `verify`
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.cpu_usage.clone()),
+            Box::new(self.memory_usage.clone()),
+            Box::new(self.goroutines.clone()),
+            Box::new(self.open_connections.clone()),
+        ]
     }
 }
 
@@ -341,24 +612,58 @@ pub struct MatrixMetrics {
     pub market: MarketMetrics,
     pub risk: RiskMetrics,
     pub system: SystemMetrics,
+    registry: Registry,
 }
 
 impl MatrixMetrics {
-    pub fn new() -> Self {
-        let registry = registry();
-        Self {
-            agent: AgentMetrics::new(registry),
-            arbitrage: ArbitrageMetrics::new(registry),
-            market: MarketMetrics::new(registry),
-            risk: RiskMetrics::new(registry),
-            system: SystemMetrics::new(registry),
+    /// Create and register all metrics in the global registry
+    ///
+    /// Fails if any metric can't be registered (e.g. it already is).
+    pub fn new() -> Result<Self, MetricsError> {
+        Self::with_registry(registry())
+    }
+
+    /// Create and register all metrics in `registry`
+    pub fn with_registry(registry: &Registry) -> Result<Self, MetricsError> {
+        Ok(Self {
+            agent: AgentMetrics::new(registry)?,
+            arbitrage: ArbitrageMetrics::new(registry)?,
+            market: MarketMetrics::new(registry)?,
+            risk: RiskMetrics::new(registry)?,
+            system: SystemMetrics::new(registry)?,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Confirm every expected collector is still registered
+    ///
+    /// Probes by re-registering: a collector the registry accepts was
+    /// missing, so it is unregistered again and reported.
+    pub fn verify(&self) -> Result<(), MetricsError> {
+        let mut missing = Vec::new();
+        for (probe, undo) in self.collectors().into_iter().zip(self.collectors()) {
+            let name = collector_name(probe.as_ref());
+            if self.registry.register(probe).is_ok() {
+                let _ = self.registry.unregister(undo);
+                missing.push(name);
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MetricsError::Missing(missing))
         }
     }
-}
 
-impl Default for MatrixMetrics {
-    fn default() -> Self {
-        Self::new()
+    /// Every collector across all groups
+    pub fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        let mut collectors = self.agent.collectors();
+        collectors.extend(self.arbitrage.collectors());
+        collectors.extend(self.market.collectors());
+        collectors.extend(self.risk.collectors());
+        collectors.extend(self.system.collectors());
+        collectors
     }
 }
 
@@ -378,7 +683,8 @@ mod tests {
 
     #[test]
     fn test_metrics_creation() {
-        let metrics = MatrixMetrics::new();
+        let metrics = MatrixMetrics::new().unwrap();
+        metrics.verify().unwrap();
 
         // Test setting some values
         metrics.agent.status.with_label_values(&["neo"]).set(2);
@@ -390,4 +696,155 @@ mod tests {
         let output = gather_metrics();
         assert!(!output.is_empty());
     }
+
+    #[test]
+    fn test_duplicate_registration_reported() {
+        let registry = Registry::new();
+        let squatter = IntGauge::new("matrix_feed_status", "Registered elsewhere first").unwrap();
+        registry.register(Box::new(squatter)).unwrap();
+
+        match MatrixMetrics::with_registry(&registry) {
+            Err(MetricsError::Registration { metric, .. }) => assert_eq!(metric, "matrix_feed_status"),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("duplicate registration was silently accepted"),
+        }
+
+        // The same set twice in one registry is a duplicate too
+        let registry = Registry::new();
+        MatrixMetrics::with_registry(&registry).unwrap();
+        assert!(matches!(
+            MatrixMetrics::with_registry(&registry),
+            Err(MetricsError::Registration { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_reports_missing_collector() {
+        let registry = Registry::new();
+        let metrics = MatrixMetrics::with_registry(&registry).unwrap();
+        metrics.verify().unwrap();
+
+        registry.unregister(Box::new(metrics.market.price_updates.clone())).unwrap();
+        match metrics.verify() {
+            Err(MetricsError::Missing(names)) => assert_eq!(names, vec!["matrix_price_updates_total"]),
+            other => panic!("expected missing metric, got {:?}", other.err()),
+        }
+
+        // verify() leaves the registry as it found it
+        assert!(matches!(metrics.verify(), Err(MetricsError::Missing(_))));
+    }
+
+    #[test]
+    fn test_pnl_gauges_signed() {
+        let metrics = RiskMetrics::new(&Registry::new()).unwrap();
+        metrics.set_pnl(SignedWei::from(-250_000_000_000_000_000), SignedWei::from(1_500_000_000_000_000_000));
+        assert_eq!(metrics.hourly_pnl_eth.get(), -0.25);
+        assert_eq!(metrics.daily_pnl_eth.get(), 1.5);
+    }
+
+    #[test]
+    fn test_profit_usd_only_when_priced() {
+        let registry = Registry::new();
+        let metrics = ArbitrageMetrics::new(&registry).unwrap();
+        let families = |registry: &Registry| {
+            registry
+                .gather()
+                .into_iter()
+                .filter(|family| family.get_name() == "matrix_realized_profit_usd")
+                .count()
+        };
+
+        metrics.record_profit_usd("ethereum", None);
+        assert_eq!(families(&registry), 0);
+
+        metrics.record_profit_usd("ethereum", Some(500.0));
+        metrics.record_profit_usd("ethereum", Some(250.0));
+        assert_eq!(families(&registry), 1);
+        assert_eq!(metrics.profit_usd.with_label_values(&["ethereum"]).get(), 750.0);
+    }
+
+    #[test]
+    fn test_failure_reason_labels_stable() {
+        let labels: Vec<&str> = FailureReason::ALL.iter().map(|r| r.as_label()).collect();
+        assert_eq!(
+            labels,
+            vec!["gas_too_high", "reverted", "slippage", "not_included", "nonce_error", "timeout", "underpriced"]
+        );
+        assert_eq!(labels.iter().collect::<HashSet<_>>().len(), FailureReason::ALL.len());
+        assert_eq!(FailureReason::Timeout.to_string(), "timeout");
+
+        let metrics = ArbitrageMetrics::new(&Registry::new()).unwrap();
+        metrics.record_execution_failed("ethereum", FailureReason::Reverted);
+        metrics.record_execution_failed("ethereum", FailureReason::Reverted);
+        assert_eq!(metrics.execution_failed.with_label_values(&["ethereum", "reverted"]).get(), 2);
+    }
+
+    #[test]
+    fn test_failure_reason_classify() {
+        let cases = [
+            ("nonce too low", Some(FailureReason::NonceError)),
+            ("replacement transaction underpriced", Some(FailureReason::Underpriced)),
+            ("execution reverted: UniswapV2: INSUFFICIENT_OUTPUT_AMOUNT", Some(FailureReason::Slippage)),
+            ("execution reverted", Some(FailureReason::Reverted)),
+            ("0xabc not confirmed after 120000ms", Some(FailureReason::Timeout)),
+            ("tx fee (2.10 ether) exceeds the configured cap (1.00 ether)", Some(FailureReason::GasTooHigh)),
+            ("bundle not included in target block", Some(FailureReason::NotIncluded)),
+            ("connection refused", None),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(FailureReason::classify(message), expected, "{}", message);
+        }
+    }
+
+    /// Distinct `pool` label values in the `price_updates` family
+    fn pool_series(registry: &Registry) -> HashSet<String> {
+        registry
+            .gather()
+            .iter()
+            .filter(|mf| mf.get_name() == "matrix_price_updates_total")
+            .flat_map(|mf| mf.get_metric().iter())
+            .flat_map(|m| m.get_label().iter())
+            .filter(|l| l.get_name() == "pool")
+            .map(|l| l.get_value().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_pool_label_cardinality_capped() {
+        let registry = Registry::new();
+        let market = MarketMetrics::new(&registry)
+            .unwrap()
+            .with_pool_label_policy(PoolLabelPolicy::Limit(3));
+
+        for i in 0..100 {
+            market.record_price_update("ethereum", "uniswap_v3", &format!("0xpool{}", i));
+        }
+        // Tracked pools keep their own series after the limit is hit
+        market.record_price_update("ethereum", "uniswap_v3", "0xpool0");
+
+        let series = pool_series(&registry);
+        assert_eq!(series.len(), 4);
+        assert!(series.contains(AGGREGATED_POOL_LABEL));
+        assert_eq!(market.tracked_pool_count(), 3);
+
+        let count = |pool: &str| market.price_updates.with_label_values(&["ethereum", "uniswap_v3", pool]).get();
+        assert_eq!(count("0xpool0"), 2);
+        assert_eq!(count(AGGREGATED_POOL_LABEL), 97);
+    }
+
+    #[test]
+    fn test_pool_label_dropped() {
+        let registry = Registry::new();
+        let market = MarketMetrics::new(&registry)
+            .unwrap()
+            .with_pool_label_policy(PoolLabelPolicy::Drop);
+
+        for i in 0..10 {
+            market.record_price_update("bsc", "pancakeswap", &format!("0xpool{}", i));
+            market.set_price_staleness("bsc", "pancakeswap", &format!("0xpool{}", i), 1.5);
+        }
+
+        assert_eq!(pool_series(&registry), HashSet::from([AGGREGATED_POOL_LABEL.to_string()]));
+        assert_eq!(market.tracked_pool_count(), 0);
+    }
 }
@@ -0,0 +1,302 @@
+//! Versioned wire format for inter-agent messages
+//!
+//! Every message is wrapped as `{version, kind, payload}`. The version is
+//! checked before the payload is touched, so an agent running a different
+//! schema rejects the message instead of misparsing it.
+//!
+//! Payloads serialize through `serde_json::Value`, whose maps are sorted,
+//! so the same message always encodes to the same bytes.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{ExecutionResult, Opportunity, PriceUpdate};
+
+/// Current envelope schema version
+pub const ENVELOPE_VERSION: u16 = 1;
+
+/// Envelope errors
+#[derive(Error, Debug)]
+pub enum EnvelopeError {
+    #[error("Unsupported envelope version {found} (expected {expected})")]
+    UnsupportedVersion { found: u16, expected: u16 },
+
+    #[error("Unknown message kind: {0}")]
+    UnknownKind(String),
+
+    #[error("Malformed envelope: {0}")]
+    Malformed(String),
+}
+
+/// Message kind tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    Opportunity,
+    PriceUpdate,
+    ExecutionResult,
+}
+
+impl MessageKind {
+    fn parse(tag: &str) -> Result<Self, EnvelopeError> {
+        match tag {
+            "opportunity" => Ok(MessageKind::Opportunity),
+            "price_update" => Ok(MessageKind::PriceUpdate),
+            "execution_result" => Ok(MessageKind::ExecutionResult),
+            other => Err(EnvelopeError::UnknownKind(other.to_string())),
+        }
+    }
+}
+
+/// Wire envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u16,
+    pub kind: MessageKind,
+    pub payload: serde_json::Value,
+}
+
+/// Decoded inter-agent message
+#[derive(Debug, Clone)]
+pub enum Message {
+    Opportunity(Opportunity),
+    PriceUpdate(PriceUpdate),
+    ExecutionResult(ExecutionResult),
+}
+
+impl Message {
+    /// Kind tag for this message
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::Opportunity(_) => MessageKind::Opportunity,
+            Message::PriceUpdate(_) => MessageKind::PriceUpdate,
+            Message::ExecutionResult(_) => MessageKind::ExecutionResult,
+        }
+    }
+
+    /// Wrap in a current-version envelope
+    pub fn to_envelope(&self) -> Result<Envelope, EnvelopeError> {
+        let payload = match self {
+            Message::Opportunity(o) => serde_json::to_value(o),
+            Message::PriceUpdate(u) => serde_json::to_value(u),
+            Message::ExecutionResult(r) => serde_json::to_value(r),
+        }
+        .map_err(|e| EnvelopeError::Malformed(e.to_string()))?;
+
+        Ok(Envelope {
+            version: ENVELOPE_VERSION,
+            kind: self.kind(),
+            payload,
+        })
+    }
+
+    /// Unwrap an envelope, rejecting other versions
+    pub fn from_envelope(envelope: Envelope) -> Result<Self, EnvelopeError> {
+        check_version(envelope.version)?;
+
+        let malformed = |e: serde_json::Error| EnvelopeError::Malformed(e.to_string());
+        let message = match envelope.kind {
+            MessageKind::Opportunity => {
+                Message::Opportunity(serde_json::from_value(envelope.payload).map_err(malformed)?)
+            }
+            MessageKind::PriceUpdate => {
+                Message::PriceUpdate(serde_json::from_value(envelope.payload).map_err(malformed)?)
+            }
+            MessageKind::ExecutionResult => {
+                Message::ExecutionResult(serde_json::from_value(envelope.payload).map_err(malformed)?)
+            }
+        };
+
+        Ok(message)
+    }
+
+    /// Encode to envelope bytes
+    pub fn encode(&self) -> Result<Vec<u8>, EnvelopeError> {
+        serde_json::to_vec(&self.to_envelope()?).map_err(|e| EnvelopeError::Malformed(e.to_string()))
+    }
+
+    /// Decode from envelope bytes
+    ///
+    /// Version and kind are read first; the payload is only parsed once
+    /// both are known to this build.
+    pub fn decode(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let mut raw: serde_json::Value =
+            serde_json::from_slice(bytes).map_err(|e| EnvelopeError::Malformed(e.to_string()))?;
+
+        let version = raw
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| EnvelopeError::Malformed("missing version".to_string()))?;
+        check_version(u16::try_from(version).unwrap_or(u16::MAX))?;
+
+        let kind = raw
+            .get("kind")
+            .and_then(|k| k.as_str())
+            .ok_or_else(|| EnvelopeError::Malformed("missing kind".to_string()))
+            .and_then(MessageKind::parse)?;
+
+        let payload = raw
+            .get_mut("payload")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| EnvelopeError::Malformed("missing payload".to_string()))?;
+
+        Self::from_envelope(Envelope {
+            version: ENVELOPE_VERSION,
+            kind,
+            payload,
+        })
+    }
+}
+
+fn check_version(version: u16) -> Result<(), EnvelopeError> {
+    if version != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion {
+            found: version,
+            expected: ENVELOPE_VERSION,
+        });
+    }
+    Ok(())
+}
+
+impl From<Opportunity> for Message {
+    fn from(opportunity: Opportunity) -> Self {
+        Message::Opportunity(opportunity)
+    }
+}
+
+impl From<PriceUpdate> for Message {
+    fn from(update: PriceUpdate) -> Self {
+        Message::PriceUpdate(update)
+    }
+}
+
+impl From<ExecutionResult> for Message {
+    fn from(result: ExecutionResult) -> Self {
+        Message::ExecutionResult(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChainId, DexId, SwapKind, SwapStep};
+    use ethers_core::types::{Address, H256, U256};
+
+    fn opportunity() -> Opportunity {
+        Opportunity {
+            id: 42,
+            timestamp_ms: 1_700_000_000_000,
+            chain: ChainId::Arbitrum,
+            profit_wei: U256::exp10(16),
+            gas_estimate: 350_000,
+            path: vec![SwapStep {
+                dex: DexId::Camelot,
+                pool: Address::repeat_byte(1),
+                token_in: Address::repeat_byte(2),
+                token_out: Address::repeat_byte(3),
+                amount_in: U256::exp10(18),
+                amount_out: U256::exp10(18) * 2,
+                kind: SwapKind::Swap,
+            }],
+            flash_loan_token: Address::repeat_byte(2),
+            flash_loan_amount: U256::exp10(18),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_all_kinds() {
+        let update = PriceUpdate {
+            timestamp_ms: 1,
+            chain: ChainId::Base,
+            dex: DexId::Aerodrome,
+            pool: Address::repeat_byte(4),
+            token0: Address::repeat_byte(5),
+            token1: Address::repeat_byte(6),
+            reserve0: U256::from(100u64),
+            reserve1: U256::from(200u64),
+            price: U256::exp10(18) * 2,
+            fee_bps: None,
+            provider: None,
+        };
+        let result = ExecutionResult {
+            opportunity_id: 42,
+            tx_hash: H256::repeat_byte(7),
+            success: true,
+            actual_profit: U256::exp10(15),
+            gas_used: 310_000,
+            block_number: 19_000_000,
+            timestamp_ms: 2,
+        };
+
+        let opp = Message::decode(&Message::from(opportunity()).encode().unwrap()).unwrap();
+        assert!(matches!(opp, Message::Opportunity(ref o) if o.id == 42 && o.path.len() == 1));
+
+        let upd = Message::decode(&Message::from(update).encode().unwrap()).unwrap();
+        assert!(matches!(upd, Message::PriceUpdate(ref u) if u.dex == DexId::Aerodrome));
+
+        let res = Message::decode(&Message::from(result).encode().unwrap()).unwrap();
+        assert!(matches!(res, Message::ExecutionResult(ref r) if r.tx_hash == H256::repeat_byte(7)));
+    }
+
+    #[test]
+    fn test_encoding_is_deterministic() {
+        let message = Message::from(opportunity());
+        assert_eq!(message.encode().unwrap(), message.encode().unwrap());
+
+        let envelope = message.to_envelope().unwrap();
+        assert_eq!(envelope.version, ENVELOPE_VERSION);
+        assert_eq!(envelope.kind, MessageKind::Opportunity);
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let mut envelope = Message::from(opportunity()).to_envelope().unwrap();
+
+        // Older agent: valid-looking payload must not be parsed
+        envelope.version = 0;
+        let bytes = serde_json::to_vec(&envelope).unwrap();
+        assert!(matches!(
+            Message::decode(&bytes),
+            Err(EnvelopeError::UnsupportedVersion { found: 0, expected: ENVELOPE_VERSION })
+        ));
+
+        // Newer agent with a payload this build can't read
+        let bytes = serde_json::to_vec(&serde_json::json!({
+            "version": 2,
+            "kind": "opportunity",
+            "payload": {"id": "not-a-number"},
+        }))
+        .unwrap();
+        assert!(matches!(
+            Message::decode(&bytes),
+            Err(EnvelopeError::UnsupportedVersion { found: 2, .. })
+        ));
+
+        envelope.version = 99;
+        assert!(matches!(
+            Message::from_envelope(envelope),
+            Err(EnvelopeError::UnsupportedVersion { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let unknown_kind = serde_json::to_vec(&serde_json::json!({
+            "version": ENVELOPE_VERSION,
+            "kind": "heartbeat",
+            "payload": {},
+        }))
+        .unwrap();
+        assert!(matches!(Message::decode(&unknown_kind), Err(EnvelopeError::UnknownKind(k)) if k == "heartbeat"));
+
+        let bad_payload = serde_json::to_vec(&serde_json::json!({
+            "version": ENVELOPE_VERSION,
+            "kind": "price_update",
+            "payload": {"timestamp_ms": 1},
+        }))
+        .unwrap();
+        assert!(matches!(Message::decode(&bad_payload), Err(EnvelopeError::Malformed(_))));
+
+        assert!(matches!(Message::decode(b"{}"), Err(EnvelopeError::Malformed(_))));
+    }
+}
@@ -0,0 +1,99 @@
+//! Flash loan providers
+//!
+//! Providers differ in premium and in the callback they invoke on the
+//! receiver contract, so the executor must know which one it borrows from.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Supported flash loan providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlashLoanProvider {
+    /// Aave V3 `flashLoanSimple`
+    Aave,
+    /// Balancer V2 vault
+    Balancer,
+    /// dYdX SoloMargin (Ethereum only)
+    Dydx,
+}
+
+impl FlashLoanProvider {
+    pub const ALL: [FlashLoanProvider; 3] = [Self::Aave, Self::Balancer, Self::Dydx];
+
+    /// Premium in basis points of the borrowed amount
+    ///
+    /// dYdX charges a flat 2 wei, which rounds to zero.
+    pub const fn premium_bps(&self) -> u64 {
+        match self {
+            Self::Aave => 9,
+            Self::Balancer | Self::Dydx => 0,
+        }
+    }
+
+    /// Selector of the callback the provider invokes on the receiver
+    pub fn callback_selector(&self) -> [u8; 4] {
+        match self {
+            // executeOperation(address,uint256,uint256,address,bytes)
+            Self::Aave => [0x1b, 0x11, 0xd0, 0xff],
+            // receiveFlashLoan(address[],uint256[],uint256[],bytes)
+            Self::Balancer => [0xf0, 0x4f, 0x27, 0x07],
+            // callFunction(address,(address,uint256),bytes)
+            Self::Dydx => [0x8b, 0x41, 0x87, 0x13],
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Aave => "aave",
+            Self::Balancer => "balancer",
+            Self::Dydx => "dydx",
+        }
+    }
+}
+
+impl fmt::Display for FlashLoanProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FlashLoanProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|provider| provider.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown flash loan provider: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::utils::id;
+
+    #[test]
+    fn test_callback_selectors_match_signatures() {
+        let signatures = [
+            (FlashLoanProvider::Aave, "executeOperation(address,uint256,uint256,address,bytes)"),
+            (FlashLoanProvider::Balancer, "receiveFlashLoan(address[],uint256[],uint256[],bytes)"),
+            (FlashLoanProvider::Dydx, "callFunction(address,(address,uint256),bytes)"),
+        ];
+        for (provider, signature) in signatures {
+            assert_eq!(provider.callback_selector(), id(signature), "{}", provider);
+        }
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for provider in FlashLoanProvider::ALL {
+            assert_eq!(provider.to_string().parse::<FlashLoanProvider>(), Ok(provider));
+        }
+        assert_eq!(" Aave ".parse::<FlashLoanProvider>(), Ok(FlashLoanProvider::Aave));
+        assert!("uniswap".parse::<FlashLoanProvider>().is_err());
+    }
+}
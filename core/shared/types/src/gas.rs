@@ -0,0 +1,109 @@
+//! Gas price units
+//!
+//! Limits were configured in gwei in some places and wei in others, an
+//! easy 10^9 mistake. `GasPrice` always holds wei; gwei only appears at
+//! the edges via `from_gwei` / `as_gwei`. It serializes as gwei, the unit
+//! operators write in config files.
+
+use std::fmt;
+
+use ethers_core::types::U256;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+/// Gas price, stored in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct GasPrice(U256);
+
+impl GasPrice {
+    pub const ZERO: GasPrice = GasPrice(U256::zero());
+
+    pub const fn from_wei(wei: U256) -> Self {
+        Self(wei)
+    }
+
+    pub fn from_gwei(gwei: u64) -> Self {
+        Self(U256::from(gwei) * U256::from(WEI_PER_GWEI))
+    }
+
+    /// Fractional gwei (e.g. 0.05 on L2s), rounded to the nearest wei
+    ///
+    /// Negative and non-finite values map to zero.
+    pub fn from_gwei_f64(gwei: f64) -> Self {
+        if !gwei.is_finite() || gwei <= 0.0 {
+            return Self::ZERO;
+        }
+        Self(U256::from((gwei * WEI_PER_GWEI as f64).round() as u128))
+    }
+
+    pub fn as_wei(&self) -> U256 {
+        self.0
+    }
+
+    /// Value in gwei, fractional below 1 gwei
+    pub fn as_gwei(&self) -> f64 {
+        let whole = self.0 / U256::from(WEI_PER_GWEI);
+        let fraction = (self.0 % U256::from(WEI_PER_GWEI)).as_u64() as f64 / WEI_PER_GWEI as f64;
+        let whole = if whole > U256::from(u128::MAX) { u128::MAX } else { whole.as_u128() };
+        whole as f64 + fraction
+    }
+}
+
+impl From<U256> for GasPrice {
+    fn from(wei: U256) -> Self {
+        Self(wei)
+    }
+}
+
+impl fmt::Display for GasPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} gwei", self.as_gwei())
+    }
+}
+
+impl Serialize for GasPrice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_gwei())
+    }
+}
+
+impl<'de> Deserialize<'de> for GasPrice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self::from_gwei_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gwei_wei_round_trip() {
+        let price = GasPrice::from_gwei(300);
+        assert_eq!(price.as_wei(), U256::from(300_000_000_000u64));
+        assert_eq!(price.as_gwei(), 300.0);
+        assert_eq!(GasPrice::from_wei(price.as_wei()), price);
+        assert_eq!(GasPrice::from_gwei_f64(price.as_gwei()), price);
+
+        let l2 = GasPrice::from_gwei_f64(0.05);
+        assert_eq!(l2.as_wei(), U256::from(50_000_000u64));
+        assert_eq!(l2.as_gwei(), 0.05);
+        assert_eq!(GasPrice::from_gwei_f64(-1.0), GasPrice::ZERO);
+        assert_eq!(price.to_string(), "300 gwei");
+
+        let json = serde_json::to_string(&l2).unwrap();
+        assert_eq!(json, "0.05");
+        assert_eq!(serde_json::from_str::<GasPrice>("300").unwrap(), price);
+    }
+
+    #[test]
+    fn test_comparisons_across_units() {
+        let limit = GasPrice::from_gwei(300);
+        assert!(GasPrice::from_wei(U256::from(300_000_000_001u64)) > limit);
+        assert!(GasPrice::from_gwei_f64(299.999) < limit);
+        // 300 wei is not 300 gwei
+        assert!(GasPrice::from_wei(U256::from(300u64)) < GasPrice::from_gwei(1));
+        assert_eq!(GasPrice::from_gwei(500).max(limit), GasPrice::from_gwei(500));
+    }
+}
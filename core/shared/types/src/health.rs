@@ -0,0 +1,102 @@
+//! Live agent health counters
+//!
+//! Agents embed a `HealthReporter`, beat it whenever they do work and
+//! bump it on errors; `report` turns the counters into the `AgentHealth`
+//! that Neo aggregates and the health endpoint serves.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::{AgentHealth, AgentStatus};
+
+/// Heartbeat, error count and gauges for one agent
+#[derive(Debug)]
+pub struct HealthReporter {
+    name: String,
+    /// Unix ms of the last heartbeat (0 = never)
+    last_heartbeat_ms: AtomicU64,
+    errors: AtomicU64,
+    metrics: Mutex<HashMap<String, f64>>,
+}
+
+impl HealthReporter {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            last_heartbeat_ms: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            metrics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record that the agent is alive, stamped now
+    pub fn heartbeat(&self) {
+        self.heartbeat_at(chrono::Utc::now().timestamp_millis() as u64);
+    }
+
+    /// Record a heartbeat at `timestamp_ms`; never moves backwards
+    pub fn heartbeat_at(&self, timestamp_ms: u64) {
+        self.last_heartbeat_ms.fetch_max(timestamp_ms, Ordering::Relaxed);
+    }
+
+    /// Unix ms of the last heartbeat, 0 if none yet
+    pub fn last_heartbeat_ms(&self) -> u64 {
+        self.last_heartbeat_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn error_count(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Set a gauge reported alongside the counters
+    pub fn set_metric(&self, name: &str, value: f64) {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), value);
+    }
+
+    /// Snapshot as `AgentHealth` with the given status
+    pub fn report(&self, status: AgentStatus) -> AgentHealth {
+        AgentHealth {
+            name: self.name.clone(),
+            status,
+            last_heartbeat_ms: self.last_heartbeat_ms(),
+            error_count: self.error_count(),
+            metrics: self.metrics.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_reflects_counters() {
+        let reporter = HealthReporter::new("dozer");
+        let health = reporter.report(AgentStatus::Starting);
+        assert_eq!((health.last_heartbeat_ms, health.error_count), (0, 0));
+
+        reporter.heartbeat_at(2_000);
+        reporter.heartbeat_at(1_000);
+        reporter.record_error();
+        reporter.record_error();
+        reporter.set_metric("pools", 12.0);
+
+        let health = reporter.report(AgentStatus::Running);
+        assert_eq!(health.name, "dozer");
+        assert_eq!(health.status, AgentStatus::Running);
+        assert_eq!(health.last_heartbeat_ms, 2_000);
+        assert_eq!(health.error_count, 2);
+        assert_eq!(health.metrics["pools"], 12.0);
+
+        reporter.heartbeat();
+        assert!(reporter.last_heartbeat_ms() > 2_000);
+    }
+}
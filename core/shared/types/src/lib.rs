@@ -1,8 +1,28 @@
 //! Matrix Types - Shared types for the flash loan arbitrage bot
 
-use ethers_core::types::{Address, U256, H256};
+use ethers_core::types::{Address, U256, U512, H256};
 use serde::{Deserialize, Serialize};
 
+pub mod envelope;
+pub mod flash_loan;
+pub mod gas;
+pub mod health;
+#[cfg(any(test, feature = "mock-rpc"))]
+pub mod mock_rpc;
+pub mod oracle;
+pub mod pnl;
+pub mod retry;
+pub mod safe_mode;
+
+pub use envelope::{Envelope, EnvelopeError, Message, MessageKind, ENVELOPE_VERSION};
+pub use flash_loan::FlashLoanProvider;
+pub use gas::GasPrice;
+pub use health::HealthReporter;
+pub use oracle::{profit_usd, wei_to_native, FixedPriceOracle, PriceOracle};
+pub use pnl::SignedWei;
+pub use retry::{is_transient_rpc_error, retry_with_backoff, retry_with_backoff_if, RetryError, RetryPolicy, Retryable};
+pub use safe_mode::SafeMode;
+
 /// Chain identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChainId {
@@ -13,6 +33,22 @@ pub enum ChainId {
     Base = 8453,
 }
 
+impl TryFrom<u64> for ChainId {
+    type Error = u64;
+
+    /// Chain from its EIP-155 chain id
+    fn try_from(chain_id: u64) -> Result<Self, Self::Error> {
+        match chain_id {
+            1 => Ok(ChainId::Ethereum),
+            56 => Ok(ChainId::Bsc),
+            10 => Ok(ChainId::Optimism),
+            42161 => Ok(ChainId::Arbitrum),
+            8453 => Ok(ChainId::Base),
+            other => Err(other),
+        }
+    }
+}
+
 /// DEX identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DexId {
@@ -26,6 +62,68 @@ pub enum DexId {
     Aerodrome,
 }
 
+/// Price confidence in basis points (0 - 10000)
+///
+/// Integer-valued so confidence compares and serializes deterministically
+/// across nodes. Values above 10000 are clamped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(from = "u16", into = "u16")]
+pub struct Confidence(u16);
+
+impl Confidence {
+    pub const MAX_BPS: u16 = 10_000;
+    pub const ZERO: Confidence = Confidence(0);
+    pub const FULL: Confidence = Confidence(Self::MAX_BPS);
+
+    /// Create from basis points, clamping to 10000
+    pub const fn from_bps(bps: u16) -> Self {
+        if bps > Self::MAX_BPS {
+            Confidence(Self::MAX_BPS)
+        } else {
+            Confidence(bps)
+        }
+    }
+
+    /// Create from a 0.0 - 1.0 fraction (rounded, clamped, NaN = 0)
+    pub fn from_f64(value: f64) -> Self {
+        if value.is_nan() || value <= 0.0 {
+            return Self::ZERO;
+        }
+        if value >= 1.0 {
+            return Self::FULL;
+        }
+        Confidence((value * Self::MAX_BPS as f64).round() as u16)
+    }
+
+    /// Confidence in basis points
+    pub const fn bps(self) -> u16 {
+        self.0
+    }
+
+    /// Confidence as a 0.0 - 1.0 fraction
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / Self::MAX_BPS as f64
+    }
+}
+
+impl From<u16> for Confidence {
+    fn from(bps: u16) -> Self {
+        Confidence::from_bps(bps)
+    }
+}
+
+impl From<Confidence> for u16 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.0
+    }
+}
+
+impl From<Confidence> for f64 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.as_f64()
+    }
+}
+
 /// Price update from data feed
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceUpdate {
@@ -37,7 +135,60 @@ pub struct PriceUpdate {
     pub token1: Address,
     pub reserve0: U256,
     pub reserve1: U256,
-    pub price: U256, // token0 price in terms of token1 (18 decimals)
+    pub price: U256, // token0 price in terms of token1: reserve1 / reserve0 (18 decimals)
+    /// LP fee of the pool's fee tier, if the feed knows it
+    #[serde(default)]
+    pub fee_bps: Option<u64>,
+    /// Endpoint the update was read from, for multi-provider quorum
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+impl PriceUpdate {
+    /// Reserves as `(token, other token)`, whatever the on-chain order
+    ///
+    /// `None` if `token` isn't in this pool.
+    pub fn reserves_for(&self, token: Address) -> Option<(U256, U256)> {
+        oriented_reserves(self.token0, self.token1, self.reserve0, self.reserve1, token)
+    }
+
+    /// Price of `token` in terms of the pool's other token (18 decimals)
+    ///
+    /// Same basis as `price` when `token` is token0, inverted when it is
+    /// token1. `None` if `token` isn't in this pool or its reserve is zero.
+    pub fn price_of(&self, token: Address) -> Option<U256> {
+        let (reserve_base, reserve_quote) = self.reserves_for(token)?;
+        price_from_reserves(reserve_base, reserve_quote)
+    }
+}
+
+/// Reserves of a pool ordered as `(base, other)` regardless of token order
+pub fn oriented_reserves(
+    token0: Address,
+    token1: Address,
+    reserve0: U256,
+    reserve1: U256,
+    base: Address,
+) -> Option<(U256, U256)> {
+    if base == token0 {
+        Some((reserve0, reserve1))
+    } else if base == token1 {
+        Some((reserve1, reserve0))
+    } else {
+        None
+    }
+}
+
+/// Price of the base token in the quote token (18 decimals): quote / base
+///
+/// The product is widened to 512 bits, so only a price that itself
+/// exceeds `U256` (or a zero base reserve) yields `None`.
+pub fn price_from_reserves(reserve_base: U256, reserve_quote: U256) -> Option<U256> {
+    if reserve_base.is_zero() {
+        return None;
+    }
+    let scaled = reserve_quote.full_mul(U256::exp10(18));
+    U256::try_from(scaled / U512::from(reserve_base)).ok()
 }
 
 /// Arbitrage opportunity
@@ -53,15 +204,60 @@ pub struct Opportunity {
     pub flash_loan_amount: U256,
 }
 
+impl Opportunity {
+    /// Gas of the path's wrap/unwrap legs, on top of `gas_estimate`
+    pub fn wrap_gas(&self) -> u64 {
+        self.path.iter().map(|step| step.kind.extra_gas()).sum()
+    }
+
+    /// `gas_estimate` plus wrap/unwrap legs
+    pub fn total_gas(&self) -> u64 {
+        self.gas_estimate.saturating_add(self.wrap_gas())
+    }
+}
+
+/// Gas of a wrapped-token `deposit()` called from the executor contract
+pub const WRAP_GAS: u64 = 25_000;
+
+/// Gas of a wrapped-token `withdraw()` called from the executor contract
+pub const UNWRAP_GAS: u64 = 20_000;
+
+/// What a path step does
+///
+/// Native-token arbitrage starts and ends in ETH/BNB while pools trade the
+/// wrapped token. `Wrap`/`Unwrap` legs convert 1:1 through the wrapped
+/// token contract and cost gas of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SwapKind {
+    #[default]
+    Swap,
+    Wrap,
+    Unwrap,
+}
+
+impl SwapKind {
+    /// Gas on top of the swap estimate
+    pub fn extra_gas(&self) -> u64 {
+        match self {
+            SwapKind::Swap => 0,
+            SwapKind::Wrap => WRAP_GAS,
+            SwapKind::Unwrap => UNWRAP_GAS,
+        }
+    }
+}
+
 /// Single swap step in arbitrage path
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapStep {
     pub dex: DexId,
+    /// Pool swapped through; the wrapped token contract for wrap legs
     pub pool: Address,
     pub token_in: Address,
     pub token_out: Address,
     pub amount_in: U256,
     pub amount_out: U256,
+    #[serde(default)]
+    pub kind: SwapKind,
 }
 
 /// Execution result
@@ -81,6 +277,7 @@ pub struct ExecutionResult {
 pub struct AgentHealth {
     pub name: String,
     pub status: AgentStatus,
+    /// Unix time (ms) of the last heartbeat; 0 if none was recorded
     pub last_heartbeat_ms: u64,
     pub error_count: u64,
     pub metrics: std::collections::HashMap<String, f64>,
@@ -96,3 +293,101 @@ pub enum AgentStatus {
     Stopped,
     Failed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_conversions() {
+        assert_eq!(Confidence::from_f64(1.0), Confidence::FULL);
+        assert_eq!(Confidence::from_f64(0.0), Confidence::ZERO);
+        assert_eq!(Confidence::from_f64(0.9).bps(), 9000);
+        assert_eq!(Confidence::from_f64(0.12345).bps(), 1235);
+        assert_eq!(Confidence::from_bps(7000).as_f64(), 0.7);
+
+        // Out-of-range and NaN inputs clamp
+        assert_eq!(Confidence::from_f64(1.5), Confidence::FULL);
+        assert_eq!(Confidence::from_f64(-0.1), Confidence::ZERO);
+        assert_eq!(Confidence::from_f64(f64::NAN), Confidence::ZERO);
+        assert_eq!(Confidence::from_bps(u16::MAX), Confidence::FULL);
+    }
+
+    #[test]
+    fn test_confidence_ordering() {
+        assert!(Confidence::ZERO < Confidence::from_bps(1));
+        assert!(Confidence::from_bps(9999) < Confidence::FULL);
+        assert_eq!(Confidence::from_bps(10_001), Confidence::from_bps(10_000));
+    }
+
+    #[test]
+    fn test_price_of_either_token() {
+        let weth = Address::from_low_u64_be(1);
+        let usdc = Address::from_low_u64_be(2);
+        let update = PriceUpdate {
+            timestamp_ms: 0,
+            chain: ChainId::Ethereum,
+            dex: DexId::UniswapV3,
+            pool: Address::from_low_u64_be(3),
+            token0: usdc,
+            token1: weth,
+            reserve0: U256::from(2_000_000u64),
+            reserve1: U256::from(1_000u64),
+            price: U256::exp10(15) / 2,
+            fee_bps: None,
+            provider: None,
+        };
+
+        assert_eq!(update.price_of(usdc), Some(update.price));
+        assert_eq!(update.price_of(weth), Some(U256::exp10(18) * 2_000));
+        assert_eq!(update.reserves_for(weth), Some((update.reserve1, update.reserve0)));
+        assert_eq!(update.price_of(Address::zero()), None);
+    }
+
+    #[test]
+    fn test_confidence_serde() {
+        let json = serde_json::to_string(&Confidence::from_bps(7000)).unwrap();
+        assert_eq!(json, "7000");
+
+        let clamped: Confidence = serde_json::from_str("20000").unwrap();
+        assert_eq!(clamped, Confidence::FULL);
+    }
+
+    #[test]
+    fn test_wrap_legs_add_gas() {
+        let wbnb = Address::repeat_byte(0xbb);
+        let busd = Address::repeat_byte(0xe9);
+        let step = |kind: SwapKind, token_in: Address, token_out: Address| SwapStep {
+            dex: DexId::PancakeSwap,
+            pool: if kind == SwapKind::Swap { Address::repeat_byte(1) } else { wbnb },
+            token_in,
+            token_out,
+            amount_in: U256::exp10(18),
+            amount_out: U256::exp10(18),
+            kind,
+        };
+        let opportunity = Opportunity {
+            id: 1,
+            timestamp_ms: 0,
+            chain: ChainId::Bsc,
+            profit_wei: U256::zero(),
+            gas_estimate: 300_000,
+            path: vec![
+                step(SwapKind::Wrap, Address::zero(), wbnb),
+                step(SwapKind::Swap, wbnb, busd),
+                step(SwapKind::Swap, busd, wbnb),
+                step(SwapKind::Unwrap, wbnb, Address::zero()),
+            ],
+            flash_loan_token: Address::zero(),
+            flash_loan_amount: U256::zero(),
+        };
+        assert_eq!(opportunity.wrap_gas(), WRAP_GAS + UNWRAP_GAS);
+        assert_eq!(opportunity.total_gas(), 300_000 + WRAP_GAS + UNWRAP_GAS);
+
+        // Steps serialized before wrap legs existed are swaps
+        let mut json = serde_json::to_value(&opportunity.path[1]).unwrap();
+        json.as_object_mut().unwrap().remove("kind");
+        let old: SwapStep = serde_json::from_value(json).unwrap();
+        assert_eq!(old.kind, SwapKind::Swap);
+    }
+}
@@ -0,0 +1,151 @@
+//! Local JSON-RPC server for tests
+//!
+//! Accepts keep-alive HTTP/1.1 connections on an ephemeral localhost port
+//! and answers each JSON-RPC request body with whatever the handler
+//! returns. Mock relays and nodes in the agent crates only supply the
+//! handler.
+//!
+//! Enabled in tests and with the `mock-rpc` feature.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Running server; stops accepting when dropped
+pub struct MockRpcServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MockRpcServer {
+    /// Bind `127.0.0.1:0` and answer every request with `handler(request)`
+    pub async fn start<F>(handler: F) -> std::io::Result<Self>
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let handler = Arc::new(handler);
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let _ = serve_connection(stream, &*handler).await;
+                });
+            }
+        });
+
+        Ok(Self { addr, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `http://` URL of the server
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for MockRpcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answer requests on one connection until it closes
+async fn serve_connection<F>(stream: TcpStream, handler: &F) -> std::io::Result<()>
+where
+    F: Fn(&Value) -> Value,
+{
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut content_length = 0usize;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body).await?;
+
+        let response = match serde_json::from_slice::<Value>(&body) {
+            Ok(request) => handler(&request),
+            Err(e) => json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": e.to_string() } }),
+        };
+        let payload = response.to_string();
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            payload.len(),
+            payload
+        );
+        reader.get_mut().write_all(reply.as_bytes()).await?;
+    }
+}
+
+/// JSON-RPC success reply to `request`
+pub fn rpc_result(request: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+}
+
+/// JSON-RPC error reply to `request`
+pub fn rpc_error(request: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_answers_keep_alive_requests() {
+        let server = MockRpcServer::start(|request| match request["method"].as_str() {
+            Some("echo") => rpc_result(request, request["params"].clone()),
+            _ => rpc_error(request, -32601, "method not found"),
+        })
+        .await
+        .unwrap();
+
+        let stream = TcpStream::connect(server.addr()).await.unwrap();
+        let mut reader = BufReader::new(stream);
+        for (method, expected) in [("echo", r#""result":[1]"#), ("nope", r#""code":-32601"#)] {
+            let body = json!({"jsonrpc": "2.0", "id": 7, "method": method, "params": [1]}).to_string();
+            let request = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            reader.get_mut().write_all(request.as_bytes()).await.unwrap();
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                match line.trim_end().split_once(": ") {
+                    Some(("Content-Length", len)) => content_length = len.parse().unwrap(),
+                    None if line.trim_end().is_empty() => break,
+                    _ => {}
+                }
+            }
+            let mut response = vec![0u8; content_length];
+            reader.read_exact(&mut response).await.unwrap();
+            let response = String::from_utf8(response).unwrap();
+            assert!(response.contains(expected), "{}", response);
+            assert!(response.contains(r#""id":7"#));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! USD valuation
+//!
+//! Profit is tracked in native wei, which isn't comparable across chains
+//! (ETH vs BNB) and isn't what operators reason in. A `PriceOracle`
+//! supplies native/USD rates for reporting; a missing rate yields no USD
+//! figure rather than an error, so reporting never blocks execution.
+
+use std::collections::HashMap;
+
+use ethers_core::types::U256;
+
+use crate::{ChainId, ExecutionResult};
+
+/// Source of native token / USD rates
+pub trait PriceOracle: Send + Sync {
+    /// USD price of one native token on `chain` (ETH, or BNB on BSC)
+    fn native_usd(&self, chain: ChainId) -> Option<f64>;
+}
+
+/// Oracle with fixed rates, for tests and manual overrides
+#[derive(Debug, Clone, Default)]
+pub struct FixedPriceOracle {
+    prices: HashMap<ChainId, f64>,
+}
+
+impl FixedPriceOracle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price(mut self, chain: ChainId, usd: f64) -> Self {
+        self.set_price(chain, usd);
+        self
+    }
+
+    pub fn set_price(&mut self, chain: ChainId, usd: f64) {
+        self.prices.insert(chain, usd);
+    }
+}
+
+impl PriceOracle for FixedPriceOracle {
+    fn native_usd(&self, chain: ChainId) -> Option<f64> {
+        self.prices.get(&chain).copied()
+    }
+}
+
+/// Wei amount in whole native tokens (18 decimals)
+pub fn wei_to_native(wei: U256) -> f64 {
+    let unit = U256::exp10(18);
+    let whole = wei / unit;
+    let fraction = (wei % unit).as_u128() as f64 / 1e18;
+    // Limbs are little-endian u64s
+    let whole = whole.0.iter().rev().fold(0.0, |acc, limb| acc * 18_446_744_073_709_551_616.0 + *limb as f64);
+    whole + fraction
+}
+
+/// Realized profit of `result` on `chain` in USD
+///
+/// None when the oracle has no usable rate for the chain.
+pub fn profit_usd(result: &ExecutionResult, chain: ChainId, oracle: &dyn PriceOracle) -> Option<f64> {
+    let rate = oracle.native_usd(chain).filter(|rate| rate.is_finite() && *rate > 0.0)?;
+    Some(wei_to_native(result.actual_profit) * rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(profit_wei: U256) -> ExecutionResult {
+        ExecutionResult {
+            opportunity_id: 1,
+            tx_hash: Default::default(),
+            success: true,
+            actual_profit: profit_wei,
+            gas_used: 250_000,
+            block_number: 18_000_000,
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_profit_converted_at_fixed_rate() {
+        let oracle = FixedPriceOracle::new()
+            .with_price(ChainId::Ethereum, 2_000.0)
+            .with_price(ChainId::Bsc, 300.0);
+
+        // 0.25 ETH at $2000
+        let profit = U256::exp10(17) * U256::from(25u64) / U256::from(10u64);
+        assert_eq!(profit_usd(&result(profit), ChainId::Ethereum, &oracle), Some(500.0));
+        // Same wei on BSC is BNB, at its own rate
+        assert_eq!(profit_usd(&result(profit), ChainId::Bsc, &oracle), Some(75.0));
+        assert_eq!(wei_to_native(U256::exp10(18) * U256::from(u128::MAX)), u128::MAX as f64);
+    }
+
+    #[test]
+    fn test_missing_rate_yields_none() {
+        let mut oracle = FixedPriceOracle::new();
+        let profit = result(U256::exp10(18));
+        assert_eq!(profit_usd(&profit, ChainId::Arbitrum, &oracle), None);
+
+        oracle.set_price(ChainId::Arbitrum, f64::NAN);
+        assert_eq!(profit_usd(&profit, ChainId::Arbitrum, &oracle), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! Signed profit and loss
+//!
+//! PnL in wei needs a sign, and squeezing `U256` amounts into `i128`
+//! panics or wraps on large values. `SignedWei` wraps `I256`, saturates
+//! instead of overflowing, and keeps PnL from being mixed up with
+//! unsigned amounts.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use ethers_core::types::{I256, U256};
+
+use crate::oracle::wei_to_native;
+
+/// Signed amount in wei (profit positive, loss negative)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SignedWei(pub I256);
+
+impl SignedWei {
+    pub const ZERO: SignedWei = SignedWei(I256::zero());
+
+    /// `gain - cost`, saturating at the I256 bounds
+    pub fn from_diff(gain: U256, cost: U256) -> Self {
+        if gain >= cost {
+            Self::from_magnitude(gain - cost, false)
+        } else {
+            Self::from_magnitude(cost - gain, true)
+        }
+    }
+
+    fn from_magnitude(magnitude: U256, negative: bool) -> Self {
+        let value = I256::try_from(magnitude).unwrap_or(I256::MAX);
+        Self(if negative { value.saturating_neg() } else { value })
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.0.is_positive()
+    }
+
+    /// Magnitude in wei
+    pub fn abs_wei(&self) -> U256 {
+        self.0.unsigned_abs()
+    }
+
+    /// Value in ETH (or the chain's 18-decimal native token)
+    pub fn to_eth_f64(&self) -> f64 {
+        let eth = wei_to_native(self.abs_wei());
+        if self.is_negative() {
+            -eth
+        } else {
+            eth
+        }
+    }
+
+    /// Value in wei as a float, for statistics
+    pub fn to_wei_f64(&self) -> f64 {
+        self.to_eth_f64() * 1e18
+    }
+}
+
+impl From<i128> for SignedWei {
+    fn from(wei: i128) -> Self {
+        Self(I256::from(wei))
+    }
+}
+
+impl From<I256> for SignedWei {
+    fn from(wei: I256) -> Self {
+        Self(wei)
+    }
+}
+
+impl Add for SignedWei {
+    type Output = SignedWei;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for SignedWei {
+    type Output = SignedWei;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl AddAssign for SignedWei {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for SignedWei {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for SignedWei {
+    type Output = SignedWei;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl Sum for SignedWei {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a SignedWei> for SignedWei {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Exact ETH with trailing zeros trimmed, e.g. `-0.25 ETH`
+impl fmt::Display for SignedWei {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = U256::exp10(18);
+        let abs = self.abs_wei();
+        let sign = if self.is_negative() { "-" } else { "" };
+        let fraction = format!("{:018}", (abs % unit).as_u64());
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}{} ETH", sign, abs / unit)
+        } else {
+            write!(f, "{}{}.{} ETH", sign, abs / unit, fraction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth_tenths(n: i128) -> SignedWei {
+        SignedWei::from(n * 100_000_000_000_000_000)
+    }
+
+    #[test]
+    fn test_negative_pnl() {
+        let loss = SignedWei::from_diff(U256::exp10(17), U256::exp10(18) / 4 * 3);
+        assert!(loss.is_negative());
+        assert_eq!(loss.abs_wei(), U256::from(650_000_000_000_000_000u64));
+        assert_eq!(loss.to_string(), "-0.65 ETH");
+        assert_eq!(loss.to_eth_f64(), -0.65);
+
+        let total: SignedWei = [eth_tenths(3), loss, eth_tenths(-1)].iter().sum();
+        assert_eq!(total, eth_tenths(3) - eth_tenths(1) + loss);
+        assert_eq!(total.to_string(), "-0.45 ETH");
+        assert_eq!(-total, SignedWei::from_diff(U256::exp10(17) * 45 / 10, U256::zero()));
+        assert_eq!(SignedWei::ZERO.to_string(), "0 ETH");
+    }
+
+    #[test]
+    fn test_large_magnitudes_saturate() {
+        // Beyond i128: 1e30 ETH in wei
+        let huge = SignedWei::from_diff(U256::exp10(48), U256::zero());
+        assert_eq!(huge.to_eth_f64(), 1e30);
+        assert_eq!((huge - huge - huge).to_eth_f64(), -1e30);
+
+        // Past I256::MAX clamps instead of wrapping negative
+        let max = SignedWei::from_diff(U256::MAX, U256::zero());
+        assert!(max.is_positive());
+        assert_eq!(max + huge, max);
+        assert!((-max - huge).is_negative());
+        assert!((-max - huge - huge) <= -max);
+    }
+
+    #[test]
+    fn test_eth_conversion() {
+        assert_eq!(eth_tenths(25).to_eth_f64(), 2.5);
+        assert_eq!(eth_tenths(25).to_string(), "2.5 ETH");
+        assert_eq!(SignedWei::from(1).to_string(), "0.000000000000000001 ETH");
+        assert_eq!(SignedWei::from(-1).to_wei_f64(), -1.0);
+    }
+}
@@ -0,0 +1,224 @@
+//! Retry with backoff for RPC calls
+//!
+//! Reserve warm-up, token metadata lookups and confirmation polling all
+//! call a node that sometimes times out or rate-limits. Instead of a retry
+//! loop at each call site, wrap the call in `retry_with_backoff`: transient
+//! errors are retried with exponential backoff, terminal ones (a revert, a
+//! bad argument) are returned at once.
+
+use std::future::Future;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Error message fragments that indicate a transient node-side failure
+const TRANSIENT_PATTERNS: &[&str] = &[
+    "timeout",
+    "timed out",
+    "rate limit",
+    "too many requests",
+    "429",
+    "502",
+    "503",
+    "504",
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "broken pipe",
+    "header not found",
+    "temporarily unavailable",
+];
+
+/// Whether an RPC error message looks transient (worth retrying)
+///
+/// For foreign error types that can't implement `Retryable`, e.g.
+/// `retry_with_backoff_if(op, &policy, |e| is_transient_rpc_error(&e.to_string()))`.
+pub fn is_transient_rpc_error(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    TRANSIENT_PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+/// Errors that know whether retrying can help
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+/// How often and how patiently to retry
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first (minimum 1)
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay_ms: u64,
+    /// Cap on the doubling delay
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 100,
+            max_delay_ms: 2_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based), doubling up to the cap
+    pub fn delay_ms(&self, retry: u32) -> u64 {
+        self.initial_delay_ms
+            .saturating_mul(1u64.checked_shl(retry).unwrap_or(u64::MAX))
+            .min(self.max_delay_ms)
+    }
+}
+
+/// Why a retried operation gave up
+#[derive(Error, Debug)]
+pub enum RetryError<E> {
+    #[error("terminal error: {0}")]
+    Terminal(E),
+
+    #[error("gave up after {attempts} attempts: {last}")]
+    Exhausted { attempts: u32, last: E },
+}
+
+impl<E> RetryError<E> {
+    /// The underlying error from the last attempt
+    pub fn into_inner(self) -> E {
+        match self {
+            RetryError::Terminal(e) | RetryError::Exhausted { last: e, .. } => e,
+        }
+    }
+}
+
+/// Run `op` until it succeeds, fails terminally or runs out of attempts
+pub async fn retry_with_backoff<F, Fut, T, E>(op: F, policy: &RetryPolicy) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Retryable,
+{
+    retry_with_backoff_if(op, policy, E::is_retryable).await
+}
+
+/// `retry_with_backoff` with the retryable/terminal split decided by `is_retryable`
+pub async fn retry_with_backoff_if<F, Fut, T, E, C>(
+    mut op: F,
+    policy: &RetryPolicy,
+    is_retryable: C,
+) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: Fn(&E) -> bool,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if !is_retryable(&e) => return Err(RetryError::Terminal(e)),
+            Err(e) if attempt >= max_attempts => {
+                return Err(RetryError::Exhausted { attempts: attempt, last: e })
+            }
+            Err(_) => {
+                tokio::time::sleep(Duration::from_millis(policy.delay_ms(attempt - 1))).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Debug, PartialEq)]
+    enum RpcError {
+        Timeout,
+        Reverted,
+    }
+
+    impl Retryable for RpcError {
+        fn is_retryable(&self) -> bool {
+            matches!(self, RpcError::Timeout)
+        }
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay_ms: 1,
+            max_delay_ms: 4,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_succeeds_after_transient_failures() {
+        let calls = Cell::new(0);
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                let n = calls.get();
+                async move { if n <= 2 { Err(RpcError::Timeout) } else { Ok(n) } }
+            },
+            &fast_policy(5),
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.get(), 3);
+
+        // Same failures with too few attempts
+        calls.set(0);
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(RpcError::Timeout) }
+            },
+            &fast_policy(2),
+        )
+        .await;
+        assert!(matches!(result, Err(RetryError::Exhausted { attempts: 2, last: RpcError::Timeout })));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_terminal_error_not_retried() {
+        let calls = Cell::new(0);
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err::<(), _>(RpcError::Reverted) }
+            },
+            &fast_policy(5),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err().into_inner(), RpcError::Reverted);
+        assert_eq!(calls.get(), 1);
+
+        // Classified by message for foreign error types
+        let result = retry_with_backoff_if(
+            || async { Err::<(), _>("execution reverted".to_string()) },
+            &fast_policy(5),
+            |e| is_transient_rpc_error(e),
+        )
+        .await;
+        assert!(matches!(result, Err(RetryError::Terminal(_))));
+    }
+
+    #[test]
+    fn test_delay_doubles_to_cap() {
+        let policy = RetryPolicy::default();
+        let delays: Vec<u64> = (0..7).map(|retry| policy.delay_ms(retry)).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_600, 2_000, 2_000]);
+        assert_eq!(policy.delay_ms(200), 2_000);
+
+        assert!(is_transient_rpc_error("HTTP 429 Too Many Requests"));
+        assert!(is_transient_rpc_error("request timed out"));
+        assert!(!is_transient_rpc_error("execution reverted: K"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Safe mode
+//!
+//! Shared switch that disables execution while detection keeps running.
+//! Unlike a halt, feeds, Dozer and the scanner carry on as normal so
+//! operators can watch what the system would do; only submission is
+//! refused. Clones share the same flag, so Neo can own it and hand a
+//! handle to each executor.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared execution kill switch (disabled by default)
+#[derive(Debug, Clone, Default)]
+pub struct SafeMode(Arc<AtomicBool>);
+
+impl SafeMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse all submissions until disabled
+    pub fn enable(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn disable(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_state() {
+        let safe_mode = SafeMode::new();
+        let handle = safe_mode.clone();
+        assert!(!handle.is_enabled());
+
+        safe_mode.enable();
+        assert!(handle.is_enabled());
+        handle.disable();
+        assert!(!safe_mode.is_enabled());
+    }
+}
@@ -0,0 +1,165 @@
+//! Profit split accounting
+//!
+//! Some deployments divide realized profit between named buckets, e.g. a
+//! treasury and a gas-refill wallet. This is bookkeeping only: each
+//! successful `ExecutionResult` is split by the configured shares and the
+//! running totals are kept (and published as metrics); nothing moves on
+//! chain.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use ethers::types::U256;
+use matrix_metrics::ArbitrageMetrics;
+use matrix_types::wei_to_native;
+
+use crate::{Chain, ExecutionResult, TrinityError};
+
+/// Named shares of profit in bps, summing to 100%
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfitSplit {
+    shares: Vec<(String, u64)>,
+}
+
+impl ProfitSplit {
+    /// Buckets in order with their share in bps; must be unique and total 10000
+    pub fn new<S: Into<String>>(shares: impl IntoIterator<Item = (S, u64)>) -> Result<Self, TrinityError> {
+        let shares: Vec<(String, u64)> = shares.into_iter().map(|(name, bps)| (name.into(), bps)).collect();
+        let total: u64 = shares.iter().map(|(_, bps)| bps).sum();
+        if total != 10_000 {
+            return Err(TrinityError::InvalidOperation(format!(
+                "Profit split totals {} bps, expected 10000",
+                total
+            )));
+        }
+        let mut seen = HashSet::new();
+        if let Some((name, _)) = shares.iter().find(|(name, _)| !seen.insert(name.as_str())) {
+            return Err(TrinityError::InvalidOperation(format!("Profit split bucket {} listed twice", name)));
+        }
+        Ok(Self { shares })
+    }
+
+    /// Divide `profit` by share; rounding dust goes to the first bucket
+    pub fn allocate(&self, profit: U256) -> Vec<(String, U256)> {
+        let mut allocation: Vec<(String, U256)> = self
+            .shares
+            .iter()
+            .map(|(name, bps)| (name.clone(), profit * U256::from(*bps) / U256::from(10_000u64)))
+            .collect();
+        let allocated = allocation.iter().fold(U256::zero(), |sum, (_, amount)| sum + amount);
+        if let Some((_, first)) = allocation.first_mut() {
+            *first += profit - allocated;
+        }
+        allocation
+    }
+
+    pub fn buckets(&self) -> impl Iterator<Item = &str> {
+        self.shares.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// Cumulative profit per bucket
+pub struct ProfitLedger {
+    chain: Chain,
+    split: ProfitSplit,
+    totals: BTreeMap<String, U256>,
+    metrics: Option<Arc<ArbitrageMetrics>>,
+}
+
+impl ProfitLedger {
+    pub fn new(chain: Chain, split: ProfitSplit) -> Self {
+        let totals = split.buckets().map(|name| (name.to_string(), U256::zero())).collect();
+        Self {
+            chain,
+            split,
+            totals,
+            metrics: None,
+        }
+    }
+
+    /// Also publish allocations as `matrix_profit_allocated_eth`
+    pub fn with_metrics(mut self, metrics: Arc<ArbitrageMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Split a realized execution's profit; failed or zero-profit ones allocate nothing
+    pub fn record(&mut self, result: &ExecutionResult) -> Vec<(String, U256)> {
+        if !result.success || result.actual_profit.is_zero() {
+            return Vec::new();
+        }
+
+        let allocation = self.split.allocate(result.actual_profit);
+        let chain = format!("{:?}", self.chain).to_lowercase();
+        for (bucket, amount) in &allocation {
+            *self.totals.entry(bucket.clone()).or_default() += *amount;
+            if let Some(metrics) = &self.metrics {
+                metrics.record_profit_allocation(&chain, bucket, wei_to_native(*amount));
+            }
+        }
+        allocation
+    }
+
+    /// Total allocated to `bucket` so far
+    pub fn total(&self, bucket: &str) -> U256 {
+        self.totals.get(bucket).copied().unwrap_or_default()
+    }
+
+    /// Totals for every bucket, by name
+    pub fn totals(&self) -> &BTreeMap<String, U256> {
+        &self.totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::H256;
+    use prometheus::Registry;
+
+    fn executed(profit: U256, success: bool) -> ExecutionResult {
+        ExecutionResult {
+            tx_hash: H256::zero(),
+            success,
+            actual_profit: profit,
+            gas_used: 250_000,
+            block_number: 1,
+        }
+    }
+
+    #[test]
+    fn test_seventy_thirty_split_sums() {
+        let metrics = Arc::new(ArbitrageMetrics::new(&Registry::new()).unwrap());
+        let split = ProfitSplit::new([("treasury", 7_000), ("gas_refill", 3_000)]).unwrap();
+        let mut ledger = ProfitLedger::new(Chain::Ethereum, split).with_metrics(metrics.clone());
+
+        let milli = U256::exp10(15);
+        let profits = [milli * 100, milli * 250, U256::from(1_001u64), milli * 50];
+        for profit in profits {
+            let allocation = ledger.record(&executed(profit, true));
+            assert_eq!(allocation[0].1 + allocation[1].1, profit);
+        }
+        // Failures realize nothing
+        assert!(ledger.record(&executed(milli * 999, false)).is_empty());
+
+        // 1001 wei: 700 + 300, plus 1 wei of dust to the treasury
+        let total: U256 = profits.iter().fold(U256::zero(), |sum, p| sum + p);
+        assert_eq!(ledger.total("treasury"), milli * 280 + U256::from(701u64));
+        assert_eq!(ledger.total("gas_refill"), milli * 120 + U256::from(300u64));
+        assert_eq!(ledger.total("treasury") + ledger.total("gas_refill"), total);
+
+        let gauge = |bucket: &str| metrics.profit_allocated_eth.with_label_values(&["ethereum", bucket]).get();
+        assert!((gauge("treasury") - 0.28).abs() < 1e-9);
+        assert!((gauge("gas_refill") - 0.12).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_split_must_total_100_percent() {
+        assert!(ProfitSplit::new([("treasury", 7_000), ("gas_refill", 2_000)]).is_err());
+        assert!(ProfitSplit::new([("treasury", 5_000), ("treasury", 5_000)]).is_err());
+        assert!(ProfitSplit::new(Vec::<(String, u64)>::new()).is_err());
+
+        let all = ProfitSplit::new([("treasury", 10_000)]).unwrap();
+        assert_eq!(all.allocate(U256::from(7u64)), vec![("treasury".to_string(), U256::from(7u64))]);
+    }
+}
@@ -0,0 +1,218 @@
+//! Execution confirmation
+//!
+//! An `ExecutionResult` is recorded at inclusion, but a reorg can orphan the
+//! inclusion block. `confirm_execution` waits for N confirmations and
+//! downgrades the result to failed if the transaction drops off the
+//! canonical chain, so reported PnL only counts trades that stuck.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use ethers::types::{H256, U256};
+use matrix_config::{ChainConfig, DEFAULT_MIN_CONFIRMATIONS};
+
+use crate::{ExecutionResult, TrinityError};
+
+/// Chain queries needed to confirm an execution
+#[async_trait]
+pub trait ChainView: Send + Sync {
+    /// Current head block number
+    async fn block_number(&self) -> Result<u64, TrinityError>;
+
+    /// Block including `tx_hash` on the canonical chain, if any
+    async fn inclusion_block(&self, tx_hash: H256) -> Result<Option<u64>, TrinityError>;
+}
+
+#[async_trait]
+impl<M: Middleware> ChainView for M {
+    async fn block_number(&self) -> Result<u64, TrinityError> {
+        self.get_block_number()
+            .await
+            .map(|n| n.as_u64())
+            .map_err(|e| TrinityError::ConfirmationFailed(e.to_string()))
+    }
+
+    async fn inclusion_block(&self, tx_hash: H256) -> Result<Option<u64>, TrinityError> {
+        let receipt = self
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| TrinityError::ConfirmationFailed(e.to_string()))?;
+        Ok(receipt.and_then(|r| r.block_number).map(|n| n.as_u64()))
+    }
+}
+
+/// Confirmation settings
+#[derive(Debug, Clone)]
+pub struct ConfirmationConfig {
+    /// Blocks (including the inclusion block) required before a result is final
+    pub confirmations: u64,
+    /// Time between chain polls
+    pub poll_interval_ms: u64,
+    /// Give up waiting after this long
+    pub timeout_ms: u64,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            confirmations: DEFAULT_MIN_CONFIRMATIONS,
+            poll_interval_ms: 1_000,
+            timeout_ms: 120_000,   // 2 minutes
+        }
+    }
+}
+
+impl ConfirmationConfig {
+    /// `chain`'s `min_confirmations`, polled once per block
+    pub fn for_chain(chain: &ChainConfig) -> Self {
+        Self {
+            confirmations: chain.min_confirmations.max(1),
+            poll_interval_ms: chain.block_time_ms.max(1),
+            ..Self::default()
+        }
+    }
+}
+
+/// Where an included execution stands on its way to final
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    /// On chain with `confirmations` blocks so far, not yet enough
+    Pending { confirmations: u64 },
+    /// Reached the required confirmations; profit may be booked
+    Confirmed,
+    /// No longer on the canonical chain
+    Dropped,
+}
+
+/// Poll once: how many confirmations `result` has against `required`
+///
+/// Follows a re-inclusion by updating `block_number`; a dropped
+/// transaction is marked `success: false` with zero profit.
+pub async fn check_confirmations<C: ChainView + ?Sized>(
+    chain: &C,
+    result: &mut ExecutionResult,
+    required: u64,
+) -> Result<ExecutionStatus, TrinityError> {
+    let inclusion = match chain.inclusion_block(result.tx_hash).await? {
+        Some(block) => block,
+        None => {
+            tracing::warn!(
+                "TRINITY: {:?} no longer on canonical chain (was block {}), marking failed",
+                result.tx_hash,
+                result.block_number
+            );
+            result.success = false;
+            result.actual_profit = U256::zero();
+            return Ok(ExecutionStatus::Dropped);
+        }
+    };
+
+    if inclusion != result.block_number {
+        tracing::warn!(
+            "TRINITY: {:?} reorged from block {} to {}",
+            result.tx_hash,
+            result.block_number,
+            inclusion
+        );
+        result.block_number = inclusion;
+    }
+
+    let confirmations = (chain.block_number().await? + 1).saturating_sub(inclusion);
+    if confirmations >= required {
+        Ok(ExecutionStatus::Confirmed)
+    } else {
+        Ok(ExecutionStatus::Pending { confirmations })
+    }
+}
+
+/// Wait for `result` to reach the configured confirmations
+///
+/// Returns the result with `success: false` and zero profit if the
+/// transaction is no longer on the canonical chain, or with an updated
+/// `block_number` if it was re-included in a different block. Failed
+/// results are returned unchanged.
+pub async fn confirm_execution<C: ChainView + ?Sized>(
+    chain: &C,
+    mut result: ExecutionResult,
+    config: &ConfirmationConfig,
+) -> Result<ExecutionResult, TrinityError> {
+    if !result.success {
+        return Ok(result);
+    }
+
+    let wait = async {
+        loop {
+            match check_confirmations(chain, &mut result, config.confirmations).await? {
+                ExecutionStatus::Pending { .. } => {
+                    tokio::time::sleep(Duration::from_millis(config.poll_interval_ms)).await
+                }
+                ExecutionStatus::Confirmed | ExecutionStatus::Dropped => return Ok(()),
+            }
+        }
+    };
+
+    match tokio::time::timeout(Duration::from_millis(config.timeout_ms), wait).await {
+        Ok(Ok(())) => Ok(result),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(TrinityError::ConfirmationFailed(format!(
+            "{:?} not confirmed after {}ms",
+            result.tx_hash, config.timeout_ms
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{included, MockChain};
+
+    fn fast_config() -> ConfirmationConfig {
+        ConfirmationConfig {
+            confirmations: 3,
+            poll_interval_ms: 1,
+            timeout_ms: 1_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirms_after_n_blocks() {
+        let chain = MockChain::new(vec![(100, Some(100)), (101, Some(100)), (102, Some(100))]);
+
+        let confirmed = confirm_execution(&chain, included(0xab, 100), &fast_config()).await.unwrap();
+        assert!(confirmed.success);
+        assert_eq!(confirmed.block_number, 100);
+        assert_eq!(confirmed.actual_profit, U256::exp10(17));
+    }
+
+    #[tokio::test]
+    async fn test_reorged_away_is_downgraded() {
+        // Included at 100, then the block is orphaned before 3 confirmations
+        let chain = MockChain::new(vec![(100, Some(100)), (101, Some(100)), (101, None)]);
+
+        let result = confirm_execution(&chain, included(0xab, 100), &fast_config()).await.unwrap();
+        assert!(!result.success);
+        assert!(result.actual_profit.is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_reincluded_in_new_block() {
+        let chain = MockChain::new(vec![(100, Some(100)), (101, Some(101)), (103, Some(101))]);
+
+        let result = confirm_execution(&chain, included(0xab, 100), &fast_config()).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.block_number, 101);
+    }
+
+    #[tokio::test]
+    async fn test_times_out_without_confirmations() {
+        let chain = MockChain::new(vec![(100, Some(100))]);
+        let config = ConfirmationConfig {
+            timeout_ms: 20,
+            ..fast_config()
+        };
+
+        let result = confirm_execution(&chain, included(0xab, 100), &config).await;
+        assert!(matches!(result, Err(TrinityError::ConfirmationFailed(_))));
+    }
+}
@@ -0,0 +1,59 @@
+//! Flash loan provider selection
+//!
+//! Each chain allows a set of providers, each lending a set of tokens.
+//! For a given token Trinity borrows from the cheapest provider that lends
+//! it; among equal premiums the first configured provider wins.
+
+use ethers::types::Address;
+use matrix_types::FlashLoanProvider;
+
+/// A provider allowed on this chain and the tokens it lends
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashLoanSource {
+    pub provider: FlashLoanProvider,
+    pub tokens: Vec<Address>,
+}
+
+impl FlashLoanSource {
+    pub fn new(provider: FlashLoanProvider, tokens: Vec<Address>) -> Self {
+        Self { provider, tokens }
+    }
+
+    pub fn supports(&self, token: Address) -> bool {
+        self.tokens.contains(&token)
+    }
+}
+
+/// Cheapest provider in `sources` that lends `token`
+pub fn select_provider(sources: &[FlashLoanSource], token: Address) -> Option<FlashLoanProvider> {
+    sources
+        .iter()
+        .filter(|source| source.supports(token))
+        .min_by_key(|source| source.provider.premium_bps())
+        .map(|source| source.provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cheapest_supporting_provider_selected() {
+        let weth = Address::repeat_byte(1);
+        let usdc = Address::repeat_byte(2);
+        let pepe = Address::repeat_byte(3);
+        let sources = vec![
+            FlashLoanSource::new(FlashLoanProvider::Aave, vec![weth, usdc, pepe]),
+            FlashLoanSource::new(FlashLoanProvider::Balancer, vec![weth]),
+            FlashLoanSource::new(FlashLoanProvider::Dydx, vec![weth, usdc]),
+        ];
+
+        // Balancer and dYdX are both free: the first configured wins
+        assert_eq!(select_provider(&sources, weth), Some(FlashLoanProvider::Balancer));
+        assert_eq!(select_provider(&sources, usdc), Some(FlashLoanProvider::Dydx));
+        // Only Aave lends it, premium or not
+        assert_eq!(select_provider(&sources, pepe), Some(FlashLoanProvider::Aave));
+        assert_eq!(select_provider(&sources, Address::zero()), None);
+        assert_eq!(select_provider(&[], weth), None);
+    }
+}
@@ -11,6 +11,10 @@ use thiserror::Error;
 pub const FLASHBOTS_RELAY: &str = "https://relay.flashbots.net";
 pub const FLASHBOTS_PROTECT: &str = "https://rpc.flashbots.net";
 
+/// Default bundle limits checked locally before submission
+pub const DEFAULT_MAX_BUNDLE_TXS: usize = 100;
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;   // Ethereum mainnet
+
 /// Flashbots errors
 #[derive(Error, Debug)]
 pub enum FlashbotsError {
@@ -28,6 +32,9 @@ pub enum FlashbotsError {
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
 }
 
 /// Flashbots bundle
@@ -54,6 +61,13 @@ pub struct Bundle {
     pub reverting_tx_hashes: Vec<String>,
 }
 
+impl Bundle {
+    /// Target block parsed from the hex `block_number`
+    pub fn target_block(&self) -> Option<U64> {
+        U64::from_str_radix(self.block_number.trim_start_matches("0x"), 16).ok()
+    }
+}
+
 /// Simulation result
 #[derive(Debug, Clone, Deserialize)]
 pub struct SimulationResult {
@@ -77,6 +91,35 @@ pub struct SimulationResult {
     pub results: Option<Vec<TxSimResult>>,
 }
 
+impl SimulationResult {
+    /// Check the simulation is safe to submit
+    ///
+    /// Fails if any transaction reverted that is not in `allowed_reverts`,
+    /// or if the coinbase diff (builder payment) is negative.
+    pub fn check(&self, allowed_reverts: &[String]) -> Result<(), FlashbotsError> {
+        for tx in self.results.iter().flatten() {
+            if let Some(reason) = &tx.revert {
+                if !allowed_reverts.iter().any(|h| h.eq_ignore_ascii_case(&tx.tx_hash)) {
+                    return Err(FlashbotsError::SimulationFailed(format!(
+                        "Transaction {} reverted: {}",
+                        tx.tx_hash, reason
+                    )));
+                }
+            }
+        }
+
+        let diff = self.coinbase_diff.trim();
+        if diff.starts_with('-') && diff.trim_start_matches(['-', '0']).chars().any(|c| c != '.') {
+            return Err(FlashbotsError::SimulationFailed(format!(
+                "Negative coinbase diff: {}",
+                self.coinbase_diff
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 /// Per-transaction simulation result
 #[derive(Debug, Clone, Deserialize)]
 pub struct TxSimResult {
@@ -104,11 +147,86 @@ pub struct SubmissionResult {
     pub bundle_hash: String,
 }
 
+/// Builder timestamp entry in bundle stats
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BuilderTimestamp {
+    /// Builder public key
+    pub pubkey: String,
+
+    /// When the builder acted on the bundle (ISO 8601)
+    pub timestamp: String,
+}
+
+/// Bundle stats from `flashbots_getBundleStats`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BundleStats {
+    /// Bundle passed relay simulation
+    pub is_simulated: bool,
+
+    /// Bundle was prioritized by the relay
+    pub is_high_priority: bool,
+
+    /// Simulation time (ISO 8601)
+    pub simulated_at: Option<String>,
+
+    /// Submission time (ISO 8601)
+    pub submitted_at: Option<String>,
+
+    /// Builders that considered the bundle
+    pub considered_by_builders_at: Vec<BuilderTimestamp>,
+
+    /// Builders that sealed the bundle into a block
+    pub sealed_by_builders_at: Vec<BuilderTimestamp>,
+}
+
+impl BundleStats {
+    /// Whether any builder sealed the bundle into a block
+    ///
+    /// A sealed block may still lose the slot auction; confirm on-chain
+    /// before treating the bundle as final.
+    pub fn is_included(&self) -> bool {
+        !self.sealed_by_builders_at.is_empty()
+    }
+
+    /// Whether any builder considered the bundle
+    pub fn is_considered(&self) -> bool {
+        !self.considered_by_builders_at.is_empty()
+    }
+}
+
+/// State an `eth_callBundle` simulation runs on top of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateBlock {
+    Latest,
+    /// Latest block plus mempool-visible changes
+    Pending,
+    Number(U64),
+}
+
+impl StateBlock {
+    /// `stateBlockNumber` parameter value
+    pub fn to_param(&self) -> String {
+        match self {
+            StateBlock::Latest => "latest".to_string(),
+            StateBlock::Pending => "pending".to_string(),
+            StateBlock::Number(block) => format!("0x{:x}", block),
+        }
+    }
+}
+
+impl From<U64> for StateBlock {
+    fn from(block: U64) -> Self {
+        StateBlock::Number(block)
+    }
+}
+
 /// Flashbots client
 pub struct FlashbotsClient {
     client: Client,
     relay_url: String,
     signing_key: Option<String>,
+    require_simulation: bool,
 }
 
 impl FlashbotsClient {
@@ -118,6 +236,7 @@ impl FlashbotsClient {
             client: Client::new(),
             relay_url: relay_url.unwrap_or_else(|| FLASHBOTS_RELAY.to_string()),
             signing_key: None,
+            require_simulation: false,
         }
     }
 
@@ -127,24 +246,37 @@ impl FlashbotsClient {
         self
     }
 
-    /// Simulate a bundle
-    pub async fn simulate_bundle(
-        &self,
-        bundle: &Bundle,
-        state_block: U64,
-    ) -> Result<SimulationResult, FlashbotsError> {
+    /// Require `send_bundle` to simulate first and abort on revert
+    pub fn with_simulation_required(mut self, required: bool) -> Self {
+        self.require_simulation = required;
+        self
+    }
+
+    /// `eth_callBundle` request body for `bundle` on top of `state_block`
+    pub fn call_bundle_request(bundle: &Bundle, state_block: StateBlock) -> serde_json::Value {
         let params = serde_json::json!({
             "txs": bundle.transactions,
             "blockNumber": bundle.block_number,
-            "stateBlockNumber": format!("0x{:x}", state_block),
+            "stateBlockNumber": state_block.to_param(),
         });
 
-        let request = serde_json::json!({
+        serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
             "method": "eth_callBundle",
             "params": [params],
-        });
+        })
+    }
+
+    /// Simulate a bundle
+    ///
+    /// Accepts a block number or a `StateBlock` tag such as `Pending`.
+    pub async fn simulate_bundle(
+        &self,
+        bundle: &Bundle,
+        state_block: impl Into<StateBlock>,
+    ) -> Result<SimulationResult, FlashbotsError> {
+        let request = Self::call_bundle_request(bundle, state_block.into());
 
         let response = self
             .client
@@ -177,6 +309,17 @@ impl FlashbotsClient {
         &self,
         bundle: &Bundle,
     ) -> Result<SubmissionResult, FlashbotsError> {
+        if self.require_simulation {
+            // Simulate against the parent of the target block
+            let target = bundle.target_block().ok_or_else(|| {
+                FlashbotsError::InvalidResponse(format!("Invalid block number: {}", bundle.block_number))
+            })?;
+            let state_block = target.saturating_sub(U64::one());
+
+            let simulation = self.simulate_bundle(bundle, state_block).await?;
+            simulation.check(&bundle.reverting_tx_hashes)?;
+        }
+
         let params = serde_json::json!({
             "txs": bundle.transactions,
             "blockNumber": bundle.block_number,
@@ -230,7 +373,7 @@ impl FlashbotsClient {
         &self,
         bundle_hash: &str,
         block_number: U64,
-    ) -> Result<serde_json::Value, FlashbotsError> {
+    ) -> Result<BundleStats, FlashbotsError> {
         let request = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -250,7 +393,21 @@ impl FlashbotsClient {
             .await?;
 
         let result: serde_json::Value = response.json().await?;
-        Ok(result)
+
+        if let Some(error) = result.get("error") {
+            return Err(FlashbotsError::InvalidResponse(
+                error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error").to_string(),
+            ));
+        }
+
+        let stats: BundleStats = serde_json::from_value(
+            result.get("result").cloned().ok_or_else(|| {
+                FlashbotsError::InvalidResponse("Missing result".to_string())
+            })?,
+        )
+        .map_err(|e| FlashbotsError::InvalidResponse(e.to_string()))?;
+
+        Ok(stats)
     }
 
     /// Sign payload for Flashbots authentication
@@ -262,13 +419,84 @@ impl FlashbotsClient {
     }
 }
 
+/// A signed transaction tagged with its role in an arbitrage bundle
+///
+/// Variants are in execution order: approvals, then the flash loan, the
+/// swaps it funds, and the repayment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleStep {
+    Approval(String),
+    FlashLoanOpen(String),
+    Swap(String),
+    Repay(String),
+}
+
+/// Role of a `BundleStep`, ordered by execution phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StepKind {
+    Approval,
+    FlashLoanOpen,
+    Swap,
+    Repay,
+}
+
+impl BundleStep {
+    pub fn kind(&self) -> StepKind {
+        match self {
+            BundleStep::Approval(_) => StepKind::Approval,
+            BundleStep::FlashLoanOpen(_) => StepKind::FlashLoanOpen,
+            BundleStep::Swap(_) => StepKind::Swap,
+            BundleStep::Repay(_) => StepKind::Repay,
+        }
+    }
+
+    fn into_transaction(self) -> String {
+        match self {
+            BundleStep::Approval(tx)
+            | BundleStep::FlashLoanOpen(tx)
+            | BundleStep::Swap(tx)
+            | BundleStep::Repay(tx) => tx,
+        }
+    }
+}
+
+/// Check that typed steps run approvals, flash loan, swaps, repay in order
+///
+/// At most one flash loan; a flash loan needs a swap and a repayment, and
+/// a repayment needs a flash loan.
+pub fn validate_step_order(steps: &[StepKind]) -> Result<(), FlashbotsError> {
+    if let Some(pair) = steps.windows(2).find(|pair| pair[1] < pair[0]) {
+        return Err(FlashbotsError::InvalidBundle(format!("{:?} step after {:?}", pair[1], pair[0])));
+    }
+
+    let count = |kind: StepKind| steps.iter().filter(|s| **s == kind).count();
+    let (loans, swaps, repays) = (count(StepKind::FlashLoanOpen), count(StepKind::Swap), count(StepKind::Repay));
+    if loans > 1 {
+        return Err(FlashbotsError::InvalidBundle(format!("{} flash loans in one bundle", loans)));
+    }
+    if loans == 1 && swaps == 0 {
+        return Err(FlashbotsError::InvalidBundle("flash loan without a swap".to_string()));
+    }
+    if (loans == 1) != (repays > 0) {
+        let message = if loans == 1 { "flash loan is never repaid" } else { "repay without a flash loan" };
+        return Err(FlashbotsError::InvalidBundle(message.to_string()));
+    }
+    Ok(())
+}
+
 /// Bundle builder helper
 pub struct BundleBuilder {
     transactions: Vec<String>,
+    /// Roles of the transactions added with `add_step`, in order
+    steps: Vec<StepKind>,
     block_number: U64,
     min_timestamp: Option<u64>,
     max_timestamp: Option<u64>,
     reverting_tx_hashes: Vec<String>,
+    /// Gas limit per transaction, None once any tx was added without one
+    gas_limits: Option<Vec<u64>>,
+    max_transactions: usize,
+    block_gas_limit: u64,
 }
 
 impl BundleBuilder {
@@ -276,25 +504,68 @@ impl BundleBuilder {
     pub fn new(block_number: U64) -> Self {
         Self {
             transactions: Vec::new(),
+            steps: Vec::new(),
             block_number,
             min_timestamp: None,
             max_timestamp: None,
             reverting_tx_hashes: Vec::new(),
+            gas_limits: Some(Vec::new()),
+            max_transactions: DEFAULT_MAX_BUNDLE_TXS,
+            block_gas_limit: DEFAULT_BLOCK_GAS_LIMIT,
         }
     }
 
     /// Add a signed transaction
     pub fn add_transaction(mut self, signed_tx: String) -> Self {
         self.transactions.push(signed_tx);
+        self.gas_limits = None;
+        self
+    }
+
+    /// Add a signed transaction with its gas limit
+    pub fn add_transaction_with_gas(mut self, signed_tx: String, gas_limit: u64) -> Self {
+        self.transactions.push(signed_tx);
+        if let Some(limits) = &mut self.gas_limits {
+            limits.push(gas_limit);
+        }
         self
     }
 
+    /// Add a typed step; `try_build` checks the steps' order
+    pub fn add_step(mut self, step: BundleStep) -> Self {
+        self.steps.push(step.kind());
+        self.add_transaction(step.into_transaction())
+    }
+
+    /// Add a typed step with its gas limit
+    pub fn add_step_with_gas(mut self, step: BundleStep, gas_limit: u64) -> Self {
+        self.steps.push(step.kind());
+        self.add_transaction_with_gas(step.into_transaction(), gas_limit)
+    }
+
     /// Add multiple transactions
     pub fn add_transactions(mut self, txs: Vec<String>) -> Self {
+        if !txs.is_empty() {
+            self.gas_limits = None;
+        }
         self.transactions.extend(txs);
         self
     }
 
+    /// Override the transaction count and block gas limits
+    pub fn with_limits(mut self, max_transactions: usize, block_gas_limit: u64) -> Self {
+        self.max_transactions = max_transactions;
+        self.block_gas_limit = block_gas_limit;
+        self
+    }
+
+    /// Total gas of the bundle, if every transaction's gas limit is known
+    pub fn total_gas(&self) -> Option<u64> {
+        self.gas_limits
+            .as_ref()
+            .map(|limits| limits.iter().fold(0u64, |acc, g| acc.saturating_add(*g)))
+    }
+
     /// Set timestamp constraints
     pub fn with_timestamps(mut self, min: Option<u64>, max: Option<u64>) -> Self {
         self.min_timestamp = min;
@@ -308,8 +579,48 @@ impl BundleBuilder {
         self
     }
 
-    /// Build the bundle
+    /// Validate and build the bundle
+    ///
+    /// Rejects empty bundles, bundles over the transaction limit, (when
+    /// gas is known) bundles whose total gas exceeds the block gas limit,
+    /// and typed steps out of order (see `validate_step_order`). Untyped
+    /// transactions are not part of the order check.
+    pub fn try_build(self) -> Result<Bundle, FlashbotsError> {
+        if self.transactions.is_empty() {
+            return Err(FlashbotsError::InvalidBundle("bundle has no transactions".to_string()));
+        }
+
+        validate_step_order(&self.steps)?;
+
+        if self.transactions.len() > self.max_transactions {
+            return Err(FlashbotsError::InvalidBundle(format!(
+                "{} transactions exceeds max {}",
+                self.transactions.len(),
+                self.max_transactions
+            )));
+        }
+
+        if let Some(total_gas) = self.total_gas() {
+            if total_gas > self.block_gas_limit {
+                return Err(FlashbotsError::InvalidBundle(format!(
+                    "total gas {} exceeds block gas limit {}",
+                    total_gas, self.block_gas_limit
+                )));
+            }
+        }
+
+        Ok(self.build_unchecked())
+    }
+
+    /// Build the bundle, panicking if it fails `try_build` validation
     pub fn build(self) -> Bundle {
+        match self.try_build() {
+            Ok(bundle) => bundle,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn build_unchecked(self) -> Bundle {
         Bundle {
             transactions: self.transactions,
             block_number: format!("0x{:x}", self.block_number),
@@ -323,6 +634,7 @@ impl BundleBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock_relay::MockRelay;
 
     #[test]
     fn test_bundle_builder() {
@@ -337,6 +649,94 @@ mod tests {
         assert_eq!(bundle.min_timestamp, Some(1699999999));
     }
 
+    #[test]
+    fn test_bundle_builder_rejects_over_limit() {
+        // Too many transactions
+        let result = BundleBuilder::new(U64::from(100))
+            .add_transactions(vec!["0x01".to_string(); 3])
+            .with_limits(2, DEFAULT_BLOCK_GAS_LIMIT)
+            .try_build();
+        assert!(matches!(result, Err(FlashbotsError::InvalidBundle(_))));
+
+        // Known gas over the block limit
+        let result = BundleBuilder::new(U64::from(100))
+            .add_transaction_with_gas("0x01".to_string(), 20_000_000)
+            .add_transaction_with_gas("0x02".to_string(), 15_000_000)
+            .try_build();
+        assert!(matches!(result, Err(FlashbotsError::InvalidBundle(msg)) if msg.contains("35000000")));
+
+        // Empty
+        assert!(BundleBuilder::new(U64::from(100)).try_build().is_err());
+    }
+
+    #[test]
+    fn test_bundle_builder_gas_check_needs_known_gas() {
+        let builder = BundleBuilder::new(U64::from(100))
+            .add_transaction_with_gas("0x01".to_string(), 200_000)
+            .add_transaction_with_gas("0x02".to_string(), 300_000);
+        assert_eq!(builder.total_gas(), Some(500_000));
+        assert_eq!(builder.try_build().unwrap().transactions.len(), 2);
+
+        // One tx without gas makes the total unknown; only the count is checked
+        let builder = BundleBuilder::new(U64::from(100))
+            .add_transaction_with_gas("0x01".to_string(), 40_000_000)
+            .add_transaction("0x02".to_string());
+        assert_eq!(builder.total_gas(), None);
+        assert!(builder.try_build().is_ok());
+    }
+
+    #[test]
+    fn test_bundle_steps_in_order() {
+        let tx = |n: u8| format!("0x{:02x}", n);
+        let bundle = BundleBuilder::new(U64::from(100))
+            .add_step(BundleStep::Approval(tx(1)))
+            .add_step_with_gas(BundleStep::FlashLoanOpen(tx(2)), 100_000)
+            .add_step(BundleStep::Swap(tx(3)))
+            .add_step(BundleStep::Swap(tx(4)))
+            .add_step(BundleStep::Repay(tx(5)))
+            .try_build()
+            .unwrap();
+        assert_eq!(bundle.transactions, (1..=5).map(tx).collect::<Vec<_>>());
+
+        // Plain swaps with own capital need no loan
+        assert!(validate_step_order(&[StepKind::Approval, StepKind::Swap]).is_ok());
+        assert!(validate_step_order(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_bundle_steps_out_of_order_rejected() {
+        use StepKind::*;
+
+        let result = BundleBuilder::new(U64::from(100))
+            .add_step(BundleStep::Swap("0x01".to_string()))
+            .add_step(BundleStep::FlashLoanOpen("0x02".to_string()))
+            .add_step(BundleStep::Repay("0x03".to_string()))
+            .try_build();
+        assert!(matches!(result, Err(FlashbotsError::InvalidBundle(msg)) if msg == "FlashLoanOpen step after Swap"));
+
+        for (steps, reason) in [
+            (vec![FlashLoanOpen, Swap, Repay, Swap], "Swap step after Repay"),
+            (vec![FlashLoanOpen, Approval, Swap, Repay], "Approval step after FlashLoanOpen"),
+            (vec![FlashLoanOpen, FlashLoanOpen, Swap, Repay], "2 flash loans in one bundle"),
+            (vec![FlashLoanOpen, Repay], "flash loan without a swap"),
+            (vec![FlashLoanOpen, Swap], "flash loan is never repaid"),
+            (vec![Swap, Repay], "repay without a flash loan"),
+        ] {
+            match validate_step_order(&steps) {
+                Err(FlashbotsError::InvalidBundle(msg)) => assert_eq!(msg, reason, "{:?}", steps),
+                other => panic!("{:?} accepted: {:?}", steps, other),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid bundle")]
+    fn test_bundle_build_panics_when_invalid() {
+        BundleBuilder::new(U64::from(100))
+            .add_transaction_with_gas("0x01".to_string(), 31_000_000)
+            .build();
+    }
+
     #[test]
     fn test_flashbots_client_creation() {
         let client = FlashbotsClient::new(None);
@@ -345,4 +745,157 @@ mod tests {
         let custom_client = FlashbotsClient::new(Some("https://custom.relay".to_string()));
         assert_eq!(custom_client.relay_url, "https://custom.relay");
     }
+
+    #[test]
+    fn test_call_bundle_state_block_param() {
+        let bundle = test_bundle();
+        let state_block = |block: StateBlock| {
+            FlashbotsClient::call_bundle_request(&bundle, block)["params"][0]["stateBlockNumber"].clone()
+        };
+
+        assert_eq!(state_block(StateBlock::Latest), serde_json::json!("latest"));
+        assert_eq!(state_block(StateBlock::Pending), serde_json::json!("pending"));
+        assert_eq!(state_block(StateBlock::Number(U64::from(17_999_999))), serde_json::json!("0x112a87f"));
+        assert_eq!(StateBlock::from(U64::from(255)), StateBlock::Number(U64::from(255)));
+
+        let request = FlashbotsClient::call_bundle_request(&bundle, StateBlock::Pending);
+        assert_eq!(request["method"], "eth_callBundle");
+        assert_eq!(request["params"][0]["blockNumber"], serde_json::json!(bundle.block_number));
+        assert_eq!(request["params"][0]["txs"], serde_json::json!(bundle.transactions));
+    }
+
+    fn call_bundle_response(revert: Option<&str>, coinbase_diff: &str) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "bundleHash": "0xbundle",
+                "coinbaseDiff": coinbase_diff,
+                "gasUsed": 21000,
+                "results": [{
+                    "txHash": "0xaaa",
+                    "gasUsed": 21000,
+                    "gasPrice": "1000000000",
+                    "revert": revert,
+                }],
+            },
+        })
+    }
+
+    fn test_bundle() -> Bundle {
+        BundleBuilder::new(U64::from(18000000))
+            .add_transaction("0x1234".to_string())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_send_bundle_aborts_on_revert() {
+        let relay = MockRelay::start().await.unwrap();
+        relay.set_revert(Some("execution reverted"));
+
+        let client = FlashbotsClient::new(Some(relay.url())).with_simulation_required(true);
+        let result = client.send_bundle(&test_bundle()).await;
+
+        assert!(matches!(result, Err(FlashbotsError::SimulationFailed(ref m)) if m.contains("execution reverted")));
+        assert_eq!(relay.calls(), vec!["eth_callBundle"]);
+        assert_eq!(relay.bundle_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_bundle_aborts_on_negative_coinbase_diff() {
+        let relay = MockRelay::start().await.unwrap();
+        relay.set_coinbase_diff("-500");
+
+        let client = FlashbotsClient::new(Some(relay.url())).with_simulation_required(true);
+        let result = client.send_bundle(&test_bundle()).await;
+
+        assert!(matches!(result, Err(FlashbotsError::SimulationFailed(ref m)) if m.contains("coinbase")));
+        assert_eq!(relay.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_bundle_simulates_then_submits() {
+        let relay = MockRelay::start().await.unwrap();
+
+        let client = FlashbotsClient::new(Some(relay.url())).with_simulation_required(true);
+        let result = client.send_bundle(&test_bundle()).await.unwrap();
+
+        assert!(relay.bundle(&result.bundle_hash).is_some());
+        assert_eq!(relay.calls(), vec!["eth_callBundle", "eth_sendBundle"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_bundle_without_simulation() {
+        let relay = MockRelay::start().await.unwrap();
+
+        let client = FlashbotsClient::new(Some(relay.url()));
+        client.send_bundle(&test_bundle()).await.unwrap();
+
+        assert_eq!(relay.calls(), vec!["eth_sendBundle"]);
+    }
+
+    #[test]
+    fn test_bundle_stats_sealed() {
+        let json = serde_json::json!({
+            "isSimulated": true,
+            "isHighPriority": true,
+            "simulatedAt": "2023-10-11T12:00:00.123Z",
+            "submittedAt": "2023-10-11T12:00:00.100Z",
+            "consideredByBuildersAt": [
+                {"pubkey": "0x81babe", "timestamp": "2023-10-11T12:00:00.200Z"},
+                {"pubkey": "0xa1dead", "timestamp": "2023-10-11T12:00:00.250Z"},
+            ],
+            "sealedByBuildersAt": [
+                {"pubkey": "0x81babe", "timestamp": "2023-10-11T12:00:01.000Z"},
+            ],
+        });
+
+        let stats: BundleStats = serde_json::from_value(json).unwrap();
+        assert!(stats.is_simulated);
+        assert!(stats.is_high_priority);
+        assert_eq!(stats.simulated_at.as_deref(), Some("2023-10-11T12:00:00.123Z"));
+        assert_eq!(stats.considered_by_builders_at.len(), 2);
+        assert_eq!(stats.sealed_by_builders_at[0].pubkey, "0x81babe");
+        assert!(stats.is_considered());
+        assert!(stats.is_included());
+    }
+
+    #[test]
+    fn test_bundle_stats_not_included() {
+        // Relay omits builder fields for bundles no builder has seen
+        let json = serde_json::json!({
+            "isSimulated": true,
+            "isHighPriority": false,
+            "simulatedAt": "2023-10-11T12:00:00.123Z",
+            "submittedAt": "2023-10-11T12:00:00.100Z",
+        });
+
+        let stats: BundleStats = serde_json::from_value(json).unwrap();
+        assert!(stats.is_simulated);
+        assert!(stats.submitted_at.is_some());
+        assert!(!stats.is_considered());
+        assert!(!stats.is_included());
+    }
+
+    #[tokio::test]
+    async fn test_get_bundle_stats_decodes_result() {
+        let relay = MockRelay::start().await.unwrap();
+        let client = FlashbotsClient::new(Some(relay.url()));
+        let submission = client.send_bundle(&test_bundle()).await.unwrap();
+
+        let stats = client.get_bundle_stats(&submission.bundle_hash, U64::from(18000000)).await.unwrap();
+
+        assert!(stats.is_simulated);
+        assert!(stats.is_considered());
+        assert!(!stats.is_included());
+    }
+
+    #[test]
+    fn test_simulation_check_allows_listed_reverts() {
+        let sim: SimulationResult =
+            serde_json::from_value(call_bundle_response(Some("reverted"), "0")["result"].clone()).unwrap();
+
+        assert!(sim.check(&[]).is_err());
+        assert!(sim.check(&["0xAAA".to_string()]).is_ok());
+    }
 }
@@ -0,0 +1,169 @@
+//! In-flight bundle tracking
+//!
+//! An opportunity stays visible for several blocks, so detection keeps
+//! handing it to execution. Submitting it again while an earlier bundle
+//! is still pending risks spending the same flash loan path twice, and
+//! every bundle signed before one of ours lands carries a now-used nonce.
+//!
+//! Tracks pending submissions by opportunity id: one bundle per
+//! opportunity, at most `max` at once. Inclusion drops every other pending
+//! bundle; bundles whose target block passed are released for retry.
+
+use std::collections::HashMap;
+
+use ethers::types::U64;
+
+/// Concurrent pending bundles allowed by default
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+
+/// A submitted bundle awaiting inclusion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InFlight {
+    pub opportunity_id: u64,
+    pub target_block: U64,
+    /// Bundle hash or transaction hashes, once submitted
+    pub ids: Vec<String>,
+}
+
+/// Why a submission slot was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InFlightRejection {
+    /// This opportunity already has a bundle pending
+    Duplicate(u64),
+    /// `max` bundles are already pending
+    AtCapacity(usize),
+}
+
+impl std::fmt::Display for InFlightRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InFlightRejection::Duplicate(id) => write!(f, "opportunity {} already has a bundle in flight", id),
+            InFlightRejection::AtCapacity(max) => write!(f, "{} bundles already in flight", max),
+        }
+    }
+}
+
+/// Pending bundles by opportunity id
+#[derive(Debug, Clone)]
+pub struct InFlightBundles {
+    max: usize,
+    pending: HashMap<u64, InFlight>,
+}
+
+impl InFlightBundles {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max: max.max(1),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Reserve a slot for `opportunity_id` targeting `target_block`
+    pub fn acquire(&mut self, opportunity_id: u64, target_block: U64) -> Result<(), InFlightRejection> {
+        if self.pending.contains_key(&opportunity_id) {
+            return Err(InFlightRejection::Duplicate(opportunity_id));
+        }
+        if self.pending.len() >= self.max {
+            return Err(InFlightRejection::AtCapacity(self.max));
+        }
+        self.pending.insert(
+            opportunity_id,
+            InFlight {
+                opportunity_id,
+                target_block,
+                ids: Vec::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Attach the relay's ids to a reserved slot
+    pub fn submitted(&mut self, opportunity_id: u64, ids: Vec<String>) {
+        if let Some(bundle) = self.pending.get_mut(&opportunity_id) {
+            bundle.ids = ids;
+        }
+    }
+
+    /// Free a slot whose submission failed
+    pub fn release(&mut self, opportunity_id: u64) -> Option<InFlight> {
+        self.pending.remove(&opportunity_id)
+    }
+
+    /// An opportunity's bundle landed: drop it and cancel every other
+    /// pending bundle, all signed against the pre-inclusion nonce
+    ///
+    /// Returns the cancelled bundles.
+    pub fn included(&mut self, opportunity_id: u64) -> Vec<InFlight> {
+        self.pending.remove(&opportunity_id);
+        let mut cancelled: Vec<InFlight> = self.pending.drain().map(|(_, bundle)| bundle).collect();
+        cancelled.sort_by_key(|bundle| bundle.opportunity_id);
+        cancelled
+    }
+
+    /// Release bundles whose target block is before `block` (not included)
+    pub fn expire_before(&mut self, block: U64) -> Vec<InFlight> {
+        let expired: Vec<u64> = self
+            .pending
+            .values()
+            .filter(|bundle| bundle.target_block < block)
+            .map(|bundle| bundle.opportunity_id)
+            .collect();
+        let mut expired: Vec<InFlight> = expired.into_iter().filter_map(|id| self.pending.remove(&id)).collect();
+        expired.sort_by_key(|bundle| bundle.opportunity_id);
+        expired
+    }
+
+    pub fn contains(&self, opportunity_id: u64) -> bool {
+        self.pending.contains_key(&opportunity_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Default for InFlightBundles {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IN_FLIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_and_capacity_rejected() {
+        let mut bundles = InFlightBundles::new(2);
+        bundles.acquire(1, U64::from(100)).unwrap();
+        assert_eq!(bundles.acquire(1, U64::from(101)), Err(InFlightRejection::Duplicate(1)));
+        bundles.acquire(2, U64::from(100)).unwrap();
+        assert_eq!(bundles.acquire(3, U64::from(100)), Err(InFlightRejection::AtCapacity(2)));
+
+        // A failed submission frees its slot
+        bundles.release(2);
+        bundles.acquire(3, U64::from(100)).unwrap();
+        assert!(bundles.contains(3) && !bundles.contains(2));
+    }
+
+    #[test]
+    fn test_inclusion_cancels_others_and_expiry_releases() {
+        let mut bundles = InFlightBundles::default();
+        for (id, block) in [(1, 100), (2, 101), (3, 102)] {
+            bundles.acquire(id, U64::from(block)).unwrap();
+        }
+        bundles.submitted(1, vec!["0xbundle1".to_string()]);
+
+        let expired = bundles.expire_before(U64::from(101));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].ids, vec!["0xbundle1".to_string()]);
+
+        let cancelled = bundles.included(2);
+        assert_eq!(cancelled.iter().map(|b| b.opportunity_id).collect::<Vec<_>>(), vec![3]);
+        assert!(bundles.is_empty());
+    }
+}
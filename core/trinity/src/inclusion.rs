@@ -0,0 +1,218 @@
+//! Bundle inclusion probability
+//!
+//! Tracks recent bundle outcomes (submitted vs included) by bribe level and
+//! estimates the odds that a bundle paying a given bribe lands, so the
+//! bidding logic can decide between bumping the bribe and waiting.
+//!
+//! Rates are empirical per bribe bucket, smoothed towards 50% when a bucket
+//! has few samples, then made non-decreasing in bribe (pool adjacent
+//! violators) so noise never makes a higher bribe look worse.
+
+use std::collections::VecDeque;
+
+use ethers::types::U256;
+
+/// Outcomes remembered by default
+pub const DEFAULT_WINDOW: usize = 1_000;
+
+/// Pseudo-observations (half included) added to every bucket
+const PRIOR_WEIGHT: f64 = 2.0;
+
+/// Estimates inclusion probability from recent outcomes
+#[derive(Debug, Clone)]
+pub struct InclusionEstimator {
+    /// Lower bribe bound (wei) of each bucket, ascending, first is zero
+    edges: Vec<U256>,
+    /// Recent outcomes as (bucket, included), oldest first
+    outcomes: VecDeque<(usize, bool)>,
+    window: usize,
+    submitted: Vec<u64>,
+    included: Vec<u64>,
+}
+
+impl InclusionEstimator {
+    /// Estimator with buckets starting at each of `edges` (wei)
+    ///
+    /// Edges are sorted and deduplicated; a zero edge is always present.
+    pub fn new(mut edges: Vec<U256>) -> Self {
+        edges.push(U256::zero());
+        edges.sort();
+        edges.dedup();
+        let buckets = edges.len();
+        Self {
+            edges,
+            outcomes: VecDeque::new(),
+            window: DEFAULT_WINDOW,
+            submitted: vec![0; buckets],
+            included: vec![0; buckets],
+        }
+    }
+
+    /// Number of recent outcomes to keep
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        while self.outcomes.len() > self.window {
+            self.evict();
+        }
+        self
+    }
+
+    /// Record a submitted bundle and whether it was included
+    pub fn record(&mut self, bribe: U256, included: bool) {
+        let bucket = self.bucket(bribe);
+        self.submitted[bucket] += 1;
+        if included {
+            self.included[bucket] += 1;
+        }
+        self.outcomes.push_back((bucket, included));
+        if self.outcomes.len() > self.window {
+            self.evict();
+        }
+    }
+
+    /// Estimated probability (0.0 - 1.0) that a bundle paying `bribe` lands
+    ///
+    /// 0.5 with no history; non-decreasing in `bribe`.
+    pub fn estimate(&self, bribe: U256) -> f64 {
+        self.rates()[self.bucket(bribe)]
+    }
+
+    /// Smallest bucket edge whose estimate reaches `target`, if any
+    pub fn min_bribe_for(&self, target: f64) -> Option<U256> {
+        self.rates()
+            .iter()
+            .position(|rate| *rate >= target)
+            .map(|bucket| self.edges[bucket])
+    }
+
+    /// Outcomes currently in the window
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    fn bucket(&self, bribe: U256) -> usize {
+        // edges[0] is zero, so the partition point is at least 1
+        self.edges.partition_point(|edge| *edge <= bribe) - 1
+    }
+
+    fn evict(&mut self) {
+        if let Some((bucket, included)) = self.outcomes.pop_front() {
+            self.submitted[bucket] -= 1;
+            if included {
+                self.included[bucket] -= 1;
+            }
+        }
+    }
+
+    /// Smoothed per-bucket rates, made monotonic by pooling violators
+    fn rates(&self) -> Vec<f64> {
+        // Blocks of (weighted rate, weight, bucket count)
+        let mut blocks: Vec<(f64, f64, usize)> = Vec::with_capacity(self.edges.len());
+        for (submitted, included) in self.submitted.iter().zip(&self.included) {
+            let weight = *submitted as f64 + PRIOR_WEIGHT;
+            let rate = (*included as f64 + PRIOR_WEIGHT / 2.0) / weight;
+            blocks.push((rate, weight, 1));
+
+            while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+                let (rate_b, weight_b, count_b) = blocks.pop().unwrap();
+                let (rate_a, weight_a, count_a) = blocks.pop().unwrap();
+                let weight = weight_a + weight_b;
+                blocks.push(((rate_a * weight_a + rate_b * weight_b) / weight, weight, count_a + count_b));
+            }
+        }
+
+        blocks
+            .into_iter()
+            .flat_map(|(rate, _, count)| std::iter::repeat_n(rate, count))
+            .collect()
+    }
+}
+
+impl Default for InclusionEstimator {
+    /// Buckets at 0.001, 0.005, 0.01, 0.05, 0.1 and 0.5 ETH
+    fn default() -> Self {
+        let milli = U256::exp10(15);
+        Self::new(
+            [1u64, 5, 10, 50, 100, 500]
+                .iter()
+                .map(|m| milli * U256::from(*m))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milli(m: u64) -> U256 {
+        U256::exp10(15) * U256::from(m)
+    }
+
+    fn assert_monotonic(estimator: &InclusionEstimator) {
+        let mut previous = 0.0;
+        for m in (0..1_000).step_by(5) {
+            let p = estimator.estimate(milli(m));
+            assert!(p >= previous, "estimate dropped at {} milli-ETH: {} < {}", m, p, previous);
+            previous = p;
+        }
+    }
+
+    #[test]
+    fn test_no_history_is_even_odds() {
+        let estimator = InclusionEstimator::default();
+        assert!(estimator.is_empty());
+        assert_eq!(estimator.estimate(U256::zero()), 0.5);
+        assert_eq!(estimator.estimate(milli(1_000)), 0.5);
+    }
+
+    #[test]
+    fn test_probability_monotonic_in_bribe() {
+        let mut estimator = InclusionEstimator::default();
+        // Synthetic history: inclusion rate rises with bribe, 100 bundles per level
+        for (bribe, rate_pct) in [(0u64, 5u64), (2, 20), (7, 40), (20, 60), (70, 80), (200, 95)] {
+            for i in 0..100 {
+                estimator.record(milli(bribe), i < rate_pct);
+            }
+        }
+
+        assert_monotonic(&estimator);
+        assert!(estimator.estimate(U256::zero()) < 0.1);
+        assert!(estimator.estimate(milli(200)) > 0.9);
+        assert_eq!(estimator.min_bribe_for(0.5), Some(milli(10)));
+        assert_eq!(estimator.min_bribe_for(0.99), None);
+    }
+
+    #[test]
+    fn test_noisy_buckets_pooled() {
+        let mut estimator = InclusionEstimator::default();
+        // The 0.01 ETH bucket happens to beat the 0.05 ETH bucket
+        for i in 0..50 {
+            estimator.record(milli(10), i < 40);
+            estimator.record(milli(50), i < 30);
+        }
+
+        assert_monotonic(&estimator);
+        assert_eq!(estimator.estimate(milli(10)), estimator.estimate(milli(50)));
+    }
+
+    #[test]
+    fn test_window_evicts_old_outcomes() {
+        let mut estimator = InclusionEstimator::default().with_window(100);
+        for _ in 0..100 {
+            estimator.record(milli(10), false);
+        }
+        assert!(estimator.estimate(milli(10)) < 0.05);
+
+        // Conditions change: recent bundles at the same bribe all land
+        for _ in 0..100 {
+            estimator.record(milli(10), true);
+        }
+        assert_eq!(estimator.len(), 100);
+        assert!(estimator.estimate(milli(10)) > 0.95);
+    }
+}
@@ -10,13 +10,42 @@
 //! - Submit via Flashbots
 //! - Handle transaction failures
 
+pub mod accounting;
+pub mod confirmation;
+pub mod flash_loan;
 pub mod flashbots;
+pub mod in_flight;
+pub mod inclusion;
+#[cfg(any(test, feature = "mock-relay"))]
+pub mod mock_relay;
+pub mod nonce;
+pub mod reconciliation;
+pub mod submitter;
+pub mod tx;
+
+#[cfg(test)]
+mod test_support;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
-use ethers::types::{Address, U256, Bytes, H256};
+use ethers::types::{Address, U256, U64, Bytes, H256};
+use matrix_types::{AgentHealth, AgentStatus, FlashLoanProvider, HealthReporter, SafeMode};
+pub use matrix_types::{SwapKind, UNWRAP_GAS, WRAP_GAS};
 use thiserror::Error;
 
-pub use flashbots::{FlashbotsClient, Bundle, BundleBuilder, SimulationResult};
+pub use accounting::{ProfitLedger, ProfitSplit};
+pub use confirmation::{check_confirmations, confirm_execution, ChainView, ConfirmationConfig, ExecutionStatus};
+pub use flash_loan::{select_provider, FlashLoanSource};
+pub use flashbots::{FlashbotsClient, Bundle, BundleBuilder, BundleStats, BundleStep, SimulationResult, StateBlock, StepKind};
+pub use in_flight::{InFlight, InFlightBundles, InFlightRejection, DEFAULT_MAX_IN_FLIGHT};
+pub use inclusion::InclusionEstimator;
+pub use nonce::{NonceManager, NonceSource};
+pub use reconciliation::{ProfitReconciler, ReconciliationConfig, ReconciliationReport};
+pub use submitter::{submitter_for, Submission, SubmissionRoute, Submitter, SubmitterConfig};
+pub use tx::{build_signed_tx, execute_calldata, TxFees, EXECUTE_ARBITRAGE_SIGNATURE};
 
 /// Trinity execution errors
 #[derive(Error, Debug)]
@@ -32,6 +61,42 @@ pub enum TrinityError {
 
     #[error("Gas estimation failed: {0}")]
     GasEstimationFailed(String),
+
+    #[error("Invalid operation: {0}")]
+    InvalidOperation(String),
+
+    #[error("Confirmation failed: {0}")]
+    ConfirmationFailed(String),
+
+    #[error("Safe mode active, not submitted: {0}")]
+    SafeMode(String),
+
+    #[error("Not submitted: {0}")]
+    InFlight(InFlightRejection),
+}
+
+impl TrinityError {
+    /// Canonical metric reason, from the node's message where recognisable
+    ///
+    /// `None` for refusals that never reached the chain (safe mode, a bundle
+    /// already in flight): those are not failed executions and shouldn't be
+    /// recorded in `execution_failed`.
+    pub fn failure_reason(&self) -> Option<matrix_metrics::FailureReason> {
+        use matrix_metrics::FailureReason;
+
+        match self {
+            TrinityError::TransactionFailed(msg)
+            | TrinityError::InvalidOperation(msg)
+            | TrinityError::SimulationFailed(msg)
+            | TrinityError::GasEstimationFailed(msg) => {
+                Some(FailureReason::classify(msg).unwrap_or(FailureReason::Reverted))
+            }
+            TrinityError::FlashbotsError(msg) | TrinityError::ConfirmationFailed(msg) => {
+                Some(FailureReason::classify(msg).unwrap_or(FailureReason::NotIncluded))
+            }
+            TrinityError::SafeMode(_) | TrinityError::InFlight(_) => None,
+        }
+    }
 }
 
 /// Supported chains
@@ -54,8 +119,27 @@ impl Chain {
             Chain::Bsc => 56,
         }
     }
+
+    /// Whether a Flashbots relay is available for private submission
+    pub fn supports_flashbots(&self) -> bool {
+        matches!(self, Chain::Ethereum)
+    }
+
+    /// Whether the chain accepts EIP-1559 (type 2) transactions
+    pub fn supports_eip1559(&self) -> bool {
+        !matches!(self, Chain::Bsc)
+    }
 }
 
+/// Aave V3 flash loan premium (0.09%)
+pub const AAVE_PREMIUM_BPS: u64 = FlashLoanProvider::Aave.premium_bps();
+
+/// Balancer flash loan premium (free)
+pub const BALANCER_PREMIUM_BPS: u64 = FlashLoanProvider::Balancer.premium_bps();
+
+/// Profit always kept back from the coinbase bribe (0.001 ETH)
+pub const DEFAULT_MIN_MARGIN_WEI: u64 = 1_000_000_000_000_000;
+
 /// Flash loan parameters
 #[derive(Debug, Clone)]
 pub struct FlashLoanParams {
@@ -63,16 +147,103 @@ pub struct FlashLoanParams {
     pub token: Address,
     pub amount: U256,
     pub callback_data: Bytes,
+    /// Provider premium in basis points of `amount`
+    pub premium_bps: u64,
+}
+
+impl FlashLoanParams {
+    /// Premium owed on top of the borrowed amount (None on overflow)
+    pub fn premium(&self) -> Option<U256> {
+        self.amount
+            .checked_mul(U256::from(self.premium_bps))
+            .map(|scaled| scaled / U256::from(10000u64))
+    }
 }
 
 /// Swap operation
 #[derive(Debug, Clone)]
 pub struct SwapOp {
+    /// Pool swapped through; the wrapped token contract for wrap legs
     pub pool: Address,
     pub token_in: Address,
     pub token_out: Address,
     pub amount_in: U256,
     pub min_amount_out: U256,
+    pub kind: SwapKind,
+}
+
+impl SwapOp {
+    /// Wrap `amount` of the native token (`Address::zero()`) into `wrapped`
+    pub fn wrap(wrapped: Address, amount: U256) -> Self {
+        Self {
+            pool: wrapped,
+            token_in: Address::zero(),
+            token_out: wrapped,
+            amount_in: amount,
+            min_amount_out: amount,
+            kind: SwapKind::Wrap,
+        }
+    }
+
+    /// Unwrap `amount` of `wrapped` back to the native token
+    pub fn unwrap(wrapped: Address, amount: U256) -> Self {
+        Self {
+            pool: wrapped,
+            token_in: wrapped,
+            token_out: Address::zero(),
+            amount_in: amount,
+            min_amount_out: amount,
+            kind: SwapKind::Unwrap,
+        }
+    }
+
+    /// Set `min_amount_out` from the expected output less `slippage_bps`
+    pub fn with_slippage(mut self, expected_out: U256, slippage_bps: u64) -> Self {
+        self.min_amount_out = apply_slippage(expected_out, slippage_bps);
+        self
+    }
+}
+
+/// `amount * (10000 - slippage_bps) / 10000`, slippage capped at 100%
+pub fn apply_slippage(amount: U256, slippage_bps: u64) -> U256 {
+    let keep_bps = 10000u64.saturating_sub(slippage_bps);
+    amount.saturating_mul(U256::from(keep_bps)) / U256::from(10000u64)
+}
+
+/// Set `min_amount_out` on each hop of a path
+///
+/// Each hop's expected output is scaled down to the worst-case input it can
+/// receive (the previous hop's `min_amount_out`) before slippage is applied,
+/// so protection compounds along the path instead of assuming every earlier
+/// hop filled at its expected amount. Wrap/unwrap legs are 1:1 and take no
+/// slippage of their own.
+pub fn apply_path_slippage(
+    swaps: &mut [SwapOp],
+    expected_outs: &[U256],
+    slippage_bps: u64,
+) -> Result<(), TrinityError> {
+    if swaps.len() != expected_outs.len() {
+        return Err(TrinityError::InvalidOperation(format!(
+            "{} swaps but {} expected outputs",
+            swaps.len(),
+            expected_outs.len()
+        )));
+    }
+
+    for i in 0..swaps.len() {
+        let expected = if i == 0 || expected_outs[i - 1].is_zero() {
+            expected_outs[i]
+        } else {
+            // Scale by worst-case input / expected input of this hop
+            expected_outs[i].saturating_mul(swaps[i - 1].min_amount_out) / expected_outs[i - 1]
+        };
+        swaps[i].min_amount_out = match swaps[i].kind {
+            SwapKind::Swap => apply_slippage(expected, slippage_bps),
+            SwapKind::Wrap | SwapKind::Unwrap => expected,
+        };
+    }
+
+    Ok(())
 }
 
 /// Arbitrage opportunity
@@ -84,6 +255,37 @@ pub struct ArbitrageOp {
     pub gas_estimate: u64,
 }
 
+impl ArbitrageOp {
+    /// Gas of the wrap/unwrap legs, on top of `gas_estimate`
+    pub fn wrap_gas(&self) -> u64 {
+        self.swaps.iter().map(|swap| swap.kind.extra_gas()).sum()
+    }
+
+    /// `gas_estimate` plus wrap/unwrap legs
+    pub fn total_gas(&self) -> u64 {
+        self.gas_estimate.saturating_add(self.wrap_gas())
+    }
+
+    /// Net profit with gas for `total_gas` at `gas_price` (None if negative)
+    pub fn net_profit_at(&self, gas_price: U256) -> Option<U256> {
+        self.net_profit(gas_price.saturating_mul(U256::from(self.total_gas())))
+    }
+
+    /// Expected profit after gas and flash loan premium (None if negative or on overflow)
+    pub fn net_profit(&self, gas_cost: U256) -> Option<U256> {
+        self.expected_profit
+            .checked_sub(gas_cost)?
+            .checked_sub(self.flash_loan.premium()?)
+    }
+
+    /// Check net profit (after gas and premium) meets `min_profit`
+    pub fn is_profitable(&self, gas_cost: U256, min_profit: U256) -> bool {
+        self.net_profit(gas_cost)
+            .map(|net| net >= min_profit)
+            .unwrap_or(false)
+    }
+}
+
 /// Execution result
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
@@ -110,27 +312,760 @@ pub trait ExecutionEngine: Send + Sync {
 /// Trinity agent
 pub struct Trinity {
     chain: Chain,
+    submitter: Option<Box<dyn Submitter>>,
+    min_margin: U256,
+    safe_mode: SafeMode,
+    suppressed: AtomicU64,
+    flash_loan_sources: Vec<FlashLoanSource>,
+    reconciler: Mutex<ProfitReconciler>,
+    /// Pending bundles by opportunity id
+    in_flight: Mutex<InFlightBundles>,
+    /// Heartbeat per submission attempt, errors per failed submission
+    health: HealthReporter,
+    /// Sender nonces, shareable across chains' Trinity instances
+    nonces: Arc<NonceManager>,
+    /// Profit split bookkeeping (None = no split configured)
+    ledger: Option<Mutex<ProfitLedger>>,
+    /// Confirmations before an execution's profit is booked
+    confirmation: ConfirmationConfig,
+    /// Included executions awaiting confirmations, by tx hash
+    pending: Mutex<HashMap<H256, (ExecutionResult, ExecutionStatus)>>,
     // Provider and signer will be added
 }
 
 impl Trinity {
     pub fn new(chain: Chain) -> Self {
         tracing::info!("TRINITY: Initializing for chain {:?}", chain);
-        Self { chain }
+        Self {
+            chain,
+            submitter: None,
+            min_margin: U256::from(DEFAULT_MIN_MARGIN_WEI),
+            safe_mode: SafeMode::new(),
+            suppressed: AtomicU64::new(0),
+            flash_loan_sources: Vec::new(),
+            reconciler: Mutex::new(ProfitReconciler::default()),
+            in_flight: Mutex::new(InFlightBundles::default()),
+            health: HealthReporter::new("trinity"),
+            nonces: Arc::new(NonceManager::new()),
+            ledger: None,
+            confirmation: ConfirmationConfig::default(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Split realized profit between buckets via `account_execution`
+    pub fn with_profit_ledger(mut self, ledger: ProfitLedger) -> Self {
+        self.ledger = Some(Mutex::new(ledger));
+        self
+    }
+
+    /// Book a confirmed execution's profit against the configured split
+    ///
+    /// Returns the allocation; empty without a ledger or for a failed trade.
+    pub fn account_execution(&self, result: &ExecutionResult) -> Vec<(String, U256)> {
+        let Some(ledger) = &self.ledger else {
+            return Vec::new();
+        };
+        let allocation = ledger.lock().unwrap().record(result);
+        for (bucket, amount) in &allocation {
+            tracing::debug!("TRINITY: Allocated {} wei of {:?} profit to {}", amount, result.tx_hash, bucket);
+        }
+        allocation
+    }
+
+    /// Cumulative profit per bucket (empty without a ledger)
+    pub fn profit_allocations(&self) -> BTreeMap<String, U256> {
+        self.ledger
+            .as_ref()
+            .map(|ledger| ledger.lock().unwrap().totals().clone())
+            .unwrap_or_default()
+    }
+
+    /// Confirmations before profit is booked, e.g. `ConfirmationConfig::for_chain`
+    pub fn with_confirmation_config(mut self, config: ConfirmationConfig) -> Self {
+        self.confirmation = config;
+        self
+    }
+
+    /// Hold an included execution as pending until `finalize_executions`
+    /// sees enough confirmations; failed executions have nothing to book
+    pub fn track_execution(&self, result: ExecutionResult) {
+        if !result.success {
+            return;
+        }
+        let status = ExecutionStatus::Pending { confirmations: 0 };
+        self.pending.lock().unwrap().insert(result.tx_hash, (result, status));
+    }
+
+    /// Status of a tracked execution; None once finalized or if never tracked
+    pub fn execution_status(&self, tx_hash: H256) -> Option<ExecutionStatus> {
+        self.pending.lock().unwrap().get(&tx_hash).map(|(_, status)| *status)
+    }
+
+    /// Executions still awaiting confirmations
+    pub fn pending_executions(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Poll every pending execution once against `chain`
+    ///
+    /// Confirmed executions are booked via `account_execution`; dropped ones
+    /// come back with `success: false`. Both leave the pending set and are
+    /// returned; the rest stay pending.
+    pub async fn finalize_executions<C: ChainView + ?Sized>(
+        &self,
+        chain: &C,
+    ) -> Result<Vec<ExecutionResult>, TrinityError> {
+        let pending: Vec<ExecutionResult> =
+            self.pending.lock().unwrap().values().map(|(result, _)| result.clone()).collect();
+
+        let mut finalized = Vec::new();
+        for mut result in pending {
+            let status = check_confirmations(chain, &mut result, self.confirmation.confirmations).await?;
+            match status {
+                ExecutionStatus::Pending { .. } => {
+                    self.pending.lock().unwrap().insert(result.tx_hash, (result, status));
+                }
+                ExecutionStatus::Confirmed | ExecutionStatus::Dropped => {
+                    self.pending.lock().unwrap().remove(&result.tx_hash);
+                    if status == ExecutionStatus::Confirmed {
+                        tracing::info!(
+                            "TRINITY: {:?} final after {} confirmations, booking {} wei",
+                            result.tx_hash,
+                            self.confirmation.confirmations,
+                            result.actual_profit
+                        );
+                        self.account_execution(&result);
+                    }
+                    finalized.push(result);
+                }
+            }
+        }
+        Ok(finalized)
+    }
+
+    /// Share a nonce manager (e.g. across chains) instead of a private one
+    pub fn with_nonce_manager(mut self, nonces: Arc<NonceManager>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    /// Nonce manager for signing this chain's transactions
+    pub fn nonces(&self) -> &NonceManager {
+        &self.nonces
+    }
+
+    /// Thresholds for the simulated-vs-actual profit reconciliation
+    pub fn with_reconciliation_config(mut self, config: ReconciliationConfig) -> Self {
+        self.reconciler = Mutex::new(ProfitReconciler::new(config));
+        self
+    }
+
+    /// Cap on bundles pending at once (default `DEFAULT_MAX_IN_FLIGHT`)
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.in_flight = Mutex::new(InFlightBundles::new(max));
+        self
+    }
+
+    /// Flash loan providers allowed on this chain, in preference order
+    pub fn with_flash_loan_sources(mut self, sources: Vec<FlashLoanSource>) -> Self {
+        self.flash_loan_sources = sources;
+        self
+    }
+
+    /// Cheapest allowed provider that lends `token`
+    pub fn select_flash_loan_provider(&self, token: Address) -> Option<FlashLoanProvider> {
+        select_provider(&self.flash_loan_sources, token)
+    }
+
+    /// Flash loan of `amount` of `token` from the cheapest viable provider
+    pub fn flash_loan_params(&self, token: Address, amount: U256, callback_data: Bytes) -> Result<FlashLoanParams, TrinityError> {
+        let provider = self.select_flash_loan_provider(token).ok_or_else(|| {
+            TrinityError::InvalidOperation(format!("No flash loan provider for {:?} on {:?}", token, self.chain))
+        })?;
+        tracing::debug!("TRINITY: Borrowing {:?} from {}", token, provider);
+        Ok(FlashLoanParams {
+            chain: self.chain,
+            token,
+            amount,
+            callback_data,
+            premium_bps: provider.premium_bps(),
+        })
+    }
+
+    /// Share a safe mode switch (e.g. Neo's) instead of a private one
+    pub fn with_safe_mode(mut self, safe_mode: SafeMode) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Attach the submission route for this chain
+    pub fn with_submitter(mut self, config: &SubmitterConfig) -> Self {
+        let submitter = submitter_for(self.chain, config);
+        tracing::info!("TRINITY: Submitting via {:?} on {:?}", submitter.route(), self.chain);
+        self.submitter = Some(submitter);
+        self
     }
 
     pub fn chain(&self) -> Chain {
         self.chain
     }
+
+    /// Route of the configured submitter, if any
+    ///
+    /// The submitter itself stays private so every send goes through
+    /// `submit` and its safe mode check.
+    pub fn submission_route(&self) -> Option<SubmissionRoute> {
+        self.submitter.as_deref().map(|submitter| submitter.route())
+    }
+
+    /// Submit signed transactions through the configured submitter
+    ///
+    /// In safe mode nothing is sent: the would-be submission is logged and
+    /// `TrinityError::SafeMode` returned.
+    pub async fn submit(&self, signed_txs: &[String], target_block: U64) -> Result<Submission, TrinityError> {
+        self.health.heartbeat();
+        let result = self.submit_inner(signed_txs, target_block).await;
+        if matches!(&result, Err(e) if e.failure_reason().is_some()) {
+            self.health.record_error();
+        }
+        result
+    }
+
+    async fn submit_inner(&self, signed_txs: &[String], target_block: U64) -> Result<Submission, TrinityError> {
+        // Safe mode refuses before anything else, configured route or not
+        if self.safe_mode.is_enabled() {
+            let route = match self.submission_route() {
+                Some(route) => format!("{:?}", route),
+                None => "no submitter".to_string(),
+            };
+            let would = format!("{} tx(s) for block {} via {}", signed_txs.len(), target_block, route);
+            tracing::warn!("TRINITY: Safe mode - would have submitted {}", would);
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return Err(TrinityError::SafeMode(would));
+        }
+
+        let submitter = self
+            .submitter
+            .as_deref()
+            .ok_or_else(|| TrinityError::InvalidOperation(format!("No submitter for {:?}", self.chain)))?;
+
+        submitter.submit(signed_txs, target_block).await
+    }
+
+    /// Submit an opportunity's bundle, at most one pending per opportunity
+    ///
+    /// Refused with `TrinityError::InFlight` while the opportunity already
+    /// has a bundle pending or the in-flight cap is reached. The slot is
+    /// held until `bundle_included` or `expire_in_flight` clears it, or
+    /// freed at once if submission fails.
+    pub async fn submit_opportunity(
+        &self,
+        opportunity_id: u64,
+        signed_txs: &[String],
+        target_block: U64,
+    ) -> Result<Submission, TrinityError> {
+        self.in_flight
+            .lock()
+            .unwrap()
+            .acquire(opportunity_id, target_block)
+            .map_err(|rejection| {
+                tracing::debug!("TRINITY: Not submitting opportunity {}: {}", opportunity_id, rejection);
+                TrinityError::InFlight(rejection)
+            })?;
+
+        let result = self.submit(signed_txs, target_block).await;
+        let mut in_flight = self.in_flight.lock().unwrap();
+        match &result {
+            Ok(submission) => in_flight.submitted(opportunity_id, submission.ids.clone()),
+            Err(_) => {
+                in_flight.release(opportunity_id);
+            }
+        }
+        result
+    }
+
+    /// An opportunity's bundle landed; cancels every other pending bundle
+    ///
+    /// Returns the cancelled bundles, which were signed against a nonce
+    /// that is now used.
+    pub fn bundle_included(&self, opportunity_id: u64) -> Vec<InFlight> {
+        let cancelled = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .included(opportunity_id);
+        if !cancelled.is_empty() {
+            tracing::info!(
+                "TRINITY: Opportunity {} included, cancelling {} stale in-flight bundle(s)",
+                opportunity_id,
+                cancelled.len()
+            );
+        }
+        cancelled
+    }
+
+    /// Release bundles that targeted blocks before `block` without landing
+    pub fn expire_in_flight(&self, block: U64) -> Vec<InFlight> {
+        self.in_flight.lock().unwrap().expire_before(block)
+    }
+
+    /// Bundles currently pending
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    /// Submissions refused because safe mode was on
+    pub fn suppressed_submissions(&self) -> u64 {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+
+    /// Current health: last submission attempt and failed submissions
+    ///
+    /// `Degraded` while safe mode holds back execution.
+    pub fn health(&self) -> AgentHealth {
+        self.health.set_metric("suppressed_submissions", self.suppressed_submissions() as f64);
+        self.health.set_metric("reconciliation_samples", self.reconciliation().samples as f64);
+        let status = if self.safe_mode.is_enabled() {
+            AgentStatus::Degraded
+        } else {
+            AgentStatus::Running
+        };
+        self.health.report(status)
+    }
+
+    /// Record an execution's simulated and realized profit
+    ///
+    /// Warns when realized profit is persistently below simulation.
+    pub fn record_profit(&self, simulated_profit: U256, actual_profit: U256) {
+        let report = {
+            let mut reconciler = self.reconciler.lock().unwrap();
+            reconciler.record(simulated_profit, actual_profit);
+            reconciler.report()
+        };
+        if report.persistent_shortfall {
+            tracing::warn!(
+                "TRINITY: Realized profit persistently below simulation ({:.0}% of last {} short, median {} bps)",
+                report.negative_fraction * 100.0,
+                report.samples,
+                report.median_delta_bps
+            );
+        }
+    }
+
+    /// Simulated-vs-actual profit statistics over recent executions
+    pub fn reconciliation(&self) -> ReconciliationReport {
+        self.reconciler.lock().unwrap().report()
+    }
+
+    /// Set the profit always kept back from the bribe
+    pub fn set_min_margin(&mut self, min_margin: U256) {
+        self.min_margin = min_margin;
+    }
+
+    /// Coinbase payment for an opportunity with `net_profit`
+    ///
+    /// Pays `max_fraction_bps` of net profit so bigger opportunities bid
+    /// higher, but never more than leaves `min_margin` for us. Returns zero
+    /// when the profit doesn't cover the margin.
+    pub fn compute_bribe(&self, net_profit: U256, max_fraction_bps: u64) -> U256 {
+        let fraction_bps = max_fraction_bps.min(10000);
+        let proportional = net_profit.saturating_mul(U256::from(fraction_bps)) / U256::from(10000u64);
+        let headroom = net_profit.saturating_sub(self.min_margin);
+        proportional.min(headroom)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{included, MockChain};
+    use matrix_config::ChainConfig;
 
     #[test]
     fn test_chain_ids() {
         assert_eq!(Chain::Ethereum.chain_id(), 1);
         assert_eq!(Chain::Arbitrum.chain_id(), 42161);
     }
+
+    fn swap(amount_in: u64) -> SwapOp {
+        SwapOp {
+            pool: Address::zero(),
+            token_in: Address::zero(),
+            token_out: Address::zero(),
+            amount_in: U256::from(amount_in),
+            min_amount_out: U256::zero(),
+            kind: SwapKind::Swap,
+        }
+    }
+
+    #[test]
+    fn test_single_hop_slippage() {
+        let op = swap(1_000).with_slippage(U256::from(2_000u64), 50); // 0.5%
+        assert_eq!(op.min_amount_out, U256::from(1_990u64));
+
+        assert_eq!(apply_slippage(U256::from(2_000u64), 0), U256::from(2_000u64));
+        assert_eq!(apply_slippage(U256::from(2_000u64), 20_000), U256::zero());
+    }
+
+    #[test]
+    fn test_multi_hop_slippage_propagates() {
+        let mut swaps = vec![swap(1_000_000), swap(2_000_000), swap(3_000_000)];
+        let expected = [
+            U256::from(2_000_000u64),
+            U256::from(3_000_000u64),
+            U256::from(1_100_000u64),
+        ];
+
+        apply_path_slippage(&mut swaps, &expected, 100).unwrap(); // 1%
+
+        // Each hop compounds the previous hop's worst case
+        assert_eq!(swaps[0].min_amount_out, U256::from(1_980_000u64));
+        assert_eq!(swaps[1].min_amount_out, U256::from(2_940_300u64));
+        assert_eq!(swaps[2].min_amount_out, U256::from(1_067_328u64));
+
+        // Looser than applying slippage independently per hop
+        assert!(swaps[2].min_amount_out < apply_slippage(expected[2], 100));
+
+        let mut short = vec![swap(1)];
+        assert!(matches!(
+            apply_path_slippage(&mut short, &expected, 100),
+            Err(TrinityError::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn test_native_path_counts_wrap_gas() {
+        let wbnb = Address::repeat_byte(0xbb);
+        let amount = U256::exp10(18);
+        let mut op = arbitrage_op(BALANCER_PREMIUM_BPS);
+        op.swaps = vec![
+            SwapOp::wrap(wbnb, amount),
+            swap(1_000_000),
+            swap(2_000_000),
+            SwapOp::unwrap(wbnb, amount),
+        ];
+
+        assert_eq!(op.wrap_gas(), WRAP_GAS + UNWRAP_GAS);
+        assert_eq!(op.total_gas(), 300_000 + WRAP_GAS + UNWRAP_GAS);
+
+        // 0.09 ETH profit: 300k gas at 290 gwei fits, the wrap legs don't
+        let gas_price = U256::from(290_000_000_000u64);
+        let plain = arbitrage_op(BALANCER_PREMIUM_BPS);
+        assert_eq!(plain.net_profit_at(gas_price), Some(U256::from(3_000_000_000_000_000u64)));
+        assert_eq!(op.net_profit_at(gas_price), None);
+
+        // Wrap legs pass the amount through without slippage
+        let expected = [amount, amount * 2, amount * 3, amount * 3];
+        apply_path_slippage(&mut op.swaps, &expected, 100).unwrap();
+        assert_eq!(op.swaps[0].min_amount_out, amount);
+        assert_eq!(op.swaps[3].min_amount_out, op.swaps[2].min_amount_out);
+    }
+
+    #[test]
+    fn test_trinity_submitter_per_chain() {
+        let config = SubmitterConfig::default();
+
+        let ethereum = Trinity::new(Chain::Ethereum).with_submitter(&config);
+        assert_eq!(ethereum.submission_route(), Some(SubmissionRoute::Flashbots));
+
+        let bsc = Trinity::new(Chain::Bsc).with_submitter(&config);
+        assert_eq!(bsc.submission_route(), Some(SubmissionRoute::PublicMempool));
+
+        assert!(Trinity::new(Chain::Base).submission_route().is_none());
+    }
+
+    #[test]
+    fn test_bribe_scales_with_profit() {
+        let trinity = Trinity::new(Chain::Ethereum);
+        let eth = U256::exp10(18);
+
+        let small = trinity.compute_bribe(eth / 10, 5_000);
+        let large = trinity.compute_bribe(eth, 5_000);
+        assert_eq!(small, eth / 20);
+        assert_eq!(large, eth / 2);
+        assert!(large > small);
+
+        // Never exceeds the configured fraction
+        for profit in [eth / 100, eth / 10, eth, eth * 10] {
+            for bps in [100, 2_500, 9_000] {
+                let bribe = trinity.compute_bribe(profit, bps);
+                assert!(bribe <= profit * U256::from(bps) / U256::from(10000u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bribe_keeps_min_margin() {
+        let mut trinity = Trinity::new(Chain::Ethereum);
+        let margin = U256::from(DEFAULT_MIN_MARGIN_WEI);
+
+        // 99% fraction would eat into the margin; capped to leave it
+        let profit = margin * 10;
+        assert_eq!(trinity.compute_bribe(profit, 9_900), profit - margin);
+
+        // Profit below the margin: no bribe
+        assert_eq!(trinity.compute_bribe(margin / 2, 5_000), U256::zero());
+
+        // Fractions over 100% are clamped
+        trinity.set_min_margin(U256::zero());
+        assert_eq!(trinity.compute_bribe(profit, 20_000), profit);
+    }
+
+    #[test]
+    fn test_records_simulated_and_actual_profit() {
+        let trinity = Trinity::new(Chain::Ethereum).with_reconciliation_config(ReconciliationConfig {
+            min_samples: 3,
+            ..Default::default()
+        });
+        let simulated = U256::exp10(18);
+        for _ in 0..3 {
+            trinity.record_profit(simulated, simulated * 9 / 10);
+        }
+
+        let report = trinity.reconciliation();
+        assert_eq!(report.samples, 3);
+        assert_eq!(report.median_delta_bps, -1_000);
+        assert!(report.persistent_shortfall);
+    }
+
+    #[test]
+    fn test_errors_map_to_failure_reasons() {
+        use matrix_metrics::FailureReason;
+
+        let reason = |e: TrinityError| e.failure_reason();
+        assert_eq!(reason(TrinityError::TransactionFailed("nonce too low".into())), Some(FailureReason::NonceError));
+        assert_eq!(reason(TrinityError::TransactionFailed("Unknown error".into())), Some(FailureReason::Reverted));
+        assert_eq!(reason(TrinityError::FlashbotsError("relay busy".into())), Some(FailureReason::NotIncluded));
+        assert_eq!(
+            reason(TrinityError::ConfirmationFailed("0x01 not confirmed after 120000ms".into())),
+            Some(FailureReason::Timeout)
+        );
+
+        // Refusals never reached the chain: nothing to record
+        assert_eq!(reason(TrinityError::SafeMode("1 tx(s)".into())), None);
+        assert_eq!(reason(TrinityError::InFlight(InFlightRejection::Duplicate(7))), None);
+    }
+
+    #[tokio::test]
+    async fn test_safe_mode_detects_but_never_submits() {
+        let relay = mock_relay::MockRelay::start().await.unwrap();
+        let config = SubmitterConfig {
+            flashbots_relay: Some(relay.url()),
+            ..Default::default()
+        };
+        let safe_mode = SafeMode::new();
+        let trinity = Trinity::new(Chain::Ethereum)
+            .with_submitter(&config)
+            .with_safe_mode(safe_mode.clone());
+
+        let registry = prometheus::Registry::new();
+        let metrics = matrix_metrics::ArbitrageMetrics::new(&registry).unwrap();
+        let detected = metrics.opportunities_detected.with_label_values(&["ethereum", "uniswap_sushiswap"]);
+
+        // The scanner keeps finding the spread; every execution is refused
+        let mut scanner = hotpath::OpportunityScanner::new();
+        let fixture = hotpath::fixtures::make_pools_with_spread(200);
+        safe_mode.enable();
+        for block in 0..3u64 {
+            scanner.update_pool(fixture.buy);
+            scanner.update_pool(fixture.sell);
+            for (i, _) in scanner.scan().iter().enumerate() {
+                detected.inc();
+                let id = block * 10 + i as u64;
+                let result = trinity.submit_opportunity(id, &["0x01".to_string()], U64::from(18_000_000 + block)).await;
+                assert!(matches!(result, Err(TrinityError::SafeMode(ref m)) if m.contains("Flashbots")));
+            }
+        }
+        assert_eq!(detected.get(), 3);
+        assert_eq!(trinity.suppressed_submissions(), 3);
+        assert_eq!(trinity.in_flight(), 0);
+        assert_eq!(relay.bundle_count(), 0);
+        assert!(relay.calls().is_empty());
+
+        // Refusals are not errors, but the agent reports degraded
+        let health = trinity.health();
+        assert_eq!(health.status, matrix_types::AgentStatus::Degraded);
+        assert_eq!(health.error_count, 0);
+        assert_eq!(health.metrics["suppressed_submissions"], 3.0);
+
+        // Safe mode wins even without a submission route
+        let unrouted = Trinity::new(Chain::Base).with_safe_mode(safe_mode.clone());
+        let result = unrouted.submit(&["0x01".to_string()], U64::from(1)).await;
+        assert!(matches!(result, Err(TrinityError::SafeMode(ref m)) if m.contains("no submitter")));
+
+        // Leaving safe mode resumes submission through the same handle
+        safe_mode.disable();
+        trinity.submit(&["0x01".to_string()], U64::from(18_000_000)).await.unwrap();
+        assert_eq!(relay.bundle_count(), 1);
+        assert_eq!(trinity.health().status, matrix_types::AgentStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_same_opportunity_not_submitted_twice() {
+        let relay = mock_relay::MockRelay::start().await.unwrap();
+        let config = SubmitterConfig {
+            flashbots_relay: Some(relay.url()),
+            ..Default::default()
+        };
+        let trinity = Trinity::new(Chain::Ethereum).with_submitter(&config).with_max_in_flight(2);
+        let txs = ["0x01".to_string()];
+
+        trinity.submit_opportunity(7, &txs, U64::from(100)).await.unwrap();
+        let again = trinity.submit_opportunity(7, &txs, U64::from(101)).await;
+        assert!(matches!(again, Err(TrinityError::InFlight(InFlightRejection::Duplicate(7)))));
+        assert_eq!(relay.calls().len(), 1);
+
+        // Other opportunities fit up to the cap
+        trinity.submit_opportunity(8, &txs, U64::from(101)).await.unwrap();
+        let full = trinity.submit_opportunity(9, &txs, U64::from(101)).await;
+        assert!(matches!(full, Err(TrinityError::InFlight(InFlightRejection::AtCapacity(2)))));
+        assert_eq!(trinity.health().error_count, 0);
+
+        // 7's bundle lands: 8's was signed against the same nonce
+        let cancelled = trinity.bundle_included(7);
+        assert_eq!(cancelled.iter().map(|b| b.opportunity_id).collect::<Vec<_>>(), vec![8]);
+        assert_eq!(trinity.in_flight(), 0);
+
+        // A bundle that missed its block can be resubmitted
+        trinity.submit_opportunity(7, &txs, U64::from(102)).await.unwrap();
+        assert_eq!(trinity.expire_in_flight(U64::from(103)).len(), 1);
+        trinity.submit_opportunity(7, &txs, U64::from(103)).await.unwrap();
+        assert_eq!(relay.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_health_counts_failed_submissions() {
+        let trinity = Trinity::new(Chain::Ethereum);
+        assert_eq!(trinity.health().last_heartbeat_ms, 0);
+
+        let before = chrono::Utc::now().timestamp_millis() as u64;
+        assert!(trinity.submit(&["0x01".to_string()], U64::from(18_000_000)).await.is_err());
+        let health = trinity.health();
+        assert_eq!(health.error_count, 1);
+        assert!(health.last_heartbeat_ms >= before);
+    }
+
+    #[test]
+    fn test_flash_loan_params_use_cheapest_provider() {
+        let weth = Address::repeat_byte(1);
+        let usdc = Address::repeat_byte(2);
+        let trinity = Trinity::new(Chain::Arbitrum).with_flash_loan_sources(vec![
+            FlashLoanSource::new(FlashLoanProvider::Aave, vec![weth, usdc]),
+            FlashLoanSource::new(FlashLoanProvider::Balancer, vec![weth]),
+        ]);
+
+        let params = trinity.flash_loan_params(weth, U256::exp10(18), Bytes::default()).unwrap();
+        assert_eq!(params.premium_bps, BALANCER_PREMIUM_BPS);
+        let params = trinity.flash_loan_params(usdc, U256::exp10(6), Bytes::default()).unwrap();
+        assert_eq!(params.premium_bps, AAVE_PREMIUM_BPS);
+        assert!(matches!(
+            trinity.flash_loan_params(Address::zero(), U256::one(), Bytes::default()),
+            Err(TrinityError::InvalidOperation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_profit_booked_after_min_confirmations() {
+        let split = ProfitSplit::new([("treasury", 10_000)]).unwrap();
+        let bsc = ChainConfig {
+            name: "bsc".to_string(),
+            chain_id: 56,
+            rpc_url: String::new(),
+            ws_url: String::new(),
+            flashloan_provider: "aave".to_string(),
+            flash_loan_providers: Vec::new(),
+            flash_loan_contract: String::new(),
+            block_time_ms: 3_000,
+            gas_limit: 1_000_000,
+            priority_fee_gwei: 1,
+            base_gas: 150_000,
+            per_hop_gas: 100_000,
+            l1_data_fee_multiplier: None,
+            opportunity_ttl_ms: None,
+            price_staleness_ms: None,
+            min_confirmations: 3,
+        };
+        let trinity = Trinity::new(Chain::Bsc)
+            .with_profit_ledger(ProfitLedger::new(Chain::Bsc, split))
+            .with_confirmation_config(ConfirmationConfig::for_chain(&bsc));
+        // The head advances a block per poll
+        let chain = MockChain::new(vec![(100, Some(100)), (101, Some(100)), (102, Some(100))]);
+        let tx = H256::repeat_byte(1);
+        trinity.track_execution(included(1, 100));
+
+        for confirmations in [1, 2] {
+            assert!(trinity.finalize_executions(&chain).await.unwrap().is_empty());
+            assert_eq!(trinity.execution_status(tx), Some(ExecutionStatus::Pending { confirmations }));
+            assert!(trinity.profit_allocations()["treasury"].is_zero());
+        }
+
+        let finalized = trinity.finalize_executions(&chain).await.unwrap();
+        assert_eq!(finalized.len(), 1);
+        assert!(finalized[0].success);
+        assert_eq!(trinity.profit_allocations()["treasury"], U256::exp10(17));
+        assert_eq!(trinity.execution_status(tx), None);
+        assert_eq!(trinity.pending_executions(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reorged_pending_execution_books_nothing() {
+        let split = ProfitSplit::new([("treasury", 10_000)]).unwrap();
+        let trinity = Trinity::new(Chain::Bsc)
+            .with_profit_ledger(ProfitLedger::new(Chain::Bsc, split))
+            .with_confirmation_config(ConfirmationConfig {
+                confirmations: 5,
+                ..ConfirmationConfig::default()
+            });
+        // Orphaned on the second poll, before reaching 5 confirmations
+        let chain = MockChain::new(vec![(100, Some(100)), (103, None)]);
+        trinity.track_execution(included(1, 100));
+        trinity.track_execution(ExecutionResult {
+            success: false,
+            ..included(2, 100)
+        });
+        assert_eq!(trinity.pending_executions(), 1);
+        assert!(trinity.finalize_executions(&chain).await.unwrap().is_empty());
+
+        let finalized = trinity.finalize_executions(&chain).await.unwrap();
+        assert!(!finalized[0].success);
+        assert!(trinity.profit_allocations()["treasury"].is_zero());
+        assert_eq!(trinity.pending_executions(), 0);
+    }
+
+    fn arbitrage_op(premium_bps: u64) -> ArbitrageOp {
+        ArbitrageOp {
+            flash_loan: FlashLoanParams {
+                chain: Chain::Ethereum,
+                token: Address::zero(),
+                amount: U256::from(100u64) * U256::exp10(18), // 100 ETH
+                callback_data: Bytes::default(),
+                premium_bps,
+            },
+            swaps: Vec::new(),
+            expected_profit: U256::from(90_000_000_000_000_000u64), // 0.09 ETH
+            gas_estimate: 300_000,
+        }
+    }
+
+    #[test]
+    fn test_flash_loan_premium_in_profit() {
+        let gas_cost = U256::from(5_000_000_000_000_000u64);   // 0.005 ETH
+        let min_profit = U256::from(1_000_000_000_000_000u64); // 0.001 ETH
+
+        let balancer = arbitrage_op(BALANCER_PREMIUM_BPS);
+        assert_eq!(balancer.flash_loan.premium(), Some(U256::zero()));
+        assert!(balancer.is_profitable(gas_cost, min_profit));
+
+        let aave = arbitrage_op(AAVE_PREMIUM_BPS);
+        assert_eq!(aave.flash_loan.premium(), Some(U256::from(90_000_000_000_000_000u64)));
+        assert_eq!(aave.net_profit(gas_cost), None);
+        assert!(!aave.is_profitable(gas_cost, min_profit));
+
+        // An overflowing premium is never profitable
+        let mut huge = arbitrage_op(AAVE_PREMIUM_BPS);
+        huge.flash_loan.amount = U256::MAX;
+        assert_eq!(huge.flash_loan.premium(), None);
+        assert!(!huge.is_profitable(U256::zero(), U256::zero()));
+    }
 }
@@ -0,0 +1,297 @@
+//! In-memory Flashbots relay
+//!
+//! Serves `eth_callBundle`, `eth_sendBundle` and `flashbots_getBundleStats`
+//! over a local HTTP listener so the full simulate → send → stats flow of
+//! `FlashbotsClient` can be exercised without network access. Simulation
+//! outcomes, per-method errors and bundle sealing are scripted at runtime.
+//!
+//! Enabled in tests and with the `mock-relay` feature.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use matrix_types::mock_rpc::MockRpcServer;
+use serde_json::{json, Value};
+
+use crate::TrinityError;
+
+/// Gas reported per simulated transaction
+const DEFAULT_TX_GAS: u64 = 21_000;
+
+/// A bundle received via `eth_sendBundle`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedBundle {
+    pub bundle_hash: String,
+    pub transactions: Vec<String>,
+    /// Target block, as sent (hex)
+    pub block_number: String,
+    pub reverting_tx_hashes: Vec<String>,
+}
+
+/// Scripted relay behaviour and recorded traffic
+struct RelayState {
+    revert: Option<String>,
+    coinbase_diff: String,
+    tx_gas: u64,
+    errors: HashMap<String, String>,
+    calls: Vec<String>,
+    bundles: HashMap<String, ReceivedBundle>,
+    sealed: Vec<String>,
+}
+
+impl Default for RelayState {
+    fn default() -> Self {
+        Self {
+            revert: None,
+            coinbase_diff: "1000000000000000".to_string(),
+            tx_gas: DEFAULT_TX_GAS,
+            errors: HashMap::new(),
+            calls: Vec::new(),
+            bundles: HashMap::new(),
+            sealed: Vec::new(),
+        }
+    }
+}
+
+/// Local JSON-RPC server mimicking a Flashbots relay
+///
+/// The listener shuts down when the relay is dropped.
+pub struct MockRelay {
+    state: Arc<Mutex<RelayState>>,
+    server: MockRpcServer,
+}
+
+impl MockRelay {
+    /// Bind an ephemeral localhost port and start serving
+    pub async fn start() -> Result<Self, TrinityError> {
+        let state = Arc::new(Mutex::new(RelayState::default()));
+        let shared = state.clone();
+        let server = MockRpcServer::start(move |request| handle(&shared, request))
+            .await
+            .map_err(|e| TrinityError::FlashbotsError(format!("Mock relay bind failed: {}", e)))?;
+
+        Ok(Self { state, server })
+    }
+
+    /// Relay URL for `FlashbotsClient::new`
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Make every simulated transaction revert with `reason`, or clear it
+    pub fn set_revert(&self, reason: Option<&str>) {
+        self.state.lock().unwrap().revert = reason.map(str::to_string);
+    }
+
+    /// Coinbase diff (builder payment, wei) reported by simulations
+    pub fn set_coinbase_diff(&self, coinbase_diff: impl Into<String>) {
+        self.state.lock().unwrap().coinbase_diff = coinbase_diff.into();
+    }
+
+    /// Gas reported per simulated transaction
+    pub fn set_tx_gas(&self, gas: u64) {
+        self.state.lock().unwrap().tx_gas = gas;
+    }
+
+    /// Answer `method` with a JSON-RPC error until cleared
+    pub fn fail_method(&self, method: &str, message: &str) {
+        self.state.lock().unwrap().errors.insert(method.to_string(), message.to_string());
+    }
+
+    /// Stop failing `method`
+    pub fn clear_failure(&self, method: &str) {
+        self.state.lock().unwrap().errors.remove(method);
+    }
+
+    /// Report a received bundle as sealed by a builder
+    pub fn seal(&self, bundle_hash: &str) {
+        let mut state = self.state.lock().unwrap();
+        if !state.sealed.iter().any(|h| h == bundle_hash) {
+            state.sealed.push(bundle_hash.to_string());
+        }
+    }
+
+    /// Methods called so far, in order
+    pub fn calls(&self) -> Vec<String> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Bundle received under `bundle_hash`
+    pub fn bundle(&self, bundle_hash: &str) -> Option<ReceivedBundle> {
+        self.state.lock().unwrap().bundles.get(bundle_hash).cloned()
+    }
+
+    /// Number of bundles received via `eth_sendBundle`
+    pub fn bundle_count(&self) -> usize {
+        self.state.lock().unwrap().bundles.len()
+    }
+}
+
+/// Deterministic hash over a bundle's transactions
+fn bundle_hash(transactions: &[String]) -> String {
+    format!("0x{:x}", md5::compute(transactions.join(",")))
+}
+
+fn tx_hash(tx: &str) -> String {
+    format!("0x{:x}", md5::compute(tx))
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Build the JSON-RPC reply for one request
+fn handle(state: &Mutex<RelayState>, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request["method"].as_str().unwrap_or_default();
+    let params = &request["params"][0];
+
+    let mut state = state.lock().unwrap();
+    state.calls.push(method.to_string());
+
+    if let Some(message) = state.errors.get(method) {
+        return json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32000, "message": message } });
+    }
+
+    let result = match method {
+        "eth_callBundle" => {
+            let txs = string_list(&params["txs"]);
+            let results: Vec<Value> = txs
+                .iter()
+                .map(|tx| {
+                    json!({
+                        "txHash": tx_hash(tx),
+                        "gasUsed": state.tx_gas,
+                        "gasPrice": "0",
+                        "revert": state.revert,
+                    })
+                })
+                .collect();
+            let total_gas = state.tx_gas * txs.len() as u64;
+            json!({
+                "bundleHash": bundle_hash(&txs),
+                "coinbaseDiff": state.coinbase_diff,
+                "gasUsed": total_gas,
+                "totalGasUsed": total_gas,
+                "results": results,
+            })
+        }
+        "eth_sendBundle" => {
+            let transactions = string_list(&params["txs"]);
+            let hash = bundle_hash(&transactions);
+            let bundle = ReceivedBundle {
+                bundle_hash: hash.clone(),
+                transactions,
+                block_number: params["blockNumber"].as_str().unwrap_or_default().to_string(),
+                reverting_tx_hashes: string_list(&params["revertingTxHashes"]),
+            };
+            state.bundles.insert(hash.clone(), bundle);
+            json!({ "bundleHash": hash })
+        }
+        "flashbots_getBundleStats" => {
+            let hash = params["bundleHash"].as_str().unwrap_or_default();
+            if !state.bundles.contains_key(hash) {
+                return json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32000, "message": "bundle not found" } });
+            }
+            let builder = json!({ "pubkey": "0xmockbuilder", "timestamp": "1970-01-01T00:00:00.000Z" });
+            let sealed = state.sealed.iter().any(|h| h == hash);
+            json!({
+                "isSimulated": true,
+                "isHighPriority": false,
+                "simulatedAt": "1970-01-01T00:00:00.000Z",
+                "submittedAt": "1970-01-01T00:00:00.000Z",
+                "consideredByBuildersAt": [builder.clone()],
+                "sealedByBuildersAt": if sealed { vec![builder] } else { vec![] },
+            })
+        }
+        _ => {
+            return json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32601, "message": "method not found" } });
+        }
+    };
+
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flashbots::FlashbotsError;
+    use crate::{BundleBuilder, FlashbotsClient};
+    use ethers::types::U64;
+
+    fn bundle(txs: &[&str]) -> crate::Bundle {
+        txs.iter()
+            .fold(BundleBuilder::new(U64::from(18_000_000)), |b, tx| b.add_transaction(tx.to_string()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_simulate_send_stats_flow() {
+        let relay = MockRelay::start().await.unwrap();
+        let client = FlashbotsClient::new(Some(relay.url())).with_simulation_required(true);
+        let bundle = bundle(&["0x01", "0x02"]);
+
+        let submission = client.send_bundle(&bundle).await.unwrap();
+        assert_eq!(relay.calls(), vec!["eth_callBundle", "eth_sendBundle"]);
+
+        let received = relay.bundle(&submission.bundle_hash).unwrap();
+        assert_eq!(received.transactions, vec!["0x01", "0x02"]);
+        assert_eq!(received.block_number, bundle.block_number);
+
+        let stats = client
+            .get_bundle_stats(&submission.bundle_hash, U64::from(18_000_000))
+            .await
+            .unwrap();
+        assert!(stats.is_simulated);
+        assert!(stats.is_considered());
+        assert!(!stats.is_included());
+
+        relay.seal(&submission.bundle_hash);
+        let stats = client
+            .get_bundle_stats(&submission.bundle_hash, U64::from(18_000_000))
+            .await
+            .unwrap();
+        assert!(stats.is_included());
+    }
+
+    #[tokio::test]
+    async fn test_reverting_simulation_is_not_sent() {
+        let relay = MockRelay::start().await.unwrap();
+        relay.set_revert(Some("execution reverted: K"));
+        let client = FlashbotsClient::new(Some(relay.url())).with_simulation_required(true);
+
+        let result = client.send_bundle(&bundle(&["0x01"])).await;
+        assert!(matches!(result, Err(FlashbotsError::SimulationFailed(ref m)) if m.contains("reverted: K")));
+        assert_eq!(relay.calls(), vec!["eth_callBundle"]);
+        assert_eq!(relay.bundle_count(), 0);
+
+        // Clearing the revert lets the same bundle through
+        relay.set_revert(None);
+        client.send_bundle(&bundle(&["0x01"])).await.unwrap();
+        assert_eq!(relay.bundle_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scripted_errors() {
+        let relay = MockRelay::start().await.unwrap();
+        let client = FlashbotsClient::new(Some(relay.url()));
+
+        relay.fail_method("eth_sendBundle", "relay overloaded");
+        let result = client.send_bundle(&bundle(&["0x01"])).await;
+        assert!(matches!(result, Err(FlashbotsError::BundleRejected(ref m)) if m == "relay overloaded"));
+
+        relay.clear_failure("eth_sendBundle");
+        client.send_bundle(&bundle(&["0x01"])).await.unwrap();
+
+        // Stats for a bundle the relay never saw
+        let result = client.get_bundle_stats("0xunknown", U64::from(18_000_000)).await;
+        assert!(matches!(result, Err(FlashbotsError::InvalidResponse(_))));
+
+        relay.set_coinbase_diff("-1");
+        let sim = client.simulate_bundle(&bundle(&["0x01"]), U64::from(17_999_999)).await.unwrap();
+        assert!(sim.check(&[]).is_err());
+    }
+}
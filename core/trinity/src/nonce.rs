@@ -0,0 +1,160 @@
+//! Sender nonce management
+//!
+//! Each signed transaction needs the sender's next nonce. Asking the node
+//! per transaction races when several are signed before any lands ("nonce
+//! too low"), and guessing ahead of it leaves gaps ("nonce too high").
+//!
+//! `NonceManager` hands out nonces locally, one per call, per (chain,
+//! sender). The chain stays the source of truth: `reconcile` re-reads the
+//! pending nonce on startup and after a failed submission, since nonces
+//! allocated for transactions that never landed are free again.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use ethers::types::{Address, BlockNumber, U256};
+
+use crate::{Chain, TrinityError};
+
+/// Where the on-chain nonce comes from
+#[async_trait]
+pub trait NonceSource: Send + Sync {
+    /// Next nonce the chain expects from `address`, pending transactions included
+    async fn pending_nonce(&self, address: Address) -> Result<U256, TrinityError>;
+}
+
+#[async_trait]
+impl<M: Middleware> NonceSource for M {
+    async fn pending_nonce(&self, address: Address) -> Result<U256, TrinityError> {
+        self.get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| TrinityError::TransactionFailed(format!("Nonce lookup failed: {}", e)))
+    }
+}
+
+/// Next nonce per (chain, sender)
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    next: Mutex<HashMap<(Chain, Address), U256>>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset `address`'s next nonce on `chain` to the on-chain pending nonce
+    ///
+    /// Call on startup and after a failed submission. Returns the nonce the
+    /// next `allocate` will hand out.
+    pub async fn reconcile<S: NonceSource + ?Sized>(
+        &self,
+        chain: Chain,
+        address: Address,
+        source: &S,
+    ) -> Result<U256, TrinityError> {
+        let on_chain = source.pending_nonce(address).await?;
+        let previous = self.next.lock().unwrap().insert((chain, address), on_chain);
+        match previous {
+            Some(local) if local != on_chain => tracing::warn!(
+                "TRINITY: Nonce for {:?} on {:?} reconciled from {} to {}",
+                address,
+                chain,
+                local,
+                on_chain
+            ),
+            None => tracing::debug!("TRINITY: Nonce for {:?} on {:?} starts at {}", address, chain, on_chain),
+            _ => {}
+        }
+        Ok(on_chain)
+    }
+
+    /// Take the next nonce for a new transaction
+    ///
+    /// Fails if `address` hasn't been reconciled on `chain` yet.
+    pub fn allocate(&self, chain: Chain, address: Address) -> Result<U256, TrinityError> {
+        let mut next = self.next.lock().unwrap();
+        let nonce = next.get_mut(&(chain, address)).ok_or_else(|| {
+            TrinityError::InvalidOperation(format!("No nonce for {:?} on {:?}; reconcile first", address, chain))
+        })?;
+        let allocated = *nonce;
+        *nonce = allocated + 1;
+        Ok(allocated)
+    }
+
+    /// Nonce the next `allocate` would return, if known
+    pub fn peek(&self, chain: Chain, address: Address) -> Option<U256> {
+        self.next.lock().unwrap().get(&(chain, address)).copied()
+    }
+
+    /// Forget `address` on `chain`; allocation fails until reconciled again
+    pub fn invalidate(&self, chain: Chain, address: Address) {
+        self.next.lock().unwrap().remove(&(chain, address));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    struct FixedNonce(u64);
+
+    #[async_trait]
+    impl NonceSource for FixedNonce {
+        async fn pending_nonce(&self, _address: Address) -> Result<U256, TrinityError> {
+            Ok(U256::from(self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_allocations_unique_and_monotonic() {
+        let manager = Arc::new(NonceManager::new());
+        let sender = Address::repeat_byte(0x11);
+        manager.reconcile(Chain::Ethereum, sender, &FixedNonce(40)).await.unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                std::thread::spawn(move || {
+                    (0..50)
+                        .map(|_| manager.allocate(Chain::Ethereum, sender).unwrap().as_u64())
+                        .collect::<Vec<u64>>()
+                })
+            })
+            .collect();
+
+        let mut all = Vec::new();
+        for thread in threads {
+            let nonces = thread.join().unwrap();
+            assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", nonces);
+            all.extend(nonces);
+        }
+        all.sort();
+        assert_eq!(all, (40..440).collect::<Vec<u64>>());
+        assert_eq!(manager.peek(Chain::Ethereum, sender), Some(U256::from(440)));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_after_failure() {
+        let manager = NonceManager::new();
+        let sender = Address::repeat_byte(0x11);
+        assert!(manager.allocate(Chain::Base, sender).is_err());
+
+        manager.reconcile(Chain::Base, sender, &FixedNonce(7)).await.unwrap();
+        for expected in 7..10u64 {
+            assert_eq!(manager.allocate(Chain::Base, sender).unwrap(), U256::from(expected));
+        }
+        // Tracked separately per chain
+        assert!(manager.allocate(Chain::Ethereum, sender).is_err());
+
+        // Only nonce 7 landed; 8 and 9 are handed out again
+        manager.reconcile(Chain::Base, sender, &FixedNonce(8)).await.unwrap();
+        assert_eq!(manager.allocate(Chain::Base, sender).unwrap(), U256::from(8));
+
+        manager.invalidate(Chain::Base, sender);
+        assert_eq!(manager.peek(Chain::Base, sender), None);
+    }
+}
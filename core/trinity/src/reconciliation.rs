@@ -0,0 +1,213 @@
+//! Simulated vs actual profit reconciliation
+//!
+//! State moves between simulation and inclusion, so realized profit drifts
+//! from the simulated figure. Some drift in both directions is expected; a
+//! delta that stays negative means a modelling assumption is wrong (fees,
+//! slippage, gas) and the bot is systematically overestimating.
+//!
+//! Keeps the last `window` paired outcomes and summarises `actual -
+//! simulated`, in wei and in bps of the simulated profit.
+
+use std::collections::VecDeque;
+
+use ethers::types::{I256, U256};
+use matrix_types::SignedWei;
+
+/// Paired outcomes remembered by default
+pub const DEFAULT_RECONCILIATION_WINDOW: usize = 500;
+
+/// Thresholds for flagging a persistent shortfall
+#[derive(Debug, Clone)]
+pub struct ReconciliationConfig {
+    /// Outcomes kept for the statistics
+    pub window: usize,
+    /// Fewer samples than this never raise the flag
+    pub min_samples: usize,
+    /// Share of negative deltas (0.0 - 1.0) that counts as persistent
+    pub negative_share: f64,
+    /// Median delta must also be at or below this (bps of simulated, <= 0)
+    pub max_median_bps: i64,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_RECONCILIATION_WINDOW,
+            min_samples: 20,
+            negative_share: 0.7,
+            max_median_bps: -10,
+        }
+    }
+}
+
+/// Summary of `actual - simulated` over the window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconciliationReport {
+    pub samples: usize,
+    pub total_delta: SignedWei,
+    pub mean_delta: SignedWei,
+    pub median_delta: SignedWei,
+    pub min_delta: SignedWei,
+    pub max_delta: SignedWei,
+    /// Median delta in bps of simulated profit (zero-profit simulations excluded)
+    pub median_delta_bps: i64,
+    /// Share of outcomes that realized less than simulated
+    pub negative_fraction: f64,
+    /// Realized profit is persistently below simulation
+    pub persistent_shortfall: bool,
+}
+
+/// Aggregates simulated/actual profit pairs
+#[derive(Debug, Clone)]
+pub struct ProfitReconciler {
+    config: ReconciliationConfig,
+    /// Recent (delta, delta in bps of simulated), oldest first
+    outcomes: VecDeque<(SignedWei, Option<i64>)>,
+}
+
+impl ProfitReconciler {
+    pub fn new(config: ReconciliationConfig) -> Self {
+        Self {
+            config,
+            outcomes: VecDeque::new(),
+        }
+    }
+
+    /// Record one execution's simulated and realized profit
+    pub fn record(&mut self, simulated_profit: U256, actual_profit: U256) {
+        let delta = SignedWei::from_diff(actual_profit, simulated_profit);
+        self.outcomes.push_back((delta, delta_bps(delta, simulated_profit)));
+        while self.outcomes.len() > self.config.window.max(1) {
+            self.outcomes.pop_front();
+        }
+    }
+
+    /// Outcomes currently in the window
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    /// Statistics over the current window
+    pub fn report(&self) -> ReconciliationReport {
+        let samples = self.outcomes.len();
+        if samples == 0 {
+            return ReconciliationReport::default();
+        }
+
+        let mut deltas: Vec<SignedWei> = self.outcomes.iter().map(|(delta, _)| *delta).collect();
+        deltas.sort();
+        let mut bps: Vec<i64> = self.outcomes.iter().filter_map(|(_, bps)| *bps).collect();
+        bps.sort();
+
+        let total_delta: SignedWei = deltas.iter().sum();
+        let negative = deltas.iter().filter(|d| d.is_negative()).count();
+        let negative_fraction = negative as f64 / samples as f64;
+        let median_delta_bps = bps.get(bps.len() / 2).copied().unwrap_or(0);
+
+        let persistent_shortfall = samples >= self.config.min_samples
+            && negative_fraction >= self.config.negative_share
+            && median_delta_bps <= self.config.max_median_bps;
+
+        ReconciliationReport {
+            samples,
+            total_delta,
+            mean_delta: SignedWei(total_delta.0 / I256::from(samples as u64)),
+            median_delta: deltas[samples / 2],
+            min_delta: deltas[0],
+            max_delta: deltas[samples - 1],
+            median_delta_bps,
+            negative_fraction,
+            persistent_shortfall,
+        }
+    }
+}
+
+impl Default for ProfitReconciler {
+    fn default() -> Self {
+        Self::new(ReconciliationConfig::default())
+    }
+}
+
+/// `delta` in bps of `simulated`, saturating; None for a zero simulation
+fn delta_bps(delta: SignedWei, simulated: U256) -> Option<i64> {
+    if simulated.is_zero() {
+        return None;
+    }
+    let simulated = I256::try_from(simulated).unwrap_or(I256::MAX);
+    let bps = delta.0.saturating_mul(I256::from(10_000u64)) / simulated;
+    Some(i64::try_from(bps).unwrap_or(if bps.is_negative() { i64::MIN } else { i64::MAX }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(milli: u64) -> U256 {
+        U256::exp10(15) * milli
+    }
+
+    #[test]
+    fn test_report_statistics() {
+        let mut reconciler = ProfitReconciler::default();
+        assert_eq!(reconciler.report(), ReconciliationReport::default());
+
+        // Simulated 1 ETH each time; realized -200, -100, 0, +50, +100 milli-ETH off
+        for actual in [800, 900, 1_000, 1_050, 1_100] {
+            reconciler.record(eth(1_000), eth(actual));
+        }
+
+        let report = reconciler.report();
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.total_delta, -SignedWei::from(eth(150).as_u128() as i128));
+        assert_eq!(report.mean_delta, -SignedWei::from(eth(30).as_u128() as i128));
+        assert_eq!(report.median_delta, SignedWei::ZERO);
+        assert_eq!(report.min_delta, -SignedWei::from(eth(200).as_u128() as i128));
+        assert_eq!(report.max_delta, SignedWei::from(eth(100).as_u128() as i128));
+        assert_eq!(report.median_delta_bps, 0);
+        assert_eq!(report.negative_fraction, 0.4);
+        assert!(!report.persistent_shortfall);
+    }
+
+    #[test]
+    fn test_persistent_shortfall_flagged() {
+        let mut reconciler = ProfitReconciler::new(ReconciliationConfig {
+            min_samples: 10,
+            ..Default::default()
+        });
+
+        // Realizing ~5% less than simulated, with the odd lucky fill
+        for i in 0..20 {
+            let actual = if i % 5 == 0 { 1_010 } else { 950 };
+            reconciler.record(eth(1_000), eth(actual));
+            let report = reconciler.report();
+            assert_eq!(report.persistent_shortfall, i + 1 >= 10, "after {} samples", i + 1);
+        }
+
+        let report = reconciler.report();
+        assert_eq!(report.median_delta_bps, -500);
+        assert_eq!(report.negative_fraction, 0.8);
+    }
+
+    #[test]
+    fn test_window_evicts_old_outcomes() {
+        let mut reconciler = ProfitReconciler::new(ReconciliationConfig {
+            window: 3,
+            ..Default::default()
+        });
+        reconciler.record(eth(1_000), eth(0));
+        for _ in 0..3 {
+            reconciler.record(eth(1_000), eth(1_000));
+        }
+
+        assert_eq!(reconciler.len(), 3);
+        assert_eq!(reconciler.report().min_delta, SignedWei::ZERO);
+
+        // Zero simulated profit has no bps delta
+        reconciler.record(U256::zero(), eth(5));
+        assert_eq!(reconciler.report().median_delta_bps, 0);
+    }
+}
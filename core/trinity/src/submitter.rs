@@ -0,0 +1,198 @@
+//! Transaction submission routes
+//!
+//! Chains with a Flashbots relay submit privately as bundles; chains without
+//! one (BSC, most L2s) fall back to the public mempool via
+//! `eth_sendRawTransaction`.
+
+use async_trait::async_trait;
+use ethers::types::U64;
+use reqwest::Client;
+
+use crate::flashbots::{BundleBuilder, FlashbotsClient};
+use crate::{Chain, TrinityError};
+
+/// How a submission reached the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionRoute {
+    Flashbots,
+    PublicMempool,
+}
+
+/// Submission receipt
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub route: SubmissionRoute,
+    /// Bundle hash (Flashbots) or transaction hashes (public mempool)
+    pub ids: Vec<String>,
+}
+
+/// Transaction submitter
+#[async_trait]
+pub trait Submitter: Send + Sync {
+    /// Route used by this submitter
+    fn route(&self) -> SubmissionRoute;
+
+    /// Whether submissions are hidden from the public mempool
+    fn is_private(&self) -> bool {
+        self.route() == SubmissionRoute::Flashbots
+    }
+
+    /// Submit signed raw transactions (hex) targeting `target_block`
+    async fn submit(&self, signed_txs: &[String], target_block: U64) -> Result<Submission, TrinityError>;
+}
+
+/// Submits transactions as a Flashbots bundle
+pub struct FlashbotsSubmitter {
+    client: FlashbotsClient,
+}
+
+impl FlashbotsSubmitter {
+    pub fn new(client: FlashbotsClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Submitter for FlashbotsSubmitter {
+    fn route(&self) -> SubmissionRoute {
+        SubmissionRoute::Flashbots
+    }
+
+    async fn submit(&self, signed_txs: &[String], target_block: U64) -> Result<Submission, TrinityError> {
+        let bundle = signed_txs
+            .iter()
+            .fold(BundleBuilder::new(target_block), |builder, tx| builder.add_transaction(tx.clone()))
+            .try_build()
+            .map_err(|e| TrinityError::FlashbotsError(e.to_string()))?;
+
+        let result = self
+            .client
+            .send_bundle(&bundle)
+            .await
+            .map_err(|e| TrinityError::FlashbotsError(e.to_string()))?;
+
+        Ok(Submission {
+            route: SubmissionRoute::Flashbots,
+            ids: vec![result.bundle_hash],
+        })
+    }
+}
+
+/// Submits transactions to the public mempool over JSON-RPC
+pub struct PublicMempoolSubmitter {
+    client: Client,
+    rpc_url: String,
+}
+
+impl PublicMempoolSubmitter {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            rpc_url,
+        }
+    }
+
+    /// Send one raw transaction; returns its hash
+    async fn send_raw_transaction(&self, raw_tx: &str) -> Result<String, TrinityError> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendRawTransaction",
+            "params": [raw_tx],
+        });
+
+        let response = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| TrinityError::TransactionFailed(e.to_string()))?;
+
+        let result: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| TrinityError::TransactionFailed(e.to_string()))?;
+
+        if let Some(error) = result.get("error") {
+            return Err(TrinityError::TransactionFailed(
+                error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error").to_string(),
+            ));
+        }
+
+        result
+            .get("result")
+            .and_then(|r| r.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| TrinityError::TransactionFailed("Missing transaction hash".to_string()))
+    }
+}
+
+#[async_trait]
+impl Submitter for PublicMempoolSubmitter {
+    fn route(&self) -> SubmissionRoute {
+        SubmissionRoute::PublicMempool
+    }
+
+    /// Sends transactions in order; `target_block` is not enforceable publicly
+    async fn submit(&self, signed_txs: &[String], _target_block: U64) -> Result<Submission, TrinityError> {
+        let mut ids = Vec::with_capacity(signed_txs.len());
+        for tx in signed_txs {
+            ids.push(self.send_raw_transaction(tx).await?);
+        }
+
+        Ok(Submission {
+            route: SubmissionRoute::PublicMempool,
+            ids,
+        })
+    }
+}
+
+/// Submission endpoints
+#[derive(Debug, Clone, Default)]
+pub struct SubmitterConfig {
+    /// Flashbots relay URL (None = default relay)
+    pub flashbots_relay: Option<String>,
+    /// Flashbots signing key
+    pub flashbots_signing_key: Option<String>,
+    /// Public RPC used for `eth_sendRawTransaction`
+    pub public_rpc_url: String,
+}
+
+/// Pick the submitter for a chain: Flashbots where available, else public mempool
+pub fn submitter_for(chain: Chain, config: &SubmitterConfig) -> Box<dyn Submitter> {
+    if chain.supports_flashbots() {
+        let mut client = FlashbotsClient::new(config.flashbots_relay.clone());
+        if let Some(key) = &config.flashbots_signing_key {
+            client = client.with_signing_key(key.clone());
+        }
+        Box::new(FlashbotsSubmitter::new(client))
+    } else {
+        Box::new(PublicMempoolSubmitter::new(config.public_rpc_url.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submitter_selection() {
+        let config = SubmitterConfig {
+            public_rpc_url: "https://rpc.example".to_string(),
+            ..Default::default()
+        };
+
+        let ethereum = submitter_for(Chain::Ethereum, &config);
+        assert_eq!(ethereum.route(), SubmissionRoute::Flashbots);
+        assert!(ethereum.is_private());
+
+        let bsc = submitter_for(Chain::Bsc, &config);
+        assert_eq!(bsc.route(), SubmissionRoute::PublicMempool);
+        assert!(!bsc.is_private());
+
+        for chain in [Chain::Arbitrum, Chain::Optimism, Chain::Base] {
+            assert_eq!(submitter_for(chain, &config).route(), SubmissionRoute::PublicMempool);
+        }
+    }
+}
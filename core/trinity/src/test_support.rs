@@ -0,0 +1,56 @@
+//! Shared test fixtures
+//!
+//! A scripted chain and included executions, used by the confirmation
+//! tests and Trinity's own finalization tests.
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use ethers::types::{H256, U256};
+
+use crate::{ChainView, ExecutionResult, TrinityError};
+
+/// Scripted chain: each poll pops the next (head, inclusion) state,
+/// repeating the last one once the script runs out.
+pub(crate) struct MockChain {
+    states: Mutex<Vec<(u64, Option<u64>)>>,
+    current: Mutex<(u64, Option<u64>)>,
+}
+
+impl MockChain {
+    pub(crate) fn new(mut states: Vec<(u64, Option<u64>)>) -> Self {
+        states.reverse();
+        let first = *states.last().unwrap();
+        Self {
+            states: Mutex::new(states),
+            current: Mutex::new(first),
+        }
+    }
+}
+
+#[async_trait]
+impl ChainView for MockChain {
+    async fn block_number(&self) -> Result<u64, TrinityError> {
+        Ok(self.current.lock().unwrap().0)
+    }
+
+    async fn inclusion_block(&self, _tx_hash: H256) -> Result<Option<u64>, TrinityError> {
+        // A poll starts with the inclusion lookup: advance the script
+        let mut states = self.states.lock().unwrap();
+        if let Some(next) = states.pop() {
+            *self.current.lock().unwrap() = next;
+        }
+        Ok(self.current.lock().unwrap().1)
+    }
+}
+
+/// Successful execution of tx `0x<tx><tx>..` included at `block_number`
+pub(crate) fn included(tx: u8, block_number: u64) -> ExecutionResult {
+    ExecutionResult {
+        tx_hash: H256::repeat_byte(tx),
+        success: true,
+        actual_profit: U256::exp10(17),
+        gas_used: 250_000,
+        block_number,
+    }
+}
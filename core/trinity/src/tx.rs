@@ -0,0 +1,180 @@
+//! Signed transaction building
+//!
+//! Turns an `ArbitrageOp` into the raw transaction a bundle carries: a
+//! call to the executor contract's `executeArbitrage(asset, amount,
+//! params)`, signed and RLP-encoded. EIP-1559 (type 2) where the chain
+//! supports it; legacy (type 0, EIP-155) for chains like BSC.
+
+use ethers::abi::{self, Token};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, Eip1559TransactionRequest, TransactionRequest, U256};
+use ethers_signers::{LocalWallet, Signer};
+
+use crate::{ArbitrageOp, TrinityError};
+
+/// Entry point of the flash loan receiver contract
+pub const EXECUTE_ARBITRAGE_SIGNATURE: &str = "executeArbitrage(address,uint256,bytes)";
+
+/// Fee fields of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFees {
+    /// Type 2: base fee burned, priority fee to the builder
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+    /// Type 0: a single gas price
+    Legacy { gas_price: U256 },
+}
+
+/// Calldata for `executeArbitrage(flash loan token, amount, callback data)`
+pub fn execute_calldata(op: &ArbitrageOp) -> Bytes {
+    let selector = &ethers::utils::id(EXECUTE_ARBITRAGE_SIGNATURE)[..4];
+    let args = abi::encode(&[
+        Token::Address(op.flash_loan.token),
+        Token::Uint(op.flash_loan.amount),
+        Token::Bytes(op.flash_loan.callback_data.to_vec()),
+    ]);
+    [selector, &args].concat().into()
+}
+
+/// Sign a call executing `op` on `executor`; returns `0x`-prefixed RLP hex
+///
+/// Gas limit is `op.total_gas()`, chain id comes from the flash loan.
+/// EIP-1559 fees on a chain without 1559 are rejected rather than
+/// silently downgraded.
+pub fn build_signed_tx(
+    op: &ArbitrageOp,
+    executor: Address,
+    fees: TxFees,
+    nonce: U256,
+    signer: &LocalWallet,
+) -> Result<String, TrinityError> {
+    let chain = op.flash_loan.chain;
+    let chain_id = chain.chain_id();
+    let data = execute_calldata(op);
+    let gas = U256::from(op.total_gas());
+
+    let tx: TypedTransaction = match fees {
+        TxFees::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            if !chain.supports_eip1559() {
+                return Err(TrinityError::InvalidOperation(format!(
+                    "{:?} needs legacy transactions",
+                    chain
+                )));
+            }
+            Eip1559TransactionRequest::new()
+                .from(signer.address())
+                .to(executor)
+                .data(data)
+                .gas(gas)
+                .nonce(nonce)
+                .max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                .chain_id(chain_id)
+                .into()
+        }
+        TxFees::Legacy { gas_price } => TransactionRequest::new()
+            .from(signer.address())
+            .to(executor)
+            .data(data)
+            .gas(gas)
+            .nonce(nonce)
+            .gas_price(gas_price)
+            .chain_id(chain_id)
+            .into(),
+    };
+
+    let signature = signer
+        .sign_transaction_sync(&tx)
+        .map_err(|e| TrinityError::TransactionFailed(format!("Signing failed: {}", e)))?;
+    Ok(format!("0x{}", hex::encode(tx.rlp_signed(&signature))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Chain, FlashLoanParams};
+    use ethers::utils::rlp::Rlp;
+
+    fn op(chain: Chain) -> ArbitrageOp {
+        ArbitrageOp {
+            flash_loan: FlashLoanParams {
+                chain,
+                token: Address::repeat_byte(0xaa),
+                amount: U256::exp10(18) * 50,
+                callback_data: Bytes::from(vec![1, 2, 3]),
+                premium_bps: 9,
+            },
+            swaps: Vec::new(),
+            expected_profit: U256::exp10(17),
+            gas_estimate: 350_000,
+        }
+    }
+
+    fn signer() -> LocalWallet {
+        "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap()
+    }
+
+    fn decode(raw: &str) -> (TypedTransaction, ethers::types::Signature) {
+        let bytes = hex::decode(raw.trim_start_matches("0x")).unwrap();
+        TypedTransaction::decode_signed(&Rlp::new(&bytes)).unwrap()
+    }
+
+    #[test]
+    fn test_eip1559_tx_decodes_to_expected_fields() {
+        let op = op(Chain::Ethereum);
+        let executor = Address::repeat_byte(0xee);
+        let fees = TxFees::Eip1559 {
+            max_fee_per_gas: U256::from(40_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+        };
+        let raw = build_signed_tx(&op, executor, fees, U256::from(7), &signer()).unwrap();
+        assert!(raw.starts_with("0x02"));
+
+        let (tx, signature) = decode(&raw);
+        let TypedTransaction::Eip1559(inner) = &tx else {
+            panic!("expected a type 2 transaction");
+        };
+        assert_eq!(inner.to.as_ref().and_then(|to| to.as_address()), Some(&executor));
+        assert_eq!(inner.nonce, Some(U256::from(7)));
+        assert_eq!(inner.gas, Some(U256::from(350_000)));
+        assert_eq!(inner.max_fee_per_gas, Some(U256::from(40_000_000_000u64)));
+        assert_eq!(inner.max_priority_fee_per_gas, Some(U256::from(2_000_000_000u64)));
+        assert_eq!(inner.chain_id, Some(1.into()));
+        assert_eq!(inner.data.as_ref(), Some(&execute_calldata(&op)));
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), signer().address());
+
+        // Selector, then the ABI-encoded asset
+        let data = execute_calldata(&op);
+        assert_eq!(&data[..4], &ethers::utils::id(EXECUTE_ARBITRAGE_SIGNATURE)[..4]);
+        assert_eq!(&data[16..36], Address::repeat_byte(0xaa).as_bytes());
+    }
+
+    #[test]
+    fn test_legacy_tx_for_bsc() {
+        let op = op(Chain::Bsc);
+        let fees = TxFees::Legacy {
+            gas_price: U256::from(3_000_000_000u64),
+        };
+        let raw = build_signed_tx(&op, Address::repeat_byte(0xee), fees, U256::zero(), &signer()).unwrap();
+
+        let (tx, signature) = decode(&raw);
+        let TypedTransaction::Legacy(inner) = &tx else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(inner.gas_price, Some(U256::from(3_000_000_000u64)));
+        // EIP-155 replay protection for chain 56
+        assert_eq!(signature.v, 56 * 2 + 35 + signature.recovery_id().unwrap().to_byte() as u64);
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), signer().address());
+
+        let eip1559 = TxFees::Eip1559 {
+            max_fee_per_gas: U256::one(),
+            max_priority_fee_per_gas: U256::one(),
+        };
+        assert!(build_signed_tx(&op, Address::zero(), eip1559, U256::zero(), &signer()).is_err());
+    }
+}